@@ -0,0 +1,15 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct InitCommitBufferArgs {
+    /// The initial size of the buffer, which grows as bytes are written past its end
+    pub size: u32,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct WriteCommitBufferArgs {
+    /// The offset in the buffer at which the bytes are written
+    pub offset: u32,
+    /// The bytes overwriting the buffer data
+    pub data: Vec<u8>,
+}
@@ -0,0 +1,21 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::args::CommitStateArgs;
+
+#[derive(Default, Debug, BorshSerialize)]
+pub struct CommitBundleArgs {
+    /// The commit of each account of the bundle, in the order of the remaining accounts
+    pub commits: Vec<CommitStateArgs>,
+}
+
+/// Each commit args is followed by the next one, so unlike a single [CommitStateArgs] their
+/// trailing `actions` field cannot be omitted.
+impl BorshDeserialize for CommitBundleArgs {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        let commits = (0..len)
+            .map(|_| CommitStateArgs::deserialize_strict_reader(reader))
+            .collect::<std::io::Result<_>>()?;
+        Ok(Self { commits })
+    }
+}
@@ -1,6 +1,9 @@
+use crate::consts::RELAYED_COMMIT_MESSAGE_DOMAIN;
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::hash::hash;
+use solana_program::pubkey::Pubkey;
 
-#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+#[derive(Default, Debug, BorshSerialize)]
 pub struct CommitStateArgs {
     /// The ephemeral slot at which the account data is committed
     pub slot: u64,
@@ -10,6 +13,68 @@ pub struct CommitStateArgs {
     pub allow_undelegation: bool,
     /// The account data
     pub data: Vec<u8>,
+    /// The instructions to invoke after the commit is finalized, see [CommitAction]
+    pub actions: Vec<CommitAction>,
+}
+
+/// Validators built against the layout without `actions` omit the trailing field entirely, in
+/// which case the commit schedules no action.
+impl BorshDeserialize for CommitStateArgs {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let slot = u64::deserialize_reader(reader)?;
+        let lamports = u64::deserialize_reader(reader)?;
+        let allow_undelegation = bool::deserialize_reader(reader)?;
+        let data = Vec::<u8>::deserialize_reader(reader)?;
+        let mut actions_len = [0u8; 4];
+        let actions = match reader.read(&mut actions_len[..1])? {
+            0 => Vec::new(),
+            _ => {
+                reader.read_exact(&mut actions_len[1..])?;
+                (0..u32::from_le_bytes(actions_len))
+                    .map(|_| CommitAction::deserialize_reader(reader))
+                    .collect::<std::io::Result<_>>()?
+            }
+        };
+        Ok(Self {
+            slot,
+            lamports,
+            allow_undelegation,
+            data,
+            actions,
+        })
+    }
+}
+
+impl CommitStateArgs {
+    /// Deserialize the args in their current layout, the trailing `actions` field being
+    /// required. Used where the args are followed by other data, which the lenient layout
+    /// would misread as the `actions` field
+    pub fn deserialize_strict_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        Ok(Self {
+            slot: u64::deserialize_reader(reader)?,
+            lamports: u64::deserialize_reader(reader)?,
+            allow_undelegation: bool::deserialize_reader(reader)?,
+            data: Vec::<u8>::deserialize_reader(reader)?,
+            actions: Vec::<CommitAction>::deserialize_reader(reader)?,
+        })
+    }
+
+    /// The message the validator signs to authorize a relayed commit of the delegated account,
+    /// see [crate::processor::process_commit_state_relayed]. It is bound to the delegation
+    /// program and to the nonce of the commit, so that it authorizes a single commit
+    pub fn relayed_commit_message(&self, delegated_account: &Pubkey, commit_nonce: u64) -> Vec<u8> {
+        [
+            RELAYED_COMMIT_MESSAGE_DOMAIN,
+            crate::ID.as_ref(),
+            delegated_account.as_ref(),
+            &commit_nonce.to_le_bytes(),
+            &self.slot.to_le_bytes(),
+            &self.lamports.to_le_bytes(),
+            &[self.allow_undelegation as u8],
+            hash(&self.data).as_ref(),
+        ]
+        .concat()
+    }
 }
 
 #[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
@@ -21,3 +86,143 @@ pub struct CommitStateFromBufferArgs {
     /// Whether the account can be undelegated after the commit completes
     pub allow_undelegation: bool,
 }
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CommitStateDiffArgs {
+    /// The ephemeral slot at which the account data is committed
+    pub slot: u64,
+    /// The lamports that the account holds in the ephemeral validator
+    pub lamports: u64,
+    /// Whether the account can be undelegated after the commit completes
+    pub allow_undelegation: bool,
+    /// The patches to apply on top of the current account data
+    pub patches: Vec<StatePatch>,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct CommitStateHashArgs {
+    /// The ephemeral slot at which the account data is committed
+    pub slot: u64,
+    /// The lamports that the account holds in the ephemeral validator
+    pub lamports: u64,
+    /// Whether the account can be undelegated after the commit completes
+    pub allow_undelegation: bool,
+    /// The sha256 hash of the account data, which is supplied on finalize
+    pub data_hash: [u8; 32],
+}
+
+/// An instruction scheduled by a commit, and invoked on the base layer once the commit is
+/// finalized. It can be signed by the commit action signer of the delegated account, see
+/// [crate::pda::commit_action_signer_pda_from_delegated_account], so that the invoked program
+/// can authenticate it
+#[derive(Clone, Default, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct CommitAction {
+    /// The program to invoke
+    pub program_id: Pubkey,
+    /// The accounts of the instruction
+    pub accounts: Vec<CommitActionAccount>,
+    /// The instruction data
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct CommitActionAccount {
+    pub pubkey: Pubkey,
+    /// Only the commit action signer of the delegated account can sign a commit action
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Clone, Default, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct StatePatch {
+    /// The offset in the account data at which the bytes are written
+    pub offset: u32,
+    /// The bytes overwriting the account data
+    pub data: Vec<u8>,
+}
+
+/// The arguments of the instruction called on the owner program to validate a commit to one of
+/// its accounts, after the [crate::consts::EXTERNAL_VALIDATE_COMMIT_DISCRIMINATOR].
+///
+/// The accounts of the instruction are the delegated account, holding the data before the
+/// commit, followed by the account holding the committed state, unless it is inline. The
+/// states are not copied in the instruction data, which would exceed the CPI size limit
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct ExternalValidateCommitArgs {
+    /// The [crate::state::CommitKind] of the committed state: the full account data for a full
+    /// or hash commit, or a borsh-encoded list of [StatePatch] to apply on top of the account
+    /// data for a diff commit
+    pub kind: u64,
+    /// Where the owner program reads the committed state from
+    pub state: ExternalCommittedState,
+}
+
+/// The location of the committed state passed to the owner program, see
+/// [ExternalValidateCommitArgs]
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub enum ExternalCommittedState {
+    /// The first `len` bytes of the data of the committed state account
+    Account { len: u64 },
+    /// The committed state supplied in the instruction finalizing it, which fits in a
+    /// transaction
+    Inline(Vec<u8>),
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::to_vec;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialization_with_actions() {
+        let original = CommitStateArgs {
+            slot: 100,
+            lamports: 1_000_000,
+            allow_undelegation: true,
+            data: vec![1, 2, 3],
+            actions: vec![CommitAction {
+                program_id: Pubkey::new_unique(),
+                accounts: vec![CommitActionAccount {
+                    pubkey: Pubkey::new_unique(),
+                    is_signer: true,
+                    is_writable: false,
+                }],
+                data: vec![4, 5],
+            }],
+        };
+
+        let deserialized = CommitStateArgs::try_from_slice(&to_vec(&original).unwrap()).unwrap();
+
+        assert_eq!(deserialized.slot, original.slot);
+        assert_eq!(deserialized.lamports, original.lamports);
+        assert_eq!(deserialized.allow_undelegation, original.allow_undelegation);
+        assert_eq!(deserialized.data, original.data);
+        assert_eq!(deserialized.actions, original.actions);
+    }
+
+    #[test]
+    fn test_deserialization_without_actions_field() {
+        let data = vec![1u8, 2, 3];
+        let mut serialized = to_vec(&100u64).unwrap();
+        serialized.extend(to_vec(&1_000_000u64).unwrap());
+        serialized.extend(to_vec(&true).unwrap());
+        serialized.extend(to_vec(&data).unwrap());
+
+        let deserialized = CommitStateArgs::try_from_slice(&serialized).unwrap();
+
+        assert_eq!(deserialized.slot, 100);
+        assert_eq!(deserialized.lamports, 1_000_000);
+        assert!(deserialized.allow_undelegation);
+        assert_eq!(deserialized.data, data);
+        assert!(deserialized.actions.is_empty());
+    }
+
+    #[test]
+    fn test_deserialization_with_truncated_actions_fails() {
+        let mut serialized = to_vec(&CommitStateArgs::default()).unwrap();
+        serialized.truncate(serialized.len() - 2);
+
+        assert!(CommitStateArgs::try_from_slice(&serialized).is_err());
+    }
+}
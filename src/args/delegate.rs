@@ -1,7 +1,9 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
-#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+use crate::state::DelegationExpiry;
+
+#[derive(Default, Debug, BorshSerialize)]
 pub struct DelegateArgs {
     /// The frequency at which the validator should commit the account data
     /// if no commit is triggered by the owning program
@@ -10,4 +12,93 @@ pub struct DelegateArgs {
     pub seeds: Vec<Vec<u8>>,
     /// The validator authority that is added to the delegation record
     pub validator: Option<Pubkey>,
+    /// The deadline after which anyone can force the undelegation of the account
+    pub expiry: Option<DelegationExpiry>,
+}
+
+/// Programs built against the layout without `expiry` omit the trailing field
+/// entirely, in which case the delegation never expires.
+impl BorshDeserialize for DelegateArgs {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let commit_frequency_ms = u32::deserialize_reader(reader)?;
+        let seeds = Vec::<Vec<u8>>::deserialize_reader(reader)?;
+        let validator = Option::<Pubkey>::deserialize_reader(reader)?;
+        let mut expiry_tag = [0u8; 1];
+        let expiry = match reader.read(&mut expiry_tag)? {
+            0 => None,
+            _ => match expiry_tag[0] {
+                0 => None,
+                1 => Some(DelegationExpiry::deserialize_reader(reader)?),
+                _ => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "Invalid delegation expiry option tag",
+                    ))
+                }
+            },
+        };
+        Ok(Self {
+            commit_frequency_ms,
+            seeds,
+            validator,
+            expiry,
+        })
+    }
+}
+
+impl DelegateArgs {
+    /// Deserialize the args in their current layout, the trailing `expiry` field being
+    /// required. Used where the args are followed by other data, which the lenient layout
+    /// would misread as the `expiry` field
+    pub fn deserialize_strict_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        Ok(Self {
+            commit_frequency_ms: u32::deserialize_reader(reader)?,
+            seeds: Vec::<Vec<u8>>::deserialize_reader(reader)?,
+            validator: Option::<Pubkey>::deserialize_reader(reader)?,
+            expiry: Option::<DelegationExpiry>::deserialize_reader(reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::to_vec;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialization_with_expiry() {
+        let original = DelegateArgs {
+            commit_frequency_ms: 1_000,
+            seeds: vec![vec![1, 2, 3]],
+            validator: Some(Pubkey::new_unique()),
+            expiry: Some(DelegationExpiry::UnixTimestamp(1_700_000_000)),
+        };
+
+        let deserialized = DelegateArgs::try_from_slice(&to_vec(&original).unwrap()).unwrap();
+
+        assert_eq!(
+            deserialized.commit_frequency_ms,
+            original.commit_frequency_ms
+        );
+        assert_eq!(deserialized.seeds, original.seeds);
+        assert_eq!(deserialized.validator, original.validator);
+        assert_eq!(deserialized.expiry, original.expiry);
+    }
+
+    #[test]
+    fn test_deserialization_without_expiry_field() {
+        let validator = Pubkey::new_unique();
+        let seeds: Vec<Vec<u8>> = vec![vec![1, 2, 3]];
+        let mut serialized = to_vec(&1_000u32).unwrap();
+        serialized.extend(to_vec(&seeds).unwrap());
+        serialized.extend(to_vec(&Some(validator)).unwrap());
+
+        let deserialized = DelegateArgs::try_from_slice(&serialized).unwrap();
+
+        assert_eq!(deserialized.commit_frequency_ms, 1_000);
+        assert_eq!(deserialized.seeds, seeds);
+        assert_eq!(deserialized.validator, Some(validator));
+        assert_eq!(deserialized.expiry, None);
+    }
 }
@@ -0,0 +1,76 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::args::DelegateArgs;
+
+#[derive(Default, Debug, BorshSerialize)]
+pub struct DelegateManyArgs {
+    /// The delegation args of each delegated account, in the order of the remaining accounts
+    pub delegations: Vec<DelegateArgs>,
+}
+
+/// Each delegation args is followed by the next one, so unlike a single [DelegateArgs] their
+/// trailing `expiry` field cannot be omitted.
+impl BorshDeserialize for DelegateManyArgs {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        let delegations = (0..len)
+            .map(|_| DelegateArgs::deserialize_strict_reader(reader))
+            .collect::<std::io::Result<_>>()?;
+        Ok(Self { delegations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::to_vec;
+    use solana_program::pubkey::Pubkey;
+
+    use super::*;
+    use crate::state::DelegationExpiry;
+
+    #[test]
+    fn test_deserialization() {
+        let original = DelegateManyArgs {
+            delegations: vec![
+                DelegateArgs {
+                    commit_frequency_ms: 1_000,
+                    seeds: vec![vec![1, 2, 3]],
+                    validator: None,
+                    expiry: None,
+                },
+                DelegateArgs {
+                    commit_frequency_ms: 2_000,
+                    seeds: vec![vec![4, 5]],
+                    validator: Some(Pubkey::new_unique()),
+                    expiry: Some(DelegationExpiry::Slot(42)),
+                },
+            ],
+        };
+
+        let deserialized = DelegateManyArgs::try_from_slice(&to_vec(&original).unwrap()).unwrap();
+
+        assert_eq!(deserialized.delegations.len(), 2);
+        for (deserialized, original) in deserialized.delegations.iter().zip(original.delegations) {
+            assert_eq!(
+                deserialized.commit_frequency_ms,
+                original.commit_frequency_ms
+            );
+            assert_eq!(deserialized.seeds, original.seeds);
+            assert_eq!(deserialized.validator, original.validator);
+            assert_eq!(deserialized.expiry, original.expiry);
+        }
+    }
+
+    #[test]
+    fn test_deserialization_without_expiry_field_fails() {
+        let seeds: Vec<Vec<u8>> = vec![vec![1, 2, 3]];
+        let mut serialized = to_vec(&2u32).unwrap();
+        for _ in 0..2 {
+            serialized.extend(to_vec(&1_000u32).unwrap());
+            serialized.extend(to_vec(&seeds).unwrap());
+            serialized.extend(to_vec(&None::<Pubkey>).unwrap());
+        }
+
+        assert!(DelegateManyArgs::try_from_slice(&serialized).is_err());
+    }
+}
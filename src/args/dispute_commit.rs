@@ -0,0 +1,15 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct DisputeCommitArgs {
+    /// The nonce of the pending commit to dispute
+    pub commit_nonce: u64,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ResolveDisputeArgs {
+    /// The nonce of the disputed commit
+    pub commit_nonce: u64,
+    /// Whether the dispute is upheld, in which case the commit is never finalized
+    pub upheld: bool,
+}
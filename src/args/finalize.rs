@@ -0,0 +1,7 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct FinalizeWithDataArgs {
+    /// The account data matching the hash of the commit
+    pub data: Vec<u8>,
+}
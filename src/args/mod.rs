@@ -1,13 +1,31 @@
+mod commit_buffer;
+mod commit_bundle;
 mod commit_state;
 mod delegate;
 mod delegate_ephemeral_balance;
+mod delegate_many;
+mod dispute_commit;
+mod finalize;
+mod program_config;
+mod protocol_claim_fees;
+mod protocol_config;
 mod top_up_ephemeral_balance;
+mod validator_bond;
 mod validator_claim_fees;
 mod whitelist_validator_for_program;
 
+pub use commit_buffer::*;
+pub use commit_bundle::*;
 pub use commit_state::*;
 pub use delegate::*;
 pub use delegate_ephemeral_balance::*;
+pub use delegate_many::*;
+pub use dispute_commit::*;
+pub use finalize::*;
+pub use program_config::*;
+pub use protocol_claim_fees::*;
+pub use protocol_config::*;
 pub use top_up_ephemeral_balance::*;
+pub use validator_bond::*;
 pub use validator_claim_fees::*;
 pub use whitelist_validator_for_program::*;
@@ -0,0 +1,31 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SetChallengePeriodForProgramArgs {
+    /// The number of slots during which commits to the program accounts can be disputed
+    pub challenge_period: u64,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SetCommitFeeForProgramArgs {
+    /// The lamports charged to the payer ephemeral balance for each commit to the program accounts
+    pub commit_fee: u64,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SetCommitStalenessForProgramArgs {
+    /// The multiple of the commit frequency after which a delegation can be flagged as stale
+    pub commit_staleness_multiplier: u64,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SetCommitValidationForProgramArgs {
+    /// Whether commits to the program accounts are validated by the program on finalize
+    pub validate_commits: bool,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SetMinValidatorBondForProgramArgs {
+    /// The minimum bonded lamports a validator needs to commit to the program accounts
+    pub min_validator_bond: u64,
+}
@@ -0,0 +1,9 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ProtocolClaimFeesArgs {
+    /// The amount to claim from the protocol fees vault.
+    /// If `None`, almost the entire amount is claimed. The remaining amount
+    /// is needed to keep the protocol fees vault rent-exempt.
+    pub amount: Option<u64>,
+}
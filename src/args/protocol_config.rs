@@ -0,0 +1,31 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct InitProtocolConfigArgs {
+    /// The authority allowed to update the protocol config
+    pub admin: Pubkey,
+    /// The fees extracted from the delegation PDAs rent when they are closed, in basis points
+    pub rent_fees_bps: u16,
+    /// The fees extracted from the validator fees claims, in basis points
+    pub protocol_fees_bps: u16,
+    /// The minimum commit frequency a delegation is held to when flagging it as stale
+    pub min_commit_frequency_ms: u64,
+    /// The lamports charged to the payer ephemeral balance for each commit
+    pub commit_fee: u64,
+}
+
+/// The protocol config parameters to update, the parameters left to `None` are unchanged
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UpdateProtocolConfigArgs {
+    pub rent_fees_bps: Option<u16>,
+    pub protocol_fees_bps: Option<u16>,
+    pub min_commit_frequency_ms: Option<u64>,
+    pub commit_fee: Option<u64>,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ProposeProtocolAdminArgs {
+    /// The admin to rotate to once it accepts, or the default pubkey to cancel a pending rotation
+    pub new_admin: Pubkey,
+}
@@ -0,0 +1,21 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct DepositValidatorBondArgs {
+    /// The lamports to add to the validator bond
+    pub amount: u64,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct UnbondValidatorBondArgs {
+    /// The bonded lamports to unbond
+    pub amount: u64,
+}
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SlashValidatorBondArgs {
+    /// The lamports to slash from the validator bond
+    pub amount: u64,
+    /// The nonce of the disputed commit the validator is slashed for
+    pub commit_nonce: u64,
+}
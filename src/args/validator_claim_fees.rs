@@ -1,4 +1,5 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
 
 #[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
 pub struct ValidatorClaimFeesArgs {
@@ -7,3 +8,11 @@ pub struct ValidatorClaimFeesArgs {
     /// is needed to keep the fees vault rent-exempt.
     pub amount: Option<u64>,
 }
+
+#[derive(Default, Debug, BorshSerialize, BorshDeserialize)]
+pub struct SetValidatorFeesVaultAuthorityArgs {
+    /// The authority allowed to claim the fees and to update the withdrawal settings
+    pub withdrawal_authority: Pubkey,
+    /// The account receiving the claimed fees
+    pub payout_destination: Pubkey,
+}
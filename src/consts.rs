@@ -1,13 +1,78 @@
 use solana_program::pubkey::Pubkey;
 
+/// The default delegation session fees (extracted in basis points from the delegation PDAs rent on
+/// closure), used until the protocol config is initialized, see [crate::state::ProtocolConfig].
+pub const DEFAULT_RENT_FEES_BPS: u16 = 1_000;
+
+/// The default fees extracted from the validator earnings (extracted in basis points from the
+/// validator fees claims), used until the protocol config is initialized.
+pub const DEFAULT_PROTOCOL_FEES_BPS: u16 = 1_000;
+
 /// The delegation session fees (extracted in percentage from the delegation PDAs rent on closure).
-pub const RENT_FEES_PERCENTAGE: u8 = 10;
+#[deprecated(note = "the rent fees are set in basis points, use DEFAULT_RENT_FEES_BPS")]
+pub const RENT_FEES_PERCENTAGE: u8 = (DEFAULT_RENT_FEES_BPS / 100) as u8;
 
 /// The fees extracted from the validator earnings (extracted in percentage from the validator fees claims).
-pub const PROTOCOL_FEES_PERCENTAGE: u8 = 10;
+#[deprecated(note = "the protocol fees are set in basis points, use DEFAULT_PROTOCOL_FEES_BPS")]
+pub const PROTOCOL_FEES_PERCENTAGE: u8 = (DEFAULT_PROTOCOL_FEES_BPS / 100) as u8;
+
+/// The basis points denominator of the fee rates.
+pub const MAX_FEES_BPS: u16 = 10_000;
+
+/// The number of slots the validator has to undelegate an account after the owner program
+/// requested it, before anyone can force the undelegation.
+pub const UNDELEGATION_REQUEST_GRACE_PERIOD_SLOTS: u64 = 1_500;
+
+/// The number of slots a validator has to wait after requesting to unbond lamports from its
+/// bond, before withdrawing them. The unbonding lamports can still be slashed meanwhile.
+pub const VALIDATOR_UNBONDING_PERIOD_SLOTS: u64 = 432_000;
+
+/// The lamports a challenger bonds to dispute a commit, refunded if the dispute is upheld and
+/// paid to the validator of the disputed commit if it is rejected.
+pub const DISPUTE_BOND_LAMPORTS: u64 = 100_000_000;
+
+/// The number of slots the protocol admin has to resolve a dispute, after which anyone can
+/// reject it, see [crate::processor::process_resolve_dispute].
+pub const DISPUTE_RESOLUTION_PERIOD_SLOTS: u64 = 216_000;
+
+/// The number of slots a commit bundle can be finalized in once the challenge period of all its
+/// commits elapsed, after which anyone can abandon it, see
+/// [crate::processor::process_abandon_commit_bundle].
+pub const COMMIT_BUNDLE_FINALIZE_PERIOD_SLOTS: u64 = 216_000;
+
+/// The default multiple of the commit frequency of a delegation after which anyone can flag it
+/// as stale if no commit was received, see [crate::processor::process_flag_stale_delegation].
+pub const DEFAULT_COMMIT_STALENESS_MULTIPLIER: u64 = 10;
+
+/// The maximum number of actions a commit can schedule, see [crate::args::CommitAction].
+pub const MAX_COMMIT_ACTIONS: usize = 4;
+
+/// The compute units recommended for each action scheduled by a commit. The actions run within
+/// the finalize instruction, so the finalize transaction should request this many compute units
+/// per action on top of the finalize itself.
+///
+/// This is advisory and not enforced by the program: an action may consume more, and an action
+/// running out of compute units fails the finalize, which can be retried with a larger budget.
+pub const RECOMMENDED_COMMIT_ACTION_COMPUTE_UNITS: u32 = 100_000;
+
+/// The domain tag prefixing the message a validator signs to authorize a relayed commit, so that
+/// the signature of another message cannot be passed off as one, see
+/// [crate::args::CommitStateArgs::relayed_commit_message].
+pub const RELAYED_COMMIT_MESSAGE_DOMAIN: &[u8] = b"dlp:commit_state_relayed";
 
 /// The discriminator for the external undelegate instruction.
 pub const EXTERNAL_UNDELEGATE_DISCRIMINATOR: [u8; 8] = [196, 28, 41, 206, 48, 37, 51, 167];
 
+/// The discriminator for the external validate commit instruction, called on the owner program
+/// when finalizing a commit if its program config requires it.
+pub const EXTERNAL_VALIDATE_COMMIT_DISCRIMINATOR: [u8; 8] = [159, 115, 229, 86, 155, 204, 91, 141];
+
 /// The program ID of the delegation program.
 pub const DELEGATION_PROGRAM_ID: Pubkey = crate::id();
+
+/// The program ID of the SPL Token program, holding the tokens of the ephemeral token balances.
+pub const TOKEN_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// The size of an SPL Token account.
+pub const TOKEN_ACCOUNT_SIZE: usize = 165;
@@ -33,6 +33,80 @@ pub enum DlpDiscriminator {
     CommitStateFromBuffer = 13,
     /// See [crate::processor::process_close_validator_fees_vault] for docs.
     CloseValidatorFeesVault = 14,
+    /// See [crate::processor::process_force_undelegate] for docs.
+    ForceUndelegate = 15,
+    /// See [crate::processor::process_request_undelegation] for docs.
+    RequestUndelegation = 16,
+    /// See [crate::processor::process_redelegate] for docs.
+    Redelegate = 17,
+    /// See [crate::processor::process_delegate_many] for docs.
+    DelegateMany = 18,
+    /// See [crate::processor::process_commit_state_diff] for docs.
+    CommitStateDiff = 19,
+    /// See [crate::processor::process_commit_bundle] for docs.
+    CommitBundle = 20,
+    /// See [crate::processor::process_finalize_bundle] for docs.
+    FinalizeBundle = 21,
+    /// See [crate::processor::process_commit_and_finalize] for docs.
+    CommitAndFinalize = 22,
+    /// See [crate::processor::process_commit_state_hash] for docs.
+    CommitStateHash = 23,
+    /// See [crate::processor::process_finalize_with_data] for docs.
+    FinalizeWithData = 24,
+    /// See [crate::processor::process_finalize_from_buffer] for docs.
+    FinalizeFromBuffer = 25,
+    /// See [crate::processor::process_set_challenge_period_for_program] for docs.
+    SetChallengePeriodForProgram = 26,
+    /// See [crate::processor::process_dispute_commit] for docs.
+    DisputeCommit = 27,
+    /// See [crate::processor::process_set_min_validator_bond_for_program] for docs.
+    SetMinValidatorBondForProgram = 28,
+    /// See [crate::processor::process_deposit_validator_bond] for docs.
+    DepositValidatorBond = 29,
+    /// See [crate::processor::process_unbond_validator_bond] for docs.
+    UnbondValidatorBond = 30,
+    /// See [crate::processor::process_withdraw_validator_bond] for docs.
+    WithdrawValidatorBond = 31,
+    /// See [crate::processor::process_slash_validator_bond] for docs.
+    SlashValidatorBond = 32,
+    /// See [crate::processor::process_commit_state_relayed] for docs.
+    CommitStateRelayed = 33,
+    /// See [crate::processor::process_init_commit_buffer] for docs.
+    InitCommitBuffer = 34,
+    /// See [crate::processor::process_write_commit_buffer] for docs.
+    WriteCommitBuffer = 35,
+    /// See [crate::processor::process_close_commit_buffer] for docs.
+    CloseCommitBuffer = 36,
+    /// See [crate::processor::process_set_commit_validation_for_program] for docs.
+    SetCommitValidationForProgram = 37,
+    /// See [crate::processor::process_set_commit_staleness_for_program] for docs.
+    SetCommitStalenessForProgram = 38,
+    /// See [crate::processor::process_flag_stale_delegation] for docs.
+    FlagStaleDelegation = 39,
+    /// See [crate::processor::process_init_protocol_config] for docs.
+    InitProtocolConfig = 40,
+    /// See [crate::processor::process_update_protocol_config] for docs.
+    UpdateProtocolConfig = 41,
+    /// See [crate::processor::process_propose_protocol_admin] for docs.
+    ProposeProtocolAdmin = 42,
+    /// See [crate::processor::process_accept_protocol_admin] for docs.
+    AcceptProtocolAdmin = 43,
+    /// See [crate::processor::process_set_validator_fees_vault_authority] for docs.
+    SetValidatorFeesVaultAuthority = 44,
+    /// See [crate::processor::process_set_commit_fee_for_program] for docs.
+    SetCommitFeeForProgram = 45,
+    /// See [crate::processor::process_top_up_ephemeral_token_balance] for docs.
+    TopUpEphemeralTokenBalance = 46,
+    /// See [crate::processor::process_delegate_ephemeral_token_balance] for docs.
+    DelegateEphemeralTokenBalance = 47,
+    /// See [crate::processor::process_close_ephemeral_token_balance] for docs.
+    CloseEphemeralTokenBalance = 48,
+    /// See [crate::processor::process_migrate_delegation_metadata] for docs.
+    MigrateDelegationMetadata = 49,
+    /// See [crate::processor::process_resolve_dispute] for docs.
+    ResolveDispute = 50,
+    /// See [crate::processor::process_abandon_commit_bundle] for docs.
+    AbandonCommitBundle = 51,
 }
 
 impl DlpDiscriminator {
@@ -60,6 +134,43 @@ impl TryFrom<[u8; 8]> for DlpDiscriminator {
             0xc => Ok(DlpDiscriminator::ProtocolClaimFees),
             0xd => Ok(DlpDiscriminator::CommitStateFromBuffer),
             0xe => Ok(DlpDiscriminator::CloseValidatorFeesVault),
+            0xf => Ok(DlpDiscriminator::ForceUndelegate),
+            0x10 => Ok(DlpDiscriminator::RequestUndelegation),
+            0x11 => Ok(DlpDiscriminator::Redelegate),
+            0x12 => Ok(DlpDiscriminator::DelegateMany),
+            0x13 => Ok(DlpDiscriminator::CommitStateDiff),
+            0x14 => Ok(DlpDiscriminator::CommitBundle),
+            0x15 => Ok(DlpDiscriminator::FinalizeBundle),
+            0x16 => Ok(DlpDiscriminator::CommitAndFinalize),
+            0x17 => Ok(DlpDiscriminator::CommitStateHash),
+            0x18 => Ok(DlpDiscriminator::FinalizeWithData),
+            0x19 => Ok(DlpDiscriminator::FinalizeFromBuffer),
+            0x1a => Ok(DlpDiscriminator::SetChallengePeriodForProgram),
+            0x1b => Ok(DlpDiscriminator::DisputeCommit),
+            0x1c => Ok(DlpDiscriminator::SetMinValidatorBondForProgram),
+            0x1d => Ok(DlpDiscriminator::DepositValidatorBond),
+            0x1e => Ok(DlpDiscriminator::UnbondValidatorBond),
+            0x1f => Ok(DlpDiscriminator::WithdrawValidatorBond),
+            0x20 => Ok(DlpDiscriminator::SlashValidatorBond),
+            0x21 => Ok(DlpDiscriminator::CommitStateRelayed),
+            0x22 => Ok(DlpDiscriminator::InitCommitBuffer),
+            0x23 => Ok(DlpDiscriminator::WriteCommitBuffer),
+            0x24 => Ok(DlpDiscriminator::CloseCommitBuffer),
+            0x25 => Ok(DlpDiscriminator::SetCommitValidationForProgram),
+            0x26 => Ok(DlpDiscriminator::SetCommitStalenessForProgram),
+            0x27 => Ok(DlpDiscriminator::FlagStaleDelegation),
+            0x28 => Ok(DlpDiscriminator::InitProtocolConfig),
+            0x29 => Ok(DlpDiscriminator::UpdateProtocolConfig),
+            0x2a => Ok(DlpDiscriminator::ProposeProtocolAdmin),
+            0x2b => Ok(DlpDiscriminator::AcceptProtocolAdmin),
+            0x2c => Ok(DlpDiscriminator::SetValidatorFeesVaultAuthority),
+            0x2d => Ok(DlpDiscriminator::SetCommitFeeForProgram),
+            0x2e => Ok(DlpDiscriminator::TopUpEphemeralTokenBalance),
+            0x2f => Ok(DlpDiscriminator::DelegateEphemeralTokenBalance),
+            0x30 => Ok(DlpDiscriminator::CloseEphemeralTokenBalance),
+            0x31 => Ok(DlpDiscriminator::MigrateDelegationMetadata),
+            0x32 => Ok(DlpDiscriminator::ResolveDispute),
+            0x33 => Ok(DlpDiscriminator::AbandonCommitBundle),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
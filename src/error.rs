@@ -33,6 +33,54 @@ pub enum DlpError {
     OutdatedSlot = 12,
     #[error("Computation overflow detected")]
     Overflow = 13,
+    #[error("Validator is not the authority of the delegated account")]
+    InvalidDelegationAuthority = 14,
+    #[error("Delegation has not expired")]
+    DelegationNotExpired = 15,
+    #[error("State patch is out of the account data range")]
+    InvalidStatePatch = 16,
+    #[error("Commit does not belong to the expected commit bundle")]
+    InvalidCommitBundle = 17,
+    #[error("Finalized data does not match the committed hash")]
+    InvalidCommitHash = 18,
+    #[error("Commit challenge period has not elapsed")]
+    ChallengePeriodNotElapsed = 19,
+    #[error("Commit challenge period has elapsed")]
+    ChallengePeriodElapsed = 20,
+    #[error("Commit is disputed")]
+    CommitDisputed = 21,
+    #[error("Validator bond is insufficient")]
+    InsufficientValidatorBond = 22,
+    #[error("Validator bond unbonding period has not elapsed")]
+    UnbondingNotElapsed = 23,
+    #[error("Commit is not authorized by an Ed25519 signature of the validator")]
+    InvalidEd25519Signature = 24,
+    #[error("Commit actions are invalid")]
+    InvalidCommitActions = 25,
+    #[error("Delegation is stale")]
+    DelegationStale = 26,
+    #[error("Delegation is not stale")]
+    DelegationNotStale = 27,
+    #[error("Protocol config is invalid")]
+    InvalidProtocolConfig = 28,
+    #[error("Invalid payout destination for the validator fees vault")]
+    InvalidPayoutDestination = 29,
+    #[error("Invalid ephemeral balance to pay the commit fee")]
+    InvalidCommitFeePayer = 30,
+    #[error("Invalid ephemeral token balance state")]
+    InvalidEphemeralTokenBalance = 31,
+    #[error("Undelegation of the account was requested")]
+    UndelegationRequested = 32,
+    #[error("Previous commits are pending finalization")]
+    PendingCommits = 33,
+    #[error("Commit has no pending dispute")]
+    CommitNotDisputed = 34,
+    #[error("A dispute is pending resolution")]
+    DisputePending = 35,
+    #[error("Account is marked as undelegatable")]
+    DelegationUndelegatable = 36,
+    #[error("Commit bundle has not expired")]
+    CommitBundleNotExpired = 37,
 }
 
 impl From<DlpError> for ProgramError {
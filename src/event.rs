@@ -0,0 +1,241 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use solana_program::log::sol_log_data;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+/// Discriminators of the events emitted by the delegation program.
+/// The values are part of the program interface and must never change
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum EventDiscriminator {
+    Delegated = 0,
+    Committed = 1,
+    CommitSkipped = 2,
+    Finalized = 3,
+    Undelegated = 4,
+    FeesClaimed = 5,
+    ValidatorWhitelisted = 6,
+    EphemeralBalanceToppedUp = 7,
+    DelegationFlaggedStale = 8,
+    EphemeralTokenBalanceToppedUp = 9,
+}
+
+impl EventDiscriminator {
+    pub const fn to_bytes(&self) -> [u8; 8] {
+        let num = (*self) as u64;
+        num.to_le_bytes()
+    }
+}
+
+/// An event emitted in the program logs with `sol_log_data`, as its discriminator followed by
+/// its borsh-encoded fields
+pub trait Event: BorshSerialize {
+    fn discriminator() -> EventDiscriminator;
+
+    fn to_bytes_with_discriminator(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut data = Self::discriminator().to_bytes().to_vec();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+
+    fn emit(&self) -> Result<(), ProgramError> {
+        sol_log_data(&[&self.to_bytes_with_discriminator()?]);
+        Ok(())
+    }
+}
+
+macro_rules! impl_event {
+    ($struct_name:ident, $discriminator:ident) => {
+        impl Event for $struct_name {
+            fn discriminator() -> EventDiscriminator {
+                EventDiscriminator::$discriminator
+            }
+        }
+    };
+}
+
+/// An account was delegated
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct DelegatedEvent {
+    pub delegated_account: Pubkey,
+    pub owner: Pubkey,
+    /// The validator allowed to commit, or the default pubkey if any validator is
+    pub authority: Pubkey,
+    pub commit_frequency_ms: u64,
+    pub delegation_slot: u64,
+}
+impl_event!(DelegatedEvent, Delegated);
+
+/// A new state of a delegated account was committed, and is pending finalization. A state
+/// committed and finalized right away, see [crate::processor::process_commit_and_finalize],
+/// emits it along with a [FinalizedEvent]
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct CommittedEvent {
+    pub delegated_account: Pubkey,
+    pub validator: Pubkey,
+    /// The nonce of the commit, or None for a state committed and finalized right away, which
+    /// consumes no commit nonce
+    pub commit_nonce: Option<u64>,
+    /// The ephemeral slot at which the account data is committed
+    pub slot: u64,
+    pub lamports: u64,
+    pub allow_undelegation: bool,
+    /// The [crate::state::CommitKind] of the commit
+    pub kind: u64,
+}
+impl_event!(CommittedEvent, Committed);
+
+/// A commit was skipped, or discarded at finalization, because a more recent state was
+/// already committed
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct CommitSkippedEvent {
+    pub delegated_account: Pubkey,
+    pub validator: Pubkey,
+    /// The ephemeral slot of the skipped commit
+    pub slot: u64,
+    /// The ephemeral slot of the last queued commit, or of the last finalized state if no
+    /// commit is pending
+    pub last_update_external_slot: u64,
+}
+impl_event!(CommitSkippedEvent, CommitSkipped);
+
+/// A committed state was applied to the delegated account
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct FinalizedEvent {
+    pub delegated_account: Pubkey,
+    pub validator: Pubkey,
+    /// The ephemeral slot of the finalized state
+    pub slot: u64,
+    pub lamports: u64,
+}
+impl_event!(FinalizedEvent, Finalized);
+
+/// A delegated account was given back to its owner program
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct UndelegatedEvent {
+    pub delegated_account: Pubkey,
+    pub owner: Pubkey,
+}
+impl_event!(UndelegatedEvent, Undelegated);
+
+/// Fees were claimed from the protocol or a validator fees vault
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct FeesClaimedEvent {
+    pub fees_vault: Pubkey,
+    pub receiver: Pubkey,
+    /// The lamports withdrawn from the fees vault
+    pub amount: u64,
+    /// The part of the amount going to the protocol fees vault
+    pub protocol_fees: u64,
+}
+impl_event!(FeesClaimedEvent, FeesClaimed);
+
+/// A validator was added to, or removed from, the approved validators of a program
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct ValidatorWhitelistedEvent {
+    pub program: Pubkey,
+    pub validator: Pubkey,
+    pub whitelisted: bool,
+}
+impl_event!(ValidatorWhitelistedEvent, ValidatorWhitelisted);
+
+/// Lamports were deposited in an ephemeral balance
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct EphemeralBalanceToppedUpEvent {
+    pub payer: Pubkey,
+    /// The pubkey the ephemeral balance is derived from
+    pub pubkey: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+}
+impl_event!(EphemeralBalanceToppedUpEvent, EphemeralBalanceToppedUp);
+
+/// A delegation was flagged as stale, after no commit was received for too long
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct DelegationFlaggedStaleEvent {
+    pub delegated_account: Pubkey,
+    /// The validator allowed to commit, or the default pubkey if any validator is
+    pub authority: Pubkey,
+    pub last_commit_timestamp: i64,
+}
+impl_event!(DelegationFlaggedStaleEvent, DelegationFlaggedStale);
+
+/// Tokens were deposited in an ephemeral token balance
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct EphemeralTokenBalanceToppedUpEvent {
+    pub payer: Pubkey,
+    /// The pubkey the ephemeral token balance is derived from
+    pub pubkey: Pubkey,
+    pub mint: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+}
+impl_event!(
+    EphemeralTokenBalanceToppedUpEvent,
+    EphemeralTokenBalanceToppedUp
+);
+
+/// Any event emitted by the delegation program
+#[derive(Clone, Debug, PartialEq)]
+pub enum DlpEvent {
+    Delegated(DelegatedEvent),
+    Committed(CommittedEvent),
+    CommitSkipped(CommitSkippedEvent),
+    Finalized(FinalizedEvent),
+    Undelegated(UndelegatedEvent),
+    FeesClaimed(FeesClaimedEvent),
+    ValidatorWhitelisted(ValidatorWhitelistedEvent),
+    EphemeralBalanceToppedUp(EphemeralBalanceToppedUpEvent),
+    DelegationFlaggedStale(DelegationFlaggedStaleEvent),
+    EphemeralTokenBalanceToppedUp(EphemeralTokenBalanceToppedUpEvent),
+}
+
+impl DlpEvent {
+    /// Decodes an event from the data logged by the program, i.e. the base64-decoded
+    /// `Program data: ` log message
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < 8 {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (discriminator, data) = data.split_at(8);
+        let discriminator = u64::from_le_bytes(
+            discriminator
+                .try_into()
+                .map_err(|_| ProgramError::InvalidArgument)?,
+        );
+        let discriminator = u8::try_from(discriminator)
+            .ok()
+            .and_then(|discriminator| EventDiscriminator::try_from(discriminator).ok())
+            .ok_or(ProgramError::InvalidArgument)?;
+        let event = match discriminator {
+            EventDiscriminator::Delegated => Self::Delegated(DelegatedEvent::try_from_slice(data)?),
+            EventDiscriminator::Committed => Self::Committed(CommittedEvent::try_from_slice(data)?),
+            EventDiscriminator::CommitSkipped => {
+                Self::CommitSkipped(CommitSkippedEvent::try_from_slice(data)?)
+            }
+            EventDiscriminator::Finalized => Self::Finalized(FinalizedEvent::try_from_slice(data)?),
+            EventDiscriminator::Undelegated => {
+                Self::Undelegated(UndelegatedEvent::try_from_slice(data)?)
+            }
+            EventDiscriminator::FeesClaimed => {
+                Self::FeesClaimed(FeesClaimedEvent::try_from_slice(data)?)
+            }
+            EventDiscriminator::ValidatorWhitelisted => {
+                Self::ValidatorWhitelisted(ValidatorWhitelistedEvent::try_from_slice(data)?)
+            }
+            EventDiscriminator::EphemeralBalanceToppedUp => {
+                Self::EphemeralBalanceToppedUp(EphemeralBalanceToppedUpEvent::try_from_slice(data)?)
+            }
+            EventDiscriminator::DelegationFlaggedStale => {
+                Self::DelegationFlaggedStale(DelegationFlaggedStaleEvent::try_from_slice(data)?)
+            }
+            EventDiscriminator::EphemeralTokenBalanceToppedUp => {
+                Self::EphemeralTokenBalanceToppedUp(
+                    EphemeralTokenBalanceToppedUpEvent::try_from_slice(data)?,
+                )
+            }
+        };
+        Ok(event)
+    }
+}
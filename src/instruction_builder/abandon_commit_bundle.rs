@@ -0,0 +1,27 @@
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::commit_bundle_record_pda_from_delegated_account;
+
+/// Builds an abandon commit bundle instruction, for the bundle derived from the first
+/// delegated account of the bundle and the nonce of its commit.
+/// See [crate::processor::process_abandon_commit_bundle] for docs.
+pub fn abandon_commit_bundle(
+    signer: Pubkey,
+    validator: Pubkey,
+    first_delegated_account: Pubkey,
+    commit_nonce: u64,
+) -> Instruction {
+    let commit_bundle_record_pda =
+        commit_bundle_record_pda_from_delegated_account(&first_delegated_account, commit_nonce);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(signer, true),
+            AccountMeta::new(commit_bundle_record_pda, false),
+            AccountMeta::new(validator, false),
+        ],
+        data: DlpDiscriminator::AbandonCommitBundle.to_vec(),
+    }
+}
@@ -0,0 +1,18 @@
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::protocol_config_pda;
+
+/// Accept the protocol admin rotation.
+/// See [crate::processor::process_accept_protocol_admin] for docs.
+pub fn accept_protocol_admin(pending_admin: Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(pending_admin, true),
+            AccountMeta::new(protocol_config_pda(), false),
+        ],
+        data: DlpDiscriminator::AcceptProtocolAdmin.to_vec(),
+    }
+}
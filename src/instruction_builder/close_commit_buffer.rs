@@ -0,0 +1,21 @@
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::commit_buffer_pda_from_validator_and_delegated_account;
+
+/// Builds a close commit buffer instruction.
+/// See [crate::processor::process_close_commit_buffer] for docs.
+pub fn close_commit_buffer(validator: Pubkey, delegated_account: Pubkey) -> Instruction {
+    let commit_buffer_pda =
+        commit_buffer_pda_from_validator_and_delegated_account(&validator, &delegated_account);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(validator, true),
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new(commit_buffer_pda, false),
+        ],
+        data: DlpDiscriminator::CloseCommitBuffer.to_vec(),
+    }
+}
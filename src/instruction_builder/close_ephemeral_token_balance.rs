@@ -0,0 +1,44 @@
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::consts::TOKEN_PROGRAM_ID;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    delegation_record_pda_from_delegated_account, ephemeral_token_balance_escrow_pda_from_balance,
+    ephemeral_token_balance_pda_from_payer,
+};
+
+/// Creates instruction to close an ephemeral token balance account, transferring its tokens
+/// to the destination token account
+/// See [crate::processor::process_close_ephemeral_token_balance] for docs.
+pub fn close_ephemeral_token_balance(
+    payer: Pubkey,
+    mint: Pubkey,
+    destination_token_account: Pubkey,
+    index: u8,
+) -> Instruction {
+    let ephemeral_token_balance_pda = ephemeral_token_balance_pda_from_payer(&payer, &mint, index);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(ephemeral_token_balance_pda, false),
+            AccountMeta::new(
+                ephemeral_token_balance_escrow_pda_from_balance(&ephemeral_token_balance_pda),
+                false,
+            ),
+            AccountMeta::new(destination_token_account, false),
+            AccountMeta::new_readonly(
+                delegation_record_pda_from_delegated_account(&ephemeral_token_balance_pda),
+                false,
+            ),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        data: [
+            DlpDiscriminator::CloseEphemeralTokenBalance.to_vec(),
+            vec![index],
+        ]
+        .concat(),
+    }
+}
@@ -2,7 +2,7 @@ use solana_program::instruction::Instruction;
 use solana_program::{bpf_loader_upgradeable, instruction::AccountMeta, pubkey::Pubkey};
 
 use crate::discriminator::DlpDiscriminator;
-use crate::pda::validator_fees_vault_pda_from_validator;
+use crate::pda::{protocol_config_pda, validator_fees_vault_pda_from_validator};
 
 /// Close a validator fees vault PDA.
 /// See [crate::processor::process_close_validator_fees_vault] for docs.
@@ -22,6 +22,7 @@ pub fn close_validator_fees_vault(
             AccountMeta::new_readonly(delegation_program_data, false),
             AccountMeta::new(validator_identity, false),
             AccountMeta::new(validator_fees_vault_pda, false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
         ],
         data: DlpDiscriminator::CloseValidatorFeesVault.to_vec(),
     }
@@ -0,0 +1,67 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::CommitStateArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::instruction_builder::add_commit_fee_accounts;
+use crate::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    program_config_from_program_id, validator_bond_pda_from_validator,
+    validator_fees_vault_pda_from_validator,
+};
+
+/// Builds a commit and finalize instruction.
+/// See [crate::processor::process_commit_and_finalize] for docs.
+pub fn commit_and_finalize(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    commit_args: CommitStateArgs,
+) -> Instruction {
+    let commit_args = to_vec(&commit_args).unwrap();
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_account);
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator);
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
+    let program_config_pda = program_config_from_program_id(&delegated_account_owner);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(validator, true),
+            AccountMeta::new(delegated_account, false),
+            AccountMeta::new(delegation_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+            AccountMeta::new(validator_fees_vault_pda, false),
+            AccountMeta::new_readonly(program_config_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(delegated_account_owner, false),
+            AccountMeta::new_readonly(validator_bond_pda, false),
+        ],
+        data: [DlpDiscriminator::CommitAndFinalize.to_vec(), commit_args].concat(),
+    }
+}
+
+/// Builds a commit and finalize instruction charging the commit fee to the ephemeral balance of the
+/// payer of the delegation, at the given index.
+/// The remaining accounts, if any, are appended after the commit fee accounts.
+/// See [crate::processor::process_commit_and_finalize] for docs.
+pub fn commit_and_finalize_with_fee(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    commit_args: CommitStateArgs,
+    fee_payer: Pubkey,
+    fee_payer_index: u8,
+) -> Instruction {
+    let mut ix = commit_and_finalize(
+        validator,
+        delegated_account,
+        delegated_account_owner,
+        commit_args,
+    );
+    add_commit_fee_accounts(&mut ix, 4, fee_payer, fee_payer_index);
+    ix
+}
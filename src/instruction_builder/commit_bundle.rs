@@ -0,0 +1,89 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::{CommitBundleArgs, CommitStateArgs};
+use crate::discriminator::DlpDiscriminator;
+use crate::instruction_builder::commit_fee_payer_accounts;
+use crate::pda::{
+    commit_bundle_record_pda_from_delegated_account, commit_record_pda_from_delegated_account,
+    commit_state_pda_from_delegated_account, delegation_metadata_pda_from_delegated_account,
+    delegation_record_pda_from_delegated_account, program_config_from_program_id,
+    protocol_config_pda, validator_bond_pda_from_validator,
+    validator_fees_vault_pda_from_validator,
+};
+
+/// Builds a commit bundle instruction, committing each delegated account, given with its
+/// owner and its next commit nonce, with its args. The bundle record is derived from the
+/// first delegated account and its commit nonce.
+/// See [crate::processor::process_commit_bundle] for docs.
+pub fn commit_bundle(
+    validator: Pubkey,
+    commits: Vec<(Pubkey, Pubkey, u64, CommitStateArgs)>,
+) -> Instruction {
+    let (first_delegated_account, first_commit_nonce) = commits
+        .first()
+        .map(|(account, _, nonce, _)| (*account, *nonce))
+        .unwrap_or_default();
+    let commit_bundle_record_pda = commit_bundle_record_pda_from_delegated_account(
+        &first_delegated_account,
+        first_commit_nonce,
+    );
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator);
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(validator, true),
+        AccountMeta::new(commit_bundle_record_pda, false),
+        AccountMeta::new_readonly(validator_fees_vault_pda, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(validator_bond_pda, false),
+    ];
+    let mut args = CommitBundleArgs::default();
+    for (delegated_account, delegated_account_owner, commit_nonce, commit_args) in commits {
+        let commit_state_pda =
+            commit_state_pda_from_delegated_account(&delegated_account, commit_nonce);
+        let commit_record_pda =
+            commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
+        let delegation_record_pda =
+            delegation_record_pda_from_delegated_account(&delegated_account);
+        let delegation_metadata_pda =
+            delegation_metadata_pda_from_delegated_account(&delegated_account);
+        let program_config_pda = program_config_from_program_id(&delegated_account_owner);
+        accounts.extend([
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new(commit_state_pda, false),
+            AccountMeta::new(commit_record_pda, false),
+            AccountMeta::new_readonly(delegation_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+            AccountMeta::new_readonly(program_config_pda, false),
+        ]);
+        args.commits.push(commit_args);
+    }
+    let commit_args = to_vec(&args).unwrap();
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: [DlpDiscriminator::CommitBundle.to_vec(), commit_args].concat(),
+    }
+}
+
+/// Builds a commit bundle instruction charging the commit fee of each delegated account to
+/// the ephemeral balance of the payer of its delegation, at the given index, given in the
+/// order of the commits.
+/// See [crate::processor::process_commit_bundle] for docs.
+pub fn commit_bundle_with_fees(
+    validator: Pubkey,
+    commits: Vec<(Pubkey, Pubkey, u64, CommitStateArgs)>,
+    fee_payers: Vec<(Pubkey, u8)>,
+) -> Instruction {
+    let mut ix = commit_bundle(validator, commits);
+    ix.accounts[2].is_writable = true;
+    ix.accounts
+        .push(AccountMeta::new_readonly(protocol_config_pda(), false));
+    for (fee_payer, fee_payer_index) in fee_payers {
+        ix.accounts
+            .extend(commit_fee_payer_accounts(fee_payer, fee_payer_index));
+    }
+    ix
+}
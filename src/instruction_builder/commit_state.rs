@@ -8,22 +8,28 @@ use crate::discriminator::DlpDiscriminator;
 use crate::pda::{
     commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
-    program_config_from_program_id, validator_fees_vault_pda_from_validator,
+    ephemeral_balance_pda_from_payer, program_config_from_program_id, protocol_config_pda,
+    validator_bond_pda_from_validator, validator_fees_vault_pda_from_validator,
 };
 
 /// Builds a commit state instruction.
+/// The `commit_nonce` is the next commit nonce in the delegation metadata.
 /// See [crate::processor::process_commit_state] for docs.
 pub fn commit_state(
     validator: Pubkey,
     delegated_account: Pubkey,
     delegated_account_owner: Pubkey,
+    commit_nonce: u64,
     commit_args: CommitStateArgs,
 ) -> Instruction {
     let commit_args = to_vec(&commit_args).unwrap();
     let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
-    let commit_state_pda = commit_state_pda_from_delegated_account(&delegated_account);
-    let commit_record_pda = commit_record_pda_from_delegated_account(&delegated_account);
+    let commit_state_pda =
+        commit_state_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let commit_record_pda =
+        commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
     let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator);
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
     let delegation_metadata_pda =
         delegation_metadata_pda_from_delegated_account(&delegated_account);
     let program_config_pda = program_config_from_program_id(&delegated_account_owner);
@@ -39,7 +45,67 @@ pub fn commit_state(
             AccountMeta::new_readonly(validator_fees_vault_pda, false),
             AccountMeta::new_readonly(program_config_pda, false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(validator_bond_pda, false),
         ],
         data: [DlpDiscriminator::CommitState.to_vec(), commit_args].concat(),
     }
 }
+
+/// Builds a commit state instruction charging the commit fee to the ephemeral balance of the
+/// payer of the delegation, at the given index.
+/// See [crate::processor::process_commit_state] for docs.
+pub fn commit_state_with_fee(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    commit_nonce: u64,
+    commit_args: CommitStateArgs,
+    fee_payer: Pubkey,
+    fee_payer_index: u8,
+) -> Instruction {
+    let mut ix = commit_state(
+        validator,
+        delegated_account,
+        delegated_account_owner,
+        commit_nonce,
+        commit_args,
+    );
+    add_commit_fee_accounts(&mut ix, 6, fee_payer, fee_payer_index);
+    ix
+}
+
+/// Appends the accounts charging the commit fee to the ephemeral balance of the payer of the
+/// delegation, at the given index, to a commit instruction, making its validator fees vault
+/// writable.
+pub(crate) fn add_commit_fee_accounts(
+    ix: &mut Instruction,
+    validator_fees_vault_index: usize,
+    fee_payer: Pubkey,
+    fee_payer_index: u8,
+) {
+    ix.accounts[validator_fees_vault_index].is_writable = true;
+    ix.accounts
+        .push(AccountMeta::new_readonly(protocol_config_pda(), false));
+    ix.accounts
+        .extend(commit_fee_payer_accounts(fee_payer, fee_payer_index));
+}
+
+/// The ephemeral balance of the payer of the delegation, at the given index, with its
+/// delegation record and metadata, charged with the commit fee.
+pub(crate) fn commit_fee_payer_accounts(
+    fee_payer: Pubkey,
+    fee_payer_index: u8,
+) -> [AccountMeta; 3] {
+    let ephemeral_balance_pda = ephemeral_balance_pda_from_payer(&fee_payer, fee_payer_index);
+    [
+        AccountMeta::new(ephemeral_balance_pda, false),
+        AccountMeta::new(
+            delegation_record_pda_from_delegated_account(&ephemeral_balance_pda),
+            false,
+        ),
+        AccountMeta::new_readonly(
+            delegation_metadata_pda_from_delegated_account(&ephemeral_balance_pda),
+            false,
+        ),
+    ]
+}
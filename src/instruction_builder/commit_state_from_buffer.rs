@@ -5,26 +5,33 @@ use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
 
 use crate::args::CommitStateFromBufferArgs;
 use crate::discriminator::DlpDiscriminator;
+use crate::instruction_builder::add_commit_fee_accounts;
 use crate::pda::{
     commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
-    program_config_from_program_id, validator_fees_vault_pda_from_validator,
+    program_config_from_program_id, validator_bond_pda_from_validator,
+    validator_fees_vault_pda_from_validator,
 };
 
 /// Builds a commit state from buffer instruction.
+/// The `commit_nonce` is the next commit nonce in the delegation metadata.
 /// See [crate::processor::process_commit_state_from_buffer] for docs.
 pub fn commit_state_from_buffer(
     validator: Pubkey,
     delegated_account: Pubkey,
     delegated_account_owner: Pubkey,
     commit_state_buffer: Pubkey,
+    commit_nonce: u64,
     commit_args: CommitStateFromBufferArgs,
 ) -> Instruction {
     let commit_args = to_vec(&commit_args).unwrap();
     let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
-    let commit_state_pda = commit_state_pda_from_delegated_account(&delegated_account);
-    let commit_record_pda = commit_record_pda_from_delegated_account(&delegated_account);
+    let commit_state_pda =
+        commit_state_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let commit_record_pda =
+        commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
     let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator);
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
     let delegation_metadata_pda =
         delegation_metadata_pda_from_delegated_account(&delegated_account);
     let program_config_pda = program_config_from_program_id(&delegated_account_owner);
@@ -41,6 +48,7 @@ pub fn commit_state_from_buffer(
             AccountMeta::new_readonly(validator_fees_vault_pda, false),
             AccountMeta::new_readonly(program_config_pda, false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(validator_bond_pda, false),
         ],
         data: [
             DlpDiscriminator::CommitStateFromBuffer.to_vec(),
@@ -49,3 +57,29 @@ pub fn commit_state_from_buffer(
         .concat(),
     }
 }
+
+/// Builds a commit state from buffer instruction charging the commit fee to the ephemeral balance of the
+/// payer of the delegation, at the given index.
+/// See [crate::processor::process_commit_state_from_buffer] for docs.
+#[allow(clippy::too_many_arguments)]
+pub fn commit_state_from_buffer_with_fee(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    commit_state_buffer: Pubkey,
+    commit_nonce: u64,
+    commit_args: CommitStateFromBufferArgs,
+    fee_payer: Pubkey,
+    fee_payer_index: u8,
+) -> Instruction {
+    let mut ix = commit_state_from_buffer(
+        validator,
+        delegated_account,
+        delegated_account_owner,
+        commit_state_buffer,
+        commit_nonce,
+        commit_args,
+    );
+    add_commit_fee_accounts(&mut ix, 7, fee_payer, fee_payer_index);
+    ix
+}
@@ -0,0 +1,76 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::CommitStateHashArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::instruction_builder::add_commit_fee_accounts;
+use crate::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    program_config_from_program_id, validator_bond_pda_from_validator,
+    validator_fees_vault_pda_from_validator,
+};
+
+/// Builds a commit state hash instruction.
+/// The `commit_nonce` is the next commit nonce in the delegation metadata.
+/// See [crate::processor::process_commit_state_hash] for docs.
+pub fn commit_state_hash(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    commit_nonce: u64,
+    commit_args: CommitStateHashArgs,
+) -> Instruction {
+    let commit_args = to_vec(&commit_args).unwrap();
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
+    let commit_state_pda =
+        commit_state_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let commit_record_pda =
+        commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator);
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_account);
+    let program_config_pda = program_config_from_program_id(&delegated_account_owner);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(validator, true),
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new(commit_state_pda, false),
+            AccountMeta::new(commit_record_pda, false),
+            AccountMeta::new_readonly(delegation_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+            AccountMeta::new_readonly(validator_fees_vault_pda, false),
+            AccountMeta::new_readonly(program_config_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(validator_bond_pda, false),
+        ],
+        data: [DlpDiscriminator::CommitStateHash.to_vec(), commit_args].concat(),
+    }
+}
+
+/// Builds a commit state hash instruction charging the commit fee to the ephemeral balance of the
+/// payer of the delegation, at the given index.
+/// See [crate::processor::process_commit_state_hash] for docs.
+pub fn commit_state_hash_with_fee(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    commit_nonce: u64,
+    commit_args: CommitStateHashArgs,
+    fee_payer: Pubkey,
+    fee_payer_index: u8,
+) -> Instruction {
+    let mut ix = commit_state_hash(
+        validator,
+        delegated_account,
+        delegated_account_owner,
+        commit_nonce,
+        commit_args,
+    );
+    add_commit_fee_accounts(&mut ix, 6, fee_payer, fee_payer_index);
+    ix
+}
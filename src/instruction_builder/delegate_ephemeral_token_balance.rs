@@ -0,0 +1,50 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::DelegateEphemeralBalanceArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    delegate_buffer_pda_from_delegated_account_and_owner_program,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    ephemeral_token_balance_escrow_pda_from_balance, ephemeral_token_balance_pda_from_payer,
+};
+
+/// Delegate ephemeral token balance
+/// See [crate::processor::process_delegate_ephemeral_token_balance] for docs.
+pub fn delegate_ephemeral_token_balance(
+    payer: Pubkey,
+    pubkey: Pubkey,
+    mint: Pubkey,
+    args: DelegateEphemeralBalanceArgs,
+) -> Instruction {
+    let delegated_account = ephemeral_token_balance_pda_from_payer(&pubkey, &mint, args.index);
+    let escrow_pda = ephemeral_token_balance_escrow_pda_from_balance(&delegated_account);
+    let delegate_buffer_pda = delegate_buffer_pda_from_delegated_account_and_owner_program(
+        &delegated_account,
+        &crate::id(),
+    );
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_account);
+    let mut data = DlpDiscriminator::DelegateEphemeralTokenBalance.to_vec();
+    data.extend_from_slice(&to_vec(&args).unwrap());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(pubkey, true),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(delegated_account, false),
+            AccountMeta::new_readonly(escrow_pda, false),
+            AccountMeta::new(delegate_buffer_pda, false),
+            AccountMeta::new(delegation_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(crate::id(), false),
+        ],
+        data,
+    }
+}
@@ -0,0 +1,52 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::{DelegateArgs, DelegateManyArgs};
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    delegate_buffer_pda_from_delegated_account_and_owner_program,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+};
+
+/// Builds a delegate many instruction, delegating each account with its args
+/// See [crate::processor::process_delegate_many] for docs.
+pub fn delegate_many(
+    payer: Pubkey,
+    owner: Option<Pubkey>,
+    delegations: Vec<(Pubkey, DelegateArgs)>,
+) -> Instruction {
+    let owner = owner.unwrap_or(system_program::id());
+    let mut accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(owner, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    let mut args = DelegateManyArgs::default();
+    for (delegated_account, delegate_args) in delegations {
+        let delegate_buffer_pda = delegate_buffer_pda_from_delegated_account_and_owner_program(
+            &delegated_account,
+            &owner,
+        );
+        let delegation_record_pda =
+            delegation_record_pda_from_delegated_account(&delegated_account);
+        let delegation_metadata_pda =
+            delegation_metadata_pda_from_delegated_account(&delegated_account);
+        accounts.extend([
+            AccountMeta::new(delegated_account, true),
+            AccountMeta::new(delegate_buffer_pda, false),
+            AccountMeta::new(delegation_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+        ]);
+        args.delegations.push(delegate_args);
+    }
+    let mut data = DlpDiscriminator::DelegateMany.to_vec();
+    data.extend_from_slice(&to_vec(&args).unwrap());
+
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data,
+    }
+}
@@ -0,0 +1,29 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::DepositValidatorBondArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::validator_bond_pda_from_validator;
+
+/// Deposit lamports in the validator bond
+///
+/// See [crate::processor::process_deposit_validator_bond] for docs.
+pub fn deposit_validator_bond(validator: Pubkey, amount: u64) -> Instruction {
+    let args = DepositValidatorBondArgs { amount };
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(validator, true),
+            AccountMeta::new(validator_bond_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            DlpDiscriminator::DepositValidatorBond.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
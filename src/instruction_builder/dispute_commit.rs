@@ -0,0 +1,38 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey, system_program};
+
+use crate::args::DisputeCommitArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    commit_record_pda_from_delegated_account, delegation_metadata_pda_from_delegated_account,
+};
+
+/// Builds a dispute commit instruction.
+/// See [crate::processor::process_dispute_commit] for docs.
+pub fn dispute_commit(
+    challenger: Pubkey,
+    delegated_account: Pubkey,
+    commit_nonce: u64,
+) -> Instruction {
+    let args = DisputeCommitArgs { commit_nonce };
+    let commit_record_pda =
+        commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_account);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(challenger, true),
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new(commit_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            DlpDiscriminator::DisputeCommit.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
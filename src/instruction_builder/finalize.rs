@@ -2,22 +2,53 @@ use solana_program::instruction::Instruction;
 use solana_program::system_program;
 use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
 
+use crate::args::CommitAction;
+use crate::consts::TOKEN_PROGRAM_ID;
 use crate::discriminator::DlpDiscriminator;
 use crate::pda::{
-    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
-    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
-    validator_fees_vault_pda_from_validator,
+    commit_bundle_record_pda_from_delegated_account, commit_record_pda_from_delegated_account,
+    commit_state_pda_from_delegated_account, delegation_metadata_pda_from_delegated_account,
+    delegation_record_pda_from_delegated_account, ephemeral_token_balance_escrow_pda_from_balance,
+    program_config_from_program_id, validator_fees_vault_pda_from_validator,
 };
 
-/// Builds a finalize state instruction.
+/// Builds a finalize state instruction, for a commit paid by the validator.
+/// The `commit_nonce` is the next finalize nonce in the delegation metadata.
 /// See [crate::processor::process_finalize] for docs.
-pub fn finalize(validator: Pubkey, delegated_account: Pubkey) -> Instruction {
-    let commit_state_pda = commit_state_pda_from_delegated_account(&delegated_account);
-    let commit_record_pda = commit_record_pda_from_delegated_account(&delegated_account);
+pub fn finalize(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    commit_nonce: u64,
+) -> Instruction {
+    finalize_with_commit_payer(
+        validator,
+        delegated_account,
+        delegated_account_owner,
+        commit_nonce,
+        validator,
+    )
+}
+
+/// Builds a finalize state instruction, for a commit paid by the `commit_payer`, e.g. the
+/// relayer of a commit, see [crate::processor::process_commit_state_relayed].
+/// See [crate::processor::process_finalize] for docs.
+pub fn finalize_with_commit_payer(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    commit_nonce: u64,
+    commit_payer: Pubkey,
+) -> Instruction {
+    let commit_state_pda =
+        commit_state_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let commit_record_pda =
+        commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
     let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
     let delegation_metadata_pda =
         delegation_metadata_pda_from_delegated_account(&delegated_account);
     let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator);
+    let program_config_pda = program_config_from_program_id(&delegated_account_owner);
     Instruction {
         program_id: crate::id(),
         accounts: vec![
@@ -29,7 +60,72 @@ pub fn finalize(validator: Pubkey, delegated_account: Pubkey) -> Instruction {
             AccountMeta::new(delegation_metadata_pda, false),
             AccountMeta::new(validator_fees_vault_pda, false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(program_config_pda, false),
+            AccountMeta::new_readonly(delegated_account_owner, false),
+            AccountMeta::new(commit_payer, false),
         ],
         data: DlpDiscriminator::Finalize.to_vec(),
     }
 }
+
+/// Builds the remaining accounts of a finalize instruction invoking the given commit actions.
+/// See [crate::processor::process_finalize] for docs.
+pub fn finalize_commit_actions_accounts(actions: &[CommitAction]) -> Vec<AccountMeta> {
+    let mut accounts: Vec<AccountMeta> = vec![];
+    let action_accounts = actions.iter().flat_map(|action| {
+        action
+            .accounts
+            .iter()
+            .map(|account| (account.pubkey, account.is_writable))
+            .chain([(action.program_id, false)])
+    });
+    for (pubkey, is_writable) in action_accounts {
+        match accounts
+            .iter_mut()
+            .find(|account| account.pubkey.eq(&pubkey))
+        {
+            Some(account) => account.is_writable |= is_writable,
+            None => accounts.push(AccountMeta {
+                pubkey,
+                is_signer: false,
+                is_writable,
+            }),
+        }
+    }
+    accounts
+}
+
+/// Builds the remaining accounts of a finalize instruction finalizing alone a commit of an
+/// abandoned bundle, which precede any other remaining account. The bundle record is derived
+/// from the first delegated account of the bundle and the nonce of its commit.
+/// See [crate::processor::process_finalize] for docs.
+pub fn finalize_abandoned_commit_bundle_accounts(
+    first_delegated_account: Pubkey,
+    first_commit_nonce: u64,
+) -> Vec<AccountMeta> {
+    vec![AccountMeta::new_readonly(
+        commit_bundle_record_pda_from_delegated_account(
+            &first_delegated_account,
+            first_commit_nonce,
+        ),
+        false,
+    )]
+}
+
+/// Builds the remaining accounts of a finalize instruction settling the tokens spent by an
+/// ephemeral token balance, which precede the accounts of the commit actions, if any. The
+/// destination token account belongs to the payout destination of the validator fees vault.
+/// See [crate::processor::process_finalize] for docs.
+pub fn finalize_ephemeral_token_balance_accounts(
+    ephemeral_token_balance: Pubkey,
+    destination_token_account: Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(
+            ephemeral_token_balance_escrow_pda_from_balance(&ephemeral_token_balance),
+            false,
+        ),
+        AccountMeta::new(destination_token_account, false),
+        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+    ]
+}
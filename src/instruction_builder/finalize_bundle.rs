@@ -0,0 +1,67 @@
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    commit_bundle_record_pda_from_delegated_account, commit_record_pda_from_delegated_account,
+    commit_state_pda_from_delegated_account, delegation_metadata_pda_from_delegated_account,
+    delegation_record_pda_from_delegated_account, program_config_from_program_id,
+    validator_fees_vault_pda_from_validator,
+};
+
+/// Builds a finalize bundle instruction, for the delegated accounts, given with their owner and
+/// their next finalize nonce, in the order they were committed with
+/// [crate::instruction_builder::commit_bundle].
+/// See [crate::processor::process_finalize_bundle] for docs.
+pub fn finalize_bundle(
+    validator: Pubkey,
+    delegated_accounts: &[(Pubkey, Pubkey, u64)],
+) -> Instruction {
+    let (first_delegated_account, first_commit_nonce) = delegated_accounts
+        .first()
+        .map(|(account, _, nonce)| (*account, *nonce))
+        .unwrap_or_default();
+    let commit_bundle_record_pda = commit_bundle_record_pda_from_delegated_account(
+        &first_delegated_account,
+        first_commit_nonce,
+    );
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(validator, true),
+        AccountMeta::new(commit_bundle_record_pda, false),
+        AccountMeta::new(validator_fees_vault_pda, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for (delegated_account, delegated_account_owner, commit_nonce) in delegated_accounts {
+        accounts.extend([
+            AccountMeta::new(*delegated_account, false),
+            AccountMeta::new(
+                commit_state_pda_from_delegated_account(delegated_account, *commit_nonce),
+                false,
+            ),
+            AccountMeta::new(
+                commit_record_pda_from_delegated_account(delegated_account, *commit_nonce),
+                false,
+            ),
+            AccountMeta::new(
+                delegation_record_pda_from_delegated_account(delegated_account),
+                false,
+            ),
+            AccountMeta::new(
+                delegation_metadata_pda_from_delegated_account(delegated_account),
+                false,
+            ),
+            AccountMeta::new_readonly(
+                program_config_from_program_id(delegated_account_owner),
+                false,
+            ),
+            AccountMeta::new_readonly(*delegated_account_owner, false),
+        ]);
+    }
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: DlpDiscriminator::FinalizeBundle.to_vec(),
+    }
+}
@@ -0,0 +1,37 @@
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    program_config_from_program_id, protocol_config_pda, validator_bond_pda_from_validator,
+};
+
+/// Builds a flag stale delegation instruction.
+/// The `validator` is the authority of the delegation record.
+/// See [crate::processor::process_flag_stale_delegation] for docs.
+pub fn flag_stale_delegation(
+    flagger: Pubkey,
+    delegated_account: Pubkey,
+    delegated_account_owner: Pubkey,
+    validator: Pubkey,
+) -> Instruction {
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_account);
+    let program_config_pda = program_config_from_program_id(&delegated_account_owner);
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(flagger, true),
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new_readonly(delegation_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+            AccountMeta::new_readonly(program_config_pda, false),
+            AccountMeta::new(validator_bond_pda, false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
+        ],
+        data: DlpDiscriminator::FlagStaleDelegation.to_vec(),
+    }
+}
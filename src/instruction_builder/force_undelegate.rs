@@ -0,0 +1,67 @@
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    fees_vault_pda, protocol_config_pda, undelegate_buffer_pda_from_delegated_account,
+};
+
+/// Builds a force undelegate instruction.
+/// The `commit_nonce` is the next finalize nonce in the delegation metadata, and the
+/// `commit_payers` are the payers of the pending commits, in nonce order: the validators that
+/// committed them, unless they were relayed.
+/// See [crate::processor::process_force_undelegate] for docs.
+pub fn force_undelegate(
+    payer: Pubkey,
+    delegated_account: Pubkey,
+    owner_program: Pubkey,
+    rent_reimbursement: Pubkey,
+    commit_nonce: u64,
+    commit_payers: &[Pubkey],
+) -> Instruction {
+    let undelegate_buffer_pda = undelegate_buffer_pda_from_delegated_account(&delegated_account);
+    let commit_state_pda =
+        commit_state_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let commit_record_pda =
+        commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_account);
+    let fees_vault_pda = fees_vault_pda();
+    let mut accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new(delegated_account, false),
+        AccountMeta::new_readonly(owner_program, false),
+        AccountMeta::new(undelegate_buffer_pda, false),
+        AccountMeta::new(commit_state_pda, false),
+        AccountMeta::new(commit_record_pda, false),
+        AccountMeta::new(delegation_record_pda, false),
+        AccountMeta::new(delegation_metadata_pda, false),
+        AccountMeta::new(commit_payers.first().copied().unwrap_or(payer), false),
+        AccountMeta::new(rent_reimbursement, false),
+        AccountMeta::new(fees_vault_pda, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(protocol_config_pda(), false),
+    ];
+    for (nonce, commit_payer) in (commit_nonce..).zip(commit_payers).skip(1) {
+        accounts.extend([
+            AccountMeta::new(
+                commit_state_pda_from_delegated_account(&delegated_account, nonce),
+                false,
+            ),
+            AccountMeta::new(
+                commit_record_pda_from_delegated_account(&delegated_account, nonce),
+                false,
+            ),
+            AccountMeta::new(*commit_payer, false),
+        ]);
+    }
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: DlpDiscriminator::ForceUndelegate.to_vec(),
+    }
+}
@@ -0,0 +1,30 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::InitCommitBufferArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::commit_buffer_pda_from_validator_and_delegated_account;
+
+/// Builds an init commit buffer instruction.
+/// See [crate::processor::process_init_commit_buffer] for docs.
+pub fn init_commit_buffer(validator: Pubkey, delegated_account: Pubkey, size: u32) -> Instruction {
+    let args = InitCommitBufferArgs { size };
+    let commit_buffer_pda =
+        commit_buffer_pda_from_validator_and_delegated_account(&validator, &delegated_account);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(validator, true),
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new(commit_buffer_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            DlpDiscriminator::InitCommitBuffer.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
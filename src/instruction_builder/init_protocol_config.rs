@@ -0,0 +1,29 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::{bpf_loader_upgradeable, system_program};
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::InitProtocolConfigArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::protocol_config_pda;
+
+/// Initialize the protocol config PDA.
+/// See [crate::processor::process_init_protocol_config] for docs.
+pub fn init_protocol_config(authority: Pubkey, args: InitProtocolConfigArgs) -> Instruction {
+    let delegation_program_data =
+        Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::id()).0;
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new_readonly(delegation_program_data, false),
+            AccountMeta::new(protocol_config_pda(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            DlpDiscriminator::InitProtocolConfig.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
@@ -3,7 +3,7 @@ use solana_program::{bpf_loader_upgradeable, system_program};
 use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
 
 use crate::discriminator::DlpDiscriminator;
-use crate::pda::validator_fees_vault_pda_from_validator;
+use crate::pda::{protocol_config_pda, validator_fees_vault_pda_from_validator};
 
 /// Initialize a validator fees vault PDA.
 /// See [crate::processor::process_init_validator_fees_vault] for docs.
@@ -24,6 +24,7 @@ pub fn init_validator_fees_vault(
             AccountMeta::new(validator_identity, false),
             AccountMeta::new(validator_fees_vault_pda, false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
         ],
         data: DlpDiscriminator::InitValidatorFeesVault.to_vec(),
     }
@@ -1,30 +1,104 @@
+mod abandon_commit_bundle;
+mod accept_protocol_admin;
+mod close_commit_buffer;
 mod close_ephemeral_balance;
+mod close_ephemeral_token_balance;
+mod commit_and_finalize;
+mod commit_bundle;
 mod commit_state;
 
 mod close_validator_fees_vault;
+mod commit_state_diff;
 mod commit_state_from_buffer;
+mod commit_state_hash;
+mod commit_state_relayed;
 mod delegate;
 mod delegate_ephemeral_balance;
+mod delegate_ephemeral_token_balance;
+mod delegate_many;
+mod deposit_validator_bond;
+mod dispute_commit;
 mod finalize;
+mod finalize_bundle;
+mod finalize_from_buffer;
+mod finalize_with_data;
+mod flag_stale_delegation;
+mod force_undelegate;
+mod init_commit_buffer;
+mod init_protocol_config;
 mod init_protocol_fees_vault;
 mod init_validator_fees_vault;
+mod migrate_delegation_metadata;
+mod propose_protocol_admin;
 mod protocol_claim_fees;
+mod redelegate;
+mod request_undelegation;
+mod resolve_dispute;
+mod set_challenge_period_for_program;
+mod set_commit_fee_for_program;
+mod set_commit_staleness_for_program;
+mod set_commit_validation_for_program;
+mod set_min_validator_bond_for_program;
+mod set_validator_fees_vault_authority;
+mod slash_validator_bond;
 mod top_up_ephemeral_balance;
+mod top_up_ephemeral_token_balance;
+mod unbond_validator_bond;
 mod undelegate;
+mod update_protocol_config;
 mod validator_claim_fees;
 mod whitelist_validator_for_program;
+mod withdraw_validator_bond;
+mod write_commit_buffer;
 
+pub use abandon_commit_bundle::*;
+pub use accept_protocol_admin::*;
+pub use close_commit_buffer::*;
 pub use close_ephemeral_balance::*;
+pub use close_ephemeral_token_balance::*;
 pub use close_validator_fees_vault::*;
+pub use commit_and_finalize::*;
+pub use commit_bundle::*;
 pub use commit_state::*;
+pub use commit_state_diff::*;
 pub use commit_state_from_buffer::*;
+pub use commit_state_hash::*;
+pub use commit_state_relayed::*;
 pub use delegate::*;
 pub use delegate_ephemeral_balance::*;
+pub use delegate_ephemeral_token_balance::*;
+pub use delegate_many::*;
+pub use deposit_validator_bond::*;
+pub use dispute_commit::*;
 pub use finalize::*;
+pub use finalize_bundle::*;
+pub use finalize_from_buffer::*;
+pub use finalize_with_data::*;
+pub use flag_stale_delegation::*;
+pub use force_undelegate::*;
+pub use init_commit_buffer::*;
+pub use init_protocol_config::*;
 pub use init_protocol_fees_vault::*;
 pub use init_validator_fees_vault::*;
+pub use migrate_delegation_metadata::*;
+pub use propose_protocol_admin::*;
 pub use protocol_claim_fees::*;
+pub use redelegate::*;
+pub use request_undelegation::*;
+pub use resolve_dispute::*;
+pub use set_challenge_period_for_program::*;
+pub use set_commit_fee_for_program::*;
+pub use set_commit_staleness_for_program::*;
+pub use set_commit_validation_for_program::*;
+pub use set_min_validator_bond_for_program::*;
+pub use set_validator_fees_vault_authority::*;
+pub use slash_validator_bond::*;
 pub use top_up_ephemeral_balance::*;
+pub use top_up_ephemeral_token_balance::*;
+pub use unbond_validator_bond::*;
 pub use undelegate::*;
+pub use update_protocol_config::*;
 pub use validator_claim_fees::*;
 pub use whitelist_validator_for_program::*;
+pub use withdraw_validator_bond::*;
+pub use write_commit_buffer::*;
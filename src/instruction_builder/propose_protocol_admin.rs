@@ -0,0 +1,25 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::ProposeProtocolAdminArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::protocol_config_pda;
+
+/// Propose a new protocol admin.
+/// See [crate::processor::process_propose_protocol_admin] for docs.
+pub fn propose_protocol_admin(admin: Pubkey, new_admin: Pubkey) -> Instruction {
+    let args = ProposeProtocolAdminArgs { new_admin };
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(protocol_config_pda(), false),
+        ],
+        data: [
+            DlpDiscriminator::ProposeProtocolAdmin.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
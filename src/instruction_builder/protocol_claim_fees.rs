@@ -1,22 +1,31 @@
+use borsh::to_vec;
 use solana_program::instruction::Instruction;
 use solana_program::{bpf_loader_upgradeable, instruction::AccountMeta, pubkey::Pubkey};
 
+use crate::args::ProtocolClaimFeesArgs;
 use crate::discriminator::DlpDiscriminator;
-use crate::pda::fees_vault_pda;
+use crate::pda::{fees_vault_pda, protocol_config_pda};
 
-/// Claim the accrued fees from the protocol fees vault.
+/// Claim the accrued fees from the protocol fees vault to the destination.
 /// See [crate::processor::process_protocol_claim_fees] for docs.
-pub fn protocol_claim_fees(admin: Pubkey) -> Instruction {
+pub fn protocol_claim_fees(admin: Pubkey, destination: Pubkey, amount: Option<u64>) -> Instruction {
+    let args = ProtocolClaimFeesArgs { amount };
     let fees_vault_pda = fees_vault_pda();
     let delegation_program_data =
         Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::id()).0;
     Instruction {
         program_id: crate::id(),
         accounts: vec![
-            AccountMeta::new(admin, true),
+            AccountMeta::new_readonly(admin, true),
             AccountMeta::new(fees_vault_pda, false),
             AccountMeta::new_readonly(delegation_program_data, false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
+            AccountMeta::new(destination, false),
         ],
-        data: DlpDiscriminator::ProtocolClaimFees.to_vec(),
+        data: [
+            DlpDiscriminator::ProtocolClaimFees.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
     }
 }
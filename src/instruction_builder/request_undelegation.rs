@@ -0,0 +1,25 @@
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+};
+
+/// Builds a request undelegation instruction, to be invoked by the owner program.
+/// See [crate::processor::process_request_undelegation] for docs.
+pub fn request_undelegation(delegated_account: Pubkey, owner_program: Pubkey) -> Instruction {
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_account);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(delegated_account, true),
+            AccountMeta::new_readonly(owner_program, false),
+            AccountMeta::new_readonly(delegation_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+        ],
+        data: DlpDiscriminator::RequestUndelegation.to_vec(),
+    }
+}
@@ -0,0 +1,51 @@
+use borsh::to_vec;
+use solana_program::bpf_loader_upgradeable;
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::ResolveDisputeArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    commit_record_pda_from_delegated_account, delegation_metadata_pda_from_delegated_account,
+    protocol_config_pda,
+};
+
+/// Builds a resolve dispute instruction.
+/// See [crate::processor::process_resolve_dispute] for docs.
+pub fn resolve_dispute(
+    resolver: Pubkey,
+    delegated_account: Pubkey,
+    challenger: Pubkey,
+    validator: Pubkey,
+    commit_nonce: u64,
+    upheld: bool,
+) -> Instruction {
+    let args = ResolveDisputeArgs {
+        commit_nonce,
+        upheld,
+    };
+    let delegation_program_data =
+        Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::id()).0;
+    let commit_record_pda =
+        commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_account);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(resolver, true),
+            AccountMeta::new_readonly(delegation_program_data, false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new(commit_record_pda, false),
+            AccountMeta::new(delegation_metadata_pda, false),
+            AccountMeta::new(challenger, false),
+            AccountMeta::new(validator, false),
+        ],
+        data: [
+            DlpDiscriminator::ResolveDispute.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
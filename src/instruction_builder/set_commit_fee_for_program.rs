@@ -0,0 +1,43 @@
+use borsh::to_vec;
+use solana_program::bpf_loader_upgradeable;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::SetCommitFeeForProgramArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{program_config_from_program_id, protocol_config_pda};
+
+/// Set the lamports charged to the payer ephemeral balance for each commit to the accounts of a
+/// program
+///
+/// See [crate::processor::process_set_commit_fee_for_program] for docs.
+pub fn set_commit_fee_for_program(
+    authority: Pubkey,
+    program: Pubkey,
+    commit_fee: u64,
+) -> Instruction {
+    let args = SetCommitFeeForProgramArgs { commit_fee };
+    let program_data =
+        Pubkey::find_program_address(&[program.as_ref()], &bpf_loader_upgradeable::id()).0;
+    let delegation_program_data =
+        Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::id()).0;
+    let program_config_pda = program_config_from_program_id(&program);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new_readonly(program, false),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new_readonly(delegation_program_data, false),
+            AccountMeta::new(program_config_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
+        ],
+        data: [
+            DlpDiscriminator::SetCommitFeeForProgram.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
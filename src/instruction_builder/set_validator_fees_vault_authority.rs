@@ -0,0 +1,37 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::SetValidatorFeesVaultAuthorityArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::validator_fees_vault_pda_from_validator;
+
+/// Set the withdrawal authority and the payout destination of a validator fees vault.
+/// See [crate::processor::process_set_validator_fees_vault_authority] for docs.
+pub fn set_validator_fees_vault_authority(
+    authority: Pubkey,
+    validator_identity: Pubkey,
+    withdrawal_authority: Pubkey,
+    payout_destination: Pubkey,
+) -> Instruction {
+    let args = SetValidatorFeesVaultAuthorityArgs {
+        withdrawal_authority,
+        payout_destination,
+    };
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator_identity);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(authority, true),
+            AccountMeta::new_readonly(validator_identity, false),
+            AccountMeta::new(validator_fees_vault_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            DlpDiscriminator::SetValidatorFeesVaultAuthority.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
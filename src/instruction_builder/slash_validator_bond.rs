@@ -0,0 +1,59 @@
+use borsh::to_vec;
+use solana_program::bpf_loader_upgradeable;
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::SlashValidatorBondArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    commit_record_pda_from_delegated_account, delegation_metadata_pda_from_delegated_account,
+    protocol_config_pda, validator_bond_pda_from_validator,
+};
+
+/// Slash lamports from a validator bond to the receiver, e.g. the protocol fees vault,
+/// upholding the pending dispute of the commit of the delegated account at the given nonce
+///
+/// See [crate::processor::process_slash_validator_bond] for docs.
+pub fn slash_validator_bond(
+    admin: Pubkey,
+    validator: Pubkey,
+    receiver: Pubkey,
+    delegated_account: Pubkey,
+    challenger: Pubkey,
+    commit_nonce: u64,
+    amount: u64,
+) -> Instruction {
+    let args = SlashValidatorBondArgs {
+        amount,
+        commit_nonce,
+    };
+    let delegation_program_data =
+        Pubkey::find_program_address(&[crate::ID.as_ref()], &bpf_loader_upgradeable::id()).0;
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new_readonly(delegation_program_data, false),
+            AccountMeta::new_readonly(validator, false),
+            AccountMeta::new(validator_bond_pda, false),
+            AccountMeta::new(receiver, false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new(
+                commit_record_pda_from_delegated_account(&delegated_account, commit_nonce),
+                false,
+            ),
+            AccountMeta::new(
+                delegation_metadata_pda_from_delegated_account(&delegated_account),
+                false,
+            ),
+            AccountMeta::new(challenger, false),
+        ],
+        data: [
+            DlpDiscriminator::SlashValidatorBond.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
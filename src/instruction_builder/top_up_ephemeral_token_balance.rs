@@ -0,0 +1,45 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::TopUpEphemeralBalanceArgs;
+use crate::consts::TOKEN_PROGRAM_ID;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::{
+    ephemeral_token_balance_escrow_pda_from_balance, ephemeral_token_balance_pda_from_payer,
+};
+
+/// Builds a top-up ephemeral token balance instruction, transferring the tokens from the
+/// source token account owned by the payer.
+/// See [crate::processor::process_top_up_ephemeral_token_balance] for docs.
+pub fn top_up_ephemeral_token_balance(
+    payer: Pubkey,
+    pubkey: Pubkey,
+    mint: Pubkey,
+    source_token_account: Pubkey,
+    amount: u64,
+    index: u8,
+) -> Instruction {
+    let args = TopUpEphemeralBalanceArgs { amount, index };
+    let ephemeral_token_balance_pda = ephemeral_token_balance_pda_from_payer(&pubkey, &mint, index);
+    let escrow_pda = ephemeral_token_balance_escrow_pda_from_balance(&ephemeral_token_balance_pda);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(pubkey, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(ephemeral_token_balance_pda, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(source_token_account, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            DlpDiscriminator::TopUpEphemeralTokenBalance.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
@@ -0,0 +1,27 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::UnbondValidatorBondArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::validator_bond_pda_from_validator;
+
+/// Request to unbond lamports from the validator bond
+///
+/// See [crate::processor::process_unbond_validator_bond] for docs.
+pub fn unbond_validator_bond(validator: Pubkey, amount: u64) -> Instruction {
+    let args = UnbondValidatorBondArgs { amount };
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(validator, true),
+            AccountMeta::new(validator_bond_pda, false),
+        ],
+        data: [
+            DlpDiscriminator::UnbondValidatorBond.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
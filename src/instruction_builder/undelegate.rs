@@ -6,11 +6,12 @@ use crate::discriminator::DlpDiscriminator;
 use crate::pda::{
     commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
-    fees_vault_pda, undelegate_buffer_pda_from_delegated_account,
+    fees_vault_pda, protocol_config_pda, undelegate_buffer_pda_from_delegated_account,
     validator_fees_vault_pda_from_validator,
 };
 
 /// Builds an undelegate instruction.
+/// The `commit_nonce` is the next finalize nonce in the delegation metadata.
 /// See [crate::processor::process_undelegate] for docs.
 #[allow(clippy::too_many_arguments)]
 pub fn undelegate(
@@ -18,10 +19,13 @@ pub fn undelegate(
     delegated_account: Pubkey,
     owner_program: Pubkey,
     rent_reimbursement: Pubkey,
+    commit_nonce: u64,
 ) -> Instruction {
     let undelegate_buffer_pda = undelegate_buffer_pda_from_delegated_account(&delegated_account);
-    let commit_state_pda = commit_state_pda_from_delegated_account(&delegated_account);
-    let commit_record_pda = commit_record_pda_from_delegated_account(&delegated_account);
+    let commit_state_pda =
+        commit_state_pda_from_delegated_account(&delegated_account, commit_nonce);
+    let commit_record_pda =
+        commit_record_pda_from_delegated_account(&delegated_account, commit_nonce);
     let delegation_record_pda = delegation_record_pda_from_delegated_account(&delegated_account);
     let delegation_metadata_pda =
         delegation_metadata_pda_from_delegated_account(&delegated_account);
@@ -42,6 +46,7 @@ pub fn undelegate(
             AccountMeta::new(fees_vault_pda, false),
             AccountMeta::new(validator_fees_vault_pda, false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
         ],
         data: DlpDiscriminator::Undelegate.to_vec(),
     }
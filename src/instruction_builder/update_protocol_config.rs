@@ -0,0 +1,24 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::UpdateProtocolConfigArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::protocol_config_pda;
+
+/// Update the protocol config PDA.
+/// See [crate::processor::process_update_protocol_config] for docs.
+pub fn update_protocol_config(admin: Pubkey, args: UpdateProtocolConfigArgs) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(protocol_config_pda(), false),
+        ],
+        data: [
+            DlpDiscriminator::UpdateProtocolConfig.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
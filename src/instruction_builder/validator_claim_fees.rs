@@ -4,20 +4,35 @@ use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
 
 use crate::args::ValidatorClaimFeesArgs;
 use crate::discriminator::DlpDiscriminator;
-use crate::pda::{fees_vault_pda, validator_fees_vault_pda_from_validator};
+use crate::pda::{fees_vault_pda, protocol_config_pda, validator_fees_vault_pda_from_validator};
 
-/// Claim the accrued fees from the fees vault.
+/// Claim the accrued fees from the fees vault, for a vault withdrawn by the validator identity.
 /// See [crate::processor::process_validator_claim_fees] for docs.
 pub fn validator_claim_fees(validator: Pubkey, amount: Option<u64>) -> Instruction {
+    validator_claim_fees_with_authority(validator, validator, validator, amount)
+}
+
+/// Claim the accrued fees from the fees vault, with the withdrawal authority and payout
+/// destination of the vault.
+/// See [crate::processor::process_validator_claim_fees] for docs.
+pub fn validator_claim_fees_with_authority(
+    validator: Pubkey,
+    withdrawal_authority: Pubkey,
+    payout_destination: Pubkey,
+    amount: Option<u64>,
+) -> Instruction {
     let args = ValidatorClaimFeesArgs { amount };
     let fees_vault_pda = fees_vault_pda();
     let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator);
     Instruction {
         program_id: crate::id(),
         accounts: vec![
-            AccountMeta::new(validator, true),
+            AccountMeta::new_readonly(validator, false),
             AccountMeta::new(fees_vault_pda, false),
             AccountMeta::new(validator_fees_vault_pda, false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
+            AccountMeta::new_readonly(withdrawal_authority, true),
+            AccountMeta::new(payout_destination, false),
         ],
         data: [
             DlpDiscriminator::ValidatorClaimFees.to_vec(),
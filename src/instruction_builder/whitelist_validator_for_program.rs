@@ -6,7 +6,7 @@ use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
 
 use crate::args::WhitelistValidatorForProgramArgs;
 use crate::discriminator::DlpDiscriminator;
-use crate::pda::program_config_from_program_id;
+use crate::pda::{program_config_from_program_id, protocol_config_pda};
 
 /// Whitelist validator for program
 ///
@@ -33,6 +33,7 @@ pub fn whitelist_validator_for_program(
             AccountMeta::new_readonly(delegation_program_data, false),
             AccountMeta::new(program_config_pda, false),
             AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(protocol_config_pda(), false),
         ],
         data: [
             DlpDiscriminator::WhitelistValidatorForProgram.to_vec(),
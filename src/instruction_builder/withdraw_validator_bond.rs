@@ -0,0 +1,20 @@
+use solana_program::instruction::Instruction;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::validator_bond_pda_from_validator;
+
+/// Withdraw the unbonded lamports from the validator bond
+///
+/// See [crate::processor::process_withdraw_validator_bond] for docs.
+pub fn withdraw_validator_bond(validator: Pubkey) -> Instruction {
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(validator, true),
+            AccountMeta::new(validator_bond_pda, false),
+        ],
+        data: DlpDiscriminator::WithdrawValidatorBond.to_vec(),
+    }
+}
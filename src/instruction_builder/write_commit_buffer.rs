@@ -0,0 +1,35 @@
+use borsh::to_vec;
+use solana_program::instruction::Instruction;
+use solana_program::system_program;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+use crate::args::WriteCommitBufferArgs;
+use crate::discriminator::DlpDiscriminator;
+use crate::pda::commit_buffer_pda_from_validator_and_delegated_account;
+
+/// Builds a write commit buffer instruction, writing the data at the offset.
+/// See [crate::processor::process_write_commit_buffer] for docs.
+pub fn write_commit_buffer(
+    validator: Pubkey,
+    delegated_account: Pubkey,
+    offset: u32,
+    data: Vec<u8>,
+) -> Instruction {
+    let args = WriteCommitBufferArgs { offset, data };
+    let commit_buffer_pda =
+        commit_buffer_pda_from_validator_and_delegated_account(&validator, &delegated_account);
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(validator, true),
+            AccountMeta::new_readonly(delegated_account, false),
+            AccountMeta::new(commit_buffer_pda, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: [
+            DlpDiscriminator::WriteCommitBuffer.to_vec(),
+            to_vec(&args).unwrap(),
+        ]
+        .concat(),
+    }
+}
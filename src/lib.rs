@@ -9,6 +9,7 @@ pub mod args;
 pub mod consts;
 mod discriminator;
 pub mod error;
+pub mod event;
 pub mod instruction_builder;
 pub mod pda;
 mod processor;
@@ -93,6 +94,117 @@ pub fn process_instruction(
         discriminator::DlpDiscriminator::CloseValidatorFeesVault => {
             processor::process_close_validator_fees_vault(program_id, accounts, data)?
         }
+        discriminator::DlpDiscriminator::ForceUndelegate => {
+            processor::process_force_undelegate(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::RequestUndelegation => {
+            processor::process_request_undelegation(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::Redelegate => {
+            processor::process_redelegate(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::DelegateMany => {
+            processor::process_delegate_many(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::CommitStateDiff => {
+            processor::process_commit_state_diff(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::CommitBundle => {
+            processor::process_commit_bundle(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::FinalizeBundle => {
+            processor::process_finalize_bundle(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::CommitAndFinalize => {
+            processor::process_commit_and_finalize(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::CommitStateHash => {
+            processor::process_commit_state_hash(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::FinalizeWithData => {
+            processor::process_finalize_with_data(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::FinalizeFromBuffer => {
+            processor::process_finalize_from_buffer(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::SetChallengePeriodForProgram => {
+            processor::process_set_challenge_period_for_program(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::DisputeCommit => {
+            processor::process_dispute_commit(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::SetMinValidatorBondForProgram => {
+            processor::process_set_min_validator_bond_for_program(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::DepositValidatorBond => {
+            processor::process_deposit_validator_bond(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::UnbondValidatorBond => {
+            processor::process_unbond_validator_bond(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::WithdrawValidatorBond => {
+            processor::process_withdraw_validator_bond(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::SlashValidatorBond => {
+            processor::process_slash_validator_bond(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::CommitStateRelayed => {
+            processor::process_commit_state_relayed(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::InitCommitBuffer => {
+            processor::process_init_commit_buffer(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::WriteCommitBuffer => {
+            processor::process_write_commit_buffer(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::CloseCommitBuffer => {
+            processor::process_close_commit_buffer(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::SetCommitValidationForProgram => {
+            processor::process_set_commit_validation_for_program(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::SetCommitStalenessForProgram => {
+            processor::process_set_commit_staleness_for_program(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::FlagStaleDelegation => {
+            processor::process_flag_stale_delegation(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::InitProtocolConfig => {
+            processor::process_init_protocol_config(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::UpdateProtocolConfig => {
+            processor::process_update_protocol_config(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::ProposeProtocolAdmin => {
+            processor::process_propose_protocol_admin(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::AcceptProtocolAdmin => {
+            processor::process_accept_protocol_admin(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::SetValidatorFeesVaultAuthority => {
+            processor::process_set_validator_fees_vault_authority(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::SetCommitFeeForProgram => {
+            processor::process_set_commit_fee_for_program(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::TopUpEphemeralTokenBalance => {
+            processor::process_top_up_ephemeral_token_balance(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::DelegateEphemeralTokenBalance => {
+            processor::process_delegate_ephemeral_token_balance(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::CloseEphemeralTokenBalance => {
+            processor::process_close_ephemeral_token_balance(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::MigrateDelegationMetadata => {
+            processor::process_migrate_delegation_metadata(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::ResolveDispute => {
+            processor::process_resolve_dispute(program_id, accounts, data)?
+        }
+        discriminator::DlpDiscriminator::AbandonCommitBundle => {
+            processor::process_abandon_commit_bundle(program_id, accounts, data)?
+        }
     }
     Ok(())
 }
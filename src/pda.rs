@@ -16,15 +16,52 @@ macro_rules! delegation_metadata_seeds_from_delegated_account {
 
 #[macro_export]
 macro_rules! commit_state_seeds_from_delegated_account {
-    ($delegated_account: expr) => {
-        &[b"state-diff", &$delegated_account.as_ref()]
+    ($delegated_account: expr, $nonce: expr) => {
+        &[
+            b"state-diff",
+            &$delegated_account.as_ref(),
+            $crate::pda::commit_nonce_seed(&$nonce.to_le_bytes()),
+        ]
     };
 }
 
 #[macro_export]
 macro_rules! commit_record_seeds_from_delegated_account {
+    ($delegated_account: expr, $nonce: expr) => {
+        &[
+            b"commit-state-record",
+            &$delegated_account.as_ref(),
+            $crate::pda::commit_nonce_seed(&$nonce.to_le_bytes()),
+        ]
+    };
+}
+
+#[macro_export]
+macro_rules! commit_bundle_record_seeds_from_delegated_account {
+    ($delegated_account: expr, $nonce: expr) => {
+        &[
+            b"commit-bundle-record",
+            &$delegated_account.as_ref(),
+            &$nonce.to_le_bytes(),
+        ]
+    };
+}
+
+#[macro_export]
+macro_rules! commit_buffer_seeds_from_validator_and_delegated_account {
+    ($validator: expr, $delegated_account: expr) => {
+        &[
+            b"commit-buffer",
+            &$validator.as_ref(),
+            &$delegated_account.as_ref(),
+        ]
+    };
+}
+
+#[macro_export]
+macro_rules! commit_action_signer_seeds_from_delegated_account {
     ($delegated_account: expr) => {
-        &[b"commit-state-record", &$delegated_account.as_ref()]
+        &[b"commit-action-signer", &$delegated_account.as_ref()]
     };
 }
 
@@ -49,6 +86,13 @@ macro_rules! fees_vault_seeds {
     };
 }
 
+#[macro_export]
+macro_rules! protocol_config_seeds {
+    () => {
+        &[b"protocol-config"]
+    };
+}
+
 #[macro_export]
 macro_rules! validator_fees_vault_seeds_from_validator {
     ($validator: expr) => {
@@ -56,6 +100,13 @@ macro_rules! validator_fees_vault_seeds_from_validator {
     };
 }
 
+#[macro_export]
+macro_rules! validator_bond_seeds_from_validator {
+    ($validator: expr) => {
+        &[b"v-bond", &$validator.as_ref()]
+    };
+}
+
 #[macro_export]
 macro_rules! program_config_seeds_from_program_id {
     ($program_id: expr) => {
@@ -70,6 +121,25 @@ macro_rules! ephemeral_balance_seeds_from_payer {
     };
 }
 
+#[macro_export]
+macro_rules! ephemeral_token_balance_seeds_from_payer {
+    ($payer: expr, $mint: expr, $index: expr) => {
+        &[
+            b"token-balance",
+            &$payer.as_ref(),
+            &$mint.as_ref(),
+            &[$index],
+        ]
+    };
+}
+
+#[macro_export]
+macro_rules! ephemeral_token_balance_escrow_seeds_from_balance {
+    ($ephemeral_token_balance: expr) => {
+        &[b"token-escrow", &$ephemeral_token_balance.as_ref()]
+    };
+}
+
 pub fn delegation_record_pda_from_delegated_account(delegated_account: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(
         delegation_record_seeds_from_delegated_account!(delegated_account),
@@ -86,17 +156,58 @@ pub fn delegation_metadata_pda_from_delegated_account(delegated_account: &Pubkey
     .0
 }
 
-pub fn commit_state_pda_from_delegated_account(delegated_account: &Pubkey) -> Pubkey {
+/// The nonce seed of the commit state and commit record PDAs. It is empty for the first commit of
+/// a delegation, whose PDAs are then those derived from the seeds used before commits were
+/// queued, so that a commit pending since then is the first commit of the queue
+pub fn commit_nonce_seed(nonce_bytes: &[u8; 8]) -> &[u8] {
+    if u64::from_le_bytes(*nonce_bytes) == 0 {
+        &[]
+    } else {
+        nonce_bytes
+    }
+}
+
+pub fn commit_state_pda_from_delegated_account(delegated_account: &Pubkey, nonce: u64) -> Pubkey {
     Pubkey::find_program_address(
-        commit_state_seeds_from_delegated_account!(delegated_account),
+        commit_state_seeds_from_delegated_account!(delegated_account, nonce),
         &crate::id(),
     )
     .0
 }
 
-pub fn commit_record_pda_from_delegated_account(delegated_account: &Pubkey) -> Pubkey {
+pub fn commit_record_pda_from_delegated_account(delegated_account: &Pubkey, nonce: u64) -> Pubkey {
     Pubkey::find_program_address(
-        commit_record_seeds_from_delegated_account!(delegated_account),
+        commit_record_seeds_from_delegated_account!(delegated_account, nonce),
+        &crate::id(),
+    )
+    .0
+}
+
+pub fn commit_bundle_record_pda_from_delegated_account(
+    delegated_account: &Pubkey,
+    nonce: u64,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        commit_bundle_record_seeds_from_delegated_account!(delegated_account, nonce),
+        &crate::id(),
+    )
+    .0
+}
+
+pub fn commit_buffer_pda_from_validator_and_delegated_account(
+    validator: &Pubkey,
+    delegated_account: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        commit_buffer_seeds_from_validator_and_delegated_account!(validator, delegated_account),
+        &crate::id(),
+    )
+    .0
+}
+
+pub fn commit_action_signer_pda_from_delegated_account(delegated_account: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        commit_action_signer_seeds_from_delegated_account!(delegated_account),
         &crate::id(),
     )
     .0
@@ -125,6 +236,10 @@ pub fn fees_vault_pda() -> Pubkey {
     Pubkey::find_program_address(fees_vault_seeds!(), &crate::id()).0
 }
 
+pub fn protocol_config_pda() -> Pubkey {
+    Pubkey::find_program_address(protocol_config_seeds!(), &crate::id()).0
+}
+
 pub fn validator_fees_vault_pda_from_validator(validator: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(
         validator_fees_vault_seeds_from_validator!(validator),
@@ -133,6 +248,14 @@ pub fn validator_fees_vault_pda_from_validator(validator: &Pubkey) -> Pubkey {
     .0
 }
 
+pub fn validator_bond_pda_from_validator(validator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        validator_bond_seeds_from_validator!(validator),
+        &crate::id(),
+    )
+    .0
+}
+
 pub fn program_config_from_program_id(program_id: &Pubkey) -> Pubkey {
     Pubkey::find_program_address(
         program_config_seeds_from_program_id!(program_id),
@@ -148,3 +271,19 @@ pub fn ephemeral_balance_pda_from_payer(payer: &Pubkey, index: u8) -> Pubkey {
     )
     .0
 }
+
+pub fn ephemeral_token_balance_pda_from_payer(payer: &Pubkey, mint: &Pubkey, index: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        ephemeral_token_balance_seeds_from_payer!(payer, mint, index),
+        &crate::id(),
+    )
+    .0
+}
+
+pub fn ephemeral_token_balance_escrow_pda_from_balance(ephemeral_token_balance: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        ephemeral_token_balance_escrow_seeds_from_balance!(ephemeral_token_balance),
+        &crate::id(),
+    )
+    .0
+}
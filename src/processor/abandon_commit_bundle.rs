@@ -0,0 +1,86 @@
+use crate::commit_bundle_record_seeds_from_delegated_account;
+use crate::error::DlpError;
+use crate::processor::utils::loaders::{load_owned_pda, load_pda, load_signer};
+use crate::processor::utils::pda::close_pda;
+use crate::state::CommitBundleRecord;
+use solana_program::clock::Clock;
+use solana_program::program_error::ProgramError;
+use solana_program::sysvar::Sysvar;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+/// Abandon a commit bundle which cannot or will not be finalized as a whole, e.g. because one
+/// of its commits was discarded by a force undelegation, or disputed. Its commits can then be
+/// finalized alone with [crate::processor::process_finalize], or discarded with
+/// [crate::processor::process_force_undelegate]
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the account abandoning the bundle
+/// 1: `[writable]` the commit bundle record
+/// 2: `[writable]` the validator that committed the bundle
+///
+/// Requirements:
+///
+/// - commit bundle record is initialized
+/// - validator is the identity mentioned in the commit bundle record
+/// - the bundle is abandoned by its validator, or by anyone once it has expired, see
+///   [crate::consts::COMMIT_BUNDLE_FINALIZE_PERIOD_SLOTS]
+///
+/// Steps:
+///
+/// 1. Close the commit bundle record, refunding the validator
+pub fn process_abandon_commit_bundle(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [signer, commit_bundle_record_account, validator] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(signer, "signer")?;
+    load_owned_pda(
+        commit_bundle_record_account,
+        &crate::id(),
+        "commit bundle record",
+    )?;
+
+    // Load commit bundle record
+    let commit_bundle_record_data = commit_bundle_record_account.try_borrow_data()?;
+    let commit_bundle_record =
+        CommitBundleRecord::try_from_bytes_with_discriminator(&commit_bundle_record_data)?;
+    load_pda(
+        commit_bundle_record_account,
+        commit_bundle_record_seeds_from_delegated_account!(
+            commit_bundle_record.account,
+            commit_bundle_record.nonce
+        ),
+        &crate::id(),
+        true,
+        "commit bundle record",
+    )?;
+
+    // Check that the validator is the one refunded for the bundle record
+    if !commit_bundle_record.identity.eq(validator.key) {
+        msg!(
+            "Expected validator to be {}, but got {}",
+            commit_bundle_record.identity,
+            validator.key
+        );
+        return Err(DlpError::InvalidReimbursementAccount.into());
+    }
+
+    // Anyone can abandon the bundle once it expired, only its validator before
+    let current_slot = Clock::get()?.slot;
+    if !signer.key.eq(validator.key) && current_slot < commit_bundle_record.expiry_slot {
+        msg!(
+            "Commit bundle can be abandoned by anyone from slot {}, current slot is {}",
+            commit_bundle_record.expiry_slot,
+            current_slot
+        );
+        return Err(DlpError::CommitBundleNotExpired.into());
+    }
+    drop(commit_bundle_record_data);
+
+    close_pda(commit_bundle_record_account, validator)
+}
@@ -0,0 +1,65 @@
+use crate::error::DlpError::Unauthorized;
+use crate::processor::utils::loaders::{load_initialized_pda, load_signer};
+use crate::protocol_config_seeds;
+use crate::state::ProtocolConfig;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Accept the protocol admin rotation proposed by the current admin
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the pending admin of the protocol config
+/// 1: `[writable]` the protocol config PDA
+///
+/// Requirements:
+///
+/// - protocol config is initialized
+/// - a rotation is pending, see [crate::processor::process_propose_protocol_admin]
+/// - pending admin is the pending admin stored in the protocol config
+///
+/// Steps:
+///
+/// 1. Replace the admin with the pending admin, and clear the pending admin
+pub fn process_accept_protocol_admin(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    // Load Accounts
+    let [pending_admin, protocol_config_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(pending_admin, "pending admin")?;
+    load_initialized_pda(
+        protocol_config_account,
+        protocol_config_seeds!(),
+        &crate::id(),
+        true,
+        "protocol config",
+    )?;
+
+    let mut protocol_config_data = protocol_config_account.try_borrow_mut_data()?;
+    let mut protocol_config =
+        ProtocolConfig::try_from_bytes_with_discriminator(&protocol_config_data)?;
+
+    // Check if the pending admin is the proposed one
+    if protocol_config.pending_admin.eq(&Pubkey::default())
+        || !pending_admin.key.eq(&protocol_config.pending_admin)
+    {
+        msg!(
+            "Expected pending admin pubkey: {} but got {}",
+            protocol_config.pending_admin,
+            pending_admin.key
+        );
+        return Err(Unauthorized.into());
+    }
+
+    protocol_config.admin = protocol_config.pending_admin;
+    protocol_config.pending_admin = Pubkey::default();
+    protocol_config.to_bytes_with_discriminator(&mut protocol_config_data.as_mut())?;
+
+    Ok(())
+}
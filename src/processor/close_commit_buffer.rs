@@ -0,0 +1,37 @@
+use crate::processor::utils::loaders::{load_initialized_commit_buffer, load_signer};
+use crate::processor::utils::pda::close_pda;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Close a commit buffer, once its content was committed or is no longer needed
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator owning the buffer
+/// 1: `[]`         the delegated account
+/// 2: `[writable]` the commit buffer PDA
+///
+/// Requirements:
+///
+/// - commit buffer is initialized and derived from the validator and the delegated account
+///
+/// Steps:
+///
+/// 1. Close the commit buffer, refunding the rent to the validator
+pub fn process_close_commit_buffer(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    // Load Accounts
+    let [validator, delegated_account, commit_buffer_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(validator, "validator")?;
+    load_initialized_commit_buffer(validator, delegated_account, commit_buffer_account, true)?;
+
+    close_pda(commit_buffer_account, validator)?;
+
+    Ok(())
+}
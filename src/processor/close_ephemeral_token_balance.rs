@@ -0,0 +1,117 @@
+use crate::consts::TOKEN_PROGRAM_ID;
+use crate::processor::utils::loaders::{
+    load_owned_pda, load_pda, load_program, load_signer, load_uninitialized_pda,
+};
+use crate::processor::utils::pda::close_pda;
+use crate::processor::utils::token::{close_token_account, load_token_account, transfer_tokens};
+use crate::{
+    delegation_record_seeds_from_delegated_account,
+    ephemeral_token_balance_escrow_seeds_from_balance, ephemeral_token_balance_seeds_from_payer,
+};
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Process the closing of an ephemeral token balance account
+///
+/// Accounts:
+///
+/// 0: `[signer]` payer to pay for the transaction and receive the refund
+/// 1: `[]` mint of the tokens
+/// 2: `[writable]` ephemeral token balance account we are closing
+/// 3: `[writable]` token escrow of the ephemeral token balance
+/// 4: `[writable]` destination token account receiving the escrowed tokens
+/// 5: `[]` the delegation record of the ephemeral token balance
+/// 6: `[]` the token program
+///
+/// Requirements:
+///
+/// - ephemeral token balance account and its token escrow are initialized
+/// - ephemeral token balance account is not delegated
+///
+/// Steps:
+///
+/// 1. Transfers the escrowed tokens to the destination token account
+/// 2. Closes the token escrow and the ephemeral token balance account, and refunds the payer
+///    with their rent
+pub fn process_close_ephemeral_token_balance(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let index = *data.first().ok_or(ProgramError::InvalidInstructionData)?;
+
+    // Load Accounts
+    let [payer, mint, ephemeral_token_balance_account, escrow_account, destination_token_account, delegation_record, token_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(payer, "payer")?;
+    load_program(token_program, TOKEN_PROGRAM_ID, "token program")?;
+
+    let ephemeral_token_balance_seeds: &[&[u8]] =
+        ephemeral_token_balance_seeds_from_payer!(payer.key, mint.key, index);
+    let ephemeral_token_balance_bump = load_pda(
+        ephemeral_token_balance_account,
+        ephemeral_token_balance_seeds,
+        &crate::id(),
+        true,
+        "ephemeral token balance",
+    )?;
+    load_owned_pda(
+        ephemeral_token_balance_account,
+        &crate::id(),
+        "ephemeral token balance",
+    )?;
+    load_pda(
+        escrow_account,
+        ephemeral_token_balance_escrow_seeds_from_balance!(ephemeral_token_balance_account.key),
+        &crate::id(),
+        true,
+        "token escrow",
+    )?;
+
+    // A delegated ephemeral token balance must be undelegated first
+    load_uninitialized_pda(
+        delegation_record,
+        delegation_record_seeds_from_delegated_account!(ephemeral_token_balance_account.key),
+        &crate::id(),
+        false,
+        "delegation record",
+    )?;
+
+    let amount = load_token_account(
+        escrow_account,
+        mint.key,
+        Some(ephemeral_token_balance_account.key),
+        "token escrow",
+    )?;
+
+    let ephemeral_token_balance_bump_slice: &[u8] = &[ephemeral_token_balance_bump];
+    let ephemeral_token_balance_signer_seeds = [
+        ephemeral_token_balance_seeds,
+        &[ephemeral_token_balance_bump_slice],
+    ]
+    .concat();
+    if amount > 0 {
+        transfer_tokens(
+            escrow_account,
+            destination_token_account,
+            ephemeral_token_balance_account,
+            amount,
+            token_program,
+            &[&ephemeral_token_balance_signer_seeds],
+        )?;
+    }
+    close_token_account(
+        escrow_account,
+        payer,
+        ephemeral_token_balance_account,
+        token_program,
+        &[&ephemeral_token_balance_signer_seeds],
+    )?;
+    close_pda(ephemeral_token_balance_account, payer)?;
+
+    Ok(())
+}
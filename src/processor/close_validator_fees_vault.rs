@@ -1,11 +1,8 @@
-use solana_program::msg;
 use solana_program::program_error::ProgramError;
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
 
-use crate::error::DlpError::Unauthorized;
-use crate::processor::utils::loaders::{
-    load_initialized_pda, load_program_upgrade_authority, load_signer,
-};
+use crate::processor::utils::authority::validate_protocol_admin;
+use crate::processor::utils::loaders::{load_initialized_pda, load_signer};
 use crate::processor::utils::pda::close_pda;
 use crate::validator_fees_vault_seeds_from_validator;
 
@@ -15,13 +12,16 @@ use crate::validator_fees_vault_seeds_from_validator;
 ///
 /// 0; `[signer]` payer
 /// 1; `[signer]` admin that controls the vault
-/// 2; `[]`       validator_identity
-/// 3; `[]`       validator_fees_vault_pda
+/// 2; `[]`       delegation_program_data
+/// 3; `[]`       validator_identity
+/// 4; `[]`       validator_fees_vault_pda
+/// 5; `[]`       protocol_config_pda
 ///
 /// Requirements:
 ///
 /// - validator admin need to be signer since the existence of the validator fees vault
 ///   is used as proof later that the validator is whitelisted
+/// - validator admin is the protocol admin
 /// - validator fees vault is closed
 ///
 /// 1. Close the validator fees vault PDA
@@ -31,7 +31,7 @@ pub fn process_close_validator_fees_vault(
     _data: &[u8],
 ) -> ProgramResult {
     // Load Accounts
-    let [payer, admin, delegation_program_data, validator_identity, validator_fees_vault] =
+    let [payer, admin, delegation_program_data, validator_identity, validator_fees_vault, protocol_config_account] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -42,16 +42,7 @@ pub fn process_close_validator_fees_vault(
     load_signer(admin, "admin")?;
 
     // Check if the admin is the correct one
-    let admin_pubkey =
-        load_program_upgrade_authority(&crate::ID, delegation_program_data)?.ok_or(Unauthorized)?;
-    if !admin.key.eq(&admin_pubkey) {
-        msg!(
-            "Expected admin pubkey: {} but got {}",
-            admin_pubkey,
-            admin.key
-        );
-        return Err(Unauthorized.into());
-    }
+    validate_protocol_admin(admin, protocol_config_account, delegation_program_data)?;
 
     load_initialized_pda(
         validator_fees_vault,
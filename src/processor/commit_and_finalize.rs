@@ -0,0 +1,252 @@
+use crate::args::{CommitStateArgs, ExternalCommittedState};
+use crate::error::DlpError;
+use crate::event::{CommittedEvent, Event, FinalizedEvent};
+use crate::processor::utils::authority::{
+    load_program_config_challenge_period, load_program_config_validate_commits,
+};
+use crate::processor::utils::commit_actions::execute_commit_actions;
+use crate::processor::utils::commit_fees::{charge_commit_fee, split_commit_fee_accounts};
+use crate::processor::utils::delegation_metadata::{
+    resize_delegation_metadata, write_delegation_metadata,
+};
+use crate::processor::utils::loaders::{load_program, load_signer, split_validator_bond_account};
+use crate::processor::{
+    cpi_external_validate_commit, load_ephemeral_token_settlement, settle_ephemeral_token_balance,
+    settle_lamports_balance, validate_commit_preconditions, CommitPreconditionsArgs,
+};
+use crate::state::{CommitKind, DelegationMetadata, DelegationRecord};
+use borsh::BorshDeserialize;
+use solana_program::clock::Clock;
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::system_instruction::transfer;
+use solana_program::sysvar::Sysvar;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+/// Commit a new state of a delegated PDA and finalize it right away, without creating
+/// the commit state and commit record PDAs
+///
+/// Accounts:
+///
+///  0: `[signer]`   the validator requesting the commit
+///  1: `[writable]` the delegated account
+///  2: `[writable]` the delegation record
+///  3: `[writable]` the delegation metadata
+///  4: `[writable]` the validator fees vault
+///  5: `[]`         the program config account
+///  6: `[]`         the system program
+///  7: `[]`         the owner program
+///
+/// Optional account, identified by its PDA, as in [crate::processor::process_commit_state]:
+///
+///  8: `[]`         the validator bond
+///
+/// Optional accounts, to charge the commit fee as in [crate::processor::process_commit_state],
+/// identified by the protocol config PDA leading them:
+///
+///  9: `[]`         the protocol config PDA
+/// 10: `[writable]` the ephemeral balance of the payer of the delegation
+/// 11: `[writable]` the delegation record of the ephemeral balance
+/// 12: `[]`         the delegation metadata of the ephemeral balance
+///
+/// Remaining accounts:
+///
+/// - the token settlement accounts of an ephemeral token balance, followed by the programs and
+///   accounts used by the actions of the commit, if any, as in [crate::processor::process_finalize]
+///
+/// Requirements:
+///
+/// - same requirements as [crate::processor::process_commit_state]
+/// - there is no pending commit to finalize, the next commit and finalize nonces are equal
+/// - program config of the owner program has no challenge period, since the commit cannot
+///   be disputed before it is finalized
+/// - owner program accepts the new state, if its program config requires to validate commits,
+///   as in [crate::processor::process_finalize]
+///
+/// Steps:
+///
+/// 1. Validate the new state as in [crate::processor::process_commit_state], and with a CPI to
+///    the owner program if its program config requires it
+/// 2. Copy the new state to the delegated account
+/// 3. Invoke the commit actions in order, as in [crate::processor::process_finalize]
+/// 4. Settle the tokens spent by an ephemeral token balance, as in [crate::processor::process_finalize]
+/// 5. Settle the committed lamports, the validator deposits any extra lamports directly
+///    in the delegated account
+/// 6. Debit the commit fee from the ephemeral balance into the validator fees vault, if the
+///    ephemeral balance is provided
+/// 7. Emit a [crate::event::CommittedEvent] and a [crate::event::FinalizedEvent], the commit
+///    consuming no commit nonce
+pub fn process_commit_and_finalize(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = CommitStateArgs::try_from_slice(data)?;
+
+    let [validator, delegated_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, program_config_account, system_program, owner_program, remaining_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (validator_bond, action_accounts) =
+        split_validator_bond_account(validator, remaining_accounts);
+
+    load_signer(validator, "validator account")?;
+    let is_commit_valid = validate_commit_preconditions(CommitPreconditionsArgs {
+        commit_record_slot: args.slot,
+        validator,
+        delegated_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        validator_fees_vault,
+        validator_bond,
+        program_config_account,
+        system_program,
+    })?;
+    if !is_commit_valid {
+        return Ok(());
+    }
+
+    // The commit is finalized right away, so it cannot go through a challenge period
+    let delegation_record_data = delegation_record_account.try_borrow_data()?;
+    let owner = DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_data)?.owner;
+    drop(delegation_record_data);
+    if load_program_config_challenge_period(program_config_account, owner)? > 0 {
+        msg!("Commits to accounts with a challenge period must be finalized after it elapsed");
+        return Err(DlpError::ChallengePeriodNotElapsed.into());
+    }
+
+    // A pending commit would overwrite this state once finalized
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+    if delegation_metadata.has_pending_commits() {
+        msg!(
+            "Commits {} to {} of {} are pending finalization",
+            delegation_metadata.next_finalize_nonce,
+            delegation_metadata.next_commit_nonce,
+            delegated_account.key
+        );
+        return Err(DlpError::PendingCommits.into());
+    }
+
+    // Resize the delegation metadata created with an older layout, before any lamports move
+    resize_delegation_metadata(
+        validator,
+        delegation_metadata_account,
+        system_program,
+        &delegation_metadata,
+    )?;
+
+    // The commit fee accounts come first, the protocol config PDA cannot be a token escrow
+    // nor the program of an action
+    let (commit_fee_accounts, action_accounts) = split_commit_fee_accounts(action_accounts)?;
+
+    // An ephemeral token balance settles its tokens, before the accounts of the actions
+    let (token_settlement, action_accounts) =
+        load_ephemeral_token_settlement(delegated_account, &owner, action_accounts)?;
+
+    // Let the owner program reject the new state before it is applied
+    if load_program_config_validate_commits(program_config_account, owner)? {
+        load_program(owner_program, owner, "owner program")?;
+        cpi_external_validate_commit(
+            delegated_account,
+            owner_program,
+            CommitKind::Full,
+            ExternalCommittedState::Inline(args.data.clone()),
+            None,
+        )?;
+    }
+
+    // Copying the new state to the delegated account
+    delegated_account.realloc(args.data.len(), false)?;
+    let mut delegated_account_data = delegated_account.try_borrow_mut_data()?;
+    (*delegated_account_data).copy_from_slice(&args.data);
+    drop(delegated_account_data);
+
+    // Invoke the commit actions on top of the new state, before the lamports are settled
+    execute_commit_actions(delegated_account, &args.actions, action_accounts)?;
+    if let Some(token_settlement) = token_settlement {
+        settle_ephemeral_token_balance(
+            validator,
+            delegated_account,
+            validator_fees_vault,
+            &delegation_metadata,
+            token_settlement,
+        )?;
+    }
+
+    // Load delegation record
+    let mut delegation_record_data = delegation_record_account.try_borrow_mut_data()?;
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator_mut(&mut delegation_record_data)?;
+
+    // Settle accounts lamports. Extra lamports are deposited by the validator in the
+    // delegated account, so the validator is never used as the source of the settlement
+    if args.lamports > delegation_record.lamports {
+        let extra_lamports = args
+            .lamports
+            .checked_sub(delegation_record.lamports)
+            .ok_or(DlpError::Overflow)?;
+        invoke(
+            &transfer(validator.key, delegated_account.key, extra_lamports),
+            &[
+                validator.clone(),
+                delegated_account.clone(),
+                system_program.clone(),
+            ],
+        )?;
+    } else {
+        settle_lamports_balance(
+            delegated_account,
+            validator,
+            validator,
+            validator_fees_vault,
+            delegation_record.lamports,
+            args.lamports,
+        )?;
+    }
+
+    // Charge the commit fee once every CPI is done, since it moves lamports directly
+    if let Some(commit_fee_accounts) = &commit_fee_accounts {
+        charge_commit_fee(
+            commit_fee_accounts,
+            validator,
+            validator_fees_vault,
+            delegated_account,
+            &delegation_metadata,
+            program_config_account,
+            &owner,
+        )?;
+    }
+
+    // Update the delegation metadata
+    delegation_metadata.last_update_external_slot = args.slot;
+    delegation_metadata.is_undelegatable = args.allow_undelegation;
+    delegation_metadata.last_commit_timestamp = Clock::get()?.unix_timestamp;
+    write_delegation_metadata(delegation_metadata_account, &delegation_metadata)?;
+
+    // Update the delegation record
+    delegation_record.lamports = delegated_account.lamports();
+
+    CommittedEvent {
+        delegated_account: *delegated_account.key,
+        validator: *validator.key,
+        commit_nonce: None,
+        slot: args.slot,
+        lamports: args.lamports,
+        allow_undelegation: args.allow_undelegation,
+        kind: CommitKind::Full.into(),
+    }
+    .emit()?;
+    FinalizedEvent {
+        delegated_account: *delegated_account.key,
+        validator: *validator.key,
+        slot: args.slot,
+        lamports: args.lamports,
+    }
+    .emit()?;
+
+    Ok(())
+}
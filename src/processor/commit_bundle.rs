@@ -0,0 +1,244 @@
+use crate::args::CommitBundleArgs;
+use crate::commit_bundle_record_seeds_from_delegated_account;
+use crate::consts::COMMIT_BUNDLE_FINALIZE_PERIOD_SLOTS;
+use crate::error::DlpError;
+use crate::processor::utils::commit_fees::CommitFeeAccounts;
+use crate::processor::utils::loaders::{
+    is_uninitialized_account, load_initialized_delegation_metadata, load_signer,
+    load_uninitialized_pda, split_validator_bond_account,
+};
+use crate::processor::utils::pda::create_pda;
+use crate::processor::{process_commit_state_internal, CommitStateInternalArgs};
+use crate::state::{
+    CommitBundleRecord, CommitKind, CommitRecord, DelegationMetadata, DelegationRecord,
+};
+use borsh::BorshDeserialize;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Number of remaining accounts needed by each commit of the bundle
+const ACCOUNTS_PER_COMMIT: usize = 6;
+
+/// Number of optional accounts charged with the commit fee of each commit of the bundle
+const FEE_ACCOUNTS_PER_COMMIT: usize = 3;
+
+/// Commit the new states of many delegated accounts as a bundle, which can only be
+/// finalized as a whole with [crate::processor::process_finalize_bundle], until it is
+/// abandoned with [crate::processor::process_abandon_commit_bundle]
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator requesting the commit
+/// 1: `[writable]` the commit bundle record, derived from the first delegated account and the
+///                 nonce of its commit
+/// 2: `[]`         the validator fees vault
+/// 3: `[]`         the system program
+///
+/// Optional account, identified by its PDA, as in [crate::processor::process_commit_state]:
+///
+/// 4: `[]`         the validator bond
+///
+/// Remaining accounts, for each commit in the args:
+///
+/// 0: `[]`         the delegated account
+/// 1: `[writable]` the PDA storing the new state
+/// 2: `[writable]` the PDA storing the commit record
+/// 3: `[]`         the delegation record
+/// 4: `[writable]` the delegation metadata
+/// 5: `[]`         the program config account
+///
+/// Optional accounts, to charge the commit fees as in [crate::processor::process_commit_state],
+/// following the remaining accounts of the commits:
+///
+/// - the protocol config PDA
+/// - for each commit, the ephemeral balance of the payer of the delegation, writable, its
+///   delegation record, writable, and its delegation metadata
+///
+/// Requirements:
+///
+/// - there is at least one commit, and exactly one set of remaining accounts per commit
+/// - the commit fee accounts are either absent or given for every commit
+/// - commit bundle record is uninitialized
+/// - each commit has the same requirements as [crate::processor::process_commit_state]
+/// - no commit is outdated, otherwise the whole bundle fails instead of skipping it
+/// - no commit has actions, see [crate::args::CommitAction]
+/// - no delegated account is an ephemeral token balance, whose tokens are settled on finalize
+///
+/// Steps:
+///
+/// 1. Commit each new state as in [crate::processor::process_commit_state], with the
+///    commit record pointing to the bundle, charging its commit fee if the fee accounts are
+///    provided
+/// 2. Init the commit bundle record, which expires [crate::consts::COMMIT_BUNDLE_FINALIZE_PERIOD_SLOTS]
+///    after the challenge period of all its commits elapsed
+pub fn process_commit_bundle(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [validator, commit_bundle_record_account, validator_fees_vault, system_program, remaining_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (validator_bond, commits_accounts) =
+        split_validator_bond_account(validator, remaining_accounts);
+
+    let args = CommitBundleArgs::try_from_slice(data)?;
+
+    let commits_len = args.commits.len() * ACCOUNTS_PER_COMMIT;
+    let commit_fees_len = 1 + args.commits.len() * FEE_ACCOUNTS_PER_COMMIT;
+    if args.commits.is_empty()
+        || (commits_accounts.len() != commits_len
+            && commits_accounts.len() != commits_len + commit_fees_len)
+    {
+        msg!(
+            "Expected {} accounts for {} commits, or {} with the commit fee accounts, but got {}",
+            commits_len,
+            args.commits.len(),
+            commits_len + commit_fees_len,
+            commits_accounts.len()
+        );
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    let (commits_accounts, commit_fees_accounts) = commits_accounts.split_at(commits_len);
+    if args.commits.iter().any(|commit| !commit.actions.is_empty()) {
+        msg!("Bundled commits cannot schedule actions");
+        return Err(DlpError::InvalidCommitActions.into());
+    }
+
+    // The bundle record is derived from the first delegated account of the bundle and the
+    // nonce of its commit, so that the account can be part of many pending bundles
+    let first_delegated_account = &commits_accounts[0];
+    let first_delegation_metadata_account = &commits_accounts[4];
+    load_signer(validator, "validator")?;
+    load_initialized_delegation_metadata(
+        first_delegated_account,
+        first_delegation_metadata_account,
+        true,
+    )?;
+    let first_delegation_metadata_data = first_delegation_metadata_account.try_borrow_data()?;
+    let first_commit_nonce =
+        DelegationMetadata::try_from_bytes_with_discriminator(&first_delegation_metadata_data)?
+            .next_commit_nonce;
+    drop(first_delegation_metadata_data);
+    let commit_bundle_record_bump = load_uninitialized_pda(
+        commit_bundle_record_account,
+        commit_bundle_record_seeds_from_delegated_account!(
+            first_delegated_account.key,
+            first_commit_nonce
+        ),
+        &crate::id(),
+        true,
+        "commit bundle record",
+    )?;
+
+    let mut challenge_period_end = 0;
+
+    for (index, (commit_accounts, commit_args)) in commits_accounts
+        .chunks_exact(ACCOUNTS_PER_COMMIT)
+        .zip(args.commits.iter())
+        .enumerate()
+    {
+        let [delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, program_config_account] =
+            commit_accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let commit_fee_accounts = match commit_fees_accounts {
+            [] => None,
+            [protocol_config_account, ephemeral_balances_accounts @ ..] => {
+                let [ephemeral_balance_account, ephemeral_balance_delegation_record, ephemeral_balance_delegation_metadata] =
+                    &ephemeral_balances_accounts
+                        [index * FEE_ACCOUNTS_PER_COMMIT..(index + 1) * FEE_ACCOUNTS_PER_COMMIT]
+                else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+                Some(CommitFeeAccounts {
+                    protocol_config_account,
+                    ephemeral_balance_account,
+                    ephemeral_balance_delegation_record,
+                    ephemeral_balance_delegation_metadata,
+                })
+            }
+        };
+
+        process_commit_state_internal(CommitStateInternalArgs {
+            commit_state_bytes: &commit_args.data,
+            commit_actions_bytes: &[],
+            commit_kind: CommitKind::Full,
+            bundle: *commit_bundle_record_account.key,
+            data_hash: [0; 32],
+            commit_record_lamports: commit_args.lamports,
+            commit_record_slot: commit_args.slot,
+            allow_undelegation: commit_args.allow_undelegation,
+            payer: validator,
+            validator,
+            delegated_account,
+            commit_state_account,
+            commit_record_account,
+            delegation_record_account,
+            delegation_metadata_account,
+            validator_fees_vault,
+            validator_bond,
+            program_config_account,
+            system_program,
+            commit_fee_accounts,
+        })?;
+
+        // Outdated commits are skipped, which would break the bundle
+        if is_uninitialized_account(commit_record_account) {
+            msg!(
+                "Commit of {} at slot {} is outdated",
+                delegated_account.key,
+                commit_args.slot
+            );
+            return Err(DlpError::OutdatedSlot.into());
+        }
+
+        // The settlement accounts of an ephemeral token balance cannot be given on finalize
+        let delegation_record_data = delegation_record_account.try_borrow_data()?;
+        let delegation_record =
+            DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_data)?;
+        if delegation_record.owner.eq(&crate::id()) {
+            msg!(
+                "Ephemeral token balance {} cannot be committed in a bundle",
+                delegated_account.key
+            );
+            return Err(DlpError::InvalidCommitBundle.into());
+        }
+        drop(delegation_record_data);
+
+        // The bundle can be finalized once the challenge period of all its commits elapsed
+        let commit_record_data = commit_record_account.try_borrow_data()?;
+        let commit_record = CommitRecord::try_from_bytes_with_discriminator(&commit_record_data)?;
+        challenge_period_end = challenge_period_end.max(commit_record.challenge_period_end());
+    }
+
+    // Initialize the commit bundle record
+    create_pda(
+        commit_bundle_record_account,
+        &crate::id(),
+        CommitBundleRecord::size_with_discriminator(),
+        commit_bundle_record_seeds_from_delegated_account!(
+            first_delegated_account.key,
+            first_commit_nonce
+        ),
+        commit_bundle_record_bump,
+        system_program,
+        validator,
+    )?;
+    let commit_bundle_record = CommitBundleRecord {
+        identity: *validator.key,
+        account: *first_delegated_account.key,
+        nonce: first_commit_nonce,
+        commits_count: args.commits.len() as u64,
+        expiry_slot: challenge_period_end.saturating_add(COMMIT_BUNDLE_FINALIZE_PERIOD_SLOTS),
+    };
+    let mut commit_bundle_record_data = commit_bundle_record_account.try_borrow_mut_data()?;
+    commit_bundle_record.to_bytes_with_discriminator(&mut commit_bundle_record_data)?;
+
+    Ok(())
+}
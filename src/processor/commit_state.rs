@@ -1,19 +1,34 @@
 use crate::args::CommitStateArgs;
 use crate::error::DlpError;
+use crate::event::{CommitSkippedEvent, CommittedEvent, Event};
+use crate::processor::utils::authority::{
+    load_program_config_challenge_period, load_program_config_validate_commits,
+    validate_delegation_authority, validate_program_config_validator,
+    validate_program_config_validator_bond,
+};
+use crate::processor::utils::commit_actions::validate_commit_actions;
+use crate::processor::utils::commit_fees::{
+    charge_commit_fee, load_commit_fee_accounts, CommitFeeAccounts,
+};
+use crate::processor::utils::delegation_metadata::{
+    resize_delegation_metadata, write_delegation_metadata,
+};
 use crate::processor::utils::loaders::{
     load_initialized_delegation_metadata, load_initialized_delegation_record,
-    load_initialized_validator_fees_vault, load_owned_pda, load_program, load_program_config,
-    load_signer, load_uninitialized_pda,
+    load_initialized_validator_fees_vault, load_owned_pda, load_program, load_signer,
+    load_uninitialized_pda, split_validator_bond_account,
 };
 use crate::processor::utils::pda::create_pda;
-use crate::state::{CommitRecord, DelegationMetadata, DelegationRecord, ProgramConfig};
+use crate::state::{CommitKind, CommitRecord, DelegationMetadata, DelegationRecord};
 use crate::{
     commit_record_seeds_from_delegated_account, commit_state_seeds_from_delegated_account,
 };
-use borsh::BorshDeserialize;
+use borsh::{to_vec, BorshDeserialize};
+use solana_program::clock::Clock;
 use solana_program::program::invoke;
 use solana_program::program_error::ProgramError;
 use solana_program::system_instruction::transfer;
+use solana_program::sysvar::Sysvar;
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
 use solana_program::{msg, system_program};
 
@@ -31,22 +46,54 @@ use solana_program::{msg, system_program};
 /// 7: `[]`         the program config account
 /// 8: `[]`         the system program
 ///
+/// Optional account, identified by its PDA:
+///
+/// 9: `[]`         the validator bond, which holds no bond if absent
+///
+/// Optional accounts, to charge the commit fee:
+///
+/// 10: `[]`         the protocol config PDA
+/// 11: `[writable]` the ephemeral balance of the payer of the delegation
+/// 12: `[writable]` the delegation record of the ephemeral balance
+/// 13: `[]`         the delegation metadata of the ephemeral balance
+///
 /// Requirements:
 ///
 /// - delegation record is initialized
 /// - delegation metadata is initialized
 /// - validator fees vault is initialized
 /// - program config is initialized
-/// - commit state is uninitialized
-/// - commit record is uninitialized
+/// - commit state is uninitialized and derived from the next commit nonce
+/// - commit record is uninitialized and derived from the next commit nonce
 /// - delegated account holds at least the lamports indicated in the delegation record
-/// - account was not committed at a later slot
+/// - account was not committed at a later slot, whether the commit is pending or finalized
+/// - delegation was not flagged as stale, see [crate::processor::process_flag_stale_delegation]
+/// - validator is the delegation record authority, unless the authority is the default pubkey
+/// - validator bond holds the minimum bond required by the program config, if any
+/// - there are at most [crate::consts::MAX_COMMIT_ACTIONS] actions, which do not invoke the
+///   delegation program and are only signed by the commit action signer of the delegated account
+/// - if a commit fee is charged, the ephemeral balance is delegated to the validator, derived
+///   from the rent payer of the delegated account, and remains rent exempt after paying the fee,
+///   and the validator fees vault is writable
 ///
 /// Steps:
 /// 1. Check that the pda is delegated
 /// 2. Init a new PDA to store the new state
-/// 3. Copy the new state to the new PDA
-/// 4. Init a new PDA to store the record of the new state commitment
+/// 3. Copy the new state to the new PDA, followed by the actions to invoke once the commit is
+///    finalized, see [crate::args::CommitAction]
+/// 4. Init a new PDA to store the record of the new state commitment, which opens the
+///    challenge period configured in the program config, if any
+/// 5. Increment the next commit nonce, so that several commits can be pending at once, and
+///    record the commit time, slot and lamports in the delegation metadata, resized by the payer if
+///    it was created with an older layout
+/// 6. Deposit the committed lamports exceeding those of the last queued commit, or of the
+///    delegation record if none is pending, in the commit state account
+/// 7. Debit the commit fee of the program config, or of the protocol config, from the ephemeral
+///    balance into the validator fees vault, if the ephemeral balance is provided
+///
+/// NOTE: the commit fee is also deducted from the lamports recorded in the delegation record of
+///       the ephemeral balance, the validator is expected to debit it in the ephemeral rollup
+///       too, otherwise the fee is refunded when the ephemeral balance state is finalized
 pub fn process_commit_state(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -55,21 +102,36 @@ pub fn process_commit_state(
     let args = CommitStateArgs::try_from_slice(data)?;
 
     let commit_state_bytes: &[u8] = args.data.as_ref();
+    let commit_actions_bytes = if args.actions.is_empty() {
+        vec![]
+    } else {
+        to_vec(&args.actions)?
+    };
     let commit_record_lamports = args.lamports;
     let commit_record_slot = args.slot;
     let allow_undelegation = args.allow_undelegation;
 
-    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, program_config_account, system_program] =
+    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, program_config_account, system_program, remaining_accounts @ ..] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
+    let (validator_bond, commit_fee_accounts) =
+        split_validator_bond_account(validator, remaining_accounts);
+    let commit_fee_accounts = load_commit_fee_accounts(commit_fee_accounts)?;
+
+    validate_commit_actions(delegated_account.key, &args.actions)?;
 
     let commit_args = CommitStateInternalArgs {
         commit_state_bytes,
+        commit_actions_bytes: &commit_actions_bytes,
+        commit_kind: CommitKind::Full,
+        bundle: Pubkey::default(),
+        data_hash: [0; 32],
         commit_record_lamports,
         commit_record_slot,
         allow_undelegation,
+        payer: validator,
         validator,
         delegated_account,
         commit_state_account,
@@ -77,8 +139,10 @@ pub fn process_commit_state(
         delegation_record_account,
         delegation_metadata_account,
         validator_fees_vault,
+        validator_bond,
         program_config_account,
         system_program,
+        commit_fee_accounts,
     };
 
     process_commit_state_internal(commit_args)
@@ -87,9 +151,14 @@ pub fn process_commit_state(
 /// Arguments for the commit state internal function
 pub(crate) struct CommitStateInternalArgs<'a, 'info> {
     pub(crate) commit_state_bytes: &'a [u8],
+    pub(crate) commit_actions_bytes: &'a [u8],
+    pub(crate) commit_kind: CommitKind,
+    pub(crate) bundle: Pubkey,
+    pub(crate) data_hash: [u8; 32],
     pub(crate) commit_record_lamports: u64,
     pub(crate) commit_record_slot: u64,
     pub(crate) allow_undelegation: bool,
+    pub(crate) payer: &'a AccountInfo<'info>,
     pub(crate) validator: &'a AccountInfo<'info>,
     pub(crate) delegated_account: &'a AccountInfo<'info>,
     pub(crate) commit_state_account: &'a AccountInfo<'info>,
@@ -97,142 +166,111 @@ pub(crate) struct CommitStateInternalArgs<'a, 'info> {
     pub(crate) delegation_record_account: &'a AccountInfo<'info>,
     pub(crate) delegation_metadata_account: &'a AccountInfo<'info>,
     pub(crate) validator_fees_vault: &'a AccountInfo<'info>,
+    pub(crate) validator_bond: Option<&'a AccountInfo<'info>>,
     pub(crate) program_config_account: &'a AccountInfo<'info>,
     pub(crate) system_program: &'a AccountInfo<'info>,
+    pub(crate) commit_fee_accounts: Option<CommitFeeAccounts<'a, 'info>>,
 }
 
-/// Commit a new state of a delegated Pda
+/// Commit a new state of a delegated Pda. The payer is the validator itself, unless the
+/// validator authorized the commit otherwise, see [crate::processor::process_commit_state_relayed]
 pub(crate) fn process_commit_state_internal(
     args: CommitStateInternalArgs,
 ) -> Result<(), ProgramError> {
-    // Check that the origin account is delegated
-    load_owned_pda(args.delegated_account, &crate::id(), "delegated account")?;
-    load_signer(args.validator, "validator account")?;
-    load_initialized_delegation_record(
-        args.delegated_account,
-        args.delegation_record_account,
-        false,
-    )?;
-    load_initialized_delegation_metadata(
-        args.delegated_account,
-        args.delegation_metadata_account,
-        true,
-    )?;
-    load_initialized_validator_fees_vault(args.validator, args.validator_fees_vault, false)?;
-    load_program(args.system_program, system_program::id(), "system program")?;
-
-    // Read delegation metadata
-    let mut delegation_metadata_data = args.delegation_metadata_account.try_borrow_mut_data()?;
-    let mut delegation_metadata =
-        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
-
-    // If the commit slot is greater or equal than the last update slot, we can proceed.
-    // If the slot is less, we simply do not commit.
-    // Since commit instructions are typically bundled, we return without error
-    // so that correct commits are executed.
-    if args.commit_record_slot < delegation_metadata.last_update_external_slot {
-        msg!(
-            "Slot {} is outdated, previous slot is {}. Skipping commit",
-            args.commit_record_slot,
-            delegation_metadata.last_update_external_slot
-        );
+    load_signer(args.payer, "payer")?;
+    let is_commit_valid = validate_commit_preconditions(CommitPreconditionsArgs {
+        commit_record_slot: args.commit_record_slot,
+        validator: args.validator,
+        delegated_account: args.delegated_account,
+        delegation_record_account: args.delegation_record_account,
+        delegation_metadata_account: args.delegation_metadata_account,
+        validator_fees_vault: args.validator_fees_vault,
+        validator_bond: args.validator_bond,
+        program_config_account: args.program_config_account,
+        system_program: args.system_program,
+    })?;
+    if !is_commit_valid {
         return Ok(());
     }
 
-    // Once the account is marked as undelegatable, any subsequent commit should fail
-    if delegation_metadata.is_undelegatable {
-        msg!(
-            "delegation metadata ({}) is already undelegated",
-            args.delegation_metadata_account.key
-        );
-        return Err(DlpError::AlreadyUndelegated.into());
-    }
-
-    // Update delegation metadata undelegation flag
-    delegation_metadata.is_undelegatable = args.allow_undelegation;
-    delegation_metadata.to_bytes_with_discriminator(&mut delegation_metadata_data.as_mut())?;
-
     // Load delegation record
     let delegation_record_data = args.delegation_record_account.try_borrow_data()?;
     let delegation_record =
         DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_data)?;
 
-    // If there was an issue with the lamport accounting in the past, abort (this should never happen)
-    if args.delegated_account.lamports() < delegation_record.lamports {
-        msg!(
-            "delegated account ({}) has less lamports than the delegation record indicates",
-            args.delegated_account.key
-        );
-        return Err(DlpError::InvalidDelegatedState.into());
-    }
+    // Update delegation metadata undelegation flag and queue the commit at the next nonce
+    let delegation_metadata_data = args.delegation_metadata_account.try_borrow_data()?;
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+    let commit_nonce = delegation_metadata.next_commit_nonce;
+    let queued_lamports = delegation_metadata.queued_lamports(delegation_record.lamports);
+    delegation_metadata.is_undelegatable = args.allow_undelegation;
+    delegation_metadata.last_commit_lamports = args.commit_record_lamports;
+    delegation_metadata.last_commit_slot = args.commit_record_slot;
+    delegation_metadata.last_commit_timestamp = Clock::get()?.unix_timestamp;
+    delegation_metadata.next_commit_nonce =
+        commit_nonce.checked_add(1).ok_or(DlpError::Overflow)?;
+    resize_delegation_metadata(
+        args.payer,
+        args.delegation_metadata_account,
+        args.system_program,
+        &delegation_metadata,
+    )?;
+    write_delegation_metadata(args.delegation_metadata_account, &delegation_metadata)?;
 
     // If committed lamports are more than the previous lamports balance, deposit the difference in the commitment account
     // If committed lamports are less than the previous lamports balance, we have collateral to settle the balance at state finalization
     // We need to do that so that the finalizer already have all the lamports from the validators ready at finalize time
     // The finalizer can return any extra lamport to the validator during finalize, but this acts as the validator's proof of collateral
-    if args.commit_record_lamports > delegation_record.lamports {
+    // The previous balance is the one of the last queued commit, which is settled right before this one
+    if args.commit_record_lamports > queued_lamports {
         let extra_lamports = args
             .commit_record_lamports
-            .checked_sub(delegation_record.lamports)
+            .checked_sub(queued_lamports)
             .ok_or(DlpError::Overflow)?;
         invoke(
             &transfer(
-                args.validator.key,
+                args.payer.key,
                 args.commit_state_account.key,
                 extra_lamports,
             ),
             &[
-                args.validator.clone(),
+                args.payer.clone(),
                 args.commit_state_account.clone(),
                 args.system_program.clone(),
             ],
         )?;
     }
 
-    // Load the program configuration and validate it, if any
-    let has_program_config =
-        load_program_config(args.program_config_account, delegation_record.owner, false)?;
-    if has_program_config {
-        let program_config_data = args.program_config_account.try_borrow_data()?;
-        let program_config =
-            ProgramConfig::try_from_bytes_with_discriminator(&program_config_data)?;
-        if !program_config
-            .approved_validators
-            .contains(args.validator.key)
-        {
-            msg!(
-                "validator ({}) is not whitelisted in the program config",
-                args.validator.key
-            );
-            return Err(DlpError::InvalidWhitelistProgramConfig.into());
-        }
-    }
-
     // Load the uninitialized PDAs
     let commit_state_bump = load_uninitialized_pda(
         args.commit_state_account,
-        commit_state_seeds_from_delegated_account!(args.delegated_account.key),
+        commit_state_seeds_from_delegated_account!(args.delegated_account.key, commit_nonce),
         &crate::id(),
         true,
         "commit state account",
     )?;
     let commit_record_bump = load_uninitialized_pda(
         args.commit_record_account,
-        commit_record_seeds_from_delegated_account!(args.delegated_account.key),
+        commit_record_seeds_from_delegated_account!(args.delegated_account.key, commit_nonce),
         &crate::id(),
         true,
         "commit record",
     )?;
 
-    // Initialize the PDA containing the new committed state
+    // Initialize the PDA containing the new committed state, followed by its actions if any
+    let commit_state_len = args.commit_state_bytes.len();
     create_pda(
         args.commit_state_account,
         &crate::id(),
-        args.commit_state_bytes.len(),
-        commit_state_seeds_from_delegated_account!(args.delegated_account.key),
+        commit_state_len
+            .checked_add(args.commit_actions_bytes.len())
+            .ok_or(DlpError::Overflow)?,
+        commit_state_seeds_from_delegated_account!(args.delegated_account.key, commit_nonce),
         commit_state_bump,
         args.system_program,
-        args.validator,
+        args.payer,
     )?;
 
     // Initialize the PDA containing the record of the committed state
@@ -240,27 +278,186 @@ pub(crate) fn process_commit_state_internal(
         args.commit_record_account,
         &crate::id(),
         CommitRecord::size_with_discriminator(),
-        commit_record_seeds_from_delegated_account!(args.delegated_account.key),
+        commit_record_seeds_from_delegated_account!(args.delegated_account.key, commit_nonce),
         commit_record_bump,
         args.system_program,
-        args.validator,
+        args.payer,
     )?;
 
+    // Open the challenge period of the commit, during which it can be disputed
+    let challenge_period =
+        load_program_config_challenge_period(args.program_config_account, delegation_record.owner)?;
+    let validate_commit =
+        load_program_config_validate_commits(args.program_config_account, delegation_record.owner)?;
+
     // Initialize the commit record
     let commit_record = CommitRecord {
         identity: *args.validator.key,
         account: *args.delegated_account.key,
         slot: args.commit_record_slot,
         lamports: args.commit_record_lamports,
+        kind: args.commit_kind.into(),
+        bundle: args.bundle,
+        data_hash: args.data_hash,
+        commit_slot: Clock::get()?.slot,
+        challenge_period,
+        disputer: Pubkey::default(),
+        actions_len: args.commit_actions_bytes.len() as u64,
+        payer: *args.payer.key,
+        validate_commit: validate_commit.into(),
     };
     let mut commit_record_data = args.commit_record_account.try_borrow_mut_data()?;
     commit_record.to_bytes_with_discriminator(&mut commit_record_data)?;
 
     // Copy the new state to the initialized PDA
     let mut commit_state_data = args.commit_state_account.try_borrow_mut_data()?;
-    (*commit_state_data).copy_from_slice(args.commit_state_bytes);
+    commit_state_data[..commit_state_len].copy_from_slice(args.commit_state_bytes);
+    commit_state_data[commit_state_len..].copy_from_slice(args.commit_actions_bytes);
 
-    // TODO - Add additional validation for the commitment, e.g. sufficient validator stake
+    // Charge the commit fee once every CPI is done, since it moves lamports directly
+    if let Some(commit_fee_accounts) = &args.commit_fee_accounts {
+        charge_commit_fee(
+            commit_fee_accounts,
+            args.validator,
+            args.validator_fees_vault,
+            args.delegated_account,
+            &delegation_metadata,
+            args.program_config_account,
+            &delegation_record.owner,
+        )?;
+    }
+
+    CommittedEvent {
+        delegated_account: *args.delegated_account.key,
+        validator: *args.validator.key,
+        commit_nonce: Some(commit_nonce),
+        slot: args.commit_record_slot,
+        lamports: args.commit_record_lamports,
+        allow_undelegation: args.allow_undelegation,
+        kind: args.commit_kind.into(),
+    }
+    .emit()?;
 
     Ok(())
 }
+
+/// Arguments for the commit preconditions validation
+pub(crate) struct CommitPreconditionsArgs<'a, 'info> {
+    pub(crate) commit_record_slot: u64,
+    pub(crate) validator: &'a AccountInfo<'info>,
+    pub(crate) delegated_account: &'a AccountInfo<'info>,
+    pub(crate) delegation_record_account: &'a AccountInfo<'info>,
+    pub(crate) delegation_metadata_account: &'a AccountInfo<'info>,
+    pub(crate) validator_fees_vault: &'a AccountInfo<'info>,
+    pub(crate) validator_bond: Option<&'a AccountInfo<'info>>,
+    pub(crate) program_config_account: &'a AccountInfo<'info>,
+    pub(crate) system_program: &'a AccountInfo<'info>,
+}
+
+/// Validate the preconditions shared by every commit of a new state.
+/// Returns false if the commit is outdated and should be skipped.
+pub(crate) fn validate_commit_preconditions(
+    args: CommitPreconditionsArgs,
+) -> Result<bool, ProgramError> {
+    // Check that the origin account is delegated
+    load_owned_pda(args.delegated_account, &crate::id(), "delegated account")?;
+    load_initialized_delegation_record(
+        args.delegated_account,
+        args.delegation_record_account,
+        false,
+    )?;
+    load_initialized_delegation_metadata(
+        args.delegated_account,
+        args.delegation_metadata_account,
+        true,
+    )?;
+    load_initialized_validator_fees_vault(args.validator, args.validator_fees_vault, false)?;
+    load_program(args.system_program, system_program::id(), "system program")?;
+
+    // Load delegation record
+    let delegation_record_data = args.delegation_record_account.try_borrow_data()?;
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_data)?;
+
+    // Check that the validator is allowed to commit for the delegated account
+    validate_delegation_authority(delegation_record, args.validator)?;
+
+    // Read delegation metadata
+    let delegation_metadata_data = args.delegation_metadata_account.try_borrow_data()?;
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+
+    // If the commit slot is greater or equal than the last queued or finalized slot, we can proceed.
+    // If the slot is less, we simply do not commit.
+    // Since commit instructions are typically bundled, we return without error
+    // so that correct commits are executed.
+    let queued_slot = delegation_metadata.queued_slot();
+    if args.commit_record_slot < queued_slot {
+        msg!(
+            "Slot {} is outdated, previous slot is {}. Skipping commit",
+            args.commit_record_slot,
+            queued_slot
+        );
+        CommitSkippedEvent {
+            delegated_account: *args.delegated_account.key,
+            validator: *args.validator.key,
+            slot: args.commit_record_slot,
+            last_update_external_slot: queued_slot,
+        }
+        .emit()?;
+        return Ok(false);
+    }
+
+    // Once the account is marked as undelegatable, any subsequent commit should fail
+    if delegation_metadata.is_undelegatable {
+        msg!(
+            "delegation metadata ({}) is already undelegated",
+            args.delegation_metadata_account.key
+        );
+        return Err(DlpError::AlreadyUndelegated.into());
+    }
+
+    // Once the delegation is flagged as stale, it can only be undelegated with its last
+    // finalized state
+    if delegation_metadata.is_stale {
+        msg!(
+            "delegation metadata ({}) is flagged as stale",
+            args.delegation_metadata_account.key
+        );
+        return Err(DlpError::DelegationStale.into());
+    }
+
+    // Once a dispute was upheld, the delegation can only be force undelegated with its last
+    // finalized state
+    if delegation_metadata.has_upheld_dispute {
+        msg!(
+            "delegation metadata ({}) has an upheld dispute",
+            args.delegation_metadata_account.key
+        );
+        return Err(DlpError::CommitDisputed.into());
+    }
+
+    // If there was an issue with the lamport accounting in the past, abort (this should never happen)
+    if args.delegated_account.lamports() < delegation_record.lamports {
+        msg!(
+            "delegated account ({}) has less lamports than the delegation record indicates",
+            args.delegated_account.key
+        );
+        return Err(DlpError::InvalidDelegatedState.into());
+    }
+
+    // Load the program configuration and validate it, if any
+    validate_program_config_validator(
+        args.program_config_account,
+        delegation_record.owner,
+        args.validator.key,
+    )?;
+    validate_program_config_validator_bond(
+        args.program_config_account,
+        delegation_record.owner,
+        args.validator,
+        args.validator_bond,
+    )?;
+
+    Ok(true)
+}
@@ -0,0 +1,123 @@
+use crate::args::CommitStateDiffArgs;
+use crate::error::DlpError;
+use crate::processor::utils::commit_fees::load_commit_fee_accounts;
+use crate::processor::utils::loaders::{
+    load_initialized_delegation_metadata, split_validator_bond_account,
+};
+use crate::processor::utils::state_patch::validate_state_patches;
+use crate::processor::{process_commit_state_internal, CommitStateInternalArgs};
+use crate::state::{CommitKind, DelegationMetadata};
+use borsh::{to_vec, BorshDeserialize};
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+/// Commit a list of patches to the state of a delegated Pda
+///
+/// It is identical to [crate::processor::process_commit_state] but the commit state stores
+/// the patches, which are applied on top of the delegated account data on finalize
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator requesting the commit
+/// 1: `[]`         the delegated account
+/// 2: `[writable]` the PDA storing the patches
+/// 3: `[writable]` the PDA storing the commit record
+/// 4: `[]`         the delegation record
+/// 5: `[writable]` the delegation metadata
+/// 6: `[]`         the validator fees vault
+/// 7: `[]`         the program config account
+/// 8: `[]`         the system program
+///
+/// Optional account, identified by its PDA, as in [crate::processor::process_commit_state]:
+///
+/// 9: `[]`         the validator bond
+///
+/// Optional accounts, to charge the commit fee as in [crate::processor::process_commit_state]:
+///
+/// 10: `[]`         the protocol config PDA
+/// 11: `[writable]` the ephemeral balance of the payer of the delegation
+/// 12: `[writable]` the delegation record of the ephemeral balance
+/// 13: `[]`         the delegation metadata of the ephemeral balance
+///
+/// Requirements:
+///
+/// - delegation record is initialized
+/// - delegation metadata is initialized
+/// - validator fees vault is initialized
+/// - program config is initialized
+/// - commit state is uninitialized and derived from the next commit nonce
+/// - commit record is uninitialized and derived from the next commit nonce
+/// - delegated account holds at least the lamports indicated in the delegation record
+/// - account was not committed at a later slot
+/// - validator is the delegation record authority, unless the authority is the default pubkey
+/// - validator bond holds the minimum bond required by the program config, if any
+/// - patches are within the delegated account data range
+/// - there is no pending commit, which could change the delegated account data range
+/// - if a commit fee is charged, the ephemeral balance and the validator fees vault meet the
+///   requirements of [crate::processor::process_commit_state]
+///
+/// Steps:
+/// 1. Check that the pda is delegated
+/// 2. Init a new PDA to store the patches
+/// 3. Copy the serialized patches to the new PDA
+/// 4. Init a new PDA to store the record of the new state commitment
+/// 5. Debit the commit fee from the ephemeral balance into the validator fees vault, if the
+///    ephemeral balance is provided
+pub fn process_commit_state_diff(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = CommitStateDiffArgs::try_from_slice(data)?;
+
+    let commit_record_lamports = args.lamports;
+    let commit_record_slot = args.slot;
+    let allow_undelegation = args.allow_undelegation;
+
+    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, program_config_account, system_program, remaining_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (validator_bond, commit_fee_accounts) =
+        split_validator_bond_account(validator, remaining_accounts);
+    let commit_fee_accounts = load_commit_fee_accounts(commit_fee_accounts)?;
+
+    // The delegated data can only change on finalize, so patches valid now stay valid as
+    // long as no pending commit is finalized before this one
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    if delegation_metadata.has_pending_commits() {
+        msg!("Patches cannot be committed while previous commits are pending");
+        return Err(DlpError::PendingCommits.into());
+    }
+    drop(delegation_metadata_data);
+    validate_state_patches(&args.patches, delegated_account.data_len())?;
+    let commit_state_bytes = to_vec(&args.patches)?;
+
+    let commit_args = CommitStateInternalArgs {
+        commit_state_bytes: &commit_state_bytes,
+        commit_actions_bytes: &[],
+        commit_kind: CommitKind::Diff,
+        bundle: Pubkey::default(),
+        data_hash: [0; 32],
+        commit_record_lamports,
+        commit_record_slot,
+        allow_undelegation,
+        payer: validator,
+        validator,
+        delegated_account,
+        commit_state_account,
+        commit_record_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        validator_fees_vault,
+        validator_bond,
+        program_config_account,
+        system_program,
+        commit_fee_accounts,
+    };
+    process_commit_state_internal(commit_args)
+}
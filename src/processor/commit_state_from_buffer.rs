@@ -1,5 +1,8 @@
 use crate::args::CommitStateFromBufferArgs;
+use crate::processor::utils::commit_fees::load_commit_fee_accounts;
+use crate::processor::utils::loaders::split_validator_bond_account;
 use crate::processor::{process_commit_state_internal, CommitStateInternalArgs};
+use crate::state::CommitKind;
 use borsh::BorshDeserialize;
 use solana_program::program_error::ProgramError;
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
@@ -11,16 +14,28 @@ use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubke
 ///
 /// Accounts:
 ///
-/// 0: `[signer]`   the validator requesting the commit
-/// 1: `[]`         the delegated account
-/// 2: `[writable]` the PDA storing the new state temporarily
-/// 3: `[writable]` the PDA storing the commit record
-/// 4: `[]`         the delegation record
-/// 5: `[writable]` the delegation metadata
-/// 6: `[]`         the buffer account storing the data to be committed
-/// 7: `[]`         the validator fees vault
-/// 8: `[]`         the program config account
-/// 9: `[]`         the system program
+///  0: `[signer]`   the validator requesting the commit
+///  1: `[]`         the delegated account
+///  2: `[writable]` the PDA storing the new state temporarily
+///  3: `[writable]` the PDA storing the commit record
+///  4: `[]`         the delegation record
+///  5: `[writable]` the delegation metadata
+///  6: `[]`         the buffer account storing the data to be committed, e.g. a commit buffer
+///                  written with [crate::processor::process_write_commit_buffer]
+///  7: `[]`         the validator fees vault
+///  8: `[]`         the program config account
+///  9: `[]`         the system program
+///
+/// Optional account, identified by its PDA, as in [crate::processor::process_commit_state]:
+///
+/// 10: `[]`         the validator bond
+///
+/// Optional accounts, to charge the commit fee as in [crate::processor::process_commit_state]:
+///
+/// 11: `[]`         the protocol config PDA
+/// 12: `[writable]` the ephemeral balance of the payer of the delegation
+/// 13: `[writable]` the delegation record of the ephemeral balance
+/// 14: `[]`         the delegation metadata of the ephemeral balance
 ///
 /// Requirements:
 ///
@@ -28,16 +43,22 @@ use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubke
 /// - delegation metadata is initialized
 /// - validator fees vault is initialized
 /// - program config is initialized
-/// - commit state is uninitialized
-/// - commit record is uninitialized
+/// - commit state is uninitialized and derived from the next commit nonce
+/// - commit record is uninitialized and derived from the next commit nonce
 /// - delegated account holds at least the lamports indicated in the delegation record
 /// - account was not committed at a later slot
+/// - validator is the delegation record authority, unless the authority is the default pubkey
+/// - validator bond holds the minimum bond required by the program config, if any
+/// - if a commit fee is charged, the ephemeral balance and the validator fees vault meet the
+///   requirements of [crate::processor::process_commit_state]
 ///
 /// Steps:
 /// 1. Check that the pda is delegated
 /// 2. Init a new PDA to store the new state
 /// 3. Copy the new state to the new PDA
 /// 4. Init a new PDA to store the record of the new state commitment
+/// 5. Debit the commit fee from the ephemeral balance into the validator fees vault, if the
+///    ephemeral balance is provided
 pub fn process_commit_state_from_buffer(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -49,19 +70,27 @@ pub fn process_commit_state_from_buffer(
     let commit_record_slot = args.slot;
     let allow_undelegation = args.allow_undelegation;
 
-    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, state_buffer_account, validator_fees_vault, program_config_account, system_program] =
+    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, state_buffer_account, validator_fees_vault, program_config_account, system_program, remaining_accounts @ ..] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
+    let (validator_bond, commit_fee_accounts) =
+        split_validator_bond_account(validator, remaining_accounts);
+    let commit_fee_accounts = load_commit_fee_accounts(commit_fee_accounts)?;
     let state = state_buffer_account.try_borrow_data()?;
     let commit_state_bytes: &[u8] = *state;
 
     let commit_args = CommitStateInternalArgs {
         commit_state_bytes,
+        commit_actions_bytes: &[],
+        commit_kind: CommitKind::Full,
+        bundle: Pubkey::default(),
+        data_hash: [0; 32],
         commit_record_lamports,
         commit_record_slot,
         allow_undelegation,
+        payer: validator,
         validator,
         delegated_account,
         commit_state_account,
@@ -69,8 +98,10 @@ pub fn process_commit_state_from_buffer(
         delegation_record_account,
         delegation_metadata_account,
         validator_fees_vault,
+        validator_bond,
         program_config_account,
         system_program,
+        commit_fee_accounts,
     };
     process_commit_state_internal(commit_args)
 }
@@ -0,0 +1,103 @@
+use crate::args::CommitStateHashArgs;
+use crate::processor::utils::commit_fees::load_commit_fee_accounts;
+use crate::processor::utils::loaders::split_validator_bond_account;
+use crate::processor::{process_commit_state_internal, CommitStateInternalArgs};
+use crate::state::CommitKind;
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Commit the hash of a new state of a delegated Pda
+///
+/// It is identical to [crate::processor::process_commit_state] but the commit record only
+/// stores the hash of the new state, and the commit state is left empty. The new state is
+/// supplied on finalize, see [crate::processor::process_finalize_with_data]
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator requesting the commit
+/// 1: `[]`         the delegated account
+/// 2: `[writable]` the empty commit state PDA
+/// 3: `[writable]` the PDA storing the commit record
+/// 4: `[]`         the delegation record
+/// 5: `[writable]` the delegation metadata
+/// 6: `[]`         the validator fees vault
+/// 7: `[]`         the program config account
+/// 8: `[]`         the system program
+///
+/// Optional account, identified by its PDA, as in [crate::processor::process_commit_state]:
+///
+/// 9: `[]`         the validator bond
+///
+/// Optional accounts, to charge the commit fee as in [crate::processor::process_commit_state]:
+///
+/// 10: `[]`         the protocol config PDA
+/// 11: `[writable]` the ephemeral balance of the payer of the delegation
+/// 12: `[writable]` the delegation record of the ephemeral balance
+/// 13: `[]`         the delegation metadata of the ephemeral balance
+///
+/// Requirements:
+///
+/// - delegation record is initialized
+/// - delegation metadata is initialized
+/// - validator fees vault is initialized
+/// - program config is initialized
+/// - commit state is uninitialized and derived from the next commit nonce
+/// - commit record is uninitialized and derived from the next commit nonce
+/// - delegated account holds at least the lamports indicated in the delegation record
+/// - account was not committed at a later slot
+/// - validator is the delegation record authority, unless the authority is the default pubkey
+/// - validator bond holds the minimum bond required by the program config, if any
+/// - if a commit fee is charged, the ephemeral balance and the validator fees vault meet the
+///   requirements of [crate::processor::process_commit_state]
+///
+/// Steps:
+/// 1. Check that the pda is delegated
+/// 2. Init an empty PDA as the commit state
+/// 3. Init a new PDA to store the record of the new state commitment, with its hash
+/// 4. Debit the commit fee from the ephemeral balance into the validator fees vault, if the
+///    ephemeral balance is provided
+pub fn process_commit_state_hash(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = CommitStateHashArgs::try_from_slice(data)?;
+
+    let commit_record_lamports = args.lamports;
+    let commit_record_slot = args.slot;
+    let allow_undelegation = args.allow_undelegation;
+
+    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, program_config_account, system_program, remaining_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (validator_bond, commit_fee_accounts) =
+        split_validator_bond_account(validator, remaining_accounts);
+    let commit_fee_accounts = load_commit_fee_accounts(commit_fee_accounts)?;
+
+    let commit_args = CommitStateInternalArgs {
+        commit_state_bytes: &[],
+        commit_actions_bytes: &[],
+        commit_kind: CommitKind::Hash,
+        bundle: Pubkey::default(),
+        data_hash: args.data_hash,
+        commit_record_lamports,
+        commit_record_slot,
+        allow_undelegation,
+        payer: validator,
+        validator,
+        delegated_account,
+        commit_state_account,
+        commit_record_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        validator_fees_vault,
+        validator_bond,
+        program_config_account,
+        system_program,
+        commit_fee_accounts,
+    };
+    process_commit_state_internal(commit_args)
+}
@@ -0,0 +1,126 @@
+use crate::args::CommitStateArgs;
+use crate::error::DlpError;
+use crate::processor::utils::commit_fees::load_commit_fee_accounts;
+use crate::processor::utils::ed25519::validate_ed25519_signature;
+use crate::processor::utils::loaders::{
+    load_initialized_delegation_metadata, load_sysvar, split_validator_bond_account,
+};
+use crate::processor::{process_commit_state_internal, CommitStateInternalArgs};
+use crate::state::{CommitKind, DelegationMetadata};
+use borsh::BorshDeserialize;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::sysvar::instructions;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Commit a new state of a delegated Pda on behalf of the validator, submitted by any relayer
+///
+/// It is identical to [crate::processor::process_commit_state] but the validator authorizes
+/// the commit with an Ed25519 instruction preceding this one, signing
+/// [CommitStateArgs::relayed_commit_message], instead of signing the transaction.
+/// The relayer pays for the rent of the commit PDAs and for the extra committed lamports,
+/// which are refunded to the relayer when the commit PDAs are closed, see
+/// [crate::processor::process_finalize].
+///
+/// Accounts:
+///
+///  0: `[signer]`   the relayer paying for the commit
+///  1: `[]`         the validator authorizing the commit
+///  2: `[]`         the delegated account
+///  3: `[writable]` the PDA storing the new state
+///  4: `[writable]` the PDA storing the commit record
+///  5: `[]`         the delegation record
+///  6: `[writable]` the delegation metadata
+///  7: `[]`         the validator fees vault
+///  8: `[]`         the program config account
+///  9: `[]`         the system program
+/// 10: `[]`         the instructions sysvar
+///
+/// Optional account, identified by its PDA, as in [crate::processor::process_commit_state]:
+///
+/// 11: `[]`         the validator bond
+///
+/// Optional accounts, to charge the commit fee as in [crate::processor::process_commit_state]:
+///
+/// 12: `[]`         the protocol config PDA
+/// 13: `[writable]` the ephemeral balance of the payer of the delegation
+/// 14: `[writable]` the delegation record of the ephemeral balance
+/// 15: `[]`         the delegation metadata of the ephemeral balance
+///
+/// Requirements:
+///
+/// - same requirements as [crate::processor::process_commit_state], but the validator
+///   does not sign
+/// - the preceding instruction is an Ed25519 instruction verifying the validator signature
+///   of the delegated account, nonce, slot, lamports, undelegation flag and data hash of the
+///   commit, see [CommitStateArgs::relayed_commit_message]
+/// - the commit has no actions, since they are not covered by the validator signature
+///
+/// NOTE: the commit fee accounts are not covered by the validator signature either, a commit
+///       fee the validator does not debit in the ephemeral rollup is refunded when the
+///       ephemeral balance state is finalized, see [crate::processor::process_commit_state]
+///
+/// Steps:
+/// 1. Check that the commit is authorized by the validator
+/// 2. Commit the new state as in [crate::processor::process_commit_state], charging the
+///    commit fee if the ephemeral balance is provided
+pub fn process_commit_state_relayed(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = CommitStateArgs::try_from_slice(data)?;
+
+    let [relayer, validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, program_config_account, system_program, instructions_sysvar, remaining_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (validator_bond, commit_fee_accounts) =
+        split_validator_bond_account(validator, remaining_accounts);
+    let commit_fee_accounts = load_commit_fee_accounts(commit_fee_accounts)?;
+
+    if !args.actions.is_empty() {
+        msg!("Relayed commits cannot schedule actions");
+        return Err(DlpError::InvalidCommitActions.into());
+    }
+
+    // The signature authorizes the next commit only, so it cannot be replayed
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    let commit_nonce = delegation_metadata.next_commit_nonce;
+    drop(delegation_metadata_data);
+
+    load_sysvar(instructions_sysvar, instructions::id())?;
+    validate_ed25519_signature(
+        instructions_sysvar,
+        validator.key,
+        &args.relayed_commit_message(delegated_account.key, commit_nonce),
+    )?;
+
+    let commit_args = CommitStateInternalArgs {
+        commit_state_bytes: &args.data,
+        commit_actions_bytes: &[],
+        commit_kind: CommitKind::Full,
+        bundle: Pubkey::default(),
+        data_hash: [0; 32],
+        commit_record_lamports: args.lamports,
+        commit_record_slot: args.slot,
+        allow_undelegation: args.allow_undelegation,
+        payer: relayer,
+        validator,
+        delegated_account,
+        commit_state_account,
+        commit_record_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        validator_fees_vault,
+        validator_bond,
+        program_config_account,
+        system_program,
+        commit_fee_accounts,
+    };
+    process_commit_state_internal(commit_args)
+}
@@ -7,6 +7,7 @@ use solana_program::{
 };
 
 use crate::args::DelegateArgs;
+use crate::event::{DelegatedEvent, Event};
 use crate::processor::utils::curve::is_on_curve;
 use crate::processor::utils::loaders::{
     load_owned_pda, load_pda, load_program, load_signer, load_uninitialized_pda,
@@ -60,6 +61,31 @@ pub fn process_delegate(
 
     let args = DelegateArgs::try_from_slice(data)?;
 
+    process_delegation(
+        payer,
+        delegated_account,
+        owner_program,
+        delegate_buffer_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        system_program,
+        args,
+    )
+}
+
+/// Validate the accounts of a single delegation, then create its delegation record and
+/// metadata and copy back the buffered data into the delegated account
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn process_delegation<'a, 'info>(
+    payer: &'a AccountInfo<'info>,
+    delegated_account: &'a AccountInfo<'info>,
+    owner_program: &'a AccountInfo<'info>,
+    delegate_buffer_account: &'a AccountInfo<'info>,
+    delegation_record_account: &'a AccountInfo<'info>,
+    delegation_metadata_account: &'a AccountInfo<'info>,
+    system_program: &'a AccountInfo<'info>,
+    args: DelegateArgs,
+) -> ProgramResult {
     load_owned_pda(delegated_account, &crate::id(), "delegated account")?;
     load_program(system_program, system_program::id(), "system program")?;
 
@@ -133,7 +159,7 @@ pub fn process_delegate(
     // Initialize the delegation record
     let delegation_record = DelegationRecord {
         owner: *owner_program.key,
-        authority: args.validator.unwrap_or(Pubkey::default()),
+        authority: args.validator.unwrap_or_default(),
         commit_frequency_ms: args.commit_frequency_ms as u64,
         delegation_slot: solana_program::clock::Clock::get()?.slot,
         lamports: delegated_account.lamports(),
@@ -148,6 +174,16 @@ pub fn process_delegate(
         last_update_external_slot: 0,
         is_undelegatable: false,
         rent_payer: *payer.key,
+        expiry: args.expiry,
+        undelegation_request_slot: 0,
+        next_commit_nonce: 0,
+        next_finalize_nonce: 0,
+        last_commit_timestamp: solana_program::clock::Clock::get()?.unix_timestamp,
+        is_stale: false,
+        last_commit_lamports: 0,
+        last_commit_slot: 0,
+        dispute_slot: 0,
+        has_upheld_dispute: false,
     };
     delegation_metadata.to_bytes_with_discriminator(&mut delegation_metadata_bytes)?;
 
@@ -173,5 +209,14 @@ pub fn process_delegate(
         (*delegated_data).copy_from_slice(&delegate_buffer_data);
     }
 
+    DelegatedEvent {
+        delegated_account: *delegated_account.key,
+        owner: delegation_record.owner,
+        authority: delegation_record.authority,
+        commit_frequency_ms: delegation_record.commit_frequency_ms,
+        delegation_slot: delegation_record.delegation_slot,
+    }
+    .emit()?;
+
     Ok(())
 }
@@ -0,0 +1,138 @@
+use crate::args::DelegateEphemeralBalanceArgs;
+use crate::processor::utils::loaders::{load_owned_pda, load_pda, load_program, load_signer};
+use crate::processor::utils::token::load_token_account;
+use crate::state::EphemeralTokenBalance;
+use crate::{
+    ephemeral_token_balance_escrow_seeds_from_balance, ephemeral_token_balance_seeds_from_payer,
+};
+use borsh::BorshDeserialize;
+use solana_program::msg;
+use solana_program::program::invoke_signed;
+use solana_program::program_error::ProgramError;
+use solana_program::system_program;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Delegates an ephemeral token balance, so that its tokens can be spent inside
+/// the ephemeral.
+///
+/// Accounts:
+///
+/// 0: `[writable]` payer account
+/// 1: `[signer]`   delegatee account from which the ephemeral token balance is derived
+/// 2: `[]`         mint of the tokens
+/// 3: `[writable]` ephemeral token balance account
+/// 4: `[]`         token escrow of the ephemeral token balance
+/// 5: `[writable]` delegate buffer PDA
+/// 6: `[writable]` delegation record PDA
+/// 7: `[writable]` delegation metadata PDA
+/// 8: `[]`         system program
+/// 9: `[]`         this program
+///
+/// Requirements:
+///
+/// - ephemeral token balance and its token escrow are initialized, see
+///   [crate::processor::process_top_up_ephemeral_token_balance]
+/// - same as [crate::processor::delegate::process_delegate]
+///
+/// Steps:
+///
+/// 1. Sync the amount of the ephemeral token balance with the tokens of its escrow
+/// 2. Delegates the ephemeral token balance to the delegation program, which remains its
+///    owner, so that the tokens spent in the ephemeral are settled on finalize, see
+///    [crate::processor::process_finalize]
+pub fn process_delegate_ephemeral_token_balance(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let mut args = DelegateEphemeralBalanceArgs::try_from_slice(data)?;
+    let [payer, pubkey, mint, ephemeral_token_balance_account, escrow_account, delegate_buffer, delegation_record, delegation_metadata, system_program, delegation_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(payer, "payer")?;
+    load_signer(pubkey, "delegatee")?;
+    load_program(system_program, system_program::id(), "system program")?;
+    load_program(delegation_program, crate::id(), "delegation program")?;
+
+    // Check seeds and derive bump
+    let ephemeral_token_balance_seeds: &[&[u8]] =
+        ephemeral_token_balance_seeds_from_payer!(pubkey.key, mint.key, args.index);
+    let ephemeral_token_balance_bump = load_pda(
+        ephemeral_token_balance_account,
+        ephemeral_token_balance_seeds,
+        &crate::id(),
+        true,
+        "ephemeral token balance",
+    )?;
+    load_owned_pda(
+        ephemeral_token_balance_account,
+        &crate::id(),
+        "ephemeral token balance",
+    )?;
+
+    // The token escrow is only read, but may be writable when topped up in the same transaction
+    let (escrow_pda, _) = Pubkey::find_program_address(
+        ephemeral_token_balance_escrow_seeds_from_balance!(ephemeral_token_balance_account.key),
+        &crate::id(),
+    );
+    if !escrow_account.key.eq(&escrow_pda) {
+        msg!("Invalid seeds for token escrow ({})", escrow_account.key);
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Make the tokens of the escrow available in the ephemeral
+    let escrow_amount = load_token_account(
+        escrow_account,
+        mint.key,
+        Some(ephemeral_token_balance_account.key),
+        "token escrow",
+    )?;
+    let mut ephemeral_token_balance_data = ephemeral_token_balance_account.try_borrow_mut_data()?;
+    let ephemeral_token_balance = EphemeralTokenBalance::try_from_bytes_with_discriminator_mut(
+        &mut ephemeral_token_balance_data,
+    )?;
+    ephemeral_token_balance.amount = escrow_amount;
+    drop(ephemeral_token_balance_data);
+
+    // Set the delegation seeds
+    args.delegate_args.seeds = ephemeral_token_balance_seeds
+        .iter()
+        .map(|s| s.to_vec())
+        .collect();
+
+    // Generate the ephemeral token balance PDA's signer seeds
+    let ephemeral_token_balance_bump_slice = &[ephemeral_token_balance_bump];
+    let ephemeral_token_balance_signer_seeds = [
+        ephemeral_token_balance_seeds,
+        &[ephemeral_token_balance_bump_slice],
+    ]
+    .concat();
+
+    // Create the delegation ix, the delegation program stays the owner
+    let ix = crate::instruction_builder::delegate(
+        *payer.key,
+        *ephemeral_token_balance_account.key,
+        Some(crate::id()),
+        args.delegate_args,
+    );
+
+    // Invoke signed delegation instruction
+    invoke_signed(
+        &ix,
+        &[
+            delegation_program.clone(),
+            payer.clone(),
+            ephemeral_token_balance_account.clone(),
+            delegate_buffer.clone(),
+            delegation_record.clone(),
+            delegation_metadata.clone(),
+            system_program.clone(),
+        ],
+        &[&ephemeral_token_balance_signer_seeds],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use borsh::BorshDeserialize;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+use crate::args::DelegateManyArgs;
+use crate::processor::process_delegation;
+
+/// Number of remaining accounts needed by each delegation
+const ACCOUNTS_PER_DELEGATION: usize = 4;
+
+/// Delegates many accounts of the same owner program at once
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the account paying for the transaction
+/// 1: `[]`         the owner of the accounts to delegate
+/// 2: `[]`         the system program
+///
+/// Remaining accounts, for each delegation in the args:
+///
+/// 0: `[signer]`   the account to delegate
+/// 1: `[writable]` the buffer account we use to temporarily store the account data
+///                 during owner change
+/// 2: `[writable]` the delegation record account
+/// 3: `[writable]` the delegation metadata account
+///
+/// Requirements:
+///
+/// - there is exactly one set of remaining accounts per delegation args
+/// - each delegation has the same requirements as [crate::processor::process_delegate]
+///
+/// Steps:
+///
+/// 1. Delegate each account as in [crate::processor::process_delegate]
+///
+/// Usage:
+///
+/// This instruction is meant to be called via CPI with the owning program signing for all
+/// the delegated accounts.
+pub fn process_delegate_many(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [payer, owner_program, system_program, delegations_accounts @ ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let args = DelegateManyArgs::try_from_slice(data)?;
+
+    if delegations_accounts.len() != args.delegations.len() * ACCOUNTS_PER_DELEGATION {
+        msg!(
+            "Expected {} accounts for {} delegations, but got {}",
+            args.delegations.len() * ACCOUNTS_PER_DELEGATION,
+            args.delegations.len(),
+            delegations_accounts.len()
+        );
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    for (delegation_accounts, delegate_args) in delegations_accounts
+        .chunks_exact(ACCOUNTS_PER_DELEGATION)
+        .zip(args.delegations)
+    {
+        let [delegated_account, delegate_buffer_account, delegation_record_account, delegation_metadata_account] =
+            delegation_accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        process_delegation(
+            payer,
+            delegated_account,
+            owner_program,
+            delegate_buffer_account,
+            delegation_record_account,
+            delegation_metadata_account,
+            system_program,
+            delegate_args,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::args::DepositValidatorBondArgs;
+use crate::error::DlpError;
+use crate::processor::utils::loaders::{load_pda, load_program, load_signer};
+use crate::processor::utils::pda::create_pda;
+use crate::state::ValidatorBond;
+use crate::validator_bond_seeds_from_validator;
+use borsh::BorshDeserialize;
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::system_instruction::transfer;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Deposit lamports in the validator bond, which backs the commits of the validator
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator funding its bond
+/// 1: `[writable]` the validator bond PDA
+/// 2: `[]`         the system program
+///
+/// Requirements:
+///
+/// - validator bond is initialized or owned by the system program in
+///   which case it is created
+///
+/// Steps:
+///
+/// 1. Load the validator bond or create it
+/// 2. Transfer the lamports from the validator to the validator bond
+/// 3. Increase the bonded amount
+pub fn process_deposit_validator_bond(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = DepositValidatorBondArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [validator, validator_bond_account, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(validator, "validator")?;
+    load_program(system_program, system_program::id(), "system program")?;
+    let validator_bond_bump = load_pda(
+        validator_bond_account,
+        validator_bond_seeds_from_validator!(validator.key),
+        &crate::id(),
+        true,
+        "validator bond",
+    )?;
+
+    // Create the validator bond if it doesn't exist
+    if validator_bond_account.owner.eq(system_program.key) {
+        create_pda(
+            validator_bond_account,
+            &crate::id(),
+            ValidatorBond::size_with_discriminator(),
+            validator_bond_seeds_from_validator!(validator.key),
+            validator_bond_bump,
+            system_program,
+            validator,
+        )?;
+        let validator_bond = ValidatorBond {
+            validator: *validator.key,
+            amount: 0,
+            unbonding_amount: 0,
+            unbonding_end_slot: 0,
+            stale_delegations: 0,
+        };
+        let mut validator_bond_data = validator_bond_account.try_borrow_mut_data()?;
+        validator_bond.to_bytes_with_discriminator(&mut validator_bond_data)?;
+    }
+
+    invoke(
+        &transfer(validator.key, validator_bond_account.key, args.amount),
+        &[
+            validator.clone(),
+            validator_bond_account.clone(),
+            system_program.clone(),
+        ],
+    )?;
+
+    let mut validator_bond_data = validator_bond_account.try_borrow_mut_data()?;
+    let validator_bond =
+        ValidatorBond::try_from_bytes_with_discriminator_mut(&mut validator_bond_data)?;
+    validator_bond.amount = validator_bond
+        .amount
+        .checked_add(args.amount)
+        .ok_or(DlpError::Overflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use crate::args::DisputeCommitArgs;
+use crate::consts::DISPUTE_BOND_LAMPORTS;
+use crate::error::DlpError;
+use crate::processor::utils::delegation_metadata::{
+    resize_delegation_metadata, write_delegation_metadata,
+};
+use crate::processor::utils::loaders::{
+    load_initialized_commit_record, load_initialized_delegation_metadata, load_program, load_signer,
+};
+use crate::state::{CommitRecord, DelegationMetadata};
+use borsh::BorshDeserialize;
+use solana_program::clock::Clock;
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::system_instruction::transfer;
+use solana_program::sysvar::Sysvar;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey, system_program,
+};
+
+/// Dispute a pending commit during its challenge period, which prevents it from being finalized
+/// until the dispute is resolved
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the challenger disputing the commit and paying the dispute bond
+/// 1: `[]`         the delegated account
+/// 2: `[writable]` the commit record of the disputed commit
+/// 3: `[writable]` the delegation metadata PDA
+/// 4: `[]`         the system program
+///
+/// Requirements:
+///
+/// - commit record is initialized and derived from the delegated account and the commit nonce
+/// - delegation metadata is initialized
+/// - commit challenge period has not elapsed
+/// - commit was not already disputed
+/// - no other dispute of the delegation is pending or was upheld
+///
+/// NOTE: this operation is permissionless, the challenger bonds
+///       [crate::consts::DISPUTE_BOND_LAMPORTS] in the commit record. The dispute is then
+///       resolved with [crate::processor::process_resolve_dispute]: the bond is refunded if
+///       the dispute is upheld, and paid to the validator of the commit otherwise. The admin
+///       can also uphold the dispute by slashing the validator bond, see
+///       [crate::processor::process_slash_validator_bond].
+///
+/// Steps:
+///
+/// 1. Check that the commit can still be disputed
+/// 2. Transfer the dispute bond from the challenger to the commit record
+/// 3. Flag the commit record with the challenger
+/// 4. Record the pending dispute in the delegation metadata
+pub fn process_dispute_commit(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = DisputeCommitArgs::try_from_slice(data)?;
+
+    let [challenger, delegated_account, commit_record_account, delegation_metadata_account, system_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(challenger, "challenger")?;
+    load_initialized_commit_record(
+        delegated_account,
+        commit_record_account,
+        args.commit_nonce,
+        true,
+    )?;
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    let mut commit_record_data = commit_record_account.try_borrow_mut_data()?;
+    let commit_record =
+        CommitRecord::try_from_bytes_with_discriminator_mut(&mut commit_record_data)?;
+
+    // Check that the commit can still be disputed
+    let current_slot = Clock::get()?.slot;
+    if current_slot >= commit_record.challenge_period_end() {
+        msg!(
+            "Commit could be disputed until slot {}, current slot is {}",
+            commit_record.challenge_period_end(),
+            current_slot
+        );
+        return Err(DlpError::ChallengePeriodElapsed.into());
+    }
+    if commit_record.is_disputed() {
+        msg!("Commit was already disputed by {}", commit_record.disputer);
+        return Err(DlpError::CommitDisputed.into());
+    }
+
+    // Only one dispute of the delegation is resolved at a time, and once one is upheld the
+    // pending commits are discarded on undelegation anyway
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+    if delegation_metadata.dispute_slot > 0 || delegation_metadata.has_upheld_dispute {
+        msg!(
+            "delegation metadata ({}) already has a pending or upheld dispute",
+            delegation_metadata_account.key
+        );
+        return Err(DlpError::CommitDisputed.into());
+    }
+
+    commit_record.disputer = *challenger.key;
+    drop(commit_record_data);
+
+    // Bond the challenger in the commit record, until the dispute is resolved
+    invoke(
+        &transfer(
+            challenger.key,
+            commit_record_account.key,
+            DISPUTE_BOND_LAMPORTS,
+        ),
+        &[
+            challenger.clone(),
+            commit_record_account.clone(),
+            system_program.clone(),
+        ],
+    )?;
+
+    delegation_metadata.dispute_slot = current_slot;
+    resize_delegation_metadata(
+        challenger,
+        delegation_metadata_account,
+        system_program,
+        &delegation_metadata,
+    )?;
+    write_delegation_metadata(delegation_metadata_account, &delegation_metadata)
+}
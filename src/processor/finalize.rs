@@ -1,12 +1,36 @@
+use crate::args::{CommitAction, ExternalCommittedState, ExternalValidateCommitArgs, StatePatch};
+use crate::consts::EXTERNAL_VALIDATE_COMMIT_DISCRIMINATOR;
 use crate::error::DlpError;
+use crate::event::{CommitSkippedEvent, Event, FinalizedEvent};
+use crate::pda::program_config_from_program_id;
+use crate::processor::utils::authority::{
+    load_program_config_validate_commits, load_validator_fees_vault_settings,
+    validate_delegation_authority,
+};
+use crate::processor::utils::commit_actions::execute_commit_actions;
+use crate::processor::utils::delegation_metadata::{
+    queue_legacy_commit, resize_delegation_metadata, write_delegation_metadata,
+};
+use crate::processor::utils::fees_ledger::record_validator_fees;
 use crate::processor::utils::loaders::{
     is_uninitialized_account, load_initialized_commit_record, load_initialized_commit_state,
     load_initialized_delegation_metadata, load_initialized_delegation_record,
-    load_initialized_validator_fees_vault, load_owned_pda, load_program, load_signer,
+    load_initialized_validator_fees_vault, load_owned_pda, load_pda, load_program, load_signer,
 };
 use crate::processor::utils::pda::close_pda;
-use crate::state::{CommitRecord, DelegationMetadata, DelegationRecord};
+use crate::processor::utils::state_patch::apply_state_patches;
+use crate::processor::utils::token::{load_token_account, transfer_tokens};
+use crate::state::{
+    CommitKind, CommitRecord, DelegationMetadata, DelegationRecord, EphemeralTokenBalance,
+};
+use crate::{consts::TOKEN_PROGRAM_ID, ephemeral_token_balance_escrow_seeds_from_balance};
+use borsh::{to_vec, BorshDeserialize};
+use solana_program::clock::Clock;
+use solana_program::hash::hash;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::invoke;
 use solana_program::program_error::ProgramError;
+use solana_program::sysvar::Sysvar;
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey, system_program,
 };
@@ -24,74 +48,295 @@ use solana_program::{
 /// 6: `[writable]` the validator fees vault account
 /// 7: `[]`         the system program
 ///
+/// Optional accounts, identified by the program config PDA leading them:
+///
+///  8: `[]`         the program config account of the owner program
+///  9: `[]`         the owner program
+/// 10: `[writable]` the payer of the commit, refunded the rent and the deposit of the commit PDAs
+///
+/// Remaining accounts:
+///
+/// - for a commit of an abandoned bundle, the closed commit bundle record, see
+///   [crate::processor::process_abandon_commit_bundle]
+/// - for an ephemeral token balance, the token escrow, the token account of the validator
+///   payout destination and the token program, see [crate::processor::process_delegate_ephemeral_token_balance]
+/// - the programs and accounts used by the actions of the commit, if any, see
+///   [crate::args::CommitAction]
+///
 /// Requirements:
 ///
 /// - delegated account is owned by delegation program
 /// - delegation record is initialized
 /// - delegation metadata is initialized
 /// - validator fees vault is initialized
-/// - commit state is initialized and derived from the delegated account key
-/// - commit record is initialized and derived from the delegated account key
+/// - commit state is initialized and derived from the delegated account key and the next finalize nonce
+/// - commit record is initialized and derived from the delegated account key and the next finalize nonce
 /// - account mentioned in commit record is the same as the delegated account
 /// - identity mentioned in commit record is the same as the validator
+/// - payer mentioned in commit record is the same as the commit payer, or the validator if the
+///   optional accounts are absent
+/// - validator is the delegation record authority, unless the authority is the default pubkey
+/// - commit is not part of a bundle, see [crate::processor::process_finalize_bundle], unless
+///   the bundle was abandoned
+/// - commit challenge period has elapsed and the commit was not disputed,
+///   see [crate::processor::process_dispute_commit]
+/// - program config is derived from the delegation record owner
+/// - owner program is the delegation record owner and accepts the new state, if its program
+///   config requires to validate commits
+/// - the optional accounts are provided if the program config required to validate commits
+///   when the state was committed, see [crate::state::CommitRecord::validate_commit]
+///
+/// NOTE: that if there is no pending commit then we skip the finalize without an error
+///       in order to not affect other finalize instructions that may be bundled in the
+///       same transaction.
 ///
-/// NOTE: that if neither commit state nor commit record are as required then
-///       we skip the finalize without an error in order to not affect other finalize
-///       instructions that may be bundled in the same transaction.
+/// NOTE: a commit rejected by the owner program can never be finalized, so the account can
+///       only be restored to its last finalized state with [crate::processor::process_force_undelegate]
+///
+/// NOTE: the commit actions run in order within this instruction, and any failing action fails
+///       the finalize, which can be retried. The finalize transaction should request
+///       [crate::consts::RECOMMENDED_COMMIT_ACTION_COMPUTE_UNITS] per action on top of its own
+///       budget, which is not enforced
 ///
 /// Steps:
 ///
-/// 1. Validate the new state (currently state is valid if committed from a whitelisted validator)
-/// 2. If the state is valid, copy the committed state to the delegated account
-/// 3. Close the state diff account
-/// 4. Close the commit state record
+/// 1. Validate the new state (currently state is valid if committed from a whitelisted validator,
+///    not disputed during its challenge period and accepted by the owner program, if its program
+///    config requires the owner program to validate the commits with a CPI receiving the
+///    delegated account and the account holding the committed state, see
+///    [crate::args::ExternalValidateCommitArgs])
+/// 2. If the state is valid, copy the committed state to the delegated account, or apply
+///    the committed patches on top of the delegated account data for a diff commit.
+///    A hash commit must be finalized with its data, see [crate::processor::process_finalize_with_data]
+/// 3. Invoke the commit actions in order, signed by the commit action signer of the delegated
+///    account, see [crate::pda::commit_action_signer_pda_from_delegated_account]
+/// 4. Settle the tokens spent by an ephemeral token balance, moving them from its token escrow
+///    to the validator payout destination
+/// 5. Close the state diff account, refunding the commit payer
+/// 6. Close the commit state record, refunding the commit payer
+/// 7. Increment the next finalize nonce
+///
+/// A commit queued at an older slot than the last finalized one, before commits were queued in
+/// slot order, is discarded without being applied, unless it is part of a bundle. Its lamports
+/// are still settled, since the next commit deposited against them.
+///
+/// A commit pending since before commits were queued is finalized as the first commit of the
+/// delegation, see [crate::pda::commit_nonce_seed].
 pub fn process_finalize(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     _data: &[u8],
 ) -> ProgramResult {
-    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, system_program] =
+    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, system_program, remaining_accounts @ ..] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
+    // The owner program accounts and the commit payer are absent from the finalize
+    // instructions built before commits could be validated or relayed
+    load_initialized_delegation_record(delegated_account, delegation_record_account, true)?;
+    let delegation_record_data = delegation_record_account.try_borrow_data()?;
+    let owner = DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_data)?.owner;
+    drop(delegation_record_data);
+    let (owner_program_accounts, commit_payer, action_accounts) = match remaining_accounts {
+        [program_config_account, owner_program, commit_payer, action_accounts @ ..]
+            if program_config_account
+                .key
+                .eq(&program_config_from_program_id(&owner)) =>
+        {
+            (
+                Some(OwnerProgramAccounts {
+                    program_config_account,
+                    owner_program,
+                }),
+                commit_payer,
+                action_accounts,
+            )
+        }
+        _ => (None, validator, remaining_accounts),
+    };
+
+    let finalize_args = FinalizeInternalArgs {
+        commit_data: None,
+        commit_data_account: None,
+        validator,
+        commit_payer,
+        delegated_account,
+        commit_state_account,
+        commit_record_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        validator_fees_vault,
+        system_program,
+        owner_program_accounts,
+        action_accounts,
+    };
+    process_finalize_internal(finalize_args)
+}
+
+/// The accounts of the owner program, which validates the new state if its program config
+/// requires it
+pub(crate) struct OwnerProgramAccounts<'a, 'info> {
+    pub(crate) program_config_account: &'a AccountInfo<'info>,
+    pub(crate) owner_program: &'a AccountInfo<'info>,
+}
+
+/// Arguments for the finalize internal function
+pub(crate) struct FinalizeInternalArgs<'a, 'info> {
+    pub(crate) commit_data: Option<&'a [u8]>,
+    pub(crate) commit_data_account: Option<&'a AccountInfo<'info>>,
+    pub(crate) validator: &'a AccountInfo<'info>,
+    pub(crate) commit_payer: &'a AccountInfo<'info>,
+    pub(crate) delegated_account: &'a AccountInfo<'info>,
+    pub(crate) commit_state_account: &'a AccountInfo<'info>,
+    pub(crate) commit_record_account: &'a AccountInfo<'info>,
+    pub(crate) delegation_record_account: &'a AccountInfo<'info>,
+    pub(crate) delegation_metadata_account: &'a AccountInfo<'info>,
+    pub(crate) validator_fees_vault: &'a AccountInfo<'info>,
+    pub(crate) system_program: &'a AccountInfo<'info>,
+    pub(crate) owner_program_accounts: Option<OwnerProgramAccounts<'a, 'info>>,
+    pub(crate) action_accounts: &'a [AccountInfo<'info>],
+}
+
+/// Finalize the oldest pending commit of a delegated account, if any
+pub(crate) fn process_finalize_internal(args: FinalizeInternalArgs) -> ProgramResult {
+    let FinalizeInternalArgs {
+        commit_data,
+        commit_data_account,
+        validator,
+        commit_payer,
+        delegated_account,
+        commit_state_account,
+        commit_record_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        validator_fees_vault,
+        system_program,
+        owner_program_accounts,
+        action_accounts,
+    } = args;
+
     load_signer(validator, "validator")?;
     load_owned_pda(delegated_account, &crate::id(), "delegated account")?;
     load_initialized_delegation_record(delegated_account, delegation_record_account, true)?;
     load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
     load_initialized_validator_fees_vault(validator, validator_fees_vault, true)?;
     load_program(system_program, system_program::id(), "system program")?;
-    let load_cs = load_initialized_commit_state(delegated_account, commit_state_account, true);
-    let load_cr = load_initialized_commit_record(delegated_account, commit_record_account, true);
 
     // Since finalize instructions are typically bundled, we return without error
     // if there is nothing to be finalized, so that correct finalizes are executed
-    if let (Err(ProgramError::InvalidAccountOwner), Err(ProgramError::InvalidAccountOwner)) =
-        (&load_cs, &load_cr)
-    {
-        if is_uninitialized_account(commit_state_account)
-            && is_uninitialized_account(commit_record_account)
-        {
-            msg!("No state to be finalized. Skipping finalize.");
-            return Ok(());
-        }
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+    let has_legacy_commit = !delegation_metadata.has_pending_commits()
+        && queue_legacy_commit(
+            delegated_account,
+            commit_record_account,
+            &mut delegation_metadata,
+        )?;
+    if !delegation_metadata.has_pending_commits() {
+        msg!("No state to be finalized. Skipping finalize.");
+        return Ok(());
+    }
+
+    // Resize the delegation metadata created with an older layout, before any lamports move
+    resize_delegation_metadata(
+        validator,
+        delegation_metadata_account,
+        system_program,
+        &delegation_metadata,
+    )?;
+    if has_legacy_commit {
+        write_delegation_metadata(delegation_metadata_account, &delegation_metadata)?;
     }
-    load_cs?;
-    load_cr?;
 
+    // Commits are finalized in order, starting from the oldest pending one
+    let commit_nonce = delegation_metadata.next_finalize_nonce;
+    load_initialized_commit_state(delegated_account, commit_state_account, commit_nonce, true)?;
+    load_initialized_commit_record(delegated_account, commit_record_account, commit_nonce, true)?;
+
+    // A commit of a bundle can only be finalized alone once the bundle was abandoned, its closed
+    // bundle record preceding the remaining accounts
+    let commit_record_data = commit_record_account.try_borrow_data()?;
+    let bundle = CommitRecord::try_from_bytes_with_legacy_layout(&commit_record_data)?.bundle;
+    drop(commit_record_data);
+    let (commit_bundle_record, action_accounts) = if bundle.eq(&Pubkey::default()) {
+        (None, action_accounts)
+    } else {
+        match action_accounts {
+            [commit_bundle_record, action_accounts @ ..]
+                if commit_bundle_record.key.eq(&bundle)
+                    && is_uninitialized_account(commit_bundle_record) =>
+            {
+                (Some(commit_bundle_record), action_accounts)
+            }
+            _ => {
+                msg!(
+                    "Commit of bundle {} must be finalized with its bundle, unless the bundle was abandoned",
+                    bundle
+                );
+                return Err(DlpError::InvalidCommitBundle.into());
+            }
+        }
+    };
+
+    finalize_commit(
+        validator,
+        commit_payer,
+        delegated_account,
+        commit_state_account,
+        commit_record_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        validator_fees_vault,
+        owner_program_accounts.as_ref(),
+        action_accounts,
+        commit_bundle_record,
+        commit_data,
+        commit_data_account,
+    )
+}
+
+/// Apply the oldest pending commit to the delegated account, invoke its actions, settle the
+/// lamports and close the commit PDAs, refunding the commit payer. The commit must belong to
+/// the bundle of the given bundle record, if any, which is closed if the bundle was abandoned.
+/// The commit data must be supplied for a hash commit, and only for a hash commit, along with
+/// the account holding it, if any.
+/// The owner program validates the new state if its program config requires it
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn finalize_commit<'a, 'info>(
+    validator: &'a AccountInfo<'info>,
+    commit_payer: &'a AccountInfo<'info>,
+    delegated_account: &'a AccountInfo<'info>,
+    commit_state_account: &'a AccountInfo<'info>,
+    commit_record_account: &'a AccountInfo<'info>,
+    delegation_record_account: &'a AccountInfo<'info>,
+    delegation_metadata_account: &'a AccountInfo<'info>,
+    validator_fees_vault: &'a AccountInfo<'info>,
+    owner_program_accounts: Option<&OwnerProgramAccounts<'a, 'info>>,
+    action_accounts: &'a [AccountInfo<'info>],
+    commit_bundle_record: Option<&'a AccountInfo<'info>>,
+    commit_data: Option<&[u8]>,
+    commit_data_account: Option<&'a AccountInfo<'info>>,
+) -> ProgramResult {
     // Load delegation metadata
-    let mut delegation_metadata_data = delegation_metadata_account.try_borrow_mut_data()?;
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
     let mut delegation_metadata =
         DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
 
     let mut delegation_record_data = delegation_record_account.try_borrow_mut_data()?;
     let delegation_record =
         DelegationRecord::try_from_bytes_with_discriminator_mut(&mut delegation_record_data)?;
 
+    // Check that the validator is allowed to finalize for the delegated account
+    validate_delegation_authority(delegation_record, validator)?;
+
     // Load commit record
     let commit_record_data = commit_record_account.try_borrow_data()?;
-    let commit_record = CommitRecord::try_from_bytes_with_discriminator(&commit_record_data)?;
+    let commit_record = CommitRecord::try_from_bytes_with_legacy_layout(&commit_record_data)?;
 
     // Check that the commit record is the right one
     if !commit_record.account.eq(delegated_account.key) {
@@ -100,11 +345,223 @@ pub fn process_finalize(
     if !commit_record.identity.eq(validator.key) {
         return Err(DlpError::InvalidReimbursementAccount.into());
     }
+    if !commit_record.payer.eq(commit_payer.key) {
+        msg!(
+            "Expected commit payer to be {}, but got {}",
+            commit_record.payer,
+            commit_payer.key
+        );
+        return Err(DlpError::InvalidReimbursementAccount.into());
+    }
+    let bundle = commit_bundle_record
+        .map(|commit_bundle_record| *commit_bundle_record.key)
+        .unwrap_or_default();
+    if !commit_record.bundle.eq(&bundle) {
+        msg!(
+            "Expected commit bundle to be {}, but got {}",
+            bundle,
+            commit_record.bundle
+        );
+        return Err(DlpError::InvalidCommitBundle.into());
+    }
+
+    // Check that the commit data is supplied if and only if the commit stores its hash
+    let commit_kind =
+        CommitKind::try_from(commit_record.kind).map_err(|_| ProgramError::InvalidAccountData)?;
+    match (commit_kind, commit_data) {
+        (CommitKind::Hash, Some(commit_data)) => {
+            if hash(commit_data).to_bytes() != commit_record.data_hash {
+                msg!("Commit data does not match the committed hash");
+                return Err(DlpError::InvalidCommitHash.into());
+            }
+        }
+        (CommitKind::Hash, None) => {
+            msg!("Commit data must be supplied to finalize a hash commit");
+            return Err(DlpError::InvalidCommitHash.into());
+        }
+        (_, Some(_)) => {
+            msg!("Commit data can only be supplied to finalize a hash commit");
+            return Err(DlpError::InvalidCommitHash.into());
+        }
+        (_, None) => {}
+    }
+
+    // Check that the commit was not disputed, and can no longer be
+    if commit_record.is_disputed() {
+        msg!("Commit was disputed by {}", commit_record.disputer);
+        return Err(DlpError::CommitDisputed.into());
+    }
+    let current_slot = Clock::get()?.slot;
+    if current_slot < commit_record.challenge_period_end() {
+        msg!(
+            "Commit can be finalized from slot {}, current slot is {}",
+            commit_record.challenge_period_end(),
+            current_slot
+        );
+        return Err(DlpError::ChallengePeriodNotElapsed.into());
+    }
+
+    // Dequeue the commit
+    delegation_metadata.next_finalize_nonce = delegation_metadata
+        .next_finalize_nonce
+        .checked_add(1)
+        .ok_or(DlpError::Overflow)?;
+
+    // A more recent commit was finalized since this one was queued, it must not be applied.
+    // Commits are now queued in slot order, so this only happens to commits queued before,
+    // and a bundle cannot be finalized without all its commits, unless it was abandoned
+    if commit_record.slot < delegation_metadata.last_update_external_slot {
+        if commit_bundle_record.is_some_and(|record| !is_uninitialized_account(record)) {
+            msg!(
+                "Bundled commit at slot {} is outdated, previous slot is {}",
+                commit_record.slot,
+                delegation_metadata.last_update_external_slot
+            );
+            return Err(DlpError::OutdatedSlot.into());
+        }
+        msg!(
+            "Slot {} is outdated, previous slot is {}. Discarding commit",
+            commit_record.slot,
+            delegation_metadata.last_update_external_slot
+        );
+        CommitSkippedEvent {
+            delegated_account: *delegated_account.key,
+            validator: *validator.key,
+            slot: commit_record.slot,
+            last_update_external_slot: delegation_metadata.last_update_external_slot,
+        }
+        .emit()?;
+
+        // The next commit deposited against the lamports of this one, so they are still settled
+        settle_lamports_balance(
+            delegated_account,
+            commit_state_account,
+            validator,
+            validator_fees_vault,
+            delegation_record.lamports,
+            commit_record.lamports,
+        )?;
+        delegation_record.lamports = delegated_account.lamports();
+        if !delegation_metadata.has_pending_commits() {
+            delegation_metadata.last_commit_lamports = delegation_record.lamports;
+        }
+        write_delegation_metadata(delegation_metadata_account, &delegation_metadata)?;
+        drop(commit_record_data);
+        close_pda(commit_state_account, commit_payer)?;
+        close_pda(commit_record_account, commit_payer)?;
+        return Ok(());
+    }
+
+    // The commit state account holds the committed data, followed by the commit actions
+    let commit_state_account_data = commit_state_account.try_borrow_data()?;
+    let actions_offset = commit_state_account_data
+        .len()
+        .checked_sub(commit_record.actions_len as usize)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let (commit_state_data, commit_actions_data) =
+        commit_state_account_data.split_at(actions_offset);
+
+    // An ephemeral token balance is owned by the delegation program, its settlement accounts
+    // precede the accounts of the commit actions
+    let (token_settlement, action_accounts) = load_ephemeral_token_settlement(
+        delegated_account,
+        &delegation_record.owner,
+        action_accounts,
+    )?;
+
+    // Let the owner program reject the new state before it is applied
+    let owner_program = match owner_program_accounts {
+        Some(owner_program_accounts) => load_program_config_validate_commits(
+            owner_program_accounts.program_config_account,
+            delegation_record.owner,
+        )?
+        .then_some(owner_program_accounts.owner_program),
+        None if commit_record.requires_validation() => {
+            msg!(
+                "Commit of {} must be validated by its owner program {}",
+                delegated_account.key,
+                delegation_record.owner
+            );
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        None => None,
+    };
+    if let Some(owner_program) = owner_program {
+        load_program(owner_program, delegation_record.owner, "owner program")?;
+        let (state, state_account) = match (commit_kind, commit_data_account) {
+            (CommitKind::Full | CommitKind::Diff, _) => (
+                ExternalCommittedState::Account {
+                    len: commit_state_data.len() as u64,
+                },
+                Some(commit_state_account),
+            ),
+            (CommitKind::Hash, Some(commit_data_account)) => (
+                ExternalCommittedState::Account {
+                    len: commit_data.unwrap_or_default().len() as u64,
+                },
+                Some(commit_data_account),
+            ),
+            (CommitKind::Hash, None) => (
+                ExternalCommittedState::Inline(commit_data.unwrap_or_default().to_vec()),
+                None,
+            ),
+        };
+        cpi_external_validate_commit(
+            delegated_account,
+            owner_program,
+            commit_kind,
+            state,
+            state_account,
+        )?;
+    }
+
+    // Copying the new commit state to the delegated account, or patching it for a diff commit
+    match commit_kind {
+        CommitKind::Full => {
+            delegated_account.realloc(commit_state_data.len(), false)?;
+            let mut delegated_account_data = delegated_account.try_borrow_mut_data()?;
+            (*delegated_account_data).copy_from_slice(commit_state_data);
+        }
+        CommitKind::Diff => {
+            let patches = Vec::<StatePatch>::try_from_slice(commit_state_data)?;
+            let mut delegated_account_data = delegated_account.try_borrow_mut_data()?;
+            apply_state_patches(&mut delegated_account_data, &patches)?;
+        }
+        CommitKind::Hash => {
+            let commit_data = commit_data.unwrap_or_default();
+            delegated_account.realloc(commit_data.len(), false)?;
+            let mut delegated_account_data = delegated_account.try_borrow_mut_data()?;
+            (*delegated_account_data).copy_from_slice(commit_data);
+        }
+    }
+
+    // Invoke the commit actions on top of the new state. They run before the lamports are
+    // settled, since the accounts they use must hold balanced lamports
+    let commit_actions = if commit_actions_data.is_empty() {
+        vec![]
+    } else {
+        Vec::<CommitAction>::try_from_slice(commit_actions_data)?
+    };
+    drop(commit_state_account_data);
+    execute_commit_actions(delegated_account, &commit_actions, action_accounts)?;
 
-    // Settle accounts lamports
+    // Settle the tokens spent by an ephemeral token balance, with a CPI to the token program
+    if let Some(token_settlement) = token_settlement {
+        settle_ephemeral_token_balance(
+            validator,
+            delegated_account,
+            validator_fees_vault,
+            &delegation_metadata,
+            token_settlement,
+        )?;
+    }
+
+    // Settle accounts lamports. Commits are finalized in order, so the delegation record holds
+    // the lamports of the previous queued commit, which this commit deposited against
     settle_lamports_balance(
         delegated_account,
         commit_state_account,
+        validator,
         validator_fees_vault,
         delegation_record.lamports,
         commit_record.lamports,
@@ -112,34 +569,178 @@ pub fn process_finalize(
 
     // Update the delegation metadata
     delegation_metadata.last_update_external_slot = commit_record.slot;
-    delegation_metadata.to_bytes_with_discriminator(&mut delegation_metadata_data.as_mut())?;
+    write_delegation_metadata(delegation_metadata_account, &delegation_metadata)?;
 
     // Update the delegation record
     delegation_record.lamports = delegated_account.lamports();
 
-    // Load commit state
-    let commit_state_data = commit_state_account.try_borrow_data()?;
-
-    // Copying the new commit state to the delegated account
-    delegated_account.realloc(commit_state_data.len(), false)?;
-    let mut delegated_account_data = delegated_account.try_borrow_mut_data()?;
-    (*delegated_account_data).copy_from_slice(&commit_state_data);
+    FinalizedEvent {
+        delegated_account: *delegated_account.key,
+        validator: *validator.key,
+        slot: commit_record.slot,
+        lamports: commit_record.lamports,
+    }
+    .emit()?;
 
     // Drop remaining reference before closing accounts
     drop(commit_record_data);
-    drop(commit_state_data);
 
     // Closing accounts
-    close_pda(commit_state_account, validator)?;
-    close_pda(commit_record_account, validator)?;
+    close_pda(commit_state_account, commit_payer)?;
+    close_pda(commit_record_account, commit_payer)?;
 
     Ok(())
 }
 
-/// Settle the committed lamports to the delegated account
-fn settle_lamports_balance<'a, 'info>(
+/// The accounts settling the tokens spent by an ephemeral token balance on finalize
+pub(crate) struct EphemeralTokenSettlement<'a, 'info> {
+    pub(crate) escrow_account: &'a AccountInfo<'info>,
+    pub(crate) destination_token_account: &'a AccountInfo<'info>,
+    pub(crate) token_program: &'a AccountInfo<'info>,
+    /// The amount of the ephemeral token balance before the commit is applied
+    pub(crate) previous_amount: u64,
+}
+
+/// Split the token settlement accounts from the action accounts, if the delegated account is an
+/// ephemeral token balance, which is the only delegation owned by the delegation program
+pub(crate) fn load_ephemeral_token_settlement<'a, 'info>(
+    delegated_account: &'a AccountInfo<'info>,
+    owner: &Pubkey,
+    action_accounts: &'a [AccountInfo<'info>],
+) -> Result<
+    (
+        Option<EphemeralTokenSettlement<'a, 'info>>,
+        &'a [AccountInfo<'info>],
+    ),
+    ProgramError,
+> {
+    if !owner.eq(&crate::id()) {
+        return Ok((None, action_accounts));
+    }
+    let [escrow_account, destination_token_account, token_program, action_accounts @ ..] =
+        action_accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let delegated_account_data = delegated_account.try_borrow_data()?;
+    let previous_amount =
+        EphemeralTokenBalance::try_from_bytes_with_discriminator(&delegated_account_data)?.amount;
+    Ok((
+        Some(EphemeralTokenSettlement {
+            escrow_account,
+            destination_token_account,
+            token_program,
+            previous_amount,
+        }),
+        action_accounts,
+    ))
+}
+
+/// Transfer the tokens spent in the ephemeral by an ephemeral token balance from its token
+/// escrow to the token account of the validator payout destination. The amount of an ephemeral
+/// token balance can only decrease in the ephemeral, since its tokens are held on the base layer
+pub(crate) fn settle_ephemeral_token_balance<'a, 'info>(
+    validator: &'a AccountInfo<'info>,
+    delegated_account: &'a AccountInfo<'info>,
+    validator_fees_vault: &'a AccountInfo<'info>,
+    delegation_metadata: &DelegationMetadata,
+    token_settlement: EphemeralTokenSettlement<'a, 'info>,
+) -> Result<(), ProgramError> {
+    let delegated_account_data = delegated_account.try_borrow_data()?;
+    let ephemeral_token_balance =
+        *EphemeralTokenBalance::try_from_bytes_with_discriminator(&delegated_account_data)?;
+    drop(delegated_account_data);
+    let spent_amount = token_settlement
+        .previous_amount
+        .checked_sub(ephemeral_token_balance.amount)
+        .ok_or(DlpError::InvalidEphemeralTokenBalance)?;
+    if spent_amount == 0 {
+        return Ok(());
+    }
+
+    load_program(
+        token_settlement.token_program,
+        TOKEN_PROGRAM_ID,
+        "token program",
+    )?;
+    load_pda(
+        token_settlement.escrow_account,
+        ephemeral_token_balance_escrow_seeds_from_balance!(delegated_account.key),
+        &crate::id(),
+        true,
+        "token escrow",
+    )?;
+    load_token_account(
+        token_settlement.escrow_account,
+        &ephemeral_token_balance.mint,
+        Some(delegated_account.key),
+        "token escrow",
+    )?;
+    let payout_destination =
+        load_validator_fees_vault_settings(validator.key, validator_fees_vault)?.payout_destination;
+    load_token_account(
+        token_settlement.destination_token_account,
+        &ephemeral_token_balance.mint,
+        Some(&payout_destination),
+        "payout destination token account",
+    )?;
+
+    // The ephemeral token balance signs for its escrow with the seeds it was delegated with
+    let seeds: Vec<&[u8]> = delegation_metadata
+        .seeds
+        .iter()
+        .map(|seed| seed.as_slice())
+        .collect();
+    let (_, bump) = Pubkey::find_program_address(&seeds, &crate::id());
+    let bump_slice = &[bump];
+    let signer_seeds = [seeds.as_slice(), &[bump_slice]].concat();
+    transfer_tokens(
+        token_settlement.escrow_account,
+        token_settlement.destination_token_account,
+        delegated_account,
+        spent_amount,
+        token_settlement.token_program,
+        &[&signer_seeds],
+    )
+}
+
+/// CPI to the owner program to validate the new state of the delegated account, before it is
+/// applied. The owner program reads the committed state from the state account, if any, and
+/// rejects the commit by failing
+pub(crate) fn cpi_external_validate_commit<'a, 'info>(
+    delegated_account: &'a AccountInfo<'info>,
+    owner_program: &'a AccountInfo<'info>,
+    commit_kind: CommitKind,
+    state: ExternalCommittedState,
+    state_account: Option<&'a AccountInfo<'info>>,
+) -> ProgramResult {
+    let args = ExternalValidateCommitArgs {
+        kind: commit_kind.into(),
+        state,
+    };
+    let mut data = EXTERNAL_VALIDATE_COMMIT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&to_vec(&args)?);
+    let mut accounts = vec![AccountMeta::new_readonly(*delegated_account.key, false)];
+    let mut account_infos = vec![delegated_account.clone()];
+    if let Some(state_account) = state_account {
+        accounts.push(AccountMeta::new_readonly(*state_account.key, false));
+        account_infos.push(state_account.clone());
+    }
+    account_infos.push(owner_program.clone());
+    let external_validate_commit_instruction = Instruction {
+        program_id: *owner_program.key,
+        accounts,
+        data,
+    };
+    invoke(&external_validate_commit_instruction, &account_infos)
+}
+
+/// Settle the committed lamports to the delegated account, the lamports released by the
+/// delegated account are kept by the validator and recorded in its fees ledger
+pub(crate) fn settle_lamports_balance<'a, 'info>(
     delegated_account: &'a AccountInfo<'info>,
     commit_state_account: &'a AccountInfo<'info>,
+    validator: &'a AccountInfo<'info>,
     validator_fees_vault: &'a AccountInfo<'info>,
     delegation_record_lamports: u64,
     commit_record_lamports: u64,
@@ -172,5 +773,12 @@ fn settle_lamports_balance<'a, 'info>(
         .checked_add(transfer_lamports)
         .ok_or(DlpError::Overflow)?;
 
+    // Record the lamports kept by the validator in its fees ledger
+    if transfer_destination.key.eq(validator_fees_vault.key) {
+        record_validator_fees(validator.key, validator_fees_vault, |ledger| {
+            ledger.record_settlement_fees(transfer_lamports)
+        })?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,180 @@
+use crate::error::DlpError;
+use crate::processor::utils::delegation_metadata::resize_delegation_metadata;
+use crate::processor::utils::loaders::{
+    load_initialized_commit_bundle_record, load_initialized_commit_record,
+    load_initialized_commit_state, load_initialized_delegation_metadata,
+    load_initialized_delegation_record, load_initialized_validator_fees_vault, load_owned_pda,
+    load_program, load_signer,
+};
+use crate::processor::utils::pda::close_pda;
+use crate::processor::{finalize_commit, OwnerProgramAccounts};
+use crate::state::{CommitBundleRecord, DelegationMetadata};
+use solana_program::program_error::ProgramError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey, system_program,
+};
+
+/// Number of remaining accounts needed by each commit of the bundle
+const ACCOUNTS_PER_COMMIT: usize = 7;
+
+/// Finalize all the commits of a bundle, or none of them
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator account
+/// 1: `[writable]` the commit bundle record
+/// 2: `[writable]` the validator fees vault account
+/// 3: `[]`         the system program
+///
+/// Remaining accounts, for each commit of the bundle, starting with the account the
+/// bundle record is derived from:
+///
+/// 0: `[writable]` the delegated account
+/// 1: `[writable]` the commit state account of the oldest pending commit
+/// 2: `[writable]` the commit record account of the oldest pending commit
+/// 3: `[writable]` the delegation record account
+/// 4: `[writable]` the delegation metadata account
+/// 5: `[]`         the program config account of the owner program
+/// 6: `[]`         the owner program
+///
+/// Requirements:
+///
+/// - commit bundle record is initialized and derived from the first delegated account and its
+///   next finalize nonce
+/// - identity mentioned in the commit bundle record is the same as the validator
+/// - there is exactly one set of remaining accounts per commit of the bundle
+/// - each commit has the same requirements as [crate::processor::process_finalize], and
+///   belongs to the bundle, the validator having paid for it
+///
+/// Steps:
+///
+/// 1. Finalize each commit as in [crate::processor::process_finalize]
+/// 2. Close the commit bundle record
+pub fn process_finalize_bundle(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [validator, commit_bundle_record_account, validator_fees_vault, system_program, commits_accounts @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let [first_delegated_account, _, _, _, first_delegation_metadata_account, ..] =
+        commits_accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // The bundled commit of the first account is its oldest pending commit
+    load_signer(validator, "validator")?;
+    load_initialized_delegation_metadata(
+        first_delegated_account,
+        first_delegation_metadata_account,
+        true,
+    )?;
+    let first_delegation_metadata_data = first_delegation_metadata_account.try_borrow_data()?;
+    let first_commit_nonce =
+        DelegationMetadata::try_from_bytes_with_discriminator(&first_delegation_metadata_data)?
+            .next_finalize_nonce;
+    drop(first_delegation_metadata_data);
+    load_initialized_commit_bundle_record(
+        first_delegated_account,
+        commit_bundle_record_account,
+        first_commit_nonce,
+        true,
+    )?;
+    load_initialized_validator_fees_vault(validator, validator_fees_vault, true)?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    // Load commit bundle record
+    let commit_bundle_record_data = commit_bundle_record_account.try_borrow_data()?;
+    let commit_bundle_record =
+        CommitBundleRecord::try_from_bytes_with_discriminator(&commit_bundle_record_data)?;
+
+    // Check that the commit bundle record is the right one
+    if !commit_bundle_record.identity.eq(validator.key) {
+        return Err(DlpError::InvalidReimbursementAccount.into());
+    }
+    let commits_count = commit_bundle_record.commits_count as usize;
+    if commits_accounts.len() != commits_count * ACCOUNTS_PER_COMMIT {
+        msg!(
+            "Expected {} accounts for {} commits, but got {}",
+            commits_count * ACCOUNTS_PER_COMMIT,
+            commits_count,
+            commits_accounts.len()
+        );
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    drop(commit_bundle_record_data);
+
+    // Resize the delegation metadata created with an older layout, before any lamports move
+    for commit_accounts in commits_accounts.chunks_exact(ACCOUNTS_PER_COMMIT) {
+        let [delegated_account, _, _, _, delegation_metadata_account, _, _] = commit_accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+        let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+        let delegation_metadata =
+            DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+        drop(delegation_metadata_data);
+        resize_delegation_metadata(
+            validator,
+            delegation_metadata_account,
+            system_program,
+            &delegation_metadata,
+        )?;
+    }
+
+    // Every commit belongs to the bundle and is finalized exactly once, so all of them are
+    for commit_accounts in commits_accounts.chunks_exact(ACCOUNTS_PER_COMMIT) {
+        let [delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, program_config_account, owner_program] =
+            commit_accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        load_owned_pda(delegated_account, &crate::id(), "delegated account")?;
+        load_initialized_delegation_record(delegated_account, delegation_record_account, true)?;
+        load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+
+        // The bundled commit must be the oldest pending commit of the account
+        let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+        let delegation_metadata =
+            DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+        drop(delegation_metadata_data);
+        let commit_nonce = delegation_metadata.next_finalize_nonce;
+        load_initialized_commit_state(delegated_account, commit_state_account, commit_nonce, true)?;
+        load_initialized_commit_record(
+            delegated_account,
+            commit_record_account,
+            commit_nonce,
+            true,
+        )?;
+
+        // Bundled commits are not relayed, the validator paid for them
+        finalize_commit(
+            validator,
+            validator,
+            delegated_account,
+            commit_state_account,
+            commit_record_account,
+            delegation_record_account,
+            delegation_metadata_account,
+            validator_fees_vault,
+            Some(&OwnerProgramAccounts {
+                program_config_account,
+                owner_program,
+            }),
+            &[],
+            Some(commit_bundle_record_account),
+            None,
+            None,
+        )?;
+    }
+
+    close_pda(commit_bundle_record_account, validator)?;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::args::FinalizeWithDataArgs;
+use crate::processor::{process_finalize_internal, FinalizeInternalArgs, OwnerProgramAccounts};
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Finalize a hash commit, with the committed state supplied in the instruction data
+///
+/// It is identical to [crate::processor::process_finalize] but the committed state, whose
+/// hash was stored by [crate::processor::process_commit_state_hash], is checked against the
+/// commit record and copied to the delegated account
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator account
+/// 1: `[writable]` the delegated account
+/// 2: `[writable]` the commit state account
+/// 3: `[writable]` the commit record account
+/// 4: `[writable]` the delegation record account
+/// 5: `[writable]` the delegation metadata account
+/// 6: `[writable]` the validator fees vault account
+/// 7: `[]`         the system program
+/// 8: `[]`         the program config account of the owner program
+/// 9: `[]`         the owner program
+///
+/// Requirements:
+///
+/// - same requirements as [crate::processor::process_finalize]
+/// - the commit is a hash commit, paid by the validator which is refunded the commit PDAs
+/// - the hash of the supplied data matches the hash in the commit record
+pub fn process_finalize_with_data(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = FinalizeWithDataArgs::try_from_slice(data)?;
+
+    let [validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, system_program, program_config_account, owner_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let finalize_args = FinalizeInternalArgs {
+        commit_data: Some(&args.data),
+        commit_data_account: None,
+        validator,
+        // Hash commits are not relayed, the validator paid for them
+        commit_payer: validator,
+        delegated_account,
+        commit_state_account,
+        commit_record_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        validator_fees_vault,
+        system_program,
+        owner_program_accounts: Some(OwnerProgramAccounts {
+            program_config_account,
+            owner_program,
+        }),
+        action_accounts: &[],
+    };
+    process_finalize_internal(finalize_args)
+}
@@ -0,0 +1,141 @@
+use crate::error::DlpError;
+use crate::event::{DelegationFlaggedStaleEvent, Event};
+use crate::processor::utils::authority::{
+    load_program_config_commit_staleness_multiplier, load_protocol_config_min_commit_frequency_ms,
+};
+use crate::processor::utils::delegation_metadata::write_delegation_metadata;
+use crate::processor::utils::loaders::{
+    load_initialized_delegation_metadata, load_initialized_delegation_record, load_owned_pda,
+    load_pda, load_signer,
+};
+use crate::state::{DelegationMetadata, DelegationRecord, ValidatorBond};
+use crate::validator_bond_seeds_from_validator;
+use solana_program::clock::Clock;
+use solana_program::program_error::ProgramError;
+use solana_program::sysvar::Sysvar;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+/// Flag a delegation as stale when no commit was received for a multiple of its commit
+/// frequency, so that it can be undelegated with its last finalized state
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the account flagging the delegation
+/// 1: `[]`         the delegated account
+/// 2: `[]`         the delegation record
+/// 3: `[writable]` the delegation metadata
+/// 4: `[]`         the program config account of the owner program
+/// 5: `[writable]` the validator bond of the delegation record authority
+/// 6: `[]`         the protocol config
+///
+/// Requirements:
+///
+/// - delegated account is owned by delegation program
+/// - delegation record is initialized
+/// - delegation metadata is initialized and not flagged as stale yet
+/// - delegation record has a non-zero commit frequency
+/// - the last commit, or the delegation if there was no commit, is older than the commit
+///   frequency multiplied by the commit staleness multiplier of the program config, or
+///   [crate::consts::DEFAULT_COMMIT_STALENESS_MULTIPLIER]
+/// - the commit frequency is at least the minimum commit frequency of the protocol config
+/// - validator bond is derived from the delegation record authority
+///
+/// NOTE: this operation is permissionless and can be done by anyone, the validator
+///       signature is not required.
+///
+/// Steps:
+///
+/// 1. Flag the delegation as stale, which rejects any new commit and allows anyone to
+///    force the undelegation, see [crate::processor::process_force_undelegate]
+/// 2. Count the stale delegation in the validator bond, if the delegation record has an
+///    authority with an initialized bond
+pub fn process_flag_stale_delegation(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [flagger, delegated_account, delegation_record_account, delegation_metadata_account, program_config_account, validator_bond_account, protocol_config_account] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(flagger, "flagger")?;
+    load_owned_pda(delegated_account, &crate::id(), "delegated account")?;
+    load_initialized_delegation_record(delegated_account, delegation_record_account, false)?;
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+
+    // Load delegation record
+    let delegation_record_data = delegation_record_account.try_borrow_data()?;
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_data)?;
+
+    // Load delegation metadata
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+    if delegation_metadata.is_stale {
+        msg!(
+            "delegation metadata ({}) is already flagged as stale",
+            delegation_metadata_account.key
+        );
+        return Err(DlpError::DelegationStale.into());
+    }
+
+    // Check that no commit was received for too long
+    let commit_staleness_multiplier = load_program_config_commit_staleness_multiplier(
+        program_config_account,
+        delegation_record.owner,
+    )?;
+    let min_commit_frequency_ms =
+        load_protocol_config_min_commit_frequency_ms(protocol_config_account)?;
+    let commit_frequency_ms = match delegation_record.commit_frequency_ms {
+        0 => 0,
+        commit_frequency_ms => commit_frequency_ms.max(min_commit_frequency_ms),
+    };
+    let stale_timestamp =
+        delegation_metadata.stale_timestamp(commit_frequency_ms, commit_staleness_multiplier);
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    if stale_timestamp.map_or(true, |stale_timestamp| current_timestamp < stale_timestamp) {
+        msg!(
+            "Delegation can be flagged as stale from timestamp {:?}, current timestamp is {}",
+            stale_timestamp,
+            current_timestamp
+        );
+        return Err(DlpError::DelegationNotStale.into());
+    }
+
+    // Flag the delegation
+    delegation_metadata.is_stale = true;
+    write_delegation_metadata(delegation_metadata_account, &delegation_metadata)?;
+
+    // Count the stale delegation against the validator, if it has a bond
+    if !delegation_record.authority.eq(&Pubkey::default()) {
+        load_pda(
+            validator_bond_account,
+            validator_bond_seeds_from_validator!(delegation_record.authority),
+            &crate::id(),
+            true,
+            "validator bond",
+        )?;
+        if validator_bond_account.owner.eq(&crate::id()) {
+            let mut validator_bond_data = validator_bond_account.try_borrow_mut_data()?;
+            let validator_bond =
+                ValidatorBond::try_from_bytes_with_discriminator_mut(&mut validator_bond_data)?;
+            validator_bond.stale_delegations = validator_bond
+                .stale_delegations
+                .checked_add(1)
+                .ok_or(DlpError::Overflow)?;
+        }
+    }
+
+    DelegationFlaggedStaleEvent {
+        delegated_account: *delegated_account.key,
+        authority: delegation_record.authority,
+        last_commit_timestamp: delegation_metadata.last_commit_timestamp,
+    }
+    .emit()?;
+
+    Ok(())
+}
@@ -0,0 +1,284 @@
+use crate::error::DlpError;
+use crate::processor::process_undelegation;
+use crate::processor::utils::authority::load_protocol_config_rent_fees_bps;
+use crate::processor::utils::delegation_metadata::queue_legacy_commit;
+use crate::processor::utils::fees_ledger::record_protocol_fees;
+use crate::processor::utils::loaders::{
+    load_initialized_commit_record, load_initialized_commit_state,
+    load_initialized_delegation_metadata, load_initialized_delegation_record,
+    load_initialized_protocol_fees_vault, load_owned_pda, load_program, load_signer,
+    load_uninitialized_pda,
+};
+use crate::processor::utils::pda::close_pda;
+use crate::state::{CommitRecord, DelegationMetadata, DelegationRecord};
+use crate::{
+    commit_record_seeds_from_delegated_account, commit_state_seeds_from_delegated_account,
+};
+use solana_program::clock::Clock;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::sysvar::Sysvar;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+const ACCOUNTS_PER_PENDING_COMMIT: usize = 3;
+
+/// Forcefully undelegate an account once its delegation has expired, once the grace
+/// period of an undelegation requested by the owner program has elapsed, once the
+/// delegation was flagged as stale, or once a dispute of one of its commits was upheld
+///
+/// Accounts:
+///
+///  0: `[signer]`   the account paying for the undelegation
+///  1: `[writable]` the delegated account
+///  2: `[]`         the owner program of the delegated account
+///  3: `[writable]` the undelegate buffer PDA we use to store the data temporarily
+///  4: `[writable]` the commit state PDA of the oldest pending commit
+///  5: `[writable]` the commit record PDA of the oldest pending commit
+///  6: `[writable]` the delegation record PDA
+///  7: `[writable]` the delegation metadata PDA
+///  8: `[writable]` the payer of the oldest pending commit, if any
+///  9: `[writable]` the rent reimbursement account
+/// 10: `[writable]` the protocol fees vault account
+/// 11: `[]`         the system program
+/// 12: `[]`         the protocol config
+///
+/// Remaining accounts, repeated for each other pending commit, in nonce order:
+///
+/// 0: `[writable]` the commit state PDA
+/// 1: `[writable]` the commit record PDA
+/// 2: `[writable]` the payer of the pending commit
+///
+/// Requirements:
+///
+/// - delegated account is owned by delegation program
+/// - delegation record is initialized
+/// - delegation metadata is initialized
+/// - protocol fees vault is initialized
+/// - protocol config is initialized, or not exists in which case the default rent fees apply
+/// - delegation metadata has an expiry which has been reached, an undelegation request
+///   older than [crate::consts::UNDELEGATION_REQUEST_GRACE_PERIOD_SLOTS], or is flagged as
+///   stale, see [crate::processor::process_flag_stale_delegation], or has an upheld dispute,
+///   see [crate::processor::process_resolve_dispute]
+/// - delegation metadata has no dispute pending resolution, since the dispute bond is held
+///   in the commit record of the disputed commit
+/// - owner program account matches the owner in the delegation record
+/// - rent reimbursement account matches the rent payer in the delegation metadata
+/// - every pending commit is provided, and each payer matches the payer in its commit record
+///
+/// NOTE: this operation is permissionless and can be done by anyone, the validator
+///       signature is not required.
+///
+/// Steps:
+///
+/// 1. Discard the pending commits, if any, returning their lamports to their payers
+/// 2. Give the account back to its owner with the last finalized state, same as
+///    [crate::processor::process_undelegate]
+/// 3. Close the delegation PDAs, the rent fees set by the protocol config only go to the
+///    protocol fees vault and are recorded in its fees ledger
+pub fn process_force_undelegate(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [payer, delegated_account, owner_program, undelegate_buffer_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, commit_payer, rent_reimbursement, fees_vault, system_program, protocol_config_account, other_pending_commits @ ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check accounts
+    load_signer(payer, "payer")?;
+    load_owned_pda(delegated_account, &crate::id(), "delegated account")?;
+    load_initialized_delegation_record(delegated_account, delegation_record_account, true)?;
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+    load_initialized_protocol_fees_vault(fees_vault, true)?;
+    load_program(system_program, system_program::id(), "system program")?;
+    let rent_fees_bps = load_protocol_config_rent_fees_bps(protocol_config_account)?;
+
+    // Load delegation record
+    let delegation_record_data = delegation_record_account.try_borrow_data()?;
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_data)?;
+
+    // Check passed owner and owner stored in the delegation record match
+    if !delegation_record.owner.eq(owner_program.key) {
+        msg!(
+            "Expected delegation record owner to be {}, but got {}",
+            delegation_record.owner,
+            owner_program.key
+        );
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // Load delegated account metadata
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+
+    // Check that the delegation has expired, that the undelegation request grace period elapsed,
+    // that the delegation is stale or that a dispute was upheld
+    if !delegation_metadata.is_force_undelegatable(&Clock::get()?) {
+        msg!(
+            "delegation metadata ({}) has not expired, expiry is {:?}, undelegation requested at slot {}, stale: {}, upheld dispute: {}",
+            delegation_metadata_account.key,
+            delegation_metadata.expiry,
+            delegation_metadata.undelegation_request_slot,
+            delegation_metadata.is_stale,
+            delegation_metadata.has_upheld_dispute
+        );
+        return Err(DlpError::DelegationNotExpired.into());
+    }
+
+    // The pending commits cannot be discarded before the pending dispute is resolved
+    if delegation_metadata.dispute_slot > 0 {
+        msg!(
+            "delegation metadata ({}) has a dispute pending resolution since slot {}",
+            delegation_metadata_account.key,
+            delegation_metadata.dispute_slot
+        );
+        return Err(DlpError::DisputePending.into());
+    }
+
+    // Check if the rent payer is correct
+    if !delegation_metadata.rent_payer.eq(rent_reimbursement.key) {
+        msg!(
+            "Expected rent payer to be {}, but got {}",
+            delegation_metadata.rent_payer,
+            rent_reimbursement.key
+        );
+        return Err(DlpError::InvalidReimbursementAddressForDelegationRent.into());
+    }
+
+    // Dropping delegation references
+    drop(delegation_record_data);
+    drop(delegation_metadata_data);
+
+    // The account is restored to its last finalized state, so any pending commit is discarded,
+    // including a commit pending since before commits were queued
+    if !delegation_metadata.has_pending_commits() {
+        queue_legacy_commit(
+            delegated_account,
+            commit_record_account,
+            &mut delegation_metadata,
+        )?;
+    }
+    let pending_commits =
+        delegation_metadata.next_finalize_nonce..delegation_metadata.next_commit_nonce;
+    if pending_commits.is_empty() {
+        load_uninitialized_pda(
+            commit_state_account,
+            commit_state_seeds_from_delegated_account!(
+                delegated_account.key,
+                pending_commits.start
+            ),
+            &crate::id(),
+            false,
+            "commit state",
+        )?;
+        load_uninitialized_pda(
+            commit_record_account,
+            commit_record_seeds_from_delegated_account!(
+                delegated_account.key,
+                pending_commits.start
+            ),
+            &crate::id(),
+            false,
+            "commit record",
+        )?;
+    } else {
+        let other_pending_commits_count = pending_commits.end - pending_commits.start - 1;
+        if other_pending_commits.len() as u64
+            != other_pending_commits_count * ACCOUNTS_PER_PENDING_COMMIT as u64
+        {
+            msg!(
+                "Expected accounts for {} other pending commits, but got {} accounts",
+                other_pending_commits_count,
+                other_pending_commits.len()
+            );
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        discard_pending_commit(
+            delegated_account,
+            commit_state_account,
+            commit_record_account,
+            commit_payer,
+            pending_commits.start,
+        )?;
+        for (nonce, pending_commit_accounts) in pending_commits
+            .skip(1)
+            .zip(other_pending_commits.chunks_exact(ACCOUNTS_PER_PENDING_COMMIT))
+        {
+            let [commit_state_account, commit_record_account, commit_payer] =
+                pending_commit_accounts
+            else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            discard_pending_commit(
+                delegated_account,
+                commit_state_account,
+                commit_record_account,
+                commit_payer,
+                nonce,
+            )?;
+        }
+    }
+
+    let fees_vault_lamports = fees_vault.lamports();
+    process_undelegation(
+        payer,
+        delegated_account,
+        owner_program,
+        undelegate_buffer_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        delegation_metadata,
+        rent_reimbursement,
+        &[fees_vault],
+        rent_fees_bps,
+        system_program,
+    )?;
+
+    // Record the rent fees in the fees ledger
+    let rent_fees = fees_vault
+        .lamports()
+        .checked_sub(fees_vault_lamports)
+        .ok_or(DlpError::Overflow)?;
+    record_protocol_fees(fees_vault, |ledger| ledger.record_rent_fees(rent_fees))
+}
+
+/// Close the commit state and commit record of a pending commit, returning their lamports
+/// to the account that paid for them
+fn discard_pending_commit<'a, 'info>(
+    delegated_account: &'a AccountInfo<'info>,
+    commit_state_account: &'a AccountInfo<'info>,
+    commit_record_account: &'a AccountInfo<'info>,
+    commit_payer: &'a AccountInfo<'info>,
+    nonce: u64,
+) -> ProgramResult {
+    load_initialized_commit_state(delegated_account, commit_state_account, nonce, true)?;
+    load_initialized_commit_record(delegated_account, commit_record_account, nonce, true)?;
+
+    let commit_record_data = commit_record_account.try_borrow_data()?;
+    let commit_record = CommitRecord::try_from_bytes_with_legacy_layout(&commit_record_data)?;
+    if !commit_record.payer.eq(commit_payer.key) {
+        msg!(
+            "Expected commit payer to be {}, but got {}",
+            commit_record.payer,
+            commit_payer.key
+        );
+        return Err(DlpError::InvalidReimbursementAccount.into());
+    }
+    drop(commit_record_data);
+
+    msg!(
+        "Discarding pending commit {} for {}",
+        nonce,
+        delegated_account.key
+    );
+    close_pda(commit_state_account, commit_payer)?;
+    close_pda(commit_record_account, commit_payer)?;
+
+    Ok(())
+}
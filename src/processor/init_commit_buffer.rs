@@ -0,0 +1,75 @@
+use crate::args::InitCommitBufferArgs;
+use crate::commit_buffer_seeds_from_validator_and_delegated_account;
+use crate::processor::utils::loaders::{
+    load_owned_pda, load_program, load_signer, load_uninitialized_pda,
+};
+use crate::processor::utils::pda::create_pda;
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Initialize a commit buffer, in which a validator writes the new state of a delegated account
+/// in chunks, before committing it with [crate::processor::process_commit_state_from_buffer]
+///
+/// An account can only grow by 10KB per instruction, so a larger state is committed by hash
+/// with [crate::processor::process_commit_state_hash] and supplied on finalize from the
+/// commit buffer with [crate::processor::process_finalize_from_buffer]
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator paying for the buffer
+/// 1: `[]`         the delegated account
+/// 2: `[writable]` the commit buffer PDA
+/// 3: `[]`         the system program
+///
+/// Requirements:
+///
+/// - delegated account is owned by delegation program
+/// - commit buffer is uninitialized and derived from the validator and the delegated account
+///
+/// Steps:
+///
+/// 1. Create the commit buffer PDA with the initial size, zeroed
+pub fn process_init_commit_buffer(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = InitCommitBufferArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [validator, delegated_account, commit_buffer_account, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(validator, "validator")?;
+    load_owned_pda(delegated_account, &crate::id(), "delegated account")?;
+    load_program(system_program, system_program::id(), "system program")?;
+    let commit_buffer_bump = load_uninitialized_pda(
+        commit_buffer_account,
+        commit_buffer_seeds_from_validator_and_delegated_account!(
+            validator.key,
+            delegated_account.key
+        ),
+        &crate::id(),
+        true,
+        "commit buffer",
+    )?;
+
+    create_pda(
+        commit_buffer_account,
+        &crate::id(),
+        args.size as usize,
+        commit_buffer_seeds_from_validator_and_delegated_account!(
+            validator.key,
+            delegated_account.key
+        ),
+        commit_buffer_bump,
+        system_program,
+        validator,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use crate::args::InitProtocolConfigArgs;
+use crate::consts::MAX_FEES_BPS;
+use crate::error::DlpError::{InvalidProtocolConfig, Unauthorized};
+use crate::processor::utils::loaders::{
+    load_program, load_program_upgrade_authority, load_signer, load_uninitialized_pda,
+};
+use crate::processor::utils::pda::create_pda;
+use crate::protocol_config_seeds;
+use crate::state::ProtocolConfig;
+use borsh::BorshDeserialize;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Initialize the protocol config, which replaces the default protocol parameters
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the upgrade authority of the delegation program, paying for the account
+/// 1: `[]`         the delegation program data account
+/// 2: `[writable]` the protocol config PDA we are initializing
+/// 3: `[]`         the system program
+///
+/// Requirements:
+///
+/// - authority is the delegation program upgrade authority
+/// - protocol config is uninitialized
+/// - fee rates are at most [MAX_FEES_BPS]
+///
+/// Steps:
+///
+/// 1. Create the protocol config PDA
+/// 2. Store the admin and the protocol parameters
+pub fn process_init_protocol_config(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = InitProtocolConfigArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [authority, delegation_program_data, protocol_config_account, system_program] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(authority, "authority")?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    // Check if the authority is the upgrade authority
+    let upgrade_authority =
+        load_program_upgrade_authority(&crate::ID, delegation_program_data)?.ok_or(Unauthorized)?;
+    if !authority.key.eq(&upgrade_authority) {
+        msg!(
+            "Expected upgrade authority: {} but got {}",
+            upgrade_authority,
+            authority.key
+        );
+        return Err(Unauthorized.into());
+    }
+
+    let protocol_config = ProtocolConfig {
+        admin: args.admin,
+        pending_admin: Pubkey::default(),
+        rent_fees_bps: args.rent_fees_bps,
+        protocol_fees_bps: args.protocol_fees_bps,
+        min_commit_frequency_ms: args.min_commit_frequency_ms,
+        commit_fee: args.commit_fee,
+    };
+    if !protocol_config.has_valid_fees() {
+        msg!("Fee rates must be at most {} basis points", MAX_FEES_BPS);
+        return Err(InvalidProtocolConfig.into());
+    }
+
+    let protocol_config_bump = load_uninitialized_pda(
+        protocol_config_account,
+        protocol_config_seeds!(),
+        &crate::id(),
+        true,
+        "protocol config",
+    )?;
+
+    // Create the protocol config PDA
+    create_pda(
+        protocol_config_account,
+        &crate::id(),
+        ProtocolConfig::size_with_discriminator()?,
+        protocol_config_seeds!(),
+        protocol_config_bump,
+        system_program,
+        authority,
+    )?;
+    let mut protocol_config_data = protocol_config_account.try_borrow_mut_data()?;
+    protocol_config.to_bytes_with_discriminator(&mut protocol_config_data.as_mut())?;
+
+    Ok(())
+}
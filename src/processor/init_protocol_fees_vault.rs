@@ -6,6 +6,7 @@ use solana_program::{
 use crate::fees_vault_seeds;
 use crate::processor::utils::loaders::{load_program, load_signer, load_uninitialized_pda};
 use crate::processor::utils::pda::create_pda;
+use crate::state::ProtocolFeesVault;
 
 /// Initialize the global fees vault
 ///
@@ -22,7 +23,7 @@ use crate::processor::utils::pda::create_pda;
 ///
 /// Steps:
 ///
-/// 1. Create the protocol fees vault PDA
+/// 1. Create the protocol fees vault PDA, with an empty fees ledger
 pub fn process_init_protocol_fees_vault(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -48,12 +49,14 @@ pub fn process_init_protocol_fees_vault(
     create_pda(
         protocol_fees_vault,
         &crate::id(),
-        8,
+        ProtocolFeesVault::size_with_discriminator(),
         fees_vault_seeds!(),
         bump_fees_vault,
         system_program,
         payer,
     )?;
+    let mut protocol_fees_vault_data = protocol_fees_vault.try_borrow_mut_data()?;
+    ProtocolFeesVault::default().to_bytes_with_discriminator(&mut protocol_fees_vault_data)?;
 
     Ok(())
 }
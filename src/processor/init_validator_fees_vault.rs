@@ -1,14 +1,12 @@
-use solana_program::msg;
 use solana_program::program_error::ProgramError;
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
 };
 
-use crate::error::DlpError::Unauthorized;
-use crate::processor::utils::loaders::{
-    load_program, load_program_upgrade_authority, load_signer, load_uninitialized_pda,
-};
+use crate::processor::utils::authority::validate_protocol_admin;
+use crate::processor::utils::loaders::{load_program, load_signer, load_uninitialized_pda};
 use crate::processor::utils::pda::create_pda;
+use crate::state::{FeesLedger, ValidatorFeesVault};
 use crate::validator_fees_vault_seeds_from_validator;
 
 /// Process the initialization of the validator fees vault
@@ -17,18 +15,21 @@ use crate::validator_fees_vault_seeds_from_validator;
 ///
 /// 0; `[signer]` payer
 /// 1; `[signer]` admin that controls the vault
-/// 2; `[]`       validator_identity
-/// 3; `[]`       validator_fees_vault_pda
-/// 4; `[]`       system_program
+/// 2; `[]`       delegation_program_data
+/// 3; `[]`       validator_identity
+/// 4; `[]`       validator_fees_vault_pda
+/// 5; `[]`       system_program
+/// 6; `[]`       protocol_config_pda
 ///
 /// Requirements:
 ///
 /// - validator admin need to be signer since the existence of the validator fees vault
 ///   is used as proof later that the validator is whitelisted
-/// - validator admin is whitelisted
+/// - validator admin is the protocol admin
 /// - validator fees vault is not initialized
 ///
-/// 1. Create the validator fees vault PDA
+/// 1. Create the validator fees vault PDA, withdrawn by the validator identity until another
+///    withdrawal authority is set, see [crate::processor::process_set_validator_fees_vault_authority]
 /// 2. Currently, the existence of the validator fees vault also act as a flag to indicate that the validator is whitelisted (only the admin can create the vault)
 pub fn process_init_validator_fees_vault(
     _program_id: &Pubkey,
@@ -36,7 +37,7 @@ pub fn process_init_validator_fees_vault(
     _data: &[u8],
 ) -> ProgramResult {
     // Load Accounts
-    let [payer, admin, delegation_program_data, validator_identity, validator_fees_vault, system_program] =
+    let [payer, admin, delegation_program_data, validator_identity, validator_fees_vault, system_program, protocol_config_account] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -48,16 +49,7 @@ pub fn process_init_validator_fees_vault(
     load_program(system_program, system_program::id(), "system program")?;
 
     // Check if the admin is the correct one
-    let admin_pubkey =
-        load_program_upgrade_authority(&crate::ID, delegation_program_data)?.ok_or(Unauthorized)?;
-    if !admin.key.eq(&admin_pubkey) {
-        msg!(
-            "Expected admin pubkey: {} but got {}",
-            admin_pubkey,
-            admin.key
-        );
-        return Err(Unauthorized.into());
-    }
+    validate_protocol_admin(admin, protocol_config_account, delegation_program_data)?;
 
     let validator_fees_vault_bump = load_uninitialized_pda(
         validator_fees_vault,
@@ -71,12 +63,19 @@ pub fn process_init_validator_fees_vault(
     create_pda(
         validator_fees_vault,
         &crate::id(),
-        8,
+        ValidatorFeesVault::size_with_discriminator(),
         validator_fees_vault_seeds_from_validator!(validator_identity.key),
         validator_fees_vault_bump,
         system_program,
         payer,
     )?;
+    let validator_fees_vault_settings = ValidatorFeesVault {
+        withdrawal_authority: *validator_identity.key,
+        payout_destination: *validator_identity.key,
+        ledger: FeesLedger::default(),
+    };
+    let mut validator_fees_vault_data = validator_fees_vault.try_borrow_mut_data()?;
+    validator_fees_vault_settings.to_bytes_with_discriminator(&mut validator_fees_vault_data)?;
 
     Ok(())
 }
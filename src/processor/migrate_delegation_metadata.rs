@@ -0,0 +1,61 @@
+use crate::processor::utils::delegation_metadata::{
+    resize_delegation_metadata, write_delegation_metadata,
+};
+use crate::processor::utils::loaders::{
+    load_initialized_delegation_metadata, load_program, load_signer,
+};
+use crate::state::DelegationMetadata;
+use solana_program::program_error::ProgramError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Migrate the delegation metadata of a delegation created with an older layout to the
+/// current layout, the fields it lacks being set to their defaults
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the payer of the additional rent
+/// 1: `[]`         the delegated account
+/// 2: `[writable]` the delegation metadata PDA
+/// 3: `[]`         the system program
+///
+/// Requirements:
+///
+/// - delegation metadata is initialized
+///
+/// NOTE: this operation is permissionless. Commits and finalizes migrate the delegation
+///       metadata on their own, this instruction is only needed before the instructions
+///       without a payer update an older delegation metadata, such as
+///       [crate::processor::process_request_undelegation]
+///
+/// Steps:
+///
+/// 1. Resize the delegation metadata PDA to the current layout, the payer paying the rent
+/// 2. Write back the delegation metadata
+pub fn process_migrate_delegation_metadata(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [payer, delegated_account, delegation_metadata_account, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(payer, "payer")?;
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+
+    resize_delegation_metadata(
+        payer,
+        delegation_metadata_account,
+        system_program,
+        &delegation_metadata,
+    )?;
+    write_delegation_metadata(delegation_metadata_account, &delegation_metadata)
+}
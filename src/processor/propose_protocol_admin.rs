@@ -0,0 +1,68 @@
+use crate::args::ProposeProtocolAdminArgs;
+use crate::error::DlpError::Unauthorized;
+use crate::processor::utils::loaders::{load_initialized_pda, load_signer};
+use crate::protocol_config_seeds;
+use crate::state::ProtocolConfig;
+use borsh::BorshDeserialize;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Propose a new protocol admin, which becomes the admin once it accepts the rotation
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the admin of the protocol config
+/// 1: `[writable]` the protocol config PDA
+///
+/// Requirements:
+///
+/// - protocol config is initialized
+/// - admin is the admin stored in the protocol config
+///
+/// Steps:
+///
+/// 1. Store the proposed admin as the pending admin, replacing any pending rotation, see
+///    [crate::processor::process_accept_protocol_admin]
+///
+/// NOTE: proposing the default pubkey cancels the pending rotation
+pub fn process_propose_protocol_admin(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = ProposeProtocolAdminArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [admin, protocol_config_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(admin, "admin")?;
+    load_initialized_pda(
+        protocol_config_account,
+        protocol_config_seeds!(),
+        &crate::id(),
+        true,
+        "protocol config",
+    )?;
+
+    let mut protocol_config_data = protocol_config_account.try_borrow_mut_data()?;
+    let mut protocol_config =
+        ProtocolConfig::try_from_bytes_with_discriminator(&protocol_config_data)?;
+
+    // Check if the admin is the correct one
+    if !admin.key.eq(&protocol_config.admin) {
+        msg!(
+            "Expected admin pubkey: {} but got {}",
+            protocol_config.admin,
+            admin.key
+        );
+        return Err(Unauthorized.into());
+    }
+
+    protocol_config.pending_admin = args.new_admin;
+    protocol_config.to_bytes_with_discriminator(&mut protocol_config_data.as_mut())?;
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
-use crate::error::DlpError::Unauthorized;
-use crate::processor::utils::loaders::{
-    load_initialized_protocol_fees_vault, load_program_upgrade_authority, load_signer,
-};
+use crate::args::ProtocolClaimFeesArgs;
+use crate::event::{Event, FeesClaimedEvent};
+use crate::processor::utils::authority::validate_protocol_admin;
+use crate::processor::utils::fees_ledger::{record_protocol_fees, resize_protocol_fees_vault};
+use crate::processor::utils::loaders::{load_initialized_protocol_fees_vault, load_signer};
+use borsh::BorshDeserialize;
 use solana_program::msg;
 use solana_program::program_error::ProgramError;
 use solana_program::rent::Rent;
@@ -11,24 +13,34 @@ use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubke
 ///
 /// Accounts:
 ///
-/// 1. `[signer]`   admin account that can claim the fees
-/// 2. `[writable]` protocol fees vault PDA
+/// 0: `[signer]`   admin account that can claim the fees
+/// 1: `[writable]` protocol fees vault PDA
+/// 2: `[]`         delegation program data account
+/// 3: `[]`         protocol config PDA
+/// 4: `[writable]` destination account receiving the fees, e.g. a treasury
 ///
 /// Requirements:
 ///
 /// - protocol fees vault is initialized
 /// - protocol fees vault has enough lamports to claim fees and still be
 ///   rent exempt
-/// - admin is the protocol fees vault admin
+/// - admin is the protocol admin, see [crate::processor::process_init_protocol_config]
 ///
-/// 1. Transfer lamports from protocol fees_vault PDA to the admin authority
+/// 1. Resize the protocol fees vault if it holds no fees ledger yet and can afford the rent
+/// 2. Transfer the claimed lamports, or all the lamports above the rent exemption, from
+///    protocol fees_vault PDA to the destination
+/// 3. Record the claimed lamports in the fees ledger
 pub fn process_protocol_claim_fees(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
-    _data: &[u8],
+    data: &[u8],
 ) -> ProgramResult {
+    let args = ProtocolClaimFeesArgs::try_from_slice(data)?;
+
     // Load Accounts
-    let [admin, fees_vault, delegation_program_data] = accounts else {
+    let [admin, fees_vault, delegation_program_data, protocol_config_account, destination] =
+        accounts
+    else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
@@ -37,34 +49,50 @@ pub fn process_protocol_claim_fees(
     load_initialized_protocol_fees_vault(fees_vault, true)?;
 
     // Check if the admin is the correct one
-    let admin_pubkey =
-        load_program_upgrade_authority(&crate::ID, delegation_program_data)?.ok_or(Unauthorized)?;
-    if !admin.key.eq(&admin_pubkey) {
-        msg!(
-            "Expected admin pubkey: {} but got {}",
-            admin_pubkey,
-            admin.key
-        );
-        return Err(Unauthorized.into());
-    }
+    validate_protocol_admin(admin, protocol_config_account, delegation_program_data)?;
+
+    // Resize the vault created before the fees ledger was introduced, out of its own lamports
+    resize_protocol_fees_vault(fees_vault)?;
 
     // Calculate the amount to transfer
-    let min_rent = Rent::default().minimum_balance(8);
-    if fees_vault.lamports() < min_rent {
+    let min_rent = Rent::default().minimum_balance(fees_vault.data_len());
+    let claimable_amount = fees_vault
+        .lamports()
+        .checked_sub(min_rent)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let amount = args.amount.unwrap_or(claimable_amount);
+
+    // Ensure vault has enough lamports
+    if claimable_amount < amount {
+        msg!(
+            "Vault ({}) has insufficient funds: {} < {}",
+            fees_vault.key,
+            claimable_amount,
+            amount
+        );
         return Err(ProgramError::InsufficientFunds);
     }
-    let amount = fees_vault.lamports() - min_rent;
 
-    // Transfer fees to the admin pubkey
+    // Transfer fees to the destination
     **fees_vault.try_borrow_mut_lamports()? = fees_vault
         .lamports()
         .checked_sub(amount)
         .ok_or(ProgramError::InsufficientFunds)?;
 
-    **admin.try_borrow_mut_lamports()? = admin
+    **destination.try_borrow_mut_lamports()? = destination
         .lamports()
         .checked_add(amount)
         .ok_or(ProgramError::ArithmeticOverflow)?;
 
+    record_protocol_fees(fees_vault, |ledger| ledger.record_claim(amount, 0))?;
+
+    FeesClaimedEvent {
+        fees_vault: *fees_vault.key,
+        receiver: *destination.key,
+        amount,
+        protocol_fees: 0,
+    }
+    .emit()?;
+
     Ok(())
 }
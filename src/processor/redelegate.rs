@@ -0,0 +1,186 @@
+use crate::error::DlpError;
+use crate::processor::utils::authority::{
+    validate_delegation_authority, validate_program_config_validator,
+};
+use crate::processor::utils::delegation_metadata::{
+    resize_delegation_metadata, write_delegation_metadata,
+};
+use crate::processor::utils::loaders::{
+    load_initialized_delegation_metadata, load_initialized_delegation_record,
+    load_initialized_validator_fees_vault, load_owned_pda, load_program, load_signer,
+    load_uninitialized_pda,
+};
+use crate::state::{DelegationMetadata, DelegationRecord};
+use crate::{
+    commit_record_seeds_from_delegated_account, commit_state_seeds_from_delegated_account,
+};
+use solana_program::clock::Clock;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::sysvar::Sysvar;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Hand off a delegated account to a different validator, without undelegating it
+///
+/// Accounts:
+///
+///  0: `[signer]`   the validator currently holding the delegation, paying for the
+///                   delegation metadata migration if needed
+///  1: `[]`         the validator receiving the delegation
+///  2: `[]`         the delegated account
+///  3: `[]`         the commit state PDA
+///  4: `[]`         the commit record PDA
+///  5: `[writable]` the delegation record
+///  6: `[writable]` the delegation metadata
+///  7: `[]`         the validator fees vault
+///  8: `[]`         the new validator fees vault
+///  9: `[]`         the program config account
+/// 10: `[]`         the system program
+///
+/// Requirements:
+///
+/// - delegated account is owned by delegation program
+/// - delegation record is initialized
+/// - delegation metadata is initialized
+/// - validator fees vault is initialized, so that only a whitelisted validator can hand off
+///   a delegation whose authority is the default pubkey
+/// - new validator fees vault is initialized
+/// - commit state is uninitialized and derived from the next finalize nonce, all the
+///   committed states must be finalized
+/// - commit record is uninitialized and derived from the next finalize nonce
+/// - account is not marked as undelegatable by the validator
+/// - owner program did not request the undelegation
+/// - delegation is not flagged as stale and has no upheld dispute
+/// - validator is the delegation record authority, unless the authority is the default pubkey
+/// - validator and new validator are approved in the program config, if any
+///
+/// Steps:
+///
+/// 1. Set the new validator as the delegation record authority
+/// 2. Reset the delegation slot and the last external slot, since the new validator
+///    commits from a different ephemeral ledger
+/// 3. Reset the last commit timestamp, so the staleness period starts over for the new
+///    validator
+/// 4. Resize the delegation metadata to its current layout if needed, paid by the validator
+pub fn process_redelegate(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [validator, new_validator, delegated_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, validator_fees_vault, new_validator_fees_vault, program_config_account, system_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check accounts
+    load_signer(validator, "validator")?;
+    load_owned_pda(delegated_account, &crate::id(), "delegated account")?;
+    load_initialized_delegation_record(delegated_account, delegation_record_account, true)?;
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+    load_initialized_validator_fees_vault(validator, validator_fees_vault, false)?;
+    load_initialized_validator_fees_vault(new_validator, new_validator_fees_vault, false)?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    // A pending commit must be finalized (or discarded) before the handoff
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let commit_nonce =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?
+            .next_finalize_nonce;
+    drop(delegation_metadata_data);
+    load_uninitialized_pda(
+        commit_state_account,
+        commit_state_seeds_from_delegated_account!(delegated_account.key, commit_nonce),
+        &crate::id(),
+        false,
+        "commit state",
+    )?;
+    load_uninitialized_pda(
+        commit_record_account,
+        commit_record_seeds_from_delegated_account!(delegated_account.key, commit_nonce),
+        &crate::id(),
+        false,
+        "commit record",
+    )?;
+
+    // Load delegation record
+    let mut delegation_record_data = delegation_record_account.try_borrow_mut_data()?;
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator_mut(&mut delegation_record_data)?;
+
+    // Check that the validator is allowed to hand off the delegated account
+    validate_delegation_authority(delegation_record, validator)?;
+
+    // Check that both validators are allowed by the owner program, if configured
+    validate_program_config_validator(
+        program_config_account,
+        delegation_record.owner,
+        validator.key,
+    )?;
+    validate_program_config_validator(
+        program_config_account,
+        delegation_record.owner,
+        new_validator.key,
+    )?;
+
+    // Load delegation metadata
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+
+    // An account marked as undelegatable by the validator cannot be handed off
+    if delegation_metadata.is_undelegatable {
+        msg!(
+            "delegation metadata ({}) is marked as undelegatable",
+            delegation_metadata_account.key
+        );
+        return Err(DlpError::DelegationUndelegatable.into());
+    }
+
+    // An account whose undelegation was requested by its owner cannot be handed off
+    if delegation_metadata.undelegation_request_slot > 0 {
+        msg!(
+            "undelegation of delegation metadata ({}) was requested at slot {}",
+            delegation_metadata_account.key,
+            delegation_metadata.undelegation_request_slot
+        );
+        return Err(DlpError::UndelegationRequested.into());
+    }
+
+    // Once the delegation is flagged as stale, it can only be undelegated
+    if delegation_metadata.is_stale {
+        msg!(
+            "delegation metadata ({}) is flagged as stale",
+            delegation_metadata_account.key
+        );
+        return Err(DlpError::DelegationStale.into());
+    }
+
+    // Once a dispute was upheld, the delegation can only be force undelegated
+    if delegation_metadata.has_upheld_dispute {
+        msg!(
+            "delegation metadata ({}) has an upheld dispute",
+            delegation_metadata_account.key
+        );
+        return Err(DlpError::CommitDisputed.into());
+    }
+
+    // Hand off the delegation and reset the commit bookkeeping
+    let clock = Clock::get()?;
+    delegation_record.authority = *new_validator.key;
+    delegation_record.delegation_slot = clock.slot;
+    delegation_metadata.last_update_external_slot = 0;
+    delegation_metadata.last_commit_timestamp = clock.unix_timestamp;
+    resize_delegation_metadata(
+        validator,
+        delegation_metadata_account,
+        system_program,
+        &delegation_metadata,
+    )?;
+    write_delegation_metadata(delegation_metadata_account, &delegation_metadata)?;
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::processor::utils::delegation_metadata::write_delegation_metadata;
+use crate::processor::utils::loaders::{
+    load_initialized_delegation_metadata, load_initialized_delegation_record, load_owned_pda,
+    load_signer,
+};
+use crate::state::{DelegationMetadata, DelegationRecord};
+use solana_program::clock::Clock;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::sysvar::Sysvar;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Request the undelegation of a delegated account on behalf of its owner program
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the delegated account
+/// 1: `[]`         the owner program of the delegated account
+/// 2: `[]`         the delegation record
+/// 3: `[writable]` the delegation metadata
+///
+/// Requirements:
+///
+/// - delegated account is owned by delegation program and is a signer
+/// - delegation record is initialized
+/// - delegation metadata is initialized
+/// - owner program account matches the owner in the delegation record
+///
+/// Steps:
+///
+/// 1. Record the slot of the request in the delegation metadata, starting the grace period
+///    in which the validator can still commit and undelegate the account
+/// 2. Once [crate::consts::UNDELEGATION_REQUEST_GRACE_PERIOD_SLOTS] elapsed, anyone can
+///    undelegate the account with [crate::processor::process_force_undelegate]
+///
+/// Usage:
+///
+/// This instruction is meant to be called via CPI with the owning program signing for the
+/// delegated account with the seeds stored in the delegation metadata.
+pub fn process_request_undelegation(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    let [delegated_account, owner_program, delegation_record_account, delegation_metadata_account] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    // Check that the delegated account is a signer, this ensures the instruction is being called from CPI
+    load_signer(delegated_account, "delegated account")?;
+    load_owned_pda(delegated_account, &crate::id(), "delegated account")?;
+    load_initialized_delegation_record(delegated_account, delegation_record_account, false)?;
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+
+    // Load delegation record
+    let delegation_record_data = delegation_record_account.try_borrow_data()?;
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_data)?;
+
+    // Check passed owner and owner stored in the delegation record match
+    if !delegation_record.owner.eq(owner_program.key) {
+        msg!(
+            "Expected delegation record owner to be {}, but got {}",
+            delegation_record.owner,
+            owner_program.key
+        );
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    // Load delegation metadata
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+
+    // Repeated requests do not extend the grace period
+    if delegation_metadata.undelegation_request_slot > 0 {
+        msg!(
+            "Undelegation already requested at slot {}",
+            delegation_metadata.undelegation_request_slot
+        );
+        return Ok(());
+    }
+
+    // A zero slot means no request, so the recorded slot is at least one
+    delegation_metadata.undelegation_request_slot = Clock::get()?.slot.max(1);
+    write_delegation_metadata(delegation_metadata_account, &delegation_metadata)?;
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use crate::args::ResolveDisputeArgs;
+use crate::consts::DISPUTE_BOND_LAMPORTS;
+use crate::error::DlpError;
+use crate::processor::utils::authority::validate_protocol_admin;
+use crate::processor::utils::delegation_metadata::write_delegation_metadata;
+use crate::processor::utils::loaders::{
+    load_initialized_commit_record, load_initialized_delegation_metadata, load_signer,
+};
+use crate::state::{CommitRecord, DelegationMetadata};
+use borsh::BorshDeserialize;
+use solana_program::clock::Clock;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::Sysvar;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg};
+
+/// Resolve the pending dispute of a commit, see [crate::processor::process_dispute_commit]
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the account resolving the dispute
+/// 1: `[]`         the delegation program data account
+/// 2: `[]`         the protocol config PDA
+/// 3: `[]`         the delegated account
+/// 4: `[writable]` the commit record of the disputed commit
+/// 5: `[writable]` the delegation metadata PDA
+/// 6: `[writable]` the challenger that disputed the commit
+/// 7: `[writable]` the validator that committed the disputed state
+///
+/// Requirements:
+///
+/// - commit record is initialized and derived from the delegated account and the commit nonce
+/// - delegation metadata is initialized
+/// - commit is disputed and the dispute is pending resolution
+/// - resolver is the protocol admin, or anyone if the dispute is rejected after
+///   [crate::consts::DISPUTE_RESOLUTION_PERIOD_SLOTS]
+/// - challenger and validator match the disputer and the identity in the commit record
+///
+/// Steps:
+///
+/// 1. If the dispute is upheld, refund the dispute bond to the challenger. The commit stays
+///    disputed so it is never finalized, and the delegation can be force undelegated with its
+///    last finalized state, see [crate::processor::process_force_undelegate]
+/// 2. Otherwise pay the dispute bond to the validator and clear the dispute, so the commit can
+///    be finalized
+/// 3. Clear the pending dispute in the delegation metadata
+pub fn process_resolve_dispute(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = ResolveDisputeArgs::try_from_slice(data)?;
+
+    let [resolver, delegation_program_data, protocol_config_account, delegated_account, commit_record_account, delegation_metadata_account, challenger, validator] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(resolver, "resolver")?;
+    let delegation_metadata = load_pending_dispute(
+        delegated_account,
+        commit_record_account,
+        delegation_metadata_account,
+        args.commit_nonce,
+    )?;
+
+    // The protocol admin resolves disputes, unless it failed to do so in time, in which case
+    // anyone can reject the dispute, as the challenger failed to prove the commit invalid
+    if args.upheld || !delegation_metadata.is_dispute_resolution_elapsed(&Clock::get()?) {
+        validate_protocol_admin(resolver, protocol_config_account, delegation_program_data)?;
+    }
+
+    settle_dispute(
+        commit_record_account,
+        delegation_metadata_account,
+        delegation_metadata,
+        challenger,
+        validator,
+        args.upheld,
+    )
+}
+
+/// Load the delegation metadata of a delegation whose commit has a pending dispute
+/// - Commit record must be initialized, and derived from the delegated account and the nonce
+/// - Delegation metadata must be initialized
+/// - Commit must be disputed, and the dispute pending resolution
+pub(crate) fn load_pending_dispute(
+    delegated_account: &AccountInfo,
+    commit_record_account: &AccountInfo,
+    delegation_metadata_account: &AccountInfo,
+    commit_nonce: u64,
+) -> Result<DelegationMetadata, ProgramError> {
+    load_initialized_commit_record(delegated_account, commit_record_account, commit_nonce, true)?;
+    load_initialized_delegation_metadata(delegated_account, delegation_metadata_account, true)?;
+
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?;
+    drop(delegation_metadata_data);
+
+    let commit_record_data = commit_record_account.try_borrow_data()?;
+    let commit_record = CommitRecord::try_from_bytes_with_discriminator(&commit_record_data)?;
+    if !commit_record.is_disputed() || delegation_metadata.dispute_slot == 0 {
+        msg!(
+            "Commit {} of {} has no pending dispute",
+            commit_nonce,
+            delegated_account.key
+        );
+        return Err(DlpError::CommitNotDisputed.into());
+    }
+    Ok(delegation_metadata)
+}
+
+/// Settle a pending dispute, loaded with [load_pending_dispute]. The dispute bond goes back to
+/// the challenger if the dispute is upheld, to the validator of the commit otherwise
+pub(crate) fn settle_dispute<'a, 'info>(
+    commit_record_account: &'a AccountInfo<'info>,
+    delegation_metadata_account: &'a AccountInfo<'info>,
+    mut delegation_metadata: DelegationMetadata,
+    challenger: &'a AccountInfo<'info>,
+    validator: &'a AccountInfo<'info>,
+    upheld: bool,
+) -> ProgramResult {
+    let mut commit_record_data = commit_record_account.try_borrow_mut_data()?;
+    let commit_record =
+        CommitRecord::try_from_bytes_with_discriminator_mut(&mut commit_record_data)?;
+
+    if !commit_record.disputer.eq(challenger.key) {
+        msg!(
+            "Expected challenger to be {}, but got {}",
+            commit_record.disputer,
+            challenger.key
+        );
+        return Err(DlpError::InvalidReimbursementAccount.into());
+    }
+    if !commit_record.identity.eq(validator.key) {
+        msg!(
+            "Expected validator to be {}, but got {}",
+            commit_record.identity,
+            validator.key
+        );
+        return Err(DlpError::InvalidReimbursementAccount.into());
+    }
+
+    // The bond goes back to the challenger if the dispute is upheld, to the validator otherwise
+    let bond_receiver = if upheld {
+        challenger
+    } else {
+        commit_record.disputer = Pubkey::default();
+        validator
+    };
+    drop(commit_record_data);
+
+    **commit_record_account.try_borrow_mut_lamports()? = commit_record_account
+        .lamports()
+        .checked_sub(DISPUTE_BOND_LAMPORTS)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **bond_receiver.try_borrow_mut_lamports()? = bond_receiver
+        .lamports()
+        .checked_add(DISPUTE_BOND_LAMPORTS)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    delegation_metadata.dispute_slot = 0;
+    delegation_metadata.has_upheld_dispute |= upheld;
+    write_delegation_metadata(delegation_metadata_account, &delegation_metadata)
+}
@@ -0,0 +1,97 @@
+use crate::args::SetCommitFeeForProgramArgs;
+use crate::processor::utils::authority::validate_program_config_authority;
+use crate::processor::utils::loaders::{load_pda, load_program, load_signer};
+use crate::processor::utils::pda::{create_pda, resize_pda};
+use crate::program_config_seeds_from_program_id;
+use crate::state::ProgramConfig;
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Set the lamports charged to the payer ephemeral balance for each commit to the accounts of a
+/// program, see [crate::processor::process_commit_state]
+///
+/// Accounts:
+///
+/// 0: `[signer]`   authority that has rights to configure the program
+/// 1: `[]`         program to set the commit fee for
+/// 2: `[]`         program data account
+/// 3: `[]`         delegation program data account
+/// 4: `[writable]` program config PDA
+/// 5: `[]`         system program
+/// 6: `[]`         protocol config PDA
+///
+/// Requirements:
+///
+/// - authority is either the protocol admin or the program upgrade authority
+/// - program config is initialized or owned by the system program in
+///   which case it is created
+///
+/// Steps:
+///
+/// 1. Load the authority and validate it
+/// 2. Load the program config or create it and set the `commit_fee`
+///
+/// NOTE: a zero commit fee falls back to the commit fee of the protocol config
+pub fn process_set_commit_fee_for_program(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = SetCommitFeeForProgramArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [authority, program, program_data, delegation_program_data, program_config_account, system_program, protocol_config_account] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(authority, "authority")?;
+    validate_program_config_authority(
+        authority,
+        program,
+        program_data,
+        delegation_program_data,
+        protocol_config_account,
+    )?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    let program_config_bump = load_pda(
+        program_config_account,
+        program_config_seeds_from_program_id!(program.key),
+        &crate::id(),
+        true,
+        "program config",
+    )?;
+
+    // Get the program config. If the account doesn't exist, create it
+    let mut program_config = if program_config_account.owner.eq(system_program.key) {
+        create_pda(
+            program_config_account,
+            &crate::id(),
+            0, // It will be resized later to the proper size
+            program_config_seeds_from_program_id!(program.key),
+            program_config_bump,
+            system_program,
+            authority,
+        )?;
+        ProgramConfig::default()
+    } else {
+        let program_config_data = program_config_account.try_borrow_data()?;
+        ProgramConfig::try_from_bytes_with_discriminator(&program_config_data)?
+    };
+    program_config.commit_fee = args.commit_fee;
+    resize_pda(
+        authority,
+        program_config_account,
+        system_program,
+        program_config.size_with_discriminator(),
+    )?;
+    let mut program_config_data = program_config_account.try_borrow_mut_data()?;
+    program_config.to_bytes_with_discriminator(&mut program_config_data.as_mut())?;
+
+    Ok(())
+}
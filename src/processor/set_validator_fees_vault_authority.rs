@@ -0,0 +1,106 @@
+use crate::args::SetValidatorFeesVaultAuthorityArgs;
+use crate::error::DlpError;
+use crate::processor::utils::authority::{
+    load_validator_fees_vault_settings, validate_validator_fees_vault_withdrawal_authority,
+};
+use crate::processor::utils::loaders::{
+    load_initialized_validator_fees_vault, load_program, load_signer,
+};
+use crate::state::ValidatorFeesVault;
+use borsh::BorshDeserialize;
+use solana_program::msg;
+use solana_program::program::invoke;
+use solana_program::program_error::ProgramError;
+use solana_program::rent::Rent;
+use solana_program::system_instruction::transfer;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Set the withdrawal authority and the payout destination of a validator fees vault, so that
+/// the validator identity signing the commits does not control the accumulated fees
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the withdrawal authority of the validator fees vault
+/// 1: `[]`         the validator identity
+/// 2: `[writable]` the validator fees vault PDA
+/// 3: `[]`         the system program
+///
+/// Requirements:
+///
+/// - validator fees vault is initialized
+/// - withdrawal authority is the one of the validator fees vault, which is the validator
+///   identity until the withdrawal settings are set
+/// - the new withdrawal authority and payout destination are not the default pubkey
+///
+/// Steps:
+///
+/// 1. Resize the validator fees vault if it holds no withdrawal settings yet, the withdrawal
+///    authority paying for the additional rent
+/// 2. Store the new withdrawal authority and payout destination, keeping the fees ledger
+///
+/// NOTE: once the withdrawal authority is set to another key, the validator identity can no
+///       longer claim the fees nor update the withdrawal settings
+pub fn process_set_validator_fees_vault_authority(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = SetValidatorFeesVaultAuthorityArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [withdrawal_authority, validator_identity, validator_fees_vault, system_program] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(withdrawal_authority, "withdrawal authority")?;
+    load_initialized_validator_fees_vault(validator_identity, validator_fees_vault, true)?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    // Check if the withdrawal authority is the correct one
+    let mut validator_fees_vault_settings =
+        load_validator_fees_vault_settings(validator_identity.key, validator_fees_vault)?;
+    validate_validator_fees_vault_withdrawal_authority(
+        withdrawal_authority,
+        &validator_fees_vault_settings,
+    )?;
+
+    if args.withdrawal_authority.eq(&Pubkey::default())
+        || args.payout_destination.eq(&Pubkey::default())
+    {
+        msg!("Withdrawal authority and payout destination cannot be the default pubkey");
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Resize the vaults created before the withdrawal settings were introduced
+    let size = ValidatorFeesVault::size_with_discriminator();
+    if validator_fees_vault.data_len() < size {
+        let rent = Rent::default();
+        let rent_diff = rent
+            .minimum_balance(size)
+            .checked_sub(rent.minimum_balance(validator_fees_vault.data_len()))
+            .ok_or(DlpError::Overflow)?;
+        invoke(
+            &transfer(
+                withdrawal_authority.key,
+                validator_fees_vault.key,
+                rent_diff,
+            ),
+            &[
+                withdrawal_authority.clone(),
+                validator_fees_vault.clone(),
+                system_program.clone(),
+            ],
+        )?;
+        validator_fees_vault.realloc(size, true)?;
+    }
+
+    validator_fees_vault_settings.withdrawal_authority = args.withdrawal_authority;
+    validator_fees_vault_settings.payout_destination = args.payout_destination;
+    let mut validator_fees_vault_data = validator_fees_vault.try_borrow_mut_data()?;
+    validator_fees_vault_settings.to_bytes_with_discriminator(&mut validator_fees_vault_data)?;
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use crate::args::SlashValidatorBondArgs;
+use crate::error::DlpError;
+use crate::pda::fees_vault_pda;
+use crate::processor::utils::authority::validate_protocol_admin;
+use crate::processor::utils::fees_ledger::record_protocol_fees;
+use crate::processor::utils::loaders::{
+    load_initialized_protocol_fees_vault, load_initialized_validator_bond, load_signer,
+};
+use crate::processor::{load_pending_dispute, settle_dispute};
+use crate::state::ValidatorBond;
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+/// Slash lamports from a validator bond, upholding the pending dispute of one of its commits,
+/// to the protocol fees vault or to the parties harmed by the validator
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the admin slashing the bond
+/// 1: `[]`         the delegation program data account
+/// 2: `[]`         the validator identity
+/// 3: `[writable]` the validator bond PDA
+/// 4: `[writable]` the account receiving the slashed lamports
+/// 5: `[]`         the protocol config PDA
+/// 6: `[]`         the delegated account
+/// 7: `[writable]` the commit record of the disputed commit
+/// 8: `[writable]` the delegation metadata PDA
+/// 9: `[writable]` the challenger that disputed the commit
+///
+/// Requirements:
+///
+/// - admin is the protocol admin
+/// - validator bond is initialized
+/// - validator bond holds at least the lamports to slash, bonded or unbonding
+/// - commit is disputed and the dispute is pending resolution, see
+///   [crate::processor::process_resolve_dispute]
+/// - challenger and validator match the disputer and the identity in the commit record
+///
+/// Steps:
+///
+/// 1. Uphold the dispute as in [crate::processor::process_resolve_dispute], so that a dispute
+///    slashes the validator at most once
+/// 2. Decrease the bonded amount, then the unbonding amount once the bonded amount is exhausted
+/// 3. Transfer the slashed lamports to the receiver
+/// 4. Record the slashed lamports in the fees ledger if the receiver is the protocol fees vault
+pub fn process_slash_validator_bond(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = SlashValidatorBondArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [admin, delegation_program_data, validator, validator_bond_account, receiver, protocol_config_account, delegated_account, commit_record_account, delegation_metadata_account, challenger] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(admin, "admin")?;
+    load_initialized_validator_bond(validator, validator_bond_account, true)?;
+
+    // Check if the admin is the correct one
+    validate_protocol_admin(admin, protocol_config_account, delegation_program_data)?;
+
+    // The validator is only slashed for a disputed commit, upholding the dispute
+    let delegation_metadata = load_pending_dispute(
+        delegated_account,
+        commit_record_account,
+        delegation_metadata_account,
+        args.commit_nonce,
+    )?;
+    settle_dispute(
+        commit_record_account,
+        delegation_metadata_account,
+        delegation_metadata,
+        challenger,
+        validator,
+        true,
+    )?;
+
+    // Slash the bonded lamports first, so that unbonding does not shield the validator
+    let mut validator_bond_data = validator_bond_account.try_borrow_mut_data()?;
+    let validator_bond =
+        ValidatorBond::try_from_bytes_with_discriminator_mut(&mut validator_bond_data)?;
+    let slashed_bond = args.amount.min(validator_bond.amount);
+    let slashed_unbonding = args.amount - slashed_bond;
+    if validator_bond.unbonding_amount < slashed_unbonding {
+        msg!(
+            "Cannot slash {} lamports, the bond is {} and {} are unbonding",
+            args.amount,
+            validator_bond.amount,
+            validator_bond.unbonding_amount
+        );
+        return Err(DlpError::InsufficientValidatorBond.into());
+    }
+    validator_bond.amount -= slashed_bond;
+    validator_bond.unbonding_amount -= slashed_unbonding;
+    drop(validator_bond_data);
+
+    // Transfer the slashed lamports to the receiver
+    **validator_bond_account.try_borrow_mut_lamports()? = validator_bond_account
+        .lamports()
+        .checked_sub(args.amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **receiver.try_borrow_mut_lamports()? = receiver
+        .lamports()
+        .checked_add(args.amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if receiver.key.eq(&fees_vault_pda()) {
+        load_initialized_protocol_fees_vault(receiver, true)?;
+        record_protocol_fees(receiver, |ledger| ledger.record_slash(args.amount))?;
+    }
+
+    Ok(())
+}
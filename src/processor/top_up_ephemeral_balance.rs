@@ -1,5 +1,6 @@
 use crate::args::TopUpEphemeralBalanceArgs;
 use crate::ephemeral_balance_seeds_from_payer;
+use crate::event::{EphemeralBalanceToppedUpEvent, Event};
 use crate::processor::utils::loaders::{load_pda, load_program, load_signer};
 use crate::processor::utils::pda::create_pda;
 use borsh::BorshDeserialize;
@@ -77,5 +78,13 @@ pub fn process_top_up_ephemeral_balance(
         )?;
     }
 
+    EphemeralBalanceToppedUpEvent {
+        payer: *payer.key,
+        pubkey: *pubkey.key,
+        index: args.index,
+        amount: args.amount,
+    }
+    .emit()?;
+
     Ok(())
 }
@@ -0,0 +1,148 @@
+use crate::args::TopUpEphemeralBalanceArgs;
+use crate::consts::{TOKEN_ACCOUNT_SIZE, TOKEN_PROGRAM_ID};
+use crate::event::{EphemeralTokenBalanceToppedUpEvent, Event};
+use crate::processor::utils::loaders::{load_pda, load_program, load_signer};
+use crate::processor::utils::pda::create_pda;
+use crate::processor::utils::token::{
+    initialize_token_account, load_token_account, transfer_tokens,
+};
+use crate::state::EphemeralTokenBalance;
+use crate::{
+    ephemeral_token_balance_escrow_seeds_from_balance, ephemeral_token_balance_seeds_from_payer,
+};
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Tops up the ephemeral token balance account.
+///
+/// Accounts:
+///
+/// 0: `[signer]` payer account who funds the topup and the accounts rent
+/// 1: `[]` pubkey account that the ephemeral token balance PDA was derived from
+/// 2: `[]` mint of the tokens
+/// 3: `[writable]` ephemeral token balance account
+/// 4: `[writable]` token escrow of the ephemeral token balance to top up
+/// 5: `[writable]` source token account, whose tokens are owned by the payer
+/// 6: `[]` token program
+/// 7: `[]` system program
+///
+/// Requirements:
+///
+/// - the source token account has enough tokens to fund the transfer
+///
+/// Steps:
+///
+/// 1. Create the ephemeral token balance PDA and its token escrow if they do not exist
+/// 2. Transfer tokens from the source token account to the token escrow
+///
+/// NOTE: the tokens deposited while the ephemeral token balance is delegated are only
+///       available in the ephemeral rollup once it is delegated again
+pub fn process_top_up_ephemeral_token_balance(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    // Parse args.
+    let args = TopUpEphemeralBalanceArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [payer, pubkey, mint, ephemeral_token_balance_account, escrow_account, source_token_account, token_program, system_program] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(payer, "payer")?;
+    load_program(token_program, TOKEN_PROGRAM_ID, "token program")?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    let bump_ephemeral_token_balance = load_pda(
+        ephemeral_token_balance_account,
+        ephemeral_token_balance_seeds_from_payer!(pubkey.key, mint.key, args.index),
+        &crate::id(),
+        true,
+        "ephemeral token balance",
+    )?;
+    let bump_escrow = load_pda(
+        escrow_account,
+        ephemeral_token_balance_escrow_seeds_from_balance!(ephemeral_token_balance_account.key),
+        &crate::id(),
+        true,
+        "token escrow",
+    )?;
+
+    // Create the ephemeral token balance PDA if it does not exist
+    if ephemeral_token_balance_account
+        .owner
+        .eq(&system_program::id())
+    {
+        create_pda(
+            ephemeral_token_balance_account,
+            &crate::id(),
+            EphemeralTokenBalance::size_with_discriminator(),
+            ephemeral_token_balance_seeds_from_payer!(pubkey.key, mint.key, args.index),
+            bump_ephemeral_token_balance,
+            system_program,
+            payer,
+        )?;
+        let ephemeral_token_balance = EphemeralTokenBalance {
+            mint: *mint.key,
+            amount: 0,
+        };
+        let mut ephemeral_token_balance_data =
+            ephemeral_token_balance_account.try_borrow_mut_data()?;
+        ephemeral_token_balance.to_bytes_with_discriminator(&mut ephemeral_token_balance_data)?;
+    }
+
+    // Create the token escrow if it does not exist, its tokens are owned by the ephemeral
+    // token balance PDA
+    if escrow_account.owner.eq(&system_program::id()) {
+        create_pda(
+            escrow_account,
+            &TOKEN_PROGRAM_ID,
+            TOKEN_ACCOUNT_SIZE,
+            ephemeral_token_balance_escrow_seeds_from_balance!(ephemeral_token_balance_account.key),
+            bump_escrow,
+            system_program,
+            payer,
+        )?;
+        initialize_token_account(
+            escrow_account,
+            mint,
+            ephemeral_token_balance_account.key,
+            token_program,
+        )?;
+    }
+    load_token_account(
+        escrow_account,
+        mint.key,
+        Some(ephemeral_token_balance_account.key),
+        "token escrow",
+    )?;
+
+    // Transfer tokens from the source token account to the token escrow
+    if args.amount > 0 {
+        transfer_tokens(
+            source_token_account,
+            escrow_account,
+            payer,
+            args.amount,
+            token_program,
+            &[],
+        )?;
+    }
+
+    EphemeralTokenBalanceToppedUpEvent {
+        payer: *payer.key,
+        pubkey: *pubkey.key,
+        mint: *mint.key,
+        index: args.index,
+        amount: args.amount,
+    }
+    .emit()?;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::args::UnbondValidatorBondArgs;
+use crate::consts::VALIDATOR_UNBONDING_PERIOD_SLOTS;
+use crate::error::DlpError;
+use crate::processor::utils::loaders::{load_initialized_validator_bond, load_signer};
+use crate::state::ValidatorBond;
+use borsh::BorshDeserialize;
+use solana_program::clock::Clock;
+use solana_program::program_error::ProgramError;
+use solana_program::sysvar::Sysvar;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+/// Request to unbond lamports from the validator bond, which can be withdrawn with
+/// [crate::processor::process_withdraw_validator_bond] once the unbonding period has elapsed
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator owning the bond
+/// 1: `[writable]` the validator bond PDA
+///
+/// Requirements:
+///
+/// - validator bond is initialized
+/// - validator bond holds at least the lamports to unbond
+///
+/// Steps:
+///
+/// 1. Move the lamports from the bonded amount to the unbonding amount
+/// 2. Restart the unbonding period, see [crate::consts::VALIDATOR_UNBONDING_PERIOD_SLOTS]
+pub fn process_unbond_validator_bond(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = UnbondValidatorBondArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [validator, validator_bond_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(validator, "validator")?;
+    load_initialized_validator_bond(validator, validator_bond_account, true)?;
+
+    let mut validator_bond_data = validator_bond_account.try_borrow_mut_data()?;
+    let validator_bond =
+        ValidatorBond::try_from_bytes_with_discriminator_mut(&mut validator_bond_data)?;
+    if validator_bond.amount < args.amount {
+        msg!(
+            "Cannot unbond {} lamports, the bond is {}",
+            args.amount,
+            validator_bond.amount
+        );
+        return Err(DlpError::InsufficientValidatorBond.into());
+    }
+
+    validator_bond.amount -= args.amount;
+    validator_bond.unbonding_amount = validator_bond
+        .unbonding_amount
+        .checked_add(args.amount)
+        .ok_or(DlpError::Overflow)?;
+    validator_bond.unbonding_end_slot = Clock::get()?
+        .slot
+        .checked_add(VALIDATOR_UNBONDING_PERIOD_SLOTS)
+        .ok_or(DlpError::Overflow)?;
+
+    Ok(())
+}
@@ -1,5 +1,10 @@
-use crate::consts::{EXTERNAL_UNDELEGATE_DISCRIMINATOR, RENT_FEES_PERCENTAGE};
+use crate::consts::EXTERNAL_UNDELEGATE_DISCRIMINATOR;
 use crate::error::DlpError;
+use crate::event::{Event, UndelegatedEvent};
+use crate::processor::utils::authority::{
+    load_protocol_config_rent_fees_bps, validate_delegation_authority,
+};
+use crate::processor::utils::fees_ledger::{record_protocol_fees, record_validator_fees};
 use crate::processor::utils::loaders::{
     load_initialized_delegation_metadata, load_initialized_delegation_record,
     load_initialized_protocol_fees_vault, load_initialized_validator_fees_vault, load_owned_pda,
@@ -38,6 +43,7 @@ use solana_program::{
 ///  9: `[writable]` the protocol fees vault account
 /// 10: `[writable]` the validator fees vault account
 /// 11: `[]`         the system program
+/// 12: `[]`         the protocol config
 ///
 /// Requirements:
 ///
@@ -45,18 +51,24 @@ use solana_program::{
 /// - delegation record is initialized
 /// - delegation metadata is initialized
 /// - protocol fees vault is initialized
+/// - protocol config is initialized, or not exists in which case the default rent fees apply
 /// - validator fees vault is initialized
-/// - commit state is uninitialized
-/// - commit record is uninitialized
+/// - commit state is uninitialized and derived from the next finalize nonce
+/// - commit record is uninitialized and derived from the next finalize nonce, which for the
+///   first commit also covers a commit pending since before commits were queued, see
+///   [crate::pda::commit_nonce_seed]
 /// - delegated account is NOT undelegatable
 /// - owner program account matches the owner in the delegation record
 /// - rent reimbursement account matches the rent payer in the delegation metadata
+/// - validator is the delegation record authority, unless the authority is the default pubkey
 ///
 /// Steps:
 ///
 /// - Close the delegation metadata
-/// - Close the delegation record
-/// - If delegated account has no data, assign to prev owner (and stop here)
+/// - Close the delegation record, the rent fees are set by the protocol config and recorded
+///   in the fees ledgers of the vaults
+/// - If delegated account has no data, or is an ephemeral token balance owned by the delegation
+///   program, assign to prev owner (and stop here)
 /// - If there's data, create an "undelegate_buffer" and store the data in it
 /// - Close the original delegated account
 /// - CPI to the original owner to re-open the PDA with the original owner and the new state
@@ -69,7 +81,7 @@ pub fn process_undelegate(
     accounts: &[AccountInfo],
     _data: &[u8],
 ) -> ProgramResult {
-    let [validator, delegated_account, owner_program, undelegate_buffer_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, rent_reimbursement, fees_vault, validator_fees_vault, system_program] =
+    let [validator, delegated_account, owner_program, undelegate_buffer_account, commit_state_account, commit_record_account, delegation_record_account, delegation_metadata_account, rent_reimbursement, fees_vault, validator_fees_vault, system_program, protocol_config_account] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
@@ -83,18 +95,24 @@ pub fn process_undelegate(
     load_initialized_protocol_fees_vault(fees_vault, true)?;
     load_initialized_validator_fees_vault(validator, validator_fees_vault, true)?;
     load_program(system_program, system_program::id(), "system program")?;
+    let rent_fees_bps = load_protocol_config_rent_fees_bps(protocol_config_account)?;
 
     // Make sure there is no pending commits to be finalized before this call
+    let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
+    let commit_nonce =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_data)?
+            .next_finalize_nonce;
+    drop(delegation_metadata_data);
     load_uninitialized_pda(
         commit_state_account,
-        commit_state_seeds_from_delegated_account!(delegated_account.key),
+        commit_state_seeds_from_delegated_account!(delegated_account.key, commit_nonce),
         &crate::id(),
         false,
         "commit state",
     )?;
     load_uninitialized_pda(
         commit_record_account,
-        commit_record_seeds_from_delegated_account!(delegated_account.key),
+        commit_record_seeds_from_delegated_account!(delegated_account.key, commit_nonce),
         &crate::id(),
         false,
         "commit record",
@@ -115,6 +133,9 @@ pub fn process_undelegate(
         return Err(ProgramError::InvalidAccountOwner);
     }
 
+    // Check that the validator is allowed to undelegate the delegated account
+    validate_delegation_authority(delegation_record, validator)?;
+
     // Load delegated account metadata
     let delegation_metadata_data = delegation_metadata_account.try_borrow_data()?;
     let delegation_metadata =
@@ -143,17 +164,74 @@ pub fn process_undelegate(
     drop(delegation_record_data);
     drop(delegation_metadata_data);
 
-    // If there is no program to call CPI to, we can just assign the owner back and we're done
-    if delegated_account.data_is_empty() {
+    let validator_fees_vault_lamports = validator_fees_vault.lamports();
+    let fees_vault_lamports = fees_vault.lamports();
+    process_undelegation(
+        validator,
+        delegated_account,
+        owner_program,
+        undelegate_buffer_account,
+        delegation_record_account,
+        delegation_metadata_account,
+        delegation_metadata,
+        rent_reimbursement,
+        &[validator_fees_vault, fees_vault],
+        rent_fees_bps,
+        system_program,
+    )?;
+
+    // Record the rent fees in the fees ledgers
+    let validator_rent_fees = validator_fees_vault
+        .lamports()
+        .checked_sub(validator_fees_vault_lamports)
+        .ok_or(DlpError::Overflow)?;
+    record_validator_fees(validator.key, validator_fees_vault, |ledger| {
+        ledger.record_rent_fees(validator_rent_fees)
+    })?;
+    let protocol_rent_fees = fees_vault
+        .lamports()
+        .checked_sub(fees_vault_lamports)
+        .ok_or(DlpError::Overflow)?;
+    record_protocol_fees(fees_vault, |ledger| {
+        ledger.record_rent_fees(protocol_rent_fees)
+    })
+}
+
+/// Give the delegated account back to its owner program and close the delegation PDAs
+///
+/// The payer funds the undelegate buffer and the re-opening of the account during the CPI,
+/// and is refunded with the delegated account lamports
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn process_undelegation<'a, 'info>(
+    payer: &'a AccountInfo<'info>,
+    delegated_account: &'a AccountInfo<'info>,
+    owner_program: &'a AccountInfo<'info>,
+    undelegate_buffer_account: &'a AccountInfo<'info>,
+    delegation_record_account: &'a AccountInfo<'info>,
+    delegation_metadata_account: &'a AccountInfo<'info>,
+    delegation_metadata: DelegationMetadata,
+    rent_reimbursement: &'a AccountInfo<'info>,
+    fees_addresses: &[&'a AccountInfo<'info>],
+    rent_fees_bps: u16,
+    system_program: &'a AccountInfo<'info>,
+) -> ProgramResult {
+    // If there is no program to call CPI to, we can just assign the owner back and we're done.
+    // An ephemeral token balance is owned by the delegation program, and keeps its data
+    if delegated_account.data_is_empty() || owner_program.key.eq(&crate::id()) {
         // TODO - we could also do this fast-path if the data was non-empty but zeroed-out
         delegated_account.assign(owner_program.key);
         process_delegation_cleanup(
             delegation_record_account,
             delegation_metadata_account,
             rent_reimbursement,
-            fees_vault,
-            validator_fees_vault,
+            fees_addresses,
+            rent_fees_bps,
         )?;
+        UndelegatedEvent {
+            delegated_account: *delegated_account.key,
+            owner: *owner_program.key,
+        }
+        .emit()?;
         return Ok(());
     }
 
@@ -174,7 +252,7 @@ pub fn process_undelegate(
         undelegate_buffer_seeds,
         undelegate_buffer_bump,
         system_program,
-        validator,
+        payer,
     )?;
 
     // Copy data in the undelegation buffer PDA
@@ -188,7 +266,7 @@ pub fn process_undelegate(
 
     // Call a CPI to the owner program to give it back the new state
     process_undelegation_with_cpi(
-        validator,
+        payer,
         delegated_account,
         owner_program,
         undelegate_buffer_account,
@@ -198,16 +276,21 @@ pub fn process_undelegate(
     )?;
 
     // Done, close undelegation buffer
-    close_pda(undelegate_buffer_account, validator)?;
+    close_pda(undelegate_buffer_account, payer)?;
 
     // Closing delegation accounts
     process_delegation_cleanup(
         delegation_record_account,
         delegation_metadata_account,
         rent_reimbursement,
-        fees_vault,
-        validator_fees_vault,
+        fees_addresses,
+        rent_fees_bps,
     )?;
+    UndelegatedEvent {
+        delegated_account: *delegated_account.key,
+        owner: *owner_program.key,
+    }
+    .emit()?;
     Ok(())
 }
 
@@ -317,20 +400,20 @@ fn process_delegation_cleanup<'a, 'info>(
     delegation_record_account: &'a AccountInfo<'info>,
     delegation_metadata_account: &'a AccountInfo<'info>,
     rent_reimbursement: &'a AccountInfo<'info>,
-    fees_vault: &'a AccountInfo<'info>,
-    validator_fees_vault: &'a AccountInfo<'info>,
+    fees_addresses: &[&'a AccountInfo<'info>],
+    rent_fees_bps: u16,
 ) -> ProgramResult {
     close_pda_with_fees(
         delegation_record_account,
         rent_reimbursement,
-        &[validator_fees_vault, fees_vault],
-        RENT_FEES_PERCENTAGE,
+        fees_addresses,
+        rent_fees_bps,
     )?;
     close_pda_with_fees(
         delegation_metadata_account,
         rent_reimbursement,
-        &[validator_fees_vault, fees_vault],
-        RENT_FEES_PERCENTAGE,
+        fees_addresses,
+        rent_fees_bps,
     )?;
     Ok(())
 }
@@ -0,0 +1,84 @@
+use crate::args::UpdateProtocolConfigArgs;
+use crate::consts::MAX_FEES_BPS;
+use crate::error::DlpError::{InvalidProtocolConfig, Unauthorized};
+use crate::processor::utils::loaders::{load_initialized_pda, load_signer};
+use crate::protocol_config_seeds;
+use crate::state::ProtocolConfig;
+use borsh::BorshDeserialize;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// Update the protocol parameters, without redeploying the program
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the admin of the protocol config
+/// 1: `[writable]` the protocol config PDA
+///
+/// Requirements:
+///
+/// - protocol config is initialized
+/// - admin is the admin stored in the protocol config
+/// - fee rates are at most [MAX_FEES_BPS]
+///
+/// Steps:
+///
+/// 1. Update the parameters provided in the args, the others are unchanged
+///
+/// NOTE: the admin is rotated in two steps, see [crate::processor::process_propose_protocol_admin]
+pub fn process_update_protocol_config(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = UpdateProtocolConfigArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [admin, protocol_config_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(admin, "admin")?;
+    load_initialized_pda(
+        protocol_config_account,
+        protocol_config_seeds!(),
+        &crate::id(),
+        true,
+        "protocol config",
+    )?;
+
+    let mut protocol_config_data = protocol_config_account.try_borrow_mut_data()?;
+    let mut protocol_config =
+        ProtocolConfig::try_from_bytes_with_discriminator(&protocol_config_data)?;
+
+    // Check if the admin is the correct one
+    if !admin.key.eq(&protocol_config.admin) {
+        msg!(
+            "Expected admin pubkey: {} but got {}",
+            protocol_config.admin,
+            admin.key
+        );
+        return Err(Unauthorized.into());
+    }
+
+    if let Some(rent_fees_bps) = args.rent_fees_bps {
+        protocol_config.rent_fees_bps = rent_fees_bps;
+    }
+    if let Some(protocol_fees_bps) = args.protocol_fees_bps {
+        protocol_config.protocol_fees_bps = protocol_fees_bps;
+    }
+    if let Some(min_commit_frequency_ms) = args.min_commit_frequency_ms {
+        protocol_config.min_commit_frequency_ms = min_commit_frequency_ms;
+    }
+    if let Some(commit_fee) = args.commit_fee {
+        protocol_config.commit_fee = commit_fee;
+    }
+    if !protocol_config.has_valid_fees() {
+        msg!("Fee rates must be at most {} basis points", MAX_FEES_BPS);
+        return Err(InvalidProtocolConfig.into());
+    }
+    protocol_config.to_bytes_with_discriminator(&mut protocol_config_data.as_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,328 @@
+use crate::consts::{
+    DEFAULT_COMMIT_STALENESS_MULTIPLIER, DEFAULT_PROTOCOL_FEES_BPS, DEFAULT_RENT_FEES_BPS,
+};
+use crate::error::DlpError;
+use crate::error::DlpError::Unauthorized;
+use crate::processor::utils::loaders::{
+    load_program_config, load_program_upgrade_authority, load_protocol_config, load_validator_bond,
+};
+use crate::state::{
+    DelegationRecord, FeesLedger, ProgramConfig, ProtocolConfig, ValidatorBond, ValidatorFeesVault,
+};
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+
+/// Errors if:
+/// - The delegation record authority is set and does not match the validator.
+///
+/// A default (all zeros) authority means any whitelisted validator can operate
+/// on the delegated account.
+pub fn validate_delegation_authority(
+    delegation_record: &DelegationRecord,
+    validator: &AccountInfo,
+) -> Result<(), ProgramError> {
+    if delegation_record.authority.eq(&Pubkey::default()) {
+        return Ok(());
+    }
+
+    if !delegation_record.authority.eq(validator.key) {
+        msg!(
+            "Expected delegation authority to be {}, but got {}",
+            delegation_record.authority,
+            validator.key
+        );
+        return Err(DlpError::InvalidDelegationAuthority.into());
+    }
+
+    Ok(())
+}
+
+/// Errors if:
+/// - The program config of the delegated account owner exists and does not approve the validator.
+pub fn validate_program_config_validator(
+    program_config_account: &AccountInfo,
+    program: Pubkey,
+    validator: &Pubkey,
+) -> Result<(), ProgramError> {
+    let has_program_config = load_program_config(program_config_account, program, false)?;
+    if !has_program_config {
+        return Ok(());
+    }
+
+    let program_config_data = program_config_account.try_borrow_data()?;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(&program_config_data)?;
+    if !program_config.approved_validators.contains(validator) {
+        msg!(
+            "validator ({}) is not whitelisted in the program config",
+            validator
+        );
+        return Err(DlpError::InvalidWhitelistProgramConfig.into());
+    }
+
+    Ok(())
+}
+
+/// Errors if:
+/// - The program config of the delegated account owner exists and requires a minimum validator
+///   bond, which the validator bond does not hold. A missing validator bond holds nothing.
+pub fn validate_program_config_validator_bond(
+    program_config_account: &AccountInfo,
+    program: Pubkey,
+    validator: &AccountInfo,
+    validator_bond_account: Option<&AccountInfo>,
+) -> Result<(), ProgramError> {
+    let validator_bond_account = match validator_bond_account {
+        Some(validator_bond_account)
+            if load_validator_bond(validator, validator_bond_account, false)? =>
+        {
+            Some(validator_bond_account)
+        }
+        _ => None,
+    };
+    let has_program_config = load_program_config(program_config_account, program, false)?;
+    if !has_program_config {
+        return Ok(());
+    }
+
+    let program_config_data = program_config_account.try_borrow_data()?;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(&program_config_data)?;
+    if program_config.min_validator_bond == 0 {
+        return Ok(());
+    }
+    let bond_amount = match validator_bond_account {
+        Some(validator_bond_account) => {
+            let validator_bond_data = validator_bond_account.try_borrow_data()?;
+            ValidatorBond::try_from_bytes_with_discriminator(&validator_bond_data)?.amount
+        }
+        None => 0,
+    };
+    if bond_amount < program_config.min_validator_bond {
+        msg!(
+            "validator ({}) bond is {}, but the program config requires {}",
+            validator.key,
+            bond_amount,
+            program_config.min_validator_bond
+        );
+        return Err(DlpError::InsufficientValidatorBond.into());
+    }
+
+    Ok(())
+}
+
+/// Returns the challenge period of the program config of the delegated account owner,
+/// or zero if there is no program config.
+pub fn load_program_config_challenge_period(
+    program_config_account: &AccountInfo,
+    program: Pubkey,
+) -> Result<u64, ProgramError> {
+    let has_program_config = load_program_config(program_config_account, program, false)?;
+    if !has_program_config {
+        return Ok(0);
+    }
+
+    let program_config_data = program_config_account.try_borrow_data()?;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(&program_config_data)?;
+    Ok(program_config.challenge_period)
+}
+
+/// Returns whether the program config of the delegated account owner requires the owner program
+/// to validate the commits, or false if there is no program config.
+pub fn load_program_config_validate_commits(
+    program_config_account: &AccountInfo,
+    program: Pubkey,
+) -> Result<bool, ProgramError> {
+    let has_program_config = load_program_config(program_config_account, program, false)?;
+    if !has_program_config {
+        return Ok(false);
+    }
+
+    let program_config_data = program_config_account.try_borrow_data()?;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(&program_config_data)?;
+    Ok(program_config.validate_commits)
+}
+
+/// Returns the commit staleness multiplier of the program config of the delegated account owner,
+/// or [DEFAULT_COMMIT_STALENESS_MULTIPLIER] if there is no program config or it is not set.
+pub fn load_program_config_commit_staleness_multiplier(
+    program_config_account: &AccountInfo,
+    program: Pubkey,
+) -> Result<u64, ProgramError> {
+    let has_program_config = load_program_config(program_config_account, program, false)?;
+    if !has_program_config {
+        return Ok(DEFAULT_COMMIT_STALENESS_MULTIPLIER);
+    }
+
+    let program_config_data = program_config_account.try_borrow_data()?;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(&program_config_data)?;
+    match program_config.commit_staleness_multiplier {
+        0 => Ok(DEFAULT_COMMIT_STALENESS_MULTIPLIER),
+        multiplier => Ok(multiplier),
+    }
+}
+
+/// Returns the commit fee of the program config of the delegated account owner, or the commit
+/// fee of the protocol config if the program config does not set one, or zero if neither exists.
+pub fn load_program_config_commit_fee(
+    program_config_account: &AccountInfo,
+    program: Pubkey,
+    protocol_config_account: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    if load_program_config(program_config_account, program, false)? {
+        let program_config_data = program_config_account.try_borrow_data()?;
+        let program_config =
+            ProgramConfig::try_from_bytes_with_discriminator(&program_config_data)?;
+        if program_config.commit_fee != 0 {
+            return Ok(program_config.commit_fee);
+        }
+    }
+    Ok(
+        load_protocol_config_if_initialized(protocol_config_account)?
+            .map_or(0, |config| config.commit_fee),
+    )
+}
+
+/// Returns the protocol config, or None if it is not initialized yet.
+fn load_protocol_config_if_initialized(
+    protocol_config_account: &AccountInfo,
+) -> Result<Option<ProtocolConfig>, ProgramError> {
+    if !load_protocol_config(protocol_config_account, false)? {
+        return Ok(None);
+    }
+
+    let protocol_config_data = protocol_config_account.try_borrow_data()?;
+    let protocol_config = ProtocolConfig::try_from_bytes_with_discriminator(&protocol_config_data)?;
+    Ok(Some(protocol_config))
+}
+
+/// Returns the rent fees of the protocol config in basis points,
+/// or [DEFAULT_RENT_FEES_BPS] if there is no protocol config.
+pub fn load_protocol_config_rent_fees_bps(
+    protocol_config_account: &AccountInfo,
+) -> Result<u16, ProgramError> {
+    Ok(
+        load_protocol_config_if_initialized(protocol_config_account)?
+            .map_or(DEFAULT_RENT_FEES_BPS, |config| config.rent_fees_bps),
+    )
+}
+
+/// Returns the protocol fees of the protocol config in basis points,
+/// or [DEFAULT_PROTOCOL_FEES_BPS] if there is no protocol config.
+pub fn load_protocol_config_protocol_fees_bps(
+    protocol_config_account: &AccountInfo,
+) -> Result<u16, ProgramError> {
+    Ok(
+        load_protocol_config_if_initialized(protocol_config_account)?
+            .map_or(DEFAULT_PROTOCOL_FEES_BPS, |config| config.protocol_fees_bps),
+    )
+}
+
+/// Returns the minimum commit frequency of the protocol config,
+/// or zero if there is no protocol config.
+pub fn load_protocol_config_min_commit_frequency_ms(
+    protocol_config_account: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    Ok(
+        load_protocol_config_if_initialized(protocol_config_account)?
+            .map_or(0, |config| config.min_commit_frequency_ms),
+    )
+}
+
+/// Returns the protocol admin, which is the admin of the protocol config, or the delegation
+/// program upgrade authority until the protocol config is initialized.
+pub fn load_protocol_admin(
+    protocol_config_account: &AccountInfo,
+    delegation_program_data: &AccountInfo,
+) -> Result<Pubkey, ProgramError> {
+    match load_protocol_config_if_initialized(protocol_config_account)? {
+        Some(protocol_config) => Ok(protocol_config.admin),
+        None => Ok(
+            load_program_upgrade_authority(&crate::ID, delegation_program_data)?
+                .ok_or(Unauthorized)?,
+        ),
+    }
+}
+
+/// Errors if:
+/// - The admin is not the protocol admin, see [load_protocol_admin].
+pub fn validate_protocol_admin(
+    admin: &AccountInfo,
+    protocol_config_account: &AccountInfo,
+    delegation_program_data: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let admin_pubkey = load_protocol_admin(protocol_config_account, delegation_program_data)?;
+    if !admin.key.eq(&admin_pubkey) {
+        msg!(
+            "Expected admin pubkey: {} but got {}",
+            admin_pubkey,
+            admin.key
+        );
+        return Err(Unauthorized.into());
+    }
+
+    Ok(())
+}
+
+/// Authority is valid if either the authority is the protocol admin or the program upgrade authority
+pub fn validate_program_config_authority(
+    authority: &AccountInfo,
+    program: &AccountInfo,
+    program_data: &AccountInfo,
+    delegation_program_data: &AccountInfo,
+    protocol_config_account: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let admin_pubkey = load_protocol_admin(protocol_config_account, delegation_program_data)?;
+    if authority.key.eq(&admin_pubkey)
+        || authority
+            .key
+            .eq(&load_program_upgrade_authority(program.key, program_data)?.ok_or(Unauthorized)?)
+    {
+        Ok(())
+    } else {
+        msg!(
+            "Expected authority to be {} or program upgrade authority, but got {}",
+            admin_pubkey,
+            authority.key
+        );
+        Err(Unauthorized.into())
+    }
+}
+
+/// Returns the withdrawal settings of the validator fees vault, which default to the validator
+/// identity for vaults holding no settings yet.
+pub fn load_validator_fees_vault_settings(
+    validator_identity: &Pubkey,
+    validator_fees_vault: &AccountInfo,
+) -> Result<ValidatorFeesVault, ProgramError> {
+    if validator_fees_vault.data_len() < ValidatorFeesVault::size_with_discriminator() {
+        return Ok(ValidatorFeesVault {
+            withdrawal_authority: *validator_identity,
+            payout_destination: *validator_identity,
+            ledger: FeesLedger::default(),
+        });
+    }
+    let validator_fees_vault_data = validator_fees_vault.try_borrow_data()?;
+    Ok(*ValidatorFeesVault::try_from_bytes_with_discriminator(
+        &validator_fees_vault_data,
+    )?)
+}
+
+/// Errors if:
+/// - The withdrawal authority is not the one of the validator fees vault, see
+///   [load_validator_fees_vault_settings].
+pub fn validate_validator_fees_vault_withdrawal_authority(
+    withdrawal_authority: &AccountInfo,
+    validator_fees_vault_settings: &ValidatorFeesVault,
+) -> Result<(), ProgramError> {
+    if !withdrawal_authority
+        .key
+        .eq(&validator_fees_vault_settings.withdrawal_authority)
+    {
+        msg!(
+            "Expected withdrawal authority: {} but got {}",
+            validator_fees_vault_settings.withdrawal_authority,
+            withdrawal_authority.key
+        );
+        return Err(Unauthorized.into());
+    }
+
+    Ok(())
+}
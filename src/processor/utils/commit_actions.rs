@@ -0,0 +1,96 @@
+use crate::args::CommitAction;
+use crate::commit_action_signer_seeds_from_delegated_account;
+use crate::consts::MAX_COMMIT_ACTIONS;
+use crate::error::DlpError;
+use crate::pda::commit_action_signer_pda_from_delegated_account;
+use solana_program::account_info::AccountInfo;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::program::invoke_signed;
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+
+/// Errors if:
+/// - There are more than [MAX_COMMIT_ACTIONS] actions.
+/// - An action invokes the delegation program.
+/// - An action requires a signature from another account than the commit action signer.
+pub fn validate_commit_actions(
+    delegated_account: &Pubkey,
+    actions: &[CommitAction],
+) -> Result<(), ProgramError> {
+    if actions.len() > MAX_COMMIT_ACTIONS {
+        msg!(
+            "A commit can schedule at most {} actions, got {}",
+            MAX_COMMIT_ACTIONS,
+            actions.len()
+        );
+        return Err(DlpError::InvalidCommitActions.into());
+    }
+    let signer = commit_action_signer_pda_from_delegated_account(delegated_account);
+    for action in actions {
+        if action.program_id.eq(&crate::id()) {
+            msg!("Commit actions cannot invoke the delegation program");
+            return Err(DlpError::InvalidCommitActions.into());
+        }
+        if let Some(account) = action
+            .accounts
+            .iter()
+            .find(|account| account.is_signer && !account.pubkey.eq(&signer))
+        {
+            msg!(
+                "Commit actions can only be signed by {}, not by {}",
+                signer,
+                account.pubkey
+            );
+            return Err(DlpError::InvalidCommitActions.into());
+        }
+    }
+    Ok(())
+}
+
+/// Invoke the actions in order, signed by the commit action signer of the delegated account.
+/// The invoked programs and accounts must be found in the action accounts.
+/// Any failing action fails the whole instruction
+pub fn execute_commit_actions<'a, 'info>(
+    delegated_account: &'a AccountInfo<'info>,
+    actions: &[CommitAction],
+    action_accounts: &'a [AccountInfo<'info>],
+) -> Result<(), ProgramError> {
+    validate_commit_actions(delegated_account.key, actions)?;
+    let signer_seeds: &[&[u8]] =
+        commit_action_signer_seeds_from_delegated_account!(delegated_account.key);
+    let (_, signer_bump) = Pubkey::find_program_address(signer_seeds, &crate::id());
+    let signer_bump_slice: &[u8] = &[signer_bump];
+    let signer_seeds = [signer_seeds, &[signer_bump_slice]].concat();
+
+    let find_account_info = |pubkey: &Pubkey| {
+        action_accounts
+            .iter()
+            .find(|account_info| account_info.key.eq(pubkey))
+            .cloned()
+            .ok_or_else(|| {
+                msg!("Commit action account {} is missing", pubkey);
+                ProgramError::NotEnoughAccountKeys
+            })
+    };
+
+    for action in actions {
+        let mut account_metas = Vec::with_capacity(action.accounts.len());
+        let mut account_infos = Vec::with_capacity(action.accounts.len() + 1);
+        for account in &action.accounts {
+            account_metas.push(AccountMeta {
+                pubkey: account.pubkey,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            });
+            account_infos.push(find_account_info(&account.pubkey)?);
+        }
+        account_infos.push(find_account_info(&action.program_id)?);
+
+        let instruction = Instruction {
+            program_id: action.program_id,
+            accounts: account_metas,
+            data: action.data.clone(),
+        };
+        invoke_signed(&instruction, &account_infos, &[&signer_seeds])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use crate::error::DlpError;
+use crate::pda::protocol_config_pda;
+use crate::processor::utils::authority::{
+    load_program_config_commit_fee, validate_delegation_authority,
+};
+use crate::processor::utils::fees_ledger::record_validator_fees;
+use crate::processor::utils::loaders::{
+    load_initialized_delegation_metadata, load_initialized_delegation_record, load_owned_pda,
+};
+use crate::state::{DelegationMetadata, DelegationRecord};
+use solana_program::account_info::AccountInfo;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::sysvar::Sysvar;
+
+/// The accounts charged with the commit fee of a delegated account
+pub(crate) struct CommitFeeAccounts<'a, 'info> {
+    pub(crate) protocol_config_account: &'a AccountInfo<'info>,
+    pub(crate) ephemeral_balance_account: &'a AccountInfo<'info>,
+    pub(crate) ephemeral_balance_delegation_record: &'a AccountInfo<'info>,
+    pub(crate) ephemeral_balance_delegation_metadata: &'a AccountInfo<'info>,
+}
+
+/// Load the optional accounts charged with the commit fee, which are either absent or the
+/// protocol config PDA followed by the ephemeral balance, its delegation record and metadata
+pub(crate) fn load_commit_fee_accounts<'a, 'info>(
+    commit_fee_accounts: &'a [AccountInfo<'info>],
+) -> Result<Option<CommitFeeAccounts<'a, 'info>>, ProgramError> {
+    match commit_fee_accounts {
+        [] => Ok(None),
+        [protocol_config_account, ephemeral_balance_account, ephemeral_balance_delegation_record, ephemeral_balance_delegation_metadata] => {
+            Ok(Some(CommitFeeAccounts {
+                protocol_config_account,
+                ephemeral_balance_account,
+                ephemeral_balance_delegation_record,
+                ephemeral_balance_delegation_metadata,
+            }))
+        }
+        _ => Err(ProgramError::NotEnoughAccountKeys),
+    }
+}
+
+/// Split the optional accounts charged with the commit fee from the remaining accounts, which
+/// are led by the protocol config PDA if present
+pub(crate) fn split_commit_fee_accounts<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<
+    (
+        Option<CommitFeeAccounts<'a, 'info>>,
+        &'a [AccountInfo<'info>],
+    ),
+    ProgramError,
+> {
+    match remaining_accounts.first() {
+        Some(account) if account.key.eq(&protocol_config_pda()) => {
+            if remaining_accounts.len() < 4 {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+            let (commit_fee_accounts, remaining_accounts) = remaining_accounts.split_at(4);
+            Ok((
+                load_commit_fee_accounts(commit_fee_accounts)?,
+                remaining_accounts,
+            ))
+        }
+        _ => Ok((None, remaining_accounts)),
+    }
+}
+
+/// Debit the commit fee configured for the owner program of the delegated account from the
+/// delegated ephemeral balance of the payer of the delegation, into the validator fees vault.
+///
+/// The payer of the delegation is the rent payer of the delegated account, and its ephemeral
+/// balance must be delegated to the validator, so that the validator can account for the fee
+/// in the ephemeral rollup. Nothing is charged if no commit fee is configured.
+pub(crate) fn charge_commit_fee(
+    fee_accounts: &CommitFeeAccounts,
+    validator: &AccountInfo,
+    validator_fees_vault: &AccountInfo,
+    delegated_account: &AccountInfo,
+    delegation_metadata: &DelegationMetadata,
+    program_config_account: &AccountInfo,
+    owner: &Pubkey,
+) -> Result<(), ProgramError> {
+    let commit_fee = load_program_config_commit_fee(
+        program_config_account,
+        *owner,
+        fee_accounts.protocol_config_account,
+    )?;
+    if commit_fee == 0 {
+        return Ok(());
+    }
+
+    // The ephemeral balance must be a delegated escrow of the payer of the delegation
+    let ephemeral_balance = fee_accounts.ephemeral_balance_account;
+    if ephemeral_balance.key.eq(delegated_account.key) {
+        msg!("Ephemeral balance cannot pay the commit fee of its own commit");
+        return Err(DlpError::InvalidCommitFeePayer.into());
+    }
+    load_owned_pda(ephemeral_balance, &crate::id(), "ephemeral balance")?;
+    load_initialized_delegation_record(
+        ephemeral_balance,
+        fee_accounts.ephemeral_balance_delegation_record,
+        true,
+    )?;
+    load_initialized_delegation_metadata(
+        ephemeral_balance,
+        fee_accounts.ephemeral_balance_delegation_metadata,
+        false,
+    )?;
+    let ephemeral_balance_metadata_data = fee_accounts
+        .ephemeral_balance_delegation_metadata
+        .try_borrow_data()?;
+    let ephemeral_balance_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&ephemeral_balance_metadata_data)?;
+    let is_payer_balance = matches!(
+        ephemeral_balance_metadata.seeds.as_slice(),
+        [prefix, payer, index]
+            if prefix.as_slice() == b"balance"
+                && payer.as_slice() == delegation_metadata.rent_payer.as_ref()
+                && index.len() == 1
+    );
+    if !is_payer_balance {
+        msg!(
+            "Ephemeral balance ({}) is not an ephemeral balance of the payer ({})",
+            ephemeral_balance.key,
+            delegation_metadata.rent_payer
+        );
+        return Err(DlpError::InvalidCommitFeePayer.into());
+    }
+    drop(ephemeral_balance_metadata_data);
+
+    // Only the validator of the ephemeral balance can charge it
+    let mut ephemeral_balance_record_data = fee_accounts
+        .ephemeral_balance_delegation_record
+        .try_borrow_mut_data()?;
+    let ephemeral_balance_record = DelegationRecord::try_from_bytes_with_discriminator_mut(
+        &mut ephemeral_balance_record_data,
+    )?;
+    validate_delegation_authority(ephemeral_balance_record, validator)?;
+
+    // The ephemeral balance must remain rent exempt after paying the fee
+    let remaining_lamports = ephemeral_balance
+        .lamports()
+        .checked_sub(commit_fee)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    if remaining_lamports < Rent::get()?.minimum_balance(ephemeral_balance.data_len()) {
+        msg!(
+            "Ephemeral balance ({}) cannot pay the commit fee of {} lamports",
+            ephemeral_balance.key,
+            commit_fee
+        );
+        return Err(ProgramError::InsufficientFunds);
+    }
+    if !validator_fees_vault.is_writable {
+        msg!("Validator fees vault must be writable to receive the commit fee");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // The delegation record follows the debited lamports, the ephemeral balance is expected to
+    // be debited by the validator in the ephemeral rollup as well
+    ephemeral_balance_record.lamports = ephemeral_balance_record
+        .lamports
+        .checked_sub(commit_fee)
+        .ok_or(DlpError::Overflow)?;
+    **ephemeral_balance.try_borrow_mut_lamports()? = remaining_lamports;
+    **validator_fees_vault.try_borrow_mut_lamports()? = validator_fees_vault
+        .lamports()
+        .checked_add(commit_fee)
+        .ok_or(DlpError::Overflow)?;
+    record_validator_fees(validator.key, validator_fees_vault, |ledger| {
+        ledger.record_commit_fees(commit_fee)
+    })
+}
@@ -0,0 +1,60 @@
+use crate::processor::utils::loaders::{is_uninitialized_account, load_initialized_commit_record};
+use crate::processor::utils::pda::resize_pda;
+use crate::state::{CommitRecord, DelegationMetadata};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+
+/// Resize the delegation metadata account to the current layout of the delegation metadata, if
+/// it was created with an older and shorter layout. The payer funds the additional rent
+pub(crate) fn resize_delegation_metadata<'a, 'info>(
+    payer: &'a AccountInfo<'info>,
+    delegation_metadata_account: &'a AccountInfo<'info>,
+    system_program: &'a AccountInfo<'info>,
+    delegation_metadata: &DelegationMetadata,
+) -> ProgramResult {
+    let size = delegation_metadata.size_with_discriminator()?;
+    if delegation_metadata_account.data_len() < size {
+        resize_pda(payer, delegation_metadata_account, system_program, size)?;
+    }
+    Ok(())
+}
+
+/// Write the delegation metadata to its account, which must already hold its current layout,
+/// see [resize_delegation_metadata]
+pub(crate) fn write_delegation_metadata(
+    delegation_metadata_account: &AccountInfo,
+    delegation_metadata: &DelegationMetadata,
+) -> ProgramResult {
+    if delegation_metadata_account.data_len() < delegation_metadata.size_with_discriminator()? {
+        msg!(
+            "Delegation metadata ({}) must be migrated to its current layout",
+            delegation_metadata_account.key
+        );
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    let mut delegation_metadata_data = delegation_metadata_account.try_borrow_mut_data()?;
+    delegation_metadata.to_bytes_with_discriminator(&mut delegation_metadata_data.as_mut())
+}
+
+/// Queue the commit pending since before commits were queued, if any, as the first commit of the
+/// delegation: its PDAs are those of the first commit, see [crate::pda::commit_nonce_seed], but
+/// it was not counted in the delegation metadata. Returns whether the commit was queued
+pub(crate) fn queue_legacy_commit(
+    delegated_account: &AccountInfo,
+    commit_record_account: &AccountInfo,
+    delegation_metadata: &mut DelegationMetadata,
+) -> Result<bool, ProgramError> {
+    if delegation_metadata.next_commit_nonce > 0 || is_uninitialized_account(commit_record_account)
+    {
+        return Ok(false);
+    }
+    load_initialized_commit_record(delegated_account, commit_record_account, 0, false)?;
+    let commit_record_data = commit_record_account.try_borrow_data()?;
+    let commit_record = CommitRecord::try_from_bytes_with_legacy_layout(&commit_record_data)?;
+    delegation_metadata.next_commit_nonce = 1;
+    delegation_metadata.last_commit_lamports = commit_record.lamports;
+    delegation_metadata.last_commit_slot = commit_record.slot;
+    Ok(true)
+}
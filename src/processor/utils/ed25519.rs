@@ -0,0 +1,67 @@
+use crate::error::DlpError;
+use solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use solana_program::{
+    account_info::AccountInfo, ed25519_program, msg, program_error::ProgramError, pubkey::Pubkey,
+};
+
+/// Size of the header of an Ed25519 instruction: the signatures count, a padding byte and the
+/// offsets of a single signature
+const ED25519_HEADER_SIZE: usize = 16;
+
+/// Instruction index meaning that the data is in the Ed25519 instruction itself
+const CURRENT_INSTRUCTION_INDEX: u16 = u16::MAX;
+
+/// Errors if:
+/// - The instruction preceding the current one is not an Ed25519 instruction verifying a single
+///   signature of the message by the signer, with all the data in the Ed25519 instruction itself.
+///
+/// The Ed25519 program fails the transaction if the signature is invalid, so only the signer
+/// and the message need to be checked.
+pub fn validate_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<(), ProgramError> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let Some(ed25519_index) = current_index.checked_sub(1) else {
+        msg!("Expected an Ed25519 instruction before the current instruction");
+        return Err(DlpError::InvalidEd25519Signature.into());
+    };
+    let ed25519_ix = load_instruction_at_checked(ed25519_index as usize, instructions_sysvar)?;
+    if !ed25519_ix.program_id.eq(&ed25519_program::ID) {
+        msg!("Expected an Ed25519 instruction before the current instruction");
+        return Err(DlpError::InvalidEd25519Signature.into());
+    }
+
+    let data = &ed25519_ix.data;
+    if data.len() < ED25519_HEADER_SIZE || data[0] != 1 {
+        msg!("Expected an Ed25519 instruction verifying a single signature");
+        return Err(DlpError::InvalidEd25519Signature.into());
+    }
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let public_key_offset = read_u16(6) as usize;
+    let message_data_offset = read_u16(10) as usize;
+    let message_data_size = read_u16(12) as usize;
+    if [read_u16(4), read_u16(8), read_u16(14)]
+        .iter()
+        .any(|index| *index != CURRENT_INSTRUCTION_INDEX)
+    {
+        msg!("Expected the Ed25519 instruction to hold the signature, public key and message");
+        return Err(DlpError::InvalidEd25519Signature.into());
+    }
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32);
+    if public_key != Some(signer.as_ref()) {
+        msg!("Expected the Ed25519 signature to be from {}", signer);
+        return Err(DlpError::InvalidEd25519Signature.into());
+    }
+    let signed_message = data.get(message_data_offset..message_data_offset + message_data_size);
+    if signed_message != Some(message) {
+        msg!("Ed25519 signed message does not match the expected message");
+        return Err(DlpError::InvalidEd25519Signature.into());
+    }
+
+    Ok(())
+}
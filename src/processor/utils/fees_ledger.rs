@@ -0,0 +1,88 @@
+use crate::processor::utils::authority::load_validator_fees_vault_settings;
+use crate::state::{FeesLedger, ProtocolFeesVault, ValidatorFeesVault};
+use solana_program::account_info::AccountInfo;
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+
+/// Record lamports moved into or out of a validator fees vault in its fees ledger.
+/// Vaults created before the fees ledger was introduced are resized on their first record,
+/// see [resize_validator_fees_vault], and must hold enough lamports to be rent exempt once resized.
+pub(crate) fn record_validator_fees(
+    validator_identity: &Pubkey,
+    validator_fees_vault: &AccountInfo,
+    record: impl FnOnce(&mut FeesLedger) -> Result<(), ProgramError>,
+) -> Result<(), ProgramError> {
+    if !resize_validator_fees_vault(validator_identity, validator_fees_vault)? {
+        msg!(
+            "Validator fees vault ({}) cannot afford the rent of its fees ledger, fund it to record the lamports",
+            validator_fees_vault.key
+        );
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    let mut validator_fees_vault_data = validator_fees_vault.try_borrow_mut_data()?;
+    let validator_fees_vault =
+        ValidatorFeesVault::try_from_bytes_with_discriminator_mut(&mut validator_fees_vault_data)?;
+    record(&mut validator_fees_vault.ledger)
+}
+
+/// Record lamports moved into or out of the protocol fees vault in its fees ledger.
+/// The vault created before the fees ledger was introduced is resized on its first record,
+/// see [resize_protocol_fees_vault], and must hold enough lamports to be rent exempt once resized.
+pub(crate) fn record_protocol_fees(
+    fees_vault: &AccountInfo,
+    record: impl FnOnce(&mut FeesLedger) -> Result<(), ProgramError>,
+) -> Result<(), ProgramError> {
+    if !resize_protocol_fees_vault(fees_vault)? {
+        msg!(
+            "Protocol fees vault ({}) cannot afford the rent of its fees ledger, fund it to record the lamports",
+            fees_vault.key
+        );
+        return Err(ProgramError::AccountNotRentExempt);
+    }
+    let mut fees_vault_data = fees_vault.try_borrow_mut_data()?;
+    let fees_vault =
+        ProtocolFeesVault::try_from_bytes_with_discriminator_mut(&mut fees_vault_data)?;
+    record(&mut fees_vault.ledger)
+}
+
+/// Resize a validator fees vault created before the withdrawal settings and the fees ledger
+/// were introduced, out of its own lamports, keeping the validator identity as the withdrawal
+/// authority and the payout destination.
+/// Returns false if the vault holds too few lamports to be rent exempt once resized.
+pub(crate) fn resize_validator_fees_vault(
+    validator_identity: &Pubkey,
+    validator_fees_vault: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    let size = ValidatorFeesVault::size_with_discriminator();
+    if validator_fees_vault.data_len() >= size {
+        return Ok(true);
+    }
+    if validator_fees_vault.lamports() < Rent::default().minimum_balance(size) {
+        return Ok(false);
+    }
+    let validator_fees_vault_settings =
+        load_validator_fees_vault_settings(validator_identity, validator_fees_vault)?;
+    validator_fees_vault.realloc(size, true)?;
+    let mut validator_fees_vault_data = validator_fees_vault.try_borrow_mut_data()?;
+    validator_fees_vault_settings.to_bytes_with_discriminator(&mut validator_fees_vault_data)?;
+    Ok(true)
+}
+
+/// Resize the protocol fees vault created before the fees ledger was introduced, out of its
+/// own lamports.
+/// Returns false if the vault holds too few lamports to be rent exempt once resized.
+pub(crate) fn resize_protocol_fees_vault(fees_vault: &AccountInfo) -> Result<bool, ProgramError> {
+    let size = ProtocolFeesVault::size_with_discriminator();
+    if fees_vault.data_len() >= size {
+        return Ok(true);
+    }
+    if fees_vault.lamports() < Rent::default().minimum_balance(size) {
+        return Ok(false);
+    }
+    fees_vault.realloc(size, true)?;
+    let mut fees_vault_data = fees_vault.try_borrow_mut_data()?;
+    ProtocolFeesVault::default().to_bytes_with_discriminator(&mut fees_vault_data)?;
+    Ok(true)
+}
@@ -1,10 +1,15 @@
 use crate::error::DlpError::InvalidAuthority;
-use crate::pda::{program_config_from_program_id, validator_fees_vault_pda_from_validator};
+use crate::pda::{
+    program_config_from_program_id, validator_bond_pda_from_validator,
+    validator_fees_vault_pda_from_validator,
+};
 use crate::{
-    commit_record_seeds_from_delegated_account, commit_state_seeds_from_delegated_account,
-    delegation_metadata_seeds_from_delegated_account,
+    commit_buffer_seeds_from_validator_and_delegated_account,
+    commit_bundle_record_seeds_from_delegated_account, commit_record_seeds_from_delegated_account,
+    commit_state_seeds_from_delegated_account, delegation_metadata_seeds_from_delegated_account,
     delegation_record_seeds_from_delegated_account, fees_vault_seeds,
-    program_config_seeds_from_program_id, validator_fees_vault_seeds_from_validator,
+    program_config_seeds_from_program_id, protocol_config_seeds,
+    validator_bond_seeds_from_validator, validator_fees_vault_seeds_from_validator,
 };
 use solana_program::bpf_loader_upgradeable::UpgradeableLoaderState;
 use solana_program::{
@@ -202,14 +207,6 @@ pub fn load_program_upgrade_authority(
     let program_data_address =
         Pubkey::find_program_address(&[program.as_ref()], &bpf_loader_upgradeable::id()).0;
 
-    // During tests, the upgrade authority is a test pubkey
-    #[cfg(feature = "unit_test_config")]
-    if program.eq(&crate::ID) {
-        return Ok(Some(solana_program::pubkey!(
-            "tEsT3eV6RFCWs1BZ7AXTzasHqTtMnMLCB2tjQ42TDXD"
-        )));
-    }
-
     if !program_data_address.eq(program_data.key) {
         msg!(
             "Expected program data address to be {}, but got {}",
@@ -250,6 +247,22 @@ pub fn load_initialized_protocol_fees_vault(
     Ok(())
 }
 
+/// Load protocol config PDA
+/// - Protocol config PDA must be initialized with the expected seeds and owner, or not exists
+pub fn load_protocol_config(
+    protocol_config: &AccountInfo,
+    is_writable: bool,
+) -> Result<bool, ProgramError> {
+    load_pda(
+        protocol_config,
+        protocol_config_seeds!(),
+        &crate::id(),
+        is_writable,
+        "protocol config",
+    )?;
+    Ok(!protocol_config.owner.eq(&system_program::ID))
+}
+
 /// Load validator fee vault PDA
 /// - Validator fees vault PDA must be derived from the validator pubkey
 /// - Validator fees vault PDA must be initialized with the expected seeds and owner
@@ -277,6 +290,59 @@ pub fn load_initialized_validator_fees_vault(
     Ok(())
 }
 
+/// Load validator bond PDA
+/// - Validator bond PDA must be initialized with the expected seeds and owner, or not exists
+///
+/// Returns whether the validator bond is initialized
+pub fn load_validator_bond(
+    validator: &AccountInfo,
+    validator_bond: &AccountInfo,
+    is_writable: bool,
+) -> Result<bool, ProgramError> {
+    load_pda(
+        validator_bond,
+        validator_bond_seeds_from_validator!(validator.key),
+        &crate::id(),
+        is_writable,
+        "validator bond",
+    )?;
+    Ok(!validator_bond.owner.eq(&system_program::ID))
+}
+
+/// Split the optional validator bond PDA from the remaining accounts, which it leads if present
+pub fn split_validator_bond_account<'a, 'info>(
+    validator: &AccountInfo,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> (Option<&'a AccountInfo<'info>>, &'a [AccountInfo<'info>]) {
+    match remaining_accounts.split_first() {
+        Some((account, remaining_accounts))
+            if account
+                .key
+                .eq(&validator_bond_pda_from_validator(validator.key)) =>
+        {
+            (Some(account), remaining_accounts)
+        }
+        _ => (None, remaining_accounts),
+    }
+}
+
+/// Load initialized validator bond PDA
+/// - Validator bond PDA must be derived from the validator pubkey
+pub fn load_initialized_validator_bond(
+    validator: &AccountInfo,
+    validator_bond: &AccountInfo,
+    is_writable: bool,
+) -> Result<(), ProgramError> {
+    load_initialized_pda(
+        validator_bond,
+        validator_bond_seeds_from_validator!(validator.key),
+        &crate::id(),
+        is_writable,
+        "validator bond",
+    )?;
+    Ok(())
+}
+
 /// Load program config PDA
 /// - Program config PDA must be initialized with the expected seeds and owner, or not exists
 pub fn load_program_config(
@@ -338,15 +404,16 @@ pub fn load_initialized_delegation_metadata(
 }
 
 /// Load initialized commit state account
-/// - Commit state account must be derived from the delegated account pubkey
+/// - Commit state account must be derived from the delegated account pubkey and the commit nonce
 pub fn load_initialized_commit_state(
     delegated_account: &AccountInfo,
     commit_state: &AccountInfo,
+    nonce: u64,
     is_writable: bool,
 ) -> Result<(), ProgramError> {
     load_initialized_pda(
         commit_state,
-        commit_state_seeds_from_delegated_account!(delegated_account.key),
+        commit_state_seeds_from_delegated_account!(delegated_account.key, nonce),
         &crate::id(),
         is_writable,
         "commit state",
@@ -355,15 +422,16 @@ pub fn load_initialized_commit_state(
 }
 
 /// Load initialized commit state record
-/// - Commit record account must be derived from the delegated account pubkey
+/// - Commit record account must be derived from the delegated account pubkey and the commit nonce
 pub fn load_initialized_commit_record(
     delegated_account: &AccountInfo,
     commit_record: &AccountInfo,
+    nonce: u64,
     is_writable: bool,
 ) -> Result<(), ProgramError> {
     load_initialized_pda(
         commit_record,
-        commit_record_seeds_from_delegated_account!(delegated_account.key),
+        commit_record_seeds_from_delegated_account!(delegated_account.key, nonce),
         &crate::id(),
         is_writable,
         "commit record",
@@ -371,6 +439,46 @@ pub fn load_initialized_commit_record(
     Ok(())
 }
 
+/// Load initialized commit bundle record
+/// - Commit bundle record account must be derived from the first delegated account pubkey and
+///   the nonce of its bundled commit
+pub fn load_initialized_commit_bundle_record(
+    delegated_account: &AccountInfo,
+    commit_bundle_record: &AccountInfo,
+    nonce: u64,
+    is_writable: bool,
+) -> Result<(), ProgramError> {
+    load_initialized_pda(
+        commit_bundle_record,
+        commit_bundle_record_seeds_from_delegated_account!(delegated_account.key, nonce),
+        &crate::id(),
+        is_writable,
+        "commit bundle record",
+    )?;
+    Ok(())
+}
+
+/// Load initialized commit buffer
+/// - Commit buffer account must be derived from the validator and the delegated account pubkeys
+pub fn load_initialized_commit_buffer(
+    validator: &AccountInfo,
+    delegated_account: &AccountInfo,
+    commit_buffer: &AccountInfo,
+    is_writable: bool,
+) -> Result<(), ProgramError> {
+    load_initialized_pda(
+        commit_buffer,
+        commit_buffer_seeds_from_validator_and_delegated_account!(
+            validator.key,
+            delegated_account.key
+        ),
+        &crate::id(),
+        is_writable,
+        "commit buffer",
+    )?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use solana_program::{account_info::AccountInfo, pubkey::Pubkey, system_program};
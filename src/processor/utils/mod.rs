@@ -1,3 +1,11 @@
+pub(crate) mod authority;
+pub(crate) mod commit_actions;
+pub(crate) mod commit_fees;
 pub(crate) mod curve;
+pub(crate) mod delegation_metadata;
+pub(crate) mod ed25519;
+pub(crate) mod fees_ledger;
 pub(crate) mod loaders;
 pub(crate) mod pda;
+pub(crate) mod state_patch;
+pub(crate) mod token;
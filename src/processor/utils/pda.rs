@@ -1,3 +1,4 @@
+use crate::consts::MAX_FEES_BPS;
 use solana_program::program::invoke;
 use solana_program::program_error::ProgramError;
 use solana_program::{
@@ -117,23 +118,23 @@ pub(crate) fn close_pda<'a, 'info>(
 }
 
 /// Close PDA with fees, distributing the fees to the specified addresses in sequence
-/// The total fees are calculated as `fee_percentage` of the total lamports in the PDA
-/// Each fee address receives fee_percentage % of the previous fee address's amount
+/// The total fees are calculated as `fee_bps` basis points of the total lamports in the PDA
+/// Each fee address receives fee_bps basis points of the previous fee address's amount
 pub(crate) fn close_pda_with_fees<'a, 'info>(
     target_account: &'a AccountInfo<'info>,
     destination: &'a AccountInfo<'info>,
     fees_addresses: &[&AccountInfo<'info>],
-    fee_percentage: u8,
+    fee_bps: u16,
 ) -> ProgramResult {
-    if fees_addresses.is_empty() || fee_percentage > 100 {
+    if fees_addresses.is_empty() || fee_bps > MAX_FEES_BPS {
         return Err(ProgramError::InvalidArgument);
     }
 
     let init_lamports = target_account.lamports();
     let total_fee_amount = target_account
         .lamports()
-        .checked_mul(fee_percentage as u64)
-        .and_then(|v| v.checked_div(100))
+        .checked_mul(fee_bps as u64)
+        .and_then(|v| v.checked_div(MAX_FEES_BPS as u64))
         .ok_or(ProgramError::InsufficientFunds)?;
 
     let mut fees: Vec<u64> = vec![total_fee_amount; fees_addresses.len()];
@@ -141,8 +142,8 @@ pub(crate) fn close_pda_with_fees<'a, 'info>(
     let mut fee_amount = total_fee_amount;
     for fee in fees.iter_mut().take(fees_addresses.len()).skip(1) {
         fee_amount = fee_amount
-            .checked_mul(fee_percentage as u64)
-            .and_then(|v| v.checked_div(100))
+            .checked_mul(fee_bps as u64)
+            .and_then(|v| v.checked_div(MAX_FEES_BPS as u64))
             .ok_or(ProgramError::InsufficientFunds)?;
         *fee = fee_amount;
     }
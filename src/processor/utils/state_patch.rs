@@ -0,0 +1,80 @@
+use crate::args::StatePatch;
+use crate::error::DlpError;
+use solana_program::{msg, program_error::ProgramError};
+
+/// Errors if:
+/// - A patch writes outside of the account data range.
+pub fn validate_state_patches(patches: &[StatePatch], data_len: usize) -> Result<(), ProgramError> {
+    for patch in patches {
+        let end = (patch.offset as usize).checked_add(patch.data.len());
+        if end.map_or(true, |end| end > data_len) {
+            msg!(
+                "State patch at offset {} of {} bytes is out of the account data range ({} bytes)",
+                patch.offset,
+                patch.data.len(),
+                data_len
+            );
+            return Err(DlpError::InvalidStatePatch.into());
+        }
+    }
+    Ok(())
+}
+
+/// Apply the patches in order on top of the account data
+pub fn apply_state_patches(data: &mut [u8], patches: &[StatePatch]) -> Result<(), ProgramError> {
+    validate_state_patches(patches, data.len())?;
+    for patch in patches {
+        let offset = patch.offset as usize;
+        data[offset..offset + patch.data.len()].copy_from_slice(&patch.data);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_state_patches() {
+        let mut data = vec![0u8; 8];
+        let patches = vec![
+            StatePatch {
+                offset: 0,
+                data: vec![1, 2],
+            },
+            StatePatch {
+                offset: 6,
+                data: vec![3, 4],
+            },
+            StatePatch {
+                offset: 1,
+                data: vec![5],
+            },
+        ];
+
+        apply_state_patches(&mut data, &patches).unwrap();
+
+        assert_eq!(data, vec![1, 5, 0, 0, 0, 0, 3, 4]);
+    }
+
+    #[test]
+    fn test_apply_state_patches_out_of_range() {
+        let mut data = vec![0u8; 8];
+        let patches = vec![
+            StatePatch {
+                offset: 0,
+                data: vec![1],
+            },
+            StatePatch {
+                offset: 7,
+                data: vec![2, 3],
+            },
+        ];
+
+        assert_eq!(
+            apply_state_patches(&mut data, &patches),
+            Err(DlpError::InvalidStatePatch.into())
+        );
+        assert_eq!(data, vec![0u8; 8]);
+    }
+}
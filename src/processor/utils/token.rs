@@ -0,0 +1,121 @@
+use crate::consts::{TOKEN_ACCOUNT_SIZE, TOKEN_PROGRAM_ID};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::msg;
+use solana_program::program::{invoke, invoke_signed};
+use solana_program::program_error::ProgramError;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+/// The SPL Token instructions used by the ephemeral token balances
+const INITIALIZE_ACCOUNT_3: u8 = 18;
+const TRANSFER: u8 = 3;
+const CLOSE_ACCOUNT: u8 = 9;
+
+/// Load an initialized SPL Token account and return its amount of tokens
+/// Errors if:
+/// - Account is not owned by the SPL Token program.
+/// - Account does not hold tokens of the mint.
+/// - Account tokens are not owned by the owner, if any.
+pub(crate) fn load_token_account(
+    info: &AccountInfo,
+    mint: &Pubkey,
+    owner: Option<&Pubkey>,
+    label: &str,
+) -> Result<u64, ProgramError> {
+    if !info.owner.eq(&TOKEN_PROGRAM_ID) || info.data_len() != TOKEN_ACCOUNT_SIZE {
+        msg!("Invalid token account for {} ({})", label, info.key);
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let data = info.try_borrow_data()?;
+    if data[..32] != mint.to_bytes() {
+        msg!("Invalid mint for {} ({})", label, info.key);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if let Some(owner) = owner {
+        if data[32..64] != owner.to_bytes() {
+            msg!("Invalid token owner for {} ({})", label, info.key);
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+    let amount = data[64..72]
+        .try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(amount))
+}
+
+/// Initialize a token account created for the SPL Token program, holding tokens of the mint
+pub(crate) fn initialize_token_account<'a, 'info>(
+    account: &'a AccountInfo<'info>,
+    mint: &'a AccountInfo<'info>,
+    owner: &Pubkey,
+    token_program: &'a AccountInfo<'info>,
+) -> ProgramResult {
+    let ix = Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*account.key, false),
+            AccountMeta::new_readonly(*mint.key, false),
+        ],
+        data: [&[INITIALIZE_ACCOUNT_3], owner.as_ref()].concat(),
+    };
+    invoke(&ix, &[account.clone(), mint.clone(), token_program.clone()])
+}
+
+/// Transfer tokens between two token accounts, signed by the authority of the source
+pub(crate) fn transfer_tokens<'a, 'info>(
+    source: &'a AccountInfo<'info>,
+    destination: &'a AccountInfo<'info>,
+    authority: &'a AccountInfo<'info>,
+    amount: u64,
+    token_program: &'a AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*source.key, false),
+            AccountMeta::new(*destination.key, false),
+            AccountMeta::new_readonly(*authority.key, true),
+        ],
+        data: [&[TRANSFER], &amount.to_le_bytes()[..]].concat(),
+    };
+    invoke_signed(
+        &ix,
+        &[
+            source.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+/// Close an empty token account, refunding its rent to the destination
+pub(crate) fn close_token_account<'a, 'info>(
+    account: &'a AccountInfo<'info>,
+    destination: &'a AccountInfo<'info>,
+    authority: &'a AccountInfo<'info>,
+    token_program: &'a AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*account.key, false),
+            AccountMeta::new(*destination.key, false),
+            AccountMeta::new_readonly(*authority.key, true),
+        ],
+        data: vec![CLOSE_ACCOUNT],
+    };
+    invoke_signed(
+        &ix,
+        &[
+            account.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )
+}
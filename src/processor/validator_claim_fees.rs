@@ -1,6 +1,14 @@
 use crate::args::ValidatorClaimFeesArgs;
-use crate::consts::PROTOCOL_FEES_PERCENTAGE;
+use crate::consts::MAX_FEES_BPS;
 use crate::error::DlpError;
+use crate::event::{Event, FeesClaimedEvent};
+use crate::processor::utils::authority::{
+    load_protocol_config_protocol_fees_bps, load_validator_fees_vault_settings,
+    validate_validator_fees_vault_withdrawal_authority,
+};
+use crate::processor::utils::fees_ledger::{
+    record_protocol_fees, record_validator_fees, resize_validator_fees_vault,
+};
 use crate::processor::utils::loaders::{
     load_initialized_protocol_fees_vault, load_initialized_validator_fees_vault, load_signer,
 };
@@ -14,17 +22,28 @@ use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubke
 ///
 /// Accounts:
 ///
-/// 0: `[signer]`   the validator account.
+/// 0: `[]`         the validator identity.
 /// 1: `[writable]` the fees vault PDA.
 /// 2: `[writable]` the validator fees vault PDA.
+/// 3: `[]`         the protocol config.
+/// 4: `[signer]`   the withdrawal authority of the validator fees vault.
+/// 5: `[writable]` the payout destination of the validator fees vault.
 ///
 /// Requirements:
 ///
 /// - protocol fees vault is initialized
 /// - validator fees vault is initialized
 /// - validators fees vault needs to hold enough lamports to claim
+/// - protocol config is initialized, or not exists in which case the default protocol fees apply
+/// - withdrawal authority and payout destination are the ones of the validator fees vault,
+///   which are the validator identity until set, see
+///   [crate::processor::process_set_validator_fees_vault_authority]
 ///
-/// 1. Transfer lamports from validator fees_vault PDA to the validator authority
+/// 1. Resize the validator fees vault if it holds no fees ledger yet and can afford the rent
+/// 2. Transfer the protocol fees set by the protocol config from the validator fees_vault PDA
+///    to the fees vault
+/// 3. Transfer the remaining lamports from validator fees_vault PDA to the payout destination
+/// 4. Record the claim in the fees ledgers of both vaults
 pub fn process_validator_claim_fees(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -33,16 +52,41 @@ pub fn process_validator_claim_fees(
     let args = ValidatorClaimFeesArgs::try_from_slice(data)?;
 
     // Load Accounts
-    let [validator, fees_vault, validator_fees_vault] = accounts else {
+    let [validator, fees_vault, validator_fees_vault, protocol_config_account, withdrawal_authority, payout_destination] =
+        accounts
+    else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
-    load_signer(validator, "validator")?;
+    load_signer(withdrawal_authority, "withdrawal authority")?;
     load_initialized_protocol_fees_vault(fees_vault, true)?;
     load_initialized_validator_fees_vault(validator, validator_fees_vault, true)?;
+    let protocol_fees_bps = load_protocol_config_protocol_fees_bps(protocol_config_account)?;
+
+    // Check if the withdrawal authority and the payout destination are the correct ones
+    let validator_fees_vault_settings =
+        load_validator_fees_vault_settings(validator.key, validator_fees_vault)?;
+    validate_validator_fees_vault_withdrawal_authority(
+        withdrawal_authority,
+        &validator_fees_vault_settings,
+    )?;
+    if !payout_destination
+        .key
+        .eq(&validator_fees_vault_settings.payout_destination)
+    {
+        msg!(
+            "Expected payout destination: {} but got {}",
+            validator_fees_vault_settings.payout_destination,
+            payout_destination.key
+        );
+        return Err(DlpError::InvalidPayoutDestination.into());
+    }
+
+    // Resize the vault created before the fees ledger was introduced, out of its own lamports
+    resize_validator_fees_vault(validator.key, validator_fees_vault)?;
 
     // Calculate the amount to transfer
-    let min_rent = Rent::default().minimum_balance(8);
+    let min_rent = Rent::default().minimum_balance(validator_fees_vault.data_len());
     let amount = args
         .amount
         .unwrap_or(validator_fees_vault.lamports() - min_rent);
@@ -59,7 +103,10 @@ pub fn process_validator_claim_fees(
     }
 
     // Calculate fees and remaining amount
-    let protocol_fees = (amount * u64::from(PROTOCOL_FEES_PERCENTAGE)) / 100;
+    let protocol_fees = amount
+        .checked_mul(u64::from(protocol_fees_bps))
+        .ok_or(DlpError::Overflow)?
+        / u64::from(MAX_FEES_BPS);
     let remaining_amount = amount.saturating_sub(protocol_fees);
 
     // Transfer fees to fees_vault
@@ -68,16 +115,32 @@ pub fn process_validator_claim_fees(
         .checked_add(protocol_fees)
         .ok_or(DlpError::Overflow)?;
 
-    // Transfer remaining amount from validator_fees_vault to validator
+    // Transfer remaining amount from validator_fees_vault to the payout destination
     **validator_fees_vault.try_borrow_mut_lamports()? = validator_fees_vault
         .lamports()
         .checked_sub(amount)
         .ok_or(ProgramError::InsufficientFunds)?;
 
-    **validator.try_borrow_mut_lamports()? = validator
+    **payout_destination.try_borrow_mut_lamports()? = payout_destination
         .lamports()
         .checked_add(remaining_amount)
         .ok_or(DlpError::Overflow)?;
 
+    // Record the claim in the fees ledgers
+    record_validator_fees(validator.key, validator_fees_vault, |ledger| {
+        ledger.record_claim(amount, protocol_fees)
+    })?;
+    record_protocol_fees(fees_vault, |ledger| {
+        ledger.record_protocol_fees(protocol_fees)
+    })?;
+
+    FeesClaimedEvent {
+        fees_vault: *validator_fees_vault.key,
+        receiver: *payout_destination.key,
+        amount,
+        protocol_fees,
+    }
+    .emit()?;
+
     Ok(())
 }
@@ -1,13 +1,11 @@
 use crate::args::WhitelistValidatorForProgramArgs;
-use crate::error::DlpError::Unauthorized;
-use crate::processor::utils::loaders::{
-    load_pda, load_program, load_program_upgrade_authority, load_signer,
-};
+use crate::event::{Event, ValidatorWhitelistedEvent};
+use crate::processor::utils::authority::validate_program_config_authority;
+use crate::processor::utils::loaders::{load_pda, load_program, load_signer};
 use crate::processor::utils::pda::{create_pda, resize_pda};
 use crate::program_config_seeds_from_program_id;
 use crate::state::ProgramConfig;
 use borsh::BorshDeserialize;
-use solana_program::msg;
 use solana_program::program_error::ProgramError;
 use solana_program::{
     account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
@@ -21,12 +19,14 @@ use solana_program::{
 /// 1: `[]`         validator identity to whitelist
 /// 2: `[]`         program to whitelist the validator for
 /// 3: `[]`         program data account
-/// 4: `[writable]` program config PDA
-/// 5: `[]`         system program
+/// 4: `[]`         delegation program data account
+/// 5: `[writable]` program config PDA
+/// 6: `[]`         system program
+/// 7: `[]`         protocol config PDA
 ///
 /// Requirements:
 ///
-/// - authority is either the ADMIN_PUBKEY or the program upgrade authority
+/// - authority is either the protocol admin or the program upgrade authority
 /// - program config is initialized or owned by the system program in
 ///   which case it is created
 ///
@@ -43,14 +43,20 @@ pub fn process_whitelist_validator_for_program(
     let args = WhitelistValidatorForProgramArgs::try_from_slice(data)?;
 
     // Load Accounts
-    let [authority, validator_identity, program, program_data, delegation_program_data, program_config_account, system_program] =
+    let [authority, validator_identity, program, program_data, delegation_program_data, program_config_account, system_program, protocol_config_account] =
         accounts
     else {
         return Err(ProgramError::NotEnoughAccountKeys);
     };
 
     load_signer(authority, "authority")?;
-    validate_authority(authority, program, program_data, delegation_program_data)?;
+    validate_program_config_authority(
+        authority,
+        program,
+        program_data,
+        delegation_program_data,
+        protocol_config_account,
+    )?;
     load_program(system_program, system_program::id(), "system program")?;
 
     let program_config_bump = load_pda(
@@ -95,30 +101,12 @@ pub fn process_whitelist_validator_for_program(
     let mut program_config_data = program_config_account.try_borrow_mut_data()?;
     program_config.to_bytes_with_discriminator(&mut program_config_data.as_mut())?;
 
-    Ok(())
-}
-
-/// Authority is valid if either the authority is the ADMIN_PUBKEY or the program upgrade authority
-fn validate_authority(
-    authority: &AccountInfo,
-    program: &AccountInfo,
-    program_data: &AccountInfo,
-    delegation_program_data: &AccountInfo,
-) -> Result<(), ProgramError> {
-    let admin_pubkey =
-        load_program_upgrade_authority(&crate::ID, delegation_program_data)?.ok_or(Unauthorized)?;
-    if authority.key.eq(&admin_pubkey)
-        || authority
-            .key
-            .eq(&load_program_upgrade_authority(program.key, program_data)?.ok_or(Unauthorized)?)
-    {
-        Ok(())
-    } else {
-        msg!(
-            "Expected authority to be {} or program upgrade authority, but got {}",
-            admin_pubkey,
-            authority.key
-        );
-        Err(Unauthorized.into())
+    ValidatorWhitelistedEvent {
+        program: *program.key,
+        validator: *validator_identity.key,
+        whitelisted: args.insert,
     }
+    .emit()?;
+
+    Ok(())
 }
@@ -0,0 +1,65 @@
+use crate::error::DlpError;
+use crate::processor::utils::loaders::{load_initialized_validator_bond, load_signer};
+use crate::state::ValidatorBond;
+use solana_program::clock::Clock;
+use solana_program::program_error::ProgramError;
+use solana_program::sysvar::Sysvar;
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+
+/// Withdraw the unbonded lamports from the validator bond
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator owning the bond
+/// 1: `[writable]` the validator bond PDA
+///
+/// Requirements:
+///
+/// - validator bond is initialized
+/// - unbonding period requested with [crate::processor::process_unbond_validator_bond]
+///   has elapsed
+///
+/// Steps:
+///
+/// 1. Transfer the unbonding lamports from the validator bond to the validator
+pub fn process_withdraw_validator_bond(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    // Load Accounts
+    let [validator, validator_bond_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(validator, "validator")?;
+    load_initialized_validator_bond(validator, validator_bond_account, true)?;
+
+    let mut validator_bond_data = validator_bond_account.try_borrow_mut_data()?;
+    let validator_bond =
+        ValidatorBond::try_from_bytes_with_discriminator_mut(&mut validator_bond_data)?;
+    let current_slot = Clock::get()?.slot;
+    if current_slot < validator_bond.unbonding_end_slot {
+        msg!(
+            "Unbonded lamports can be withdrawn from slot {}, current slot is {}",
+            validator_bond.unbonding_end_slot,
+            current_slot
+        );
+        return Err(DlpError::UnbondingNotElapsed.into());
+    }
+    let amount = validator_bond.unbonding_amount;
+    validator_bond.unbonding_amount = 0;
+    drop(validator_bond_data);
+
+    // Transfer the unbonded lamports to the validator
+    **validator_bond_account.try_borrow_mut_lamports()? = validator_bond_account
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **validator.try_borrow_mut_lamports()? = validator
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::args::WriteCommitBufferArgs;
+use crate::error::DlpError;
+use crate::processor::utils::loaders::{load_initialized_commit_buffer, load_program, load_signer};
+use crate::processor::utils::pda::resize_pda;
+use borsh::BorshDeserialize;
+use solana_program::program_error::ProgramError;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey, system_program,
+};
+
+/// Write a chunk of bytes in a commit buffer
+///
+/// Accounts:
+///
+/// 0: `[signer]`   the validator owning the buffer
+/// 1: `[]`         the delegated account
+/// 2: `[writable]` the commit buffer PDA
+/// 3: `[]`         the system program
+///
+/// Requirements:
+///
+/// - commit buffer is initialized and derived from the validator and the delegated account
+///
+/// Steps:
+///
+/// 1. Grow the commit buffer if the bytes are written past its end, the validator pays
+///    for the rent of the extra space
+/// 2. Copy the bytes in the commit buffer at the offset
+pub fn process_write_commit_buffer(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let args = WriteCommitBufferArgs::try_from_slice(data)?;
+
+    // Load Accounts
+    let [validator, delegated_account, commit_buffer_account, system_program] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    load_signer(validator, "validator")?;
+    load_initialized_commit_buffer(validator, delegated_account, commit_buffer_account, true)?;
+    load_program(system_program, system_program::id(), "system program")?;
+
+    let offset = args.offset as usize;
+    let end = offset
+        .checked_add(args.data.len())
+        .ok_or(DlpError::Overflow)?;
+    if end > commit_buffer_account.data_len() {
+        resize_pda(validator, commit_buffer_account, system_program, end)?;
+    }
+
+    let mut commit_buffer_data = commit_buffer_account.try_borrow_mut_data()?;
+    commit_buffer_data[offset..end].copy_from_slice(&args.data);
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    impl_to_bytes_with_discriminator_zero_copy, impl_try_from_bytes_with_discriminator_zero_copy,
+};
+
+use super::discriminator::{AccountDiscriminator, AccountWithDiscriminator};
+
+/// The Commit Bundle Record, shared by the commit records of accounts committed together.
+/// The commits of a bundle can only be finalized together, all or none, until the bundle is
+/// abandoned, see [crate::processor::process_abandon_commit_bundle].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct CommitBundleRecord {
+    /// The identity committing the bundle
+    pub identity: Pubkey,
+
+    /// The first account of the bundle, from which the bundle record is derived
+    pub account: Pubkey,
+
+    /// The nonce of the commit of the first account, from which the bundle record is derived
+    pub nonce: u64,
+
+    /// The number of commits in the bundle
+    pub commits_count: u64,
+
+    /// The slot from which anyone can abandon the bundle, if it was not finalized
+    pub expiry_slot: u64,
+}
+
+impl AccountWithDiscriminator for CommitBundleRecord {
+    fn discriminator() -> AccountDiscriminator {
+        AccountDiscriminator::CommitBundleRecord
+    }
+}
+
+impl CommitBundleRecord {
+    pub fn size_with_discriminator() -> usize {
+        8 + size_of::<CommitBundleRecord>()
+    }
+}
+
+impl_to_bytes_with_discriminator_zero_copy!(CommitBundleRecord);
+impl_try_from_bytes_with_discriminator_zero_copy!(CommitBundleRecord);
@@ -1,6 +1,8 @@
 use std::mem::size_of;
 
 use bytemuck::{Pod, Zeroable};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
 
 use crate::{
@@ -24,6 +26,50 @@ pub struct CommitRecord {
 
     /// The account committed lamports
     pub lamports: u64,
+
+    /// The [CommitKind] of the committed state
+    pub kind: u64,
+
+    /// The commit bundle record, or the default pubkey if the commit is not part of a bundle
+    pub bundle: Pubkey,
+
+    /// The hash of the committed data for a [CommitKind::Hash] commit, zeroed otherwise
+    pub data_hash: [u8; 32],
+
+    /// The slot at which the commit was received on chain
+    pub commit_slot: u64,
+
+    /// The number of slots after the commit slot during which the commit can be disputed
+    pub challenge_period: u64,
+
+    /// The account that disputed the commit, or the default pubkey if it is not disputed
+    pub disputer: Pubkey,
+
+    /// The length of the borsh-encoded [crate::args::CommitAction] list stored after the
+    /// committed data in the commit state account, zero if the commit has no actions
+    pub actions_len: u64,
+
+    /// The account that paid the rent and the lamports deposit of the commit PDAs, refunded
+    /// when they are closed. The validator itself, unless the commit was relayed
+    pub payer: Pubkey,
+
+    /// Non-zero if the program config of the owner program required to validate the commits
+    /// when the state was committed, so that it cannot be finalized without the owner program
+    pub validate_commit: u64,
+}
+
+/// How the commit state is applied to the delegated account on finalize
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum CommitKind {
+    /// The commit state holds the full account data
+    #[default]
+    Full = 0,
+    /// The commit state holds a list of [crate::args::StatePatch] to apply to the account data
+    Diff = 1,
+    /// The commit state is empty, the data is supplied on finalize and checked against the
+    /// hash stored in the commit record
+    Hash = 2,
 }
 
 impl AccountWithDiscriminator for CommitRecord {
@@ -36,6 +82,41 @@ impl CommitRecord {
     pub fn size_with_discriminator() -> usize {
         8 + size_of::<CommitRecord>()
     }
+
+    /// The size of the commit records created before commits were queued, whose layout ends
+    /// after the committed lamports
+    pub fn legacy_size_with_discriminator() -> usize {
+        8 + 2 * size_of::<Pubkey>() + 2 * size_of::<u64>()
+    }
+
+    /// Read a commit record, which may have been created with the legacy layout, see
+    /// [CommitRecord::legacy_size_with_discriminator]. The missing fields of a legacy commit
+    /// record are read as their defaults, its payer being the validator that committed it
+    pub fn try_from_bytes_with_legacy_layout(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::legacy_size_with_discriminator() {
+            return Self::try_from_bytes_with_discriminator(data).copied();
+        }
+        if Self::discriminator().to_bytes().ne(&data[..8]) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut commit_record = Self::zeroed();
+        bytemuck::bytes_of_mut(&mut commit_record)[..data.len() - 8].copy_from_slice(&data[8..]);
+        commit_record.payer = commit_record.identity;
+        Ok(commit_record)
+    }
+
+    /// The first slot at which the commit can no longer be disputed and can be finalized
+    pub fn challenge_period_end(&self) -> u64 {
+        self.commit_slot.saturating_add(self.challenge_period)
+    }
+
+    pub fn is_disputed(&self) -> bool {
+        !self.disputer.eq(&Pubkey::default())
+    }
+
+    pub fn requires_validation(&self) -> bool {
+        self.validate_commit != 0
+    }
 }
 
 impl_to_bytes_with_discriminator_zero_copy!(CommitRecord);
@@ -1,13 +1,22 @@
+use crate::consts::{DISPUTE_RESOLUTION_PERIOD_SLOTS, UNDELEGATION_REQUEST_GRACE_PERIOD_SLOTS};
 use crate::{impl_to_bytes_with_discriminator_borsh, impl_try_from_bytes_with_discriminator_borsh};
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::clock::Clock;
+use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
 
 use super::discriminator::{AccountDiscriminator, AccountWithDiscriminator};
+use super::try_from_bytes::deserialize_trailing_field;
 
 /// The Delegated Metadata includes Account Seeds, max delegation time, seeds
 /// and other meta information about the delegated account.
 /// * Everything necessary at cloning time is instead stored in the delegation record.
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+///
+/// The fields following the rent payer were appended after delegations were created, so the
+/// metadata of those delegations ends early: the missing fields are read as their defaults, and
+/// the account is resized when the metadata is written back, see
+/// [crate::processor::process_migrate_delegation_metadata].
+#[derive(BorshSerialize, Debug, PartialEq)]
 pub struct DelegationMetadata {
     /// The last slot at which the delegation was updated
     pub last_update_external_slot: u64,
@@ -17,6 +26,142 @@ pub struct DelegationMetadata {
     pub seeds: Vec<Vec<u8>>,
     /// The account that paid the rent for the delegation PDAs
     pub rent_payer: Pubkey,
+    /// The deadline after which anyone can force the undelegation of the account
+    pub expiry: Option<DelegationExpiry>,
+    /// The slot at which the owner program requested the undelegation, zero if never requested
+    pub undelegation_request_slot: u64,
+    /// The nonce of the next commit, used to derive the commit state and commit record PDAs
+    pub next_commit_nonce: u64,
+    /// The nonce of the next commit to be finalized, commits are finalized in nonce order
+    pub next_finalize_nonce: u64,
+    /// The unix timestamp of the last commit received, or of the delegation if none was.
+    /// Zero if the delegation was created before the commit timestamps were recorded
+    pub last_commit_timestamp: i64,
+    /// Whether the delegation was flagged as stale, after no commit was received for too long
+    pub is_stale: bool,
+    /// The lamports of the last queued commit, which the next commit deposits against while
+    /// commits are pending
+    pub last_commit_lamports: u64,
+    /// The slot of the last queued commit, which the next commit cannot precede while commits
+    /// are pending
+    pub last_commit_slot: u64,
+    /// The slot at which one of the pending commits was disputed, zero if no dispute is
+    /// pending resolution
+    pub dispute_slot: u64,
+    /// Whether a dispute of one of the pending commits was upheld, in which case the
+    /// delegation can only be force undelegated with its last finalized state
+    pub has_upheld_dispute: bool,
+}
+
+/// The deadline of a delegation, either as a base layer slot or as a unix timestamp
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum DelegationExpiry {
+    Slot(u64),
+    UnixTimestamp(i64),
+}
+
+impl BorshDeserialize for DelegationMetadata {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        Ok(Self {
+            last_update_external_slot: u64::deserialize_reader(reader)?,
+            is_undelegatable: bool::deserialize_reader(reader)?,
+            seeds: Vec::<Vec<u8>>::deserialize_reader(reader)?,
+            rent_payer: Pubkey::deserialize_reader(reader)?,
+            expiry: deserialize_trailing_field(reader)?,
+            undelegation_request_slot: deserialize_trailing_field(reader)?,
+            next_commit_nonce: deserialize_trailing_field(reader)?,
+            next_finalize_nonce: deserialize_trailing_field(reader)?,
+            last_commit_timestamp: deserialize_trailing_field(reader)?,
+            is_stale: deserialize_trailing_field(reader)?,
+            last_commit_lamports: deserialize_trailing_field(reader)?,
+            last_commit_slot: deserialize_trailing_field(reader)?,
+            dispute_slot: deserialize_trailing_field(reader)?,
+            has_upheld_dispute: deserialize_trailing_field(reader)?,
+        })
+    }
+}
+
+impl DelegationExpiry {
+    /// Whether the deadline has been reached at the given clock
+    pub fn is_expired(&self, clock: &Clock) -> bool {
+        match self {
+            DelegationExpiry::Slot(slot) => clock.slot >= *slot,
+            DelegationExpiry::UnixTimestamp(timestamp) => clock.unix_timestamp >= *timestamp,
+        }
+    }
+}
+
+impl DelegationMetadata {
+    /// The size of the account holding the metadata in the current layout
+    pub fn size_with_discriminator(&self) -> Result<usize, ProgramError> {
+        Ok(8 + borsh::object_length(self)?)
+    }
+
+    /// Whether some commits were not finalized yet
+    pub fn has_pending_commits(&self) -> bool {
+        self.next_finalize_nonce < self.next_commit_nonce
+    }
+
+    /// The lamports a new commit deposits against: those of the last queued commit while
+    /// commits are pending, since they are settled against each other in order, otherwise
+    /// those of the last finalized state recorded in the delegation record
+    pub fn queued_lamports(&self, delegation_record_lamports: u64) -> u64 {
+        if self.has_pending_commits() {
+            self.last_commit_lamports
+        } else {
+            delegation_record_lamports
+        }
+    }
+
+    /// The slot a new commit cannot precede: the one of the last queued commit while commits
+    /// are pending, so that commits are queued in slot order, otherwise the one of the last
+    /// finalized state
+    pub fn queued_slot(&self) -> u64 {
+        if self.has_pending_commits() {
+            self.last_commit_slot.max(self.last_update_external_slot)
+        } else {
+            self.last_update_external_slot
+        }
+    }
+
+    /// The unix timestamp from which the delegation can be flagged as stale if no commit is
+    /// received, or None if the delegation does not commit at a fixed frequency or if no
+    /// commit timestamp was recorded yet
+    pub fn stale_timestamp(
+        &self,
+        commit_frequency_ms: u64,
+        commit_staleness_multiplier: u64,
+    ) -> Option<i64> {
+        if commit_frequency_ms == 0 || self.last_commit_timestamp == 0 {
+            return None;
+        }
+        let stale_delay_ms = commit_frequency_ms.saturating_mul(commit_staleness_multiplier);
+        let stale_delay = i64::try_from(stale_delay_ms / 1_000).unwrap_or(i64::MAX);
+        Some(self.last_commit_timestamp.saturating_add(stale_delay))
+    }
+
+    /// Whether a dispute is pending and the protocol admin did not resolve it in time, in
+    /// which case anyone can reject it
+    pub fn is_dispute_resolution_elapsed(&self, clock: &Clock) -> bool {
+        self.dispute_slot > 0
+            && clock.slot
+                >= self
+                    .dispute_slot
+                    .saturating_add(DISPUTE_RESOLUTION_PERIOD_SLOTS)
+    }
+
+    /// Whether anyone can force the undelegation of the account, either because the
+    /// delegation expired, because the grace period of an undelegation request elapsed,
+    /// because the delegation was flagged as stale or because a dispute was upheld
+    pub fn is_force_undelegatable(&self, clock: &Clock) -> bool {
+        let is_expired = self.expiry.is_some_and(|expiry| expiry.is_expired(clock));
+        let is_request_elapsed = self.undelegation_request_slot > 0
+            && clock.slot
+                >= self
+                    .undelegation_request_slot
+                    .saturating_add(UNDELEGATION_REQUEST_GRACE_PERIOD_SLOTS);
+        is_expired || is_request_elapsed || self.is_stale || self.has_upheld_dispute
+    }
 }
 
 impl AccountWithDiscriminator for DelegationMetadata {
@@ -47,6 +192,16 @@ mod tests {
             is_undelegatable: false,
             last_update_external_slot: 0,
             rent_payer: Pubkey::default(),
+            expiry: Some(DelegationExpiry::Slot(1_000)),
+            undelegation_request_slot: 0,
+            next_commit_nonce: 3,
+            next_finalize_nonce: 1,
+            last_commit_timestamp: 1_700_000_000,
+            is_stale: false,
+            last_commit_lamports: 1_000_000,
+            last_commit_slot: 100,
+            dispute_slot: 90,
+            has_upheld_dispute: false,
         };
 
         // Serialize
@@ -58,4 +213,30 @@ mod tests {
 
         assert_eq!(deserialized, original);
     }
+
+    #[test]
+    fn test_deserialization_without_trailing_fields() {
+        let rent_payer = Pubkey::new_unique();
+        let seeds: Vec<Vec<u8>> = vec![vec![1, 2, 3]];
+        let mut serialized = to_vec(&100u64).unwrap();
+        serialized.extend(to_vec(&true).unwrap());
+        serialized.extend(to_vec(&seeds).unwrap());
+        serialized.extend(to_vec(&rent_payer).unwrap());
+
+        let deserialized = DelegationMetadata::try_from_slice(&serialized).unwrap();
+
+        assert_eq!(deserialized.last_update_external_slot, 100);
+        assert!(deserialized.is_undelegatable);
+        assert_eq!(deserialized.seeds, seeds);
+        assert_eq!(deserialized.rent_payer, rent_payer);
+        assert_eq!(deserialized.expiry, None);
+        assert_eq!(deserialized.next_commit_nonce, 0);
+        assert_eq!(deserialized.last_commit_timestamp, 0);
+        assert!(!deserialized.is_stale);
+        assert_eq!(deserialized.last_commit_lamports, 0);
+        assert_eq!(deserialized.last_commit_slot, 0);
+        assert_eq!(deserialized.dispute_slot, 0);
+        assert!(!deserialized.has_upheld_dispute);
+        assert!(deserialized.size_with_discriminator().unwrap() > 8 + serialized.len());
+    }
 }
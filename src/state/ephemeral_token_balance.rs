@@ -0,0 +1,39 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    impl_to_bytes_with_discriminator_zero_copy, impl_try_from_bytes_with_discriminator_zero_copy,
+};
+
+use super::discriminator::{AccountDiscriminator, AccountWithDiscriminator};
+
+/// The Ephemeral Token Balance is the delegable counterpart of the token escrow of a payer,
+/// see [crate::pda::ephemeral_token_balance_escrow_pda_from_balance], which holds the tokens.
+/// The escrow can be topped up at any time, while the amount is synced with the escrow when the
+/// balance is delegated, then spent in the ephemeral rollup and settled on finalize.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct EphemeralTokenBalance {
+    /// The mint of the tokens held in the escrow
+    pub mint: Pubkey,
+
+    /// The tokens available in the ephemeral rollup while the balance is delegated
+    pub amount: u64,
+}
+
+impl AccountWithDiscriminator for EphemeralTokenBalance {
+    fn discriminator() -> AccountDiscriminator {
+        AccountDiscriminator::EphemeralTokenBalance
+    }
+}
+
+impl EphemeralTokenBalance {
+    pub fn size_with_discriminator() -> usize {
+        8 + size_of::<EphemeralTokenBalance>()
+    }
+}
+
+impl_to_bytes_with_discriminator_zero_copy!(EphemeralTokenBalance);
+impl_try_from_bytes_with_discriminator_zero_copy!(EphemeralTokenBalance);
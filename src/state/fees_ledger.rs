@@ -0,0 +1,76 @@
+use bytemuck::{Pod, Zeroable};
+use solana_program::program_error::ProgramError;
+
+use crate::error::DlpError;
+
+/// The cumulative lamports moved into and out of a fees vault, broken down by source
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct FeesLedger {
+    /// The lamports earned from the rent of the delegation PDAs closed at undelegation
+    pub rent_fees: u64,
+
+    /// The lamports earned from the lamports balance settled when finalizing a commit
+    pub settlement_fees: u64,
+
+    /// The lamports earned from the protocol share of the validator fees claims
+    pub protocol_fees: u64,
+
+    /// The lamports claimed from the vault, including the protocol share
+    pub claimed: u64,
+
+    /// The part of the claimed lamports paid to the protocol fees vault
+    pub protocol_share: u64,
+
+    /// The lamports earned from the validator bonds slashed to the vault
+    pub slashed: u64,
+
+    /// The lamports earned from the commit fees charged to the payers ephemeral balances
+    pub commit_fees: u64,
+}
+
+impl FeesLedger {
+    /// The lamports earned by the vault, from all sources
+    pub fn earned(&self) -> Option<u64> {
+        self.rent_fees
+            .checked_add(self.settlement_fees)?
+            .checked_add(self.protocol_fees)?
+            .checked_add(self.slashed)?
+            .checked_add(self.commit_fees)
+    }
+
+    pub fn record_rent_fees(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.rent_fees = checked_add(self.rent_fees, amount)?;
+        Ok(())
+    }
+
+    pub fn record_settlement_fees(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.settlement_fees = checked_add(self.settlement_fees, amount)?;
+        Ok(())
+    }
+
+    pub fn record_commit_fees(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.commit_fees = checked_add(self.commit_fees, amount)?;
+        Ok(())
+    }
+
+    pub fn record_protocol_fees(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.protocol_fees = checked_add(self.protocol_fees, amount)?;
+        Ok(())
+    }
+
+    pub fn record_slash(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.slashed = checked_add(self.slashed, amount)?;
+        Ok(())
+    }
+
+    pub fn record_claim(&mut self, amount: u64, protocol_share: u64) -> Result<(), ProgramError> {
+        self.claimed = checked_add(self.claimed, amount)?;
+        self.protocol_share = checked_add(self.protocol_share, protocol_share)?;
+        Ok(())
+    }
+}
+
+fn checked_add(counter: u64, amount: u64) -> Result<u64, ProgramError> {
+    Ok(counter.checked_add(amount).ok_or(DlpError::Overflow)?)
+}
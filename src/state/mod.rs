@@ -1,11 +1,25 @@
+mod commit_bundle_record;
 mod commit_record;
 mod delegation_metadata;
 mod delegation_record;
+mod ephemeral_token_balance;
+mod fees_ledger;
 mod program_config;
+mod protocol_config;
+mod protocol_fees_vault;
 mod utils;
+mod validator_bond;
+mod validator_fees_vault;
 
+pub use commit_bundle_record::*;
 pub use commit_record::*;
 pub use delegation_metadata::*;
 pub use delegation_record::*;
+pub use ephemeral_token_balance::*;
+pub use fees_ledger::*;
 pub use program_config::*;
+pub use protocol_config::*;
+pub use protocol_fees_vault::*;
 pub use utils::*;
+pub use validator_bond::*;
+pub use validator_fees_vault::*;
@@ -5,10 +5,40 @@ use std::collections::BTreeSet;
 use crate::{impl_to_bytes_with_discriminator_borsh, impl_try_from_bytes_with_discriminator_borsh};
 
 use super::discriminator::{AccountDiscriminator, AccountWithDiscriminator};
+use super::try_from_bytes::deserialize_trailing_field;
 
-#[derive(BorshSerialize, BorshDeserialize, Default, Debug)]
+/// The fields following the approved validators were appended after program configs were
+/// created, so the config of those programs ends early: the missing fields are read as their
+/// defaults, and the account is resized when the config is written back.
+#[derive(BorshSerialize, Default, Debug)]
 pub struct ProgramConfig {
     pub approved_validators: BTreeSet<Pubkey>,
+    /// The number of slots during which a commit can be disputed before it can be finalized
+    pub challenge_period: u64,
+    /// The minimum bonded lamports a validator needs to commit to the program accounts
+    pub min_validator_bond: u64,
+    /// Whether the program validates the commits to its accounts on finalize, see
+    /// [crate::consts::EXTERNAL_VALIDATE_COMMIT_DISCRIMINATOR]
+    pub validate_commits: bool,
+    /// The multiple of the commit frequency after which a delegation without commits can be
+    /// flagged as stale, or zero for [crate::consts::DEFAULT_COMMIT_STALENESS_MULTIPLIER]
+    pub commit_staleness_multiplier: u64,
+    /// The lamports charged to the payer ephemeral balance for each commit to the program
+    /// accounts, or zero for the commit fee of the protocol config
+    pub commit_fee: u64,
+}
+
+impl BorshDeserialize for ProgramConfig {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        Ok(Self {
+            approved_validators: BTreeSet::<Pubkey>::deserialize_reader(reader)?,
+            challenge_period: deserialize_trailing_field(reader)?,
+            min_validator_bond: deserialize_trailing_field(reader)?,
+            validate_commits: deserialize_trailing_field(reader)?,
+            commit_staleness_multiplier: deserialize_trailing_field(reader)?,
+            commit_fee: deserialize_trailing_field(reader)?,
+        })
+    }
 }
 
 impl AccountWithDiscriminator for ProgramConfig {
@@ -19,9 +49,32 @@ impl AccountWithDiscriminator for ProgramConfig {
 
 impl ProgramConfig {
     pub fn size_with_discriminator(&self) -> usize {
-        8 + 4 + 32 * self.approved_validators.len()
+        8 + 4 + 32 * self.approved_validators.len() + 8 + 8 + 1 + 8 + 8
     }
 }
 
 impl_to_bytes_with_discriminator_borsh!(ProgramConfig);
 impl_try_from_bytes_with_discriminator_borsh!(ProgramConfig);
+
+#[cfg(test)]
+mod tests {
+    use borsh::to_vec;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialization_without_trailing_fields() {
+        let approved_validators = BTreeSet::from([Pubkey::new_unique()]);
+        let serialized = to_vec(&approved_validators).unwrap();
+
+        let deserialized = ProgramConfig::try_from_slice(&serialized).unwrap();
+
+        assert_eq!(deserialized.approved_validators, approved_validators);
+        assert_eq!(deserialized.challenge_period, 0);
+        assert_eq!(deserialized.min_validator_bond, 0);
+        assert!(!deserialized.validate_commits);
+        assert_eq!(deserialized.commit_staleness_multiplier, 0);
+        assert_eq!(deserialized.commit_fee, 0);
+        assert!(deserialized.size_with_discriminator() > 8 + serialized.len());
+    }
+}
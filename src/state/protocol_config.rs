@@ -0,0 +1,49 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+use crate::consts::MAX_FEES_BPS;
+use crate::{impl_to_bytes_with_discriminator_borsh, impl_try_from_bytes_with_discriminator_borsh};
+
+use super::discriminator::{AccountDiscriminator, AccountWithDiscriminator};
+
+/// The protocol wide parameters, which can be updated by the admin without redeploying
+/// the program, see [crate::processor::process_update_protocol_config]
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, PartialEq, Eq)]
+pub struct ProtocolConfig {
+    /// The authority allowed to update the protocol config and to operate the protocol
+    pub admin: Pubkey,
+    /// The admin proposed by the current admin, which becomes the admin once it accepts,
+    /// or the default pubkey if no rotation is pending
+    pub pending_admin: Pubkey,
+    /// The fees extracted from the delegation PDAs rent when they are closed, in basis points
+    pub rent_fees_bps: u16,
+    /// The fees extracted from the validator fees claims, in basis points
+    pub protocol_fees_bps: u16,
+    /// The minimum commit frequency a delegation is held to when flagging it as stale, so that
+    /// a delegation requesting more frequent commits cannot be flagged earlier than this
+    pub min_commit_frequency_ms: u64,
+    /// The lamports charged to the payer ephemeral balance for each commit, unless the program
+    /// config of the delegated account owner sets its own commit fee
+    pub commit_fee: u64,
+}
+
+impl AccountWithDiscriminator for ProtocolConfig {
+    fn discriminator() -> AccountDiscriminator {
+        AccountDiscriminator::ProtocolConfig
+    }
+}
+
+impl ProtocolConfig {
+    pub fn size_with_discriminator() -> Result<usize, ProgramError> {
+        Ok(8 + borsh::object_length(&Self::default())?)
+    }
+
+    /// Whether the fee rates are at most [MAX_FEES_BPS]
+    pub fn has_valid_fees(&self) -> bool {
+        self.rent_fees_bps <= MAX_FEES_BPS && self.protocol_fees_bps <= MAX_FEES_BPS
+    }
+}
+
+impl_to_bytes_with_discriminator_borsh!(ProtocolConfig);
+impl_try_from_bytes_with_discriminator_borsh!(ProtocolConfig);
@@ -0,0 +1,35 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    impl_to_bytes_with_discriminator_zero_copy, impl_try_from_bytes_with_discriminator_zero_copy,
+};
+
+use super::discriminator::{AccountDiscriminator, AccountWithDiscriminator};
+use super::FeesLedger;
+
+/// The Protocol Fees Vault, accumulating the fees earned by the protocol.
+/// The vault created before the fees ledger was introduced holds no data until it is resized
+/// out of its own lamports, when claiming or recording its first fees.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ProtocolFeesVault {
+    /// The lamports moved into and out of the vault
+    pub ledger: FeesLedger,
+}
+
+impl AccountWithDiscriminator for ProtocolFeesVault {
+    fn discriminator() -> AccountDiscriminator {
+        AccountDiscriminator::ProtocolFeesVault
+    }
+}
+
+impl ProtocolFeesVault {
+    pub fn size_with_discriminator() -> usize {
+        8 + size_of::<ProtocolFeesVault>()
+    }
+}
+
+impl_to_bytes_with_discriminator_zero_copy!(ProtocolFeesVault);
+impl_try_from_bytes_with_discriminator_zero_copy!(ProtocolFeesVault);
@@ -7,6 +7,12 @@ pub enum AccountDiscriminator {
     DelegationMetadata = 102,
     CommitRecord = 101,
     ProgramConfig = 103,
+    CommitBundleRecord = 104,
+    ValidatorBond = 105,
+    ProtocolConfig = 106,
+    ValidatorFeesVault = 107,
+    ProtocolFeesVault = 108,
+    EphemeralTokenBalance = 109,
 }
 
 impl AccountDiscriminator {
@@ -52,3 +52,17 @@ macro_rules! impl_try_from_bytes_with_discriminator_borsh {
         }
     };
 }
+
+/// Deserialize a field appended to a borsh account layout, or its default value for the
+/// accounts created before the field was introduced, whose data ends before it
+pub fn deserialize_trailing_field<T, R>(reader: &mut R) -> std::io::Result<T>
+where
+    T: borsh::BorshDeserialize + Default,
+    R: std::io::Read,
+{
+    let mut first_byte = [0u8; 1];
+    if reader.read(&mut first_byte)? == 0 {
+        return Ok(T::default());
+    }
+    T::deserialize_reader(&mut std::io::Read::chain(&first_byte[..], reader))
+}
@@ -0,0 +1,46 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    impl_to_bytes_with_discriminator_zero_copy, impl_try_from_bytes_with_discriminator_zero_copy,
+};
+
+use super::discriminator::{AccountDiscriminator, AccountWithDiscriminator};
+
+/// The Validator Bond, the lamports staked by a validator to back its commits.
+/// Both the bonded and the unbonding lamports can be slashed.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct ValidatorBond {
+    /// The validator identity the bond belongs to
+    pub validator: Pubkey,
+
+    /// The bonded lamports, on top of the rent of the bond account
+    pub amount: u64,
+
+    /// The lamports requested to be unbonded, which are no longer backing the commits
+    pub unbonding_amount: u64,
+
+    /// The slot from which the unbonding lamports can be withdrawn
+    pub unbonding_end_slot: u64,
+
+    /// The number of delegations flagged as stale while the validator was their authority
+    pub stale_delegations: u64,
+}
+
+impl AccountWithDiscriminator for ValidatorBond {
+    fn discriminator() -> AccountDiscriminator {
+        AccountDiscriminator::ValidatorBond
+    }
+}
+
+impl ValidatorBond {
+    pub fn size_with_discriminator() -> usize {
+        8 + size_of::<ValidatorBond>()
+    }
+}
+
+impl_to_bytes_with_discriminator_zero_copy!(ValidatorBond);
+impl_try_from_bytes_with_discriminator_zero_copy!(ValidatorBond);
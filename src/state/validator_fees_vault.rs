@@ -0,0 +1,43 @@
+use std::mem::size_of;
+
+use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    impl_to_bytes_with_discriminator_zero_copy, impl_try_from_bytes_with_discriminator_zero_copy,
+};
+
+use super::discriminator::{AccountDiscriminator, AccountWithDiscriminator};
+use super::FeesLedger;
+
+/// The Validator Fees Vault, accumulating the fees earned by a validator.
+/// Vaults created before the withdrawal settings were introduced hold no data, in which case
+/// the validator identity is both the withdrawal authority and the payout destination, until
+/// the vault is resized out of its own lamports when claiming or recording its first fees.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct ValidatorFeesVault {
+    /// The authority allowed to claim the fees and to update the withdrawal settings
+    pub withdrawal_authority: Pubkey,
+
+    /// The account receiving the claimed fees
+    pub payout_destination: Pubkey,
+
+    /// The lamports moved into and out of the vault
+    pub ledger: FeesLedger,
+}
+
+impl AccountWithDiscriminator for ValidatorFeesVault {
+    fn discriminator() -> AccountDiscriminator {
+        AccountDiscriminator::ValidatorFeesVault
+    }
+}
+
+impl ValidatorFeesVault {
+    pub fn size_with_discriminator() -> usize {
+        8 + size_of::<ValidatorFeesVault>()
+    }
+}
+
+impl_to_bytes_with_discriminator_zero_copy!(ValidatorFeesVault);
+impl_try_from_bytes_with_discriminator_zero_copy!(ValidatorFeesVault);
@@ -1,9 +1,17 @@
-use dlp::state::{CommitRecord, DelegationMetadata, DelegationRecord, ProgramConfig};
+use dlp::state::discriminator::AccountDiscriminator;
+use dlp::state::{
+    CommitKind, CommitRecord, DelegationExpiry, DelegationMetadata, DelegationRecord, FeesLedger,
+    ProgramConfig, ProtocolFeesVault, ValidatorFeesVault,
+};
+use solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
 use solana_program::native_token::LAMPORTS_PER_SOL;
 use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::system_program;
+use solana_program_test::ProgramTest;
+use solana_sdk::account::Account;
 use solana_sdk::pubkey;
+use std::collections::BTreeSet;
 
 // Constants for default values
 const DEFAULT_DELEGATION_SLOT: u64 = 0;
@@ -104,16 +112,100 @@ pub fn get_delegation_metadata_data(rent_payer: Pubkey, is_undelegatable: Option
     )
 }
 
+#[allow(dead_code)]
+pub fn get_delegation_metadata_data_with_expiry(
+    rent_payer: Pubkey,
+    expiry: DelegationExpiry,
+) -> Vec<u8> {
+    create_delegation_metadata_data_with_expiry(
+        rent_payer,
+        DEFAULT_SEEDS,
+        DEFAULT_IS_UNDELEGATABLE,
+        Some(expiry),
+    )
+}
+
+/// The delegation metadata in the layout it had before any field was appended to it, as
+/// held by the delegations created back then
+#[allow(dead_code)]
+pub fn get_legacy_delegation_metadata_data_on_curve(rent_payer: Pubkey) -> Vec<u8> {
+    let mut bytes = AccountDiscriminator::DelegationMetadata.to_bytes().to_vec();
+    borsh::to_writer(
+        &mut bytes,
+        &(
+            DEFAULT_LAST_UPDATE_EXTERNAL_SLOT,
+            DEFAULT_IS_UNDELEGATABLE,
+            Vec::<Vec<u8>>::new(),
+            rent_payer,
+        ),
+    )
+    .unwrap();
+    bytes
+}
+
+/// The delegation metadata of a delegated PDA in the layout it had before any field was
+/// appended to it
+#[allow(dead_code)]
+pub fn get_legacy_delegation_metadata_data(rent_payer: Pubkey, is_undelegatable: bool) -> Vec<u8> {
+    let mut bytes = AccountDiscriminator::DelegationMetadata.to_bytes().to_vec();
+    borsh::to_writer(
+        &mut bytes,
+        &(
+            DEFAULT_LAST_UPDATE_EXTERNAL_SLOT,
+            is_undelegatable,
+            DEFAULT_SEEDS
+                .iter()
+                .map(|seed| seed.to_vec())
+                .collect::<Vec<_>>(),
+            rent_payer,
+        ),
+    )
+    .unwrap();
+    bytes
+}
+
+#[allow(dead_code)]
+pub fn with_pending_commits(delegation_metadata_data: &[u8], pending_commits: u64) -> Vec<u8> {
+    let mut delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(delegation_metadata_data).unwrap();
+    delegation_metadata.next_commit_nonce =
+        delegation_metadata.next_finalize_nonce + pending_commits;
+    let mut bytes = vec![];
+    delegation_metadata
+        .to_bytes_with_discriminator(&mut bytes)
+        .unwrap();
+    bytes
+}
+
 pub fn create_delegation_metadata_data(
     rent_payer: Pubkey,
     seeds: &[&[u8]],
     is_undelegatable: bool,
+) -> Vec<u8> {
+    create_delegation_metadata_data_with_expiry(rent_payer, seeds, is_undelegatable, None)
+}
+
+pub fn create_delegation_metadata_data_with_expiry(
+    rent_payer: Pubkey,
+    seeds: &[&[u8]],
+    is_undelegatable: bool,
+    expiry: Option<DelegationExpiry>,
 ) -> Vec<u8> {
     let delegation_metadata = DelegationMetadata {
         last_update_external_slot: DEFAULT_LAST_UPDATE_EXTERNAL_SLOT,
         is_undelegatable,
         seeds: seeds.iter().map(|s| s.to_vec()).collect(),
         rent_payer,
+        expiry,
+        undelegation_request_slot: 0,
+        next_commit_nonce: 0,
+        next_finalize_nonce: 0,
+        last_commit_timestamp: 0,
+        is_stale: false,
+        last_commit_lamports: 0,
+        last_commit_slot: 0,
+        dispute_slot: 0,
+        has_upheld_dispute: false,
     };
     let mut bytes = vec![];
     delegation_metadata
@@ -129,6 +221,15 @@ pub fn get_commit_record_account_data(authority: Pubkey) -> Vec<u8> {
         identity: authority,
         account: DELEGATED_PDA_ID,
         lamports: LAMPORTS_PER_SOL,
+        kind: CommitKind::Full.into(),
+        bundle: Pubkey::default(),
+        data_hash: [0; 32],
+        commit_slot: 0,
+        challenge_period: 0,
+        disputer: Pubkey::default(),
+        actions_len: 0,
+        payer: authority,
+        validate_commit: 0,
     };
     let mut bytes = vec![0u8; CommitRecord::size_with_discriminator()];
     commit_record
@@ -137,10 +238,41 @@ pub fn get_commit_record_account_data(authority: Pubkey) -> Vec<u8> {
     bytes
 }
 
+/// The commit record in the layout it had before commits were queued, as held by the commits
+/// pending since then
+#[allow(dead_code)]
+pub fn get_legacy_commit_record_account_data(authority: Pubkey) -> Vec<u8> {
+    let mut bytes = get_commit_record_account_data(authority);
+    bytes.truncate(CommitRecord::legacy_size_with_discriminator());
+    bytes
+}
+
 #[allow(dead_code)]
 pub fn create_program_config_data(approved_validator: Pubkey) -> Vec<u8> {
+    create_program_config_data_with_challenge_period(approved_validator, 0)
+}
+
+/// The program config in the layout it had before any field was appended to it, as held by
+/// the program configs created back then
+#[allow(dead_code)]
+pub fn create_legacy_program_config_data(approved_validator: Pubkey) -> Vec<u8> {
+    let mut bytes = AccountDiscriminator::ProgramConfig.to_bytes().to_vec();
+    borsh::to_writer(&mut bytes, &BTreeSet::from([approved_validator])).unwrap();
+    bytes
+}
+
+#[allow(dead_code)]
+pub fn create_program_config_data_with_challenge_period(
+    approved_validator: Pubkey,
+    challenge_period: u64,
+) -> Vec<u8> {
     let mut program_config = ProgramConfig {
         approved_validators: Default::default(),
+        challenge_period,
+        min_validator_bond: 0,
+        validate_commits: false,
+        commit_staleness_multiplier: 0,
+        commit_fee: 0,
     };
     program_config
         .approved_validators
@@ -151,3 +283,113 @@ pub fn create_program_config_data(approved_validator: Pubkey) -> Vec<u8> {
         .unwrap();
     bytes
 }
+
+#[allow(dead_code)]
+pub fn create_program_config_data_with_commit_fee(
+    approved_validator: Pubkey,
+    commit_fee: u64,
+) -> Vec<u8> {
+    let mut program_config = ProgramConfig {
+        approved_validators: Default::default(),
+        challenge_period: 0,
+        min_validator_bond: 0,
+        validate_commits: false,
+        commit_staleness_multiplier: 0,
+        commit_fee,
+    };
+    program_config
+        .approved_validators
+        .insert(approved_validator);
+    let mut bytes = vec![];
+    program_config
+        .to_bytes_with_discriminator(&mut bytes)
+        .unwrap();
+    bytes
+}
+
+#[allow(dead_code)]
+pub fn create_program_config_data_with_commit_validation(approved_validator: Pubkey) -> Vec<u8> {
+    let mut program_config = ProgramConfig {
+        approved_validators: Default::default(),
+        challenge_period: 0,
+        min_validator_bond: 0,
+        validate_commits: true,
+        commit_staleness_multiplier: 0,
+        commit_fee: 0,
+    };
+    program_config
+        .approved_validators
+        .insert(approved_validator);
+    let mut bytes = vec![];
+    program_config
+        .to_bytes_with_discriminator(&mut bytes)
+        .unwrap();
+    bytes
+}
+
+#[allow(dead_code)]
+pub fn create_validator_fees_vault_data(validator: Pubkey) -> Vec<u8> {
+    let validator_fees_vault = ValidatorFeesVault {
+        withdrawal_authority: validator,
+        payout_destination: validator,
+        ledger: FeesLedger::default(),
+    };
+    let mut bytes = vec![0u8; ValidatorFeesVault::size_with_discriminator()];
+    validator_fees_vault
+        .to_bytes_with_discriminator(&mut bytes)
+        .unwrap();
+    bytes
+}
+
+#[allow(dead_code)]
+pub fn create_protocol_fees_vault_data() -> Vec<u8> {
+    let mut bytes = vec![0u8; ProtocolFeesVault::size_with_discriminator()];
+    ProtocolFeesVault::default()
+        .to_bytes_with_discriminator(&mut bytes)
+        .unwrap();
+    bytes
+}
+
+/// The data of an initialized SPL Token mint, without freeze authority
+#[allow(dead_code)]
+pub fn create_mint_data(mint_authority: Pubkey, supply: u64, decimals: u8) -> Vec<u8> {
+    let mut bytes = vec![0u8; 82];
+    bytes[..4].copy_from_slice(&1u32.to_le_bytes());
+    bytes[4..36].copy_from_slice(mint_authority.as_ref());
+    bytes[36..44].copy_from_slice(&supply.to_le_bytes());
+    bytes[44] = decimals;
+    bytes[45] = 1;
+    bytes
+}
+
+/// The data of an initialized SPL Token account, without delegate nor close authority
+#[allow(dead_code)]
+pub fn create_token_account_data(mint: Pubkey, owner: Pubkey, amount: u64) -> Vec<u8> {
+    let mut bytes = vec![0u8; dlp::consts::TOKEN_ACCOUNT_SIZE];
+    bytes[..32].copy_from_slice(mint.as_ref());
+    bytes[32..64].copy_from_slice(owner.as_ref());
+    bytes[64..72].copy_from_slice(&amount.to_le_bytes());
+    bytes[108] = 1;
+    bytes
+}
+
+/// Setup the program data account of the delegation program, so that the upgrade authority
+/// acts as the protocol admin until the protocol config is initialized
+#[allow(dead_code)]
+pub fn add_delegation_program_data(program_test: &mut ProgramTest, upgrade_authority: Pubkey) {
+    let data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(upgrade_authority),
+    })
+    .unwrap();
+    program_test.add_account(
+        Pubkey::find_program_address(&[dlp::ID.as_ref()], &bpf_loader_upgradeable::id()).0,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: bpf_loader_upgradeable::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
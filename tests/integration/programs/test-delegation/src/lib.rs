@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::system_program;
+use dlp::args::DelegateArgs;
 use ephemeral_rollups_sdk::anchor::{delegate, ephemeral};
 use ephemeral_rollups_sdk::cpi::DelegateConfig;
 
@@ -6,6 +10,7 @@ declare_id!("3vAK9JQiDsKoQNwmcfeEng4Cnv22pYuj1ASfso7U4ukF");
 
 pub const TEST_PDA_SEED: &[u8] = b"test-pda";
 pub const TEST_PDA_SEED_OTHER: &[u8] = b"test-pda-other";
+pub const BUFFER_SEED: &[u8] = b"buffer";
 
 #[ephemeral]
 #[program]
@@ -54,6 +59,124 @@ pub mod test_delegation {
         )?;
         Ok(())
     }
+
+    /// Delegate two accounts to the delegation program with a single DelegateMany CPI
+    pub fn delegate_many(ctx: Context<DelegateManyInput>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let pda_seeds: &[&[u8]] = &[TEST_PDA_SEED, &[ctx.bumps.pda]];
+        let pda_other_seeds: &[&[u8]] = &[TEST_PDA_SEED_OTHER, &[ctx.bumps.pda_other]];
+        prepare_delegation(
+            &accounts.payer,
+            &accounts.pda,
+            &accounts.buffer_pda,
+            &accounts.system_program,
+            pda_seeds,
+        )?;
+        prepare_delegation(
+            &accounts.payer,
+            &accounts.pda_other,
+            &accounts.buffer_pda_other,
+            &accounts.system_program,
+            pda_other_seeds,
+        )?;
+
+        let ix = dlp::instruction_builder::delegate_many(
+            accounts.payer.key(),
+            Some(crate::ID),
+            vec![
+                (
+                    accounts.pda.key(),
+                    DelegateArgs {
+                        seeds: vec![TEST_PDA_SEED.to_vec()],
+                        ..Default::default()
+                    },
+                ),
+                (
+                    accounts.pda_other.key(),
+                    DelegateArgs {
+                        seeds: vec![TEST_PDA_SEED_OTHER.to_vec()],
+                        ..Default::default()
+                    },
+                ),
+            ],
+        );
+        invoke_signed(
+            &ix,
+            &[
+                accounts.payer.to_account_info(),
+                accounts.owner_program.to_account_info(),
+                accounts.system_program.to_account_info(),
+                accounts.pda.to_account_info(),
+                accounts.buffer_pda.to_account_info(),
+                accounts.delegation_record_pda.to_account_info(),
+                accounts.delegation_metadata_pda.to_account_info(),
+                accounts.pda_other.to_account_info(),
+                accounts.buffer_pda_other.to_account_info(),
+                accounts.delegation_record_pda_other.to_account_info(),
+                accounts.delegation_metadata_pda_other.to_account_info(),
+                accounts.delegation_program.to_account_info(),
+            ],
+            &[pda_seeds, pda_other_seeds],
+        )?;
+
+        close_buffer(&accounts.payer, &accounts.buffer_pda)?;
+        close_buffer(&accounts.payer, &accounts.buffer_pda_other)?;
+        Ok(())
+    }
+}
+
+/// Copy the PDA data to its buffer and hand the PDA over to the delegation program
+fn prepare_delegation<'info>(
+    payer: &Signer<'info>,
+    pda: &AccountInfo<'info>,
+    buffer: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    pda_seeds: &[&[u8]],
+) -> Result<()> {
+    let (_, buffer_bump) =
+        Pubkey::find_program_address(&[BUFFER_SEED, pda.key.as_ref()], &crate::ID);
+    let buffer_seeds: &[&[u8]] = &[BUFFER_SEED, pda.key.as_ref(), &[buffer_bump]];
+
+    // Create the buffer and copy the PDA data into it
+    let data_len = pda.data_len();
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            buffer.key,
+            Rent::get()?.minimum_balance(data_len),
+            data_len as u64,
+            &crate::ID,
+        ),
+        &[
+            payer.to_account_info(),
+            buffer.clone(),
+            system_program.to_account_info(),
+        ],
+        &[buffer_seeds],
+    )?;
+    buffer
+        .try_borrow_mut_data()?
+        .copy_from_slice(&pda.try_borrow_data()?);
+
+    // Clear the PDA data and assign it to the delegation program
+    pda.try_borrow_mut_data()?.fill(0);
+    pda.assign(&system_program::ID);
+    invoke_signed(
+        &system_instruction::assign(pda.key, &dlp::id()),
+        &[pda.clone(), system_program.to_account_info()],
+        &[pda_seeds],
+    )?;
+    Ok(())
+}
+
+/// Close a buffer once the delegation program copied its data back
+fn close_buffer<'info>(payer: &Signer<'info>, buffer: &AccountInfo<'info>) -> Result<()> {
+    let payer = payer.to_account_info();
+    **payer.try_borrow_mut_lamports()? += buffer.lamports();
+    **buffer.try_borrow_mut_lamports()? = 0;
+    buffer.assign(&system_program::ID);
+    buffer.realloc(0, false)?;
+    Ok(())
 }
 
 #[delegate]
@@ -77,6 +200,43 @@ pub struct DelegateInputTwo<'info> {
     pub pda_other: AccountInfo<'info>,
 }
 
+#[derive(Accounts)]
+pub struct DelegateManyInput<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: The pda to delegate
+    #[account(mut, seeds = [TEST_PDA_SEED], bump)]
+    pub pda: AccountInfo<'info>,
+    /// CHECK: The buffer of the pda to delegate
+    #[account(mut, seeds = [BUFFER_SEED, pda.key().as_ref()], bump)]
+    pub buffer_pda: AccountInfo<'info>,
+    /// CHECK: Checked by the delegation program
+    #[account(mut)]
+    pub delegation_record_pda: AccountInfo<'info>,
+    /// CHECK: Checked by the delegation program
+    #[account(mut)]
+    pub delegation_metadata_pda: AccountInfo<'info>,
+    /// CHECK: The other pda to delegate
+    #[account(mut, seeds = [TEST_PDA_SEED_OTHER], bump)]
+    pub pda_other: AccountInfo<'info>,
+    /// CHECK: The buffer of the other pda to delegate
+    #[account(mut, seeds = [BUFFER_SEED, pda_other.key().as_ref()], bump)]
+    pub buffer_pda_other: AccountInfo<'info>,
+    /// CHECK: Checked by the delegation program
+    #[account(mut)]
+    pub delegation_record_pda_other: AccountInfo<'info>,
+    /// CHECK: Checked by the delegation program
+    #[account(mut)]
+    pub delegation_metadata_pda_other: AccountInfo<'info>,
+    /// CHECK: This program
+    #[account(address = crate::ID)]
+    pub owner_program: AccountInfo<'info>,
+    /// CHECK: The delegation program
+    #[account(address = dlp::id())]
+    pub delegation_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(init, payer = user, space = 8 + 8, seeds = [TEST_PDA_SEED], bump)]
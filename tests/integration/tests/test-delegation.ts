@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, web3 } from "@coral-xyz/anchor";
 import * as beet from "@metaplex-foundation/beet";
+import * as beetSolana from "@metaplex-foundation/beet-solana";
 import { TestDelegation } from "../target/types/test_delegation";
 import {
   createDelegateInstruction,
@@ -9,8 +10,10 @@ import {
   DELEGATION_PROGRAM_ID,
 } from "@magicblock-labs/ephemeral-rollups-sdk-v2";
 import { ON_CURVE_ACCOUNT } from "./fixtures/consts";
+import { assert } from "chai";
 
 const SEED_TEST_PDA = "test-pda";
+const SEED_TEST_PDA_OTHER = "test-pda-other";
 const BPF_LOADER = new web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
 
 describe("TestDelegation", () => {
@@ -25,6 +28,10 @@ describe("TestDelegation", () => {
     [Buffer.from(SEED_TEST_PDA)],
     testDelegation.programId
   );
+  const [pdaOther] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from(SEED_TEST_PDA_OTHER)],
+    testDelegation.programId
+  );
   const payer = provider.wallet.publicKey;
   const admin = provider.wallet.publicKey;
   const validator = provider.wallet.publicKey;
@@ -73,7 +80,9 @@ describe("TestDelegation", () => {
 
   it("Initializes another counter", async () => {
     // Check if the counter is initialized
-    const counterAccountInfo = await provider.connection.getAccountInfo(pda);
+    const counterAccountInfo = await provider.connection.getAccountInfo(
+      pdaOther
+    );
     if (counterAccountInfo === null) {
       const tx = await testDelegation.methods
         .initializeOther()
@@ -83,7 +92,9 @@ describe("TestDelegation", () => {
         .rpc({ skipPreflight: true });
       console.log("Init Pda Tx: ", tx);
     }
-    const counterAccount = await testDelegation.account.counter.fetch(pda);
+    const counterAccount = await testDelegation.account.counter.fetch(
+      pdaOther
+    );
     console.log("Counter: ", counterAccount.count.toString());
   });
 
@@ -157,11 +168,13 @@ describe("TestDelegation", () => {
       lamports: new anchor.BN(1000000000),
       allow_undelegation: false,
       data: new_data,
+      actions: [],
     };
     const ix = createCommitAccountInstruction(
       validator,
       pda,
       ownerProgram,
+      0,
       args
     );
     const txId = await processInstruction(ix);
@@ -169,7 +182,7 @@ describe("TestDelegation", () => {
   });
 
   it("Finalize account state", async () => {
-    const ix = createFinalizeInstruction(validator, pda);
+    const ix = createFinalizeInstruction(validator, pda, ownerProgram, 0);
     const txId = await processInstruction(ix);
     console.log("Finalize signature", txId);
   });
@@ -184,11 +197,13 @@ describe("TestDelegation", () => {
       lamports: new anchor.BN(1000000000),
       allow_undelegation: true,
       data: new_data,
+      actions: [],
     };
     const ix = createCommitAccountInstruction(
       validator,
       pda,
       ownerProgram,
+      1,
       args
     );
     const txId = await processInstruction(ix);
@@ -196,7 +211,7 @@ describe("TestDelegation", () => {
   });
 
   it("Finalize account state again", async () => {
-    const ix = createFinalizeInstruction(validator, pda);
+    const ix = createFinalizeInstruction(validator, pda, ownerProgram, 1);
     const txId = await processInstruction(ix);
     console.log("Finalize signature", txId);
   });
@@ -206,12 +221,79 @@ describe("TestDelegation", () => {
       validator,
       pda,
       ownerProgram,
-      reimbursement
+      reimbursement,
+      2
+    );
+    const txId = await processInstruction(ix);
+    console.log("Undelegate signature", txId);
+  });
+
+  it("Commit a new state to the other PDA", async () => {
+    const account = await provider.connection.getAccountInfo(pdaOther);
+    const args: CommitAccountInstructionArgs = {
+      slot: new anchor.BN(10),
+      lamports: new anchor.BN(account.lamports),
+      allow_undelegation: true,
+      data: account.data,
+      actions: [],
+    };
+    const ix = createCommitAccountInstruction(
+      validator,
+      pdaOther,
+      ownerProgram,
+      0,
+      args
+    );
+    const txId = await processInstruction(ix);
+    console.log("Commit state signature", txId);
+  });
+
+  it("Finalize the other account state", async () => {
+    const ix = createFinalizeInstruction(validator, pdaOther, ownerProgram, 0);
+    const txId = await processInstruction(ix);
+    console.log("Finalize signature", txId);
+  });
+
+  it("Undelegate the other account", async () => {
+    const ix = createUndelegateInstruction(
+      validator,
+      pdaOther,
+      ownerProgram,
+      reimbursement,
+      1
     );
     const txId = await processInstruction(ix);
     console.log("Undelegate signature", txId);
   });
 
+  it("Delegate two PDAs with a single DelegateMany CPI", async () => {
+    const tx = await testDelegation.methods
+      .delegateMany()
+      .accounts({
+        payer: provider.wallet.publicKey,
+        delegationRecordPda: delegationRecordPdaFromDelegatedAccount(pda),
+        delegationMetadataPda: delegationMetadataPdaFromDelegatedAccount(pda),
+        delegationRecordPdaOther:
+          delegationRecordPdaFromDelegatedAccount(pdaOther),
+        delegationMetadataPdaOther:
+          delegationMetadataPdaFromDelegatedAccount(pdaOther),
+      })
+      .rpc({ skipPreflight: true });
+    console.log("Delegate many signature", tx);
+
+    // Both PDAs are now owned by the delegation program, with their delegation records
+    for (const delegated of [pda, pdaOther]) {
+      const account = await provider.connection.getAccountInfo(delegated);
+      assert.isTrue(
+        account.owner.equals(new web3.PublicKey(DELEGATION_PROGRAM_ID))
+      );
+      const delegationRecord = await provider.connection.getAccountInfo(
+        delegationRecordPdaFromDelegatedAccount(delegated)
+      );
+      assert.isNotNull(delegationRecord);
+    }
+  });
+
   it("Whitelist a validator for a program", async () => {
     const ix = createWhitelistValidatorForProgramInstruction(
       admin,
@@ -245,13 +327,44 @@ describe("TestDelegation", () => {
     delegationMetadata: web3.PublicKey;
   }
 
+  interface CommitActionAccount {
+    pubkey: web3.PublicKey;
+    is_signer: boolean;
+    is_writable: boolean;
+  }
+
+  interface CommitAction {
+    program_id: web3.PublicKey;
+    accounts: CommitActionAccount[];
+    data: Uint8Array;
+  }
+
   interface CommitAccountInstructionArgs {
     slot: beet.bignum;
     lamports: beet.bignum;
     allow_undelegation: boolean;
     data: Uint8Array;
+    actions: CommitAction[];
   }
 
+  const commitActionAccountStruct = new beet.BeetArgsStruct<CommitActionAccount>(
+    [
+      ["pubkey", beetSolana.publicKey],
+      ["is_signer", beet.bool],
+      ["is_writable", beet.bool],
+    ],
+    "CommitActionAccount"
+  );
+
+  const commitActionStruct = new beet.FixableBeetArgsStruct<CommitAction>(
+    [
+      ["program_id", beetSolana.publicKey],
+      ["accounts", beet.array(commitActionAccountStruct)],
+      ["data", beet.bytes],
+    ],
+    "CommitAction"
+  );
+
   const commitAccountStruct = new beet.FixableBeetArgsStruct<
     CommitAccountInstructionArgs & {
       instructionDiscriminator: number[] /* size: 8 */;
@@ -263,6 +376,7 @@ describe("TestDelegation", () => {
       ["lamports", beet.u64],
       ["allow_undelegation", beet.bool],
       ["data", beet.bytes],
+      ["actions", beet.array(commitActionStruct)],
     ],
     "CommitStateAccountArgs"
   );
@@ -271,13 +385,15 @@ describe("TestDelegation", () => {
     validator: web3.PublicKey,
     delegatedAccount: web3.PublicKey,
     ownerProgramId: web3.PublicKey,
+    commitNonce: number,
     args: CommitAccountInstructionArgs
   ) {
-    const commitState = commitStatePdaFromDelegatedAccount(pda);
-    const commitRecord = commitRecordPdaFromDelegatedAccount(pda);
-    const delegationRecord = delegationRecordPdaFromDelegatedAccount(pda);
-    const delegationMetadata = delegationMetadataPdaFromDelegatedAccount(pda);
+    const commitState = commitStatePdaFromDelegatedAccount(delegatedAccount, commitNonce);
+    const commitRecord = commitRecordPdaFromDelegatedAccount(delegatedAccount, commitNonce);
+    const delegationRecord = delegationRecordPdaFromDelegatedAccount(delegatedAccount);
+    const delegationMetadata = delegationMetadataPdaFromDelegatedAccount(delegatedAccount);
     const validatorFeesVault = validatorFeesVaultPdaFromValidator(validator);
+    const validatorBond = validatorBondPdaFromValidator(validator);
     const programConfig = programConfigPdaFromProgramId(ownerProgramId);
     const keys = [
       { pubkey: validator, isSigner: true, isWritable: false },
@@ -293,6 +409,7 @@ describe("TestDelegation", () => {
         isSigner: false,
         isWritable: false,
       },
+      { pubkey: validatorBond, isSigner: false, isWritable: false },
     ];
     const [data] = commitAccountStruct.serialize({
       instructionDiscriminator: [1, 0, 0, 0, 0, 0, 0, 0],
@@ -308,13 +425,16 @@ describe("TestDelegation", () => {
 
   function createFinalizeInstruction(
     validator: web3.PublicKey,
-    delegatedAccount: web3.PublicKey
+    delegatedAccount: web3.PublicKey,
+    ownerProgramId: web3.PublicKey,
+    commitNonce: number
   ) {
-    const commitState = commitStatePdaFromDelegatedAccount(pda);
-    const commitRecord = commitRecordPdaFromDelegatedAccount(pda);
-    const delegationRecord = delegationRecordPdaFromDelegatedAccount(pda);
-    const delegationMetadata = delegationMetadataPdaFromDelegatedAccount(pda);
+    const commitState = commitStatePdaFromDelegatedAccount(delegatedAccount, commitNonce);
+    const commitRecord = commitRecordPdaFromDelegatedAccount(delegatedAccount, commitNonce);
+    const delegationRecord = delegationRecordPdaFromDelegatedAccount(delegatedAccount);
+    const delegationMetadata = delegationMetadataPdaFromDelegatedAccount(delegatedAccount);
     const validatorFeesVault = validatorFeesVaultPdaFromValidator(validator);
+    const programConfig = programConfigPdaFromProgramId(ownerProgramId);
     const keys = [
       { pubkey: validator, isSigner: true, isWritable: false },
       { pubkey: delegatedAccount, isSigner: false, isWritable: true },
@@ -328,6 +448,9 @@ describe("TestDelegation", () => {
         isSigner: false,
         isWritable: false,
       },
+      { pubkey: programConfig, isSigner: false, isWritable: false },
+      { pubkey: ownerProgramId, isSigner: false, isWritable: false },
+      { pubkey: validator, isSigner: false, isWritable: true },
     ];
     const data = Buffer.from([2, 0, 0, 0, 0, 0, 0, 0]);
     const ix = new web3.TransactionInstruction({
@@ -342,18 +465,20 @@ describe("TestDelegation", () => {
     validator: web3.PublicKey,
     delegatedAccount: web3.PublicKey,
     ownerProgramId: web3.PublicKey,
-    reimbursement: web3.PublicKey
+    reimbursement: web3.PublicKey,
+    commitNonce: number
   ) {
     const buffer = web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("undelegate-buffer"), pda.toBytes()],
+      [Buffer.from("undelegate-buffer"), delegatedAccount.toBytes()],
       new web3.PublicKey(DELEGATION_PROGRAM_ID)
     )[0];
-    const commitState = commitStatePdaFromDelegatedAccount(pda);
-    const commitRecord = commitRecordPdaFromDelegatedAccount(pda);
-    const delegationRecord = delegationRecordPdaFromDelegatedAccount(pda);
-    const delegationMetadata = delegationMetadataPdaFromDelegatedAccount(pda);
+    const commitState = commitStatePdaFromDelegatedAccount(delegatedAccount, commitNonce);
+    const commitRecord = commitRecordPdaFromDelegatedAccount(delegatedAccount, commitNonce);
+    const delegationRecord = delegationRecordPdaFromDelegatedAccount(delegatedAccount);
+    const delegationMetadata = delegationMetadataPdaFromDelegatedAccount(delegatedAccount);
     const feesVault = feesVaultPda();
     const validatorFeesVault = validatorFeesVaultPdaFromValidator(validator);
+    const protocolConfig = protocolConfigPda();
     const keys = [
       { pubkey: validator, isSigner: true, isWritable: false },
       { pubkey: delegatedAccount, isSigner: false, isWritable: true },
@@ -371,6 +496,7 @@ describe("TestDelegation", () => {
         isSigner: false,
         isWritable: false,
       },
+      { pubkey: protocolConfig, isSigner: false, isWritable: false },
     ];
     const data = Buffer.from([3, 0, 0, 0, 0, 0, 0, 0]);
     const ix = new web3.TransactionInstruction({
@@ -424,6 +550,7 @@ describe("TestDelegation", () => {
         isSigner: false,
         isWritable: false,
       },
+      { pubkey: protocolConfigPda(), isSigner: false, isWritable: false },
     ];
     const data = Buffer.from([6, 0, 0, 0, 0, 0, 0, 0]);
     const ix = new web3.TransactionInstruction({
@@ -444,6 +571,9 @@ describe("TestDelegation", () => {
       { pubkey: validator, isSigner: true, isWritable: true },
       { pubkey: feesVault, isSigner: false, isWritable: true },
       { pubkey: validatorFeesVault, isSigner: false, isWritable: true },
+      { pubkey: protocolConfigPda(), isSigner: false, isWritable: false },
+      { pubkey: validator, isSigner: true, isWritable: true },
+      { pubkey: validator, isSigner: true, isWritable: true },
     ];
     const data = Buffer.from([7, 0, 0, 0, 0, 0, 0, 0, 0]);
     const ix = new web3.TransactionInstruction({
@@ -467,6 +597,8 @@ describe("TestDelegation", () => {
       { pubkey: admin, isSigner: true, isWritable: true },
       { pubkey: feesVault, isSigner: false, isWritable: true },
       { pubkey: delegationProgramData, isSigner: false, isWritable: true },
+      { pubkey: protocolConfigPda(), isSigner: false, isWritable: false },
+      { pubkey: admin, isSigner: false, isWritable: true },
     ];
     const data = Buffer.from([12, 0, 0, 0, 0, 0, 0, 0, 0]);
     const ix = new web3.TransactionInstruction({
@@ -503,6 +635,7 @@ describe("TestDelegation", () => {
         isSigner: false,
         isWritable: false,
       },
+      { pubkey: protocolConfigPda(), isSigner: false, isWritable: false },
     ];
     const data = Buffer.from([8, 0, 0, 0, 0, 0, 0, 0, insert ? 1 : 0]);
     const ix = new web3.TransactionInstruction({
@@ -514,16 +647,37 @@ describe("TestDelegation", () => {
   }
 });
 
-function commitStatePdaFromDelegatedAccount(delegatedAccount: web3.PublicKey) {
+// The first commit of a delegation has no nonce seed, mirroring `commit_nonce_seed`
+function commitNonceSeed(nonce: number) {
+  return nonce === 0
+    ? Buffer.alloc(0)
+    : new anchor.BN(nonce).toArrayLike(Buffer, "le", 8);
+}
+
+function commitStatePdaFromDelegatedAccount(
+  delegatedAccount: web3.PublicKey,
+  nonce: number
+) {
   return web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("state-diff"), delegatedAccount.toBytes()],
+    [
+      Buffer.from("state-diff"),
+      delegatedAccount.toBytes(),
+      commitNonceSeed(nonce),
+    ],
     new web3.PublicKey(DELEGATION_PROGRAM_ID)
   )[0];
 }
 
-function commitRecordPdaFromDelegatedAccount(delegatedAccount: web3.PublicKey) {
+function commitRecordPdaFromDelegatedAccount(
+  delegatedAccount: web3.PublicKey,
+  nonce: number
+) {
   return web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("commit-state-record"), delegatedAccount.toBytes()],
+    [
+      Buffer.from("commit-state-record"),
+      delegatedAccount.toBytes(),
+      commitNonceSeed(nonce),
+    ],
     new web3.PublicKey(DELEGATION_PROGRAM_ID)
   )[0];
 }
@@ -535,6 +689,13 @@ function feesVaultPda() {
   )[0];
 }
 
+function protocolConfigPda() {
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("protocol-config")],
+    new web3.PublicKey(DELEGATION_PROGRAM_ID)
+  )[0];
+}
+
 function validatorFeesVaultPdaFromValidator(validator: web3.PublicKey) {
   return web3.PublicKey.findProgramAddressSync(
     [Buffer.from("v-fees-vault"), validator.toBuffer()],
@@ -542,6 +703,13 @@ function validatorFeesVaultPdaFromValidator(validator: web3.PublicKey) {
   )[0];
 }
 
+function validatorBondPdaFromValidator(validator: web3.PublicKey) {
+  return web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("v-bond"), validator.toBuffer()],
+    new web3.PublicKey(DELEGATION_PROGRAM_ID)
+  )[0];
+}
+
 function programConfigPdaFromProgramId(programId: web3.PublicKey) {
   return web3.PublicKey.findProgramAddressSync(
     [Buffer.from("p-conf"), programId.toBuffer()],
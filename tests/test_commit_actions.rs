@@ -0,0 +1,293 @@
+use dlp::args::{CommitAction, CommitActionAccount, CommitStateArgs};
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_action_signer_pda_from_delegated_account, commit_record_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    validator_fees_vault_pda_from_validator,
+};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+/// Program invoked by the commit actions
+const ACTION_PROGRAM_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+
+/// Account of the action program storing the last score sent by a commit action
+const LEADERBOARD_ID: Pubkey = Pubkey::new_from_array([8; 32]);
+
+/// Error returned by the action program when rejecting an action
+const INVALID_ACTION_ERROR: u32 = 42;
+
+/// Action program recording the score of the delegated account in the leaderboard, if the
+/// action is signed by the commit action signer of the delegated account
+fn process_action(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [signer, leaderboard, delegated_account] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    assert!(signer.is_signer);
+    assert_eq!(
+        signer.key,
+        &commit_action_signer_pda_from_delegated_account(delegated_account.key)
+    );
+    if data[0] == 0 {
+        return Err(ProgramError::Custom(INVALID_ACTION_ERROR));
+    }
+
+    // The action runs on top of the finalized state
+    assert_eq!(delegated_account.try_borrow_data()?[0], data[0]);
+    leaderboard.try_borrow_mut_data()?[0] = data[0];
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_finalize_commit_with_actions() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Commit a new state with an action, then finalize it
+    let actions = vec![record_score_action(10)];
+    let res = commit_and_finalize_new_state(&banks, &validator, blockhash, actions).await;
+    assert!(res.is_ok());
+
+    // Assert the action was invoked after the state was finalized
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, vec![10, 1]);
+    let leaderboard = banks.get_account(LEADERBOARD_ID).await.unwrap().unwrap();
+    assert_eq!(leaderboard.data, vec![10]);
+}
+
+#[tokio::test]
+async fn test_finalize_commit_with_failing_action() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Commit a new state with an action rejected by the action program
+    let mut action = record_score_action(10);
+    action.data = vec![0];
+    let res = commit_and_finalize_new_state(&banks, &validator, blockhash, vec![action]).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(1, InstructionError::Custom(INVALID_ACTION_ERROR))
+    );
+
+    // Assert the commit was not finalized
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert!(delegated_account.data.is_empty());
+    let leaderboard = banks.get_account(LEADERBOARD_ID).await.unwrap().unwrap();
+    assert_eq!(leaderboard.data, vec![0]);
+}
+
+#[tokio::test]
+async fn test_commit_with_invalid_actions() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // An action cannot be signed by another account than the commit action signer
+    let mut action = record_score_action(10);
+    action.accounts[1].is_signer = true;
+    let ix = commit_state_ix(&validator, vec![action]);
+    let res = process_instructions(&banks, &validator, &[ix], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidCommitActions as u32)
+        )
+    );
+
+    // An action cannot invoke the delegation program
+    let mut action = record_score_action(10);
+    action.program_id = dlp::id();
+    let ix = commit_state_ix(&validator, vec![action]);
+    let res = process_instructions(&banks, &validator, &[ix], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidCommitActions as u32)
+        )
+    );
+
+    // Assert nothing was committed
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record = banks.get_account(commit_record_pda).await.unwrap();
+    assert!(commit_record.is_none());
+}
+
+fn record_score_action(score: u8) -> CommitAction {
+    CommitAction {
+        program_id: ACTION_PROGRAM_ID,
+        accounts: vec![
+            CommitActionAccount {
+                pubkey: commit_action_signer_pda_from_delegated_account(&DELEGATED_PDA_ID),
+                is_signer: true,
+                is_writable: false,
+            },
+            CommitActionAccount {
+                pubkey: LEADERBOARD_ID,
+                is_signer: false,
+                is_writable: true,
+            },
+            CommitActionAccount {
+                pubkey: DELEGATED_PDA_ID,
+                is_signer: false,
+                is_writable: false,
+            },
+        ],
+        data: vec![score],
+    }
+}
+
+fn commit_state_ix(validator: &Keypair, actions: Vec<CommitAction>) -> Instruction {
+    dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data: vec![10, 1],
+            actions,
+        },
+    )
+}
+
+async fn commit_and_finalize_new_state(
+    banks: &BanksClient,
+    validator: &Keypair,
+    blockhash: Hash,
+    actions: Vec<CommitAction>,
+) -> Result<(), BanksClientError> {
+    let ix_commit = commit_state_ix(validator, actions.clone());
+    let mut ix_finalize = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    ix_finalize
+        .accounts
+        .extend(dlp::instruction_builder::finalize_commit_actions_accounts(
+            &actions,
+        ));
+    process_instructions(banks, validator, &[ix_commit, ix_finalize], blockhash).await
+}
+
+async fn process_instructions(
+    banks: &BanksClient,
+    validator: &Keypair,
+    ixs: &[Instruction],
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    let tx =
+        Transaction::new_signed_with_payer(ixs, Some(&validator.pubkey()), &[validator], blockhash);
+    banks.process_transaction(tx).await
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the leaderboard of the action program
+    program_test.add_account(
+        LEADERBOARD_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![0],
+            owner: ACTION_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the program invoked by the commit actions
+    program_test.add_program(
+        "action_program",
+        ACTION_PROGRAM_ID,
+        processor!(process_action),
+    );
+
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator_keypair, blockhash)
+}
@@ -0,0 +1,291 @@
+use dlp::args::CommitStateArgs;
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{DelegationMetadata, DelegationRecord};
+use solana_program::instruction::InstructionError;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_commit_record_account_data, get_delegation_metadata_data, get_delegation_record_data,
+    with_pending_commits, COMMIT_NEW_STATE_ACCOUNT_DATA, DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID,
+    TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+#[tokio::test]
+async fn test_commit_and_finalize_deposits_extra_lamports() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env(false).await;
+    let new_state = vec![0, 1, 2, 9, 9, 9, 6, 7, 8, 9];
+    let delegated_account_before = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    let validator_before = banks.get_balance(validator.pubkey()).await.unwrap();
+    let record_lamports = Rent::default().minimum_balance(500);
+
+    let commit_args = CommitStateArgs {
+        data: new_state.clone(),
+        slot: 100,
+        allow_undelegation: true,
+        lamports: record_lamports + 1_000_000,
+        actions: vec![],
+    };
+
+    // Commit and finalize the state for the delegated account
+    let ix = dlp::instruction_builder::commit_and_finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        commit_args,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // Assert the new state was applied and the validator deposited the extra lamports
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, new_state);
+    assert_eq!(
+        delegated_account.lamports,
+        delegated_account_before.lamports + 1_000_000
+    );
+    let validator_after = banks.get_balance(validator.pubkey()).await.unwrap();
+    assert!(validator_after <= validator_before - 1_000_000);
+
+    // Assert no commit PDAs were created
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Assert the delegation record and metadata were updated
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let delegation_record_account = banks
+        .get_account(delegation_record_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_account.data)
+            .unwrap();
+    assert_eq!(delegation_record.lamports, delegated_account.lamports);
+
+    let delegation_metadata_pda = delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let delegation_metadata_account = banks
+        .get_account(delegation_metadata_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+            .unwrap();
+    assert_eq!(delegation_metadata.last_update_external_slot, 100);
+    assert!(delegation_metadata.is_undelegatable);
+}
+
+#[tokio::test]
+async fn test_commit_and_finalize_settles_lamports_to_fees_vault() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env(false).await;
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator.pubkey());
+    let delegated_account_before = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    let fees_vault_before = banks.get_balance(validator_fees_vault_pda).await.unwrap();
+    let record_lamports = Rent::default().minimum_balance(500);
+
+    let commit_args = CommitStateArgs {
+        data: COMMIT_NEW_STATE_ACCOUNT_DATA.to_vec(),
+        slot: 100,
+        allow_undelegation: false,
+        lamports: record_lamports - 1_000,
+        actions: vec![],
+    };
+
+    // Commit and finalize a state holding fewer lamports than recorded
+    let ix = dlp::instruction_builder::commit_and_finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        commit_args,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // Assert the lamports difference was moved to the validator fees vault
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(
+        delegated_account.data,
+        COMMIT_NEW_STATE_ACCOUNT_DATA.to_vec()
+    );
+    assert_eq!(
+        delegated_account.lamports,
+        delegated_account_before.lamports - 1_000
+    );
+    let fees_vault_after = banks.get_balance(validator_fees_vault_pda).await.unwrap();
+    assert_eq!(fees_vault_after, fees_vault_before + 1_000);
+}
+
+#[tokio::test]
+async fn test_commit_and_finalize_with_pending_commit_fails() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env(true).await;
+
+    let commit_args = CommitStateArgs {
+        data: vec![0, 1, 2, 9, 9, 9, 6, 7, 8, 9],
+        slot: 200,
+        allow_undelegation: true,
+        lamports: Rent::default().minimum_balance(500),
+        actions: vec![],
+    };
+
+    // A pending commit must be finalized first
+    let ix = dlp::instruction_builder::commit_and_finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        commit_args,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::PendingCommits as u32)
+        )
+    );
+
+    // Assert the delegated account was left untouched
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert!(delegated_account.data.is_empty());
+}
+
+async fn setup_program_test_env(with_pending_commit: bool) -> (BanksClient, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = with_pending_commits(
+        &get_delegation_metadata_data(validator_keypair.pubkey(), None),
+        with_pending_commit as u64,
+    );
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    if with_pending_commit {
+        // Setup the commit state PDA
+        program_test.add_account(
+            commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: COMMIT_NEW_STATE_ACCOUNT_DATA.to_vec(),
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        // Setup the commit record PDA
+        let commit_record_data = get_commit_record_account_data(validator_keypair.pubkey());
+        program_test.add_account(
+            commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+            Account {
+                lamports: Rent::default().minimum_balance(commit_record_data.len()),
+                data: commit_record_data,
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator_keypair, blockhash)
+}
@@ -0,0 +1,220 @@
+use dlp::args::CommitStateHashArgs;
+use dlp::pda::{
+    commit_buffer_pda_from_validator_and_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    validator_fees_vault_pda_from_validator,
+};
+use solana_program::hash::hash;
+use solana_program::instruction::Instruction;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+use crate::fixtures::{
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+const STATE_SIZE: usize = 100 * 1024;
+const CHUNK_SIZE: usize = 900;
+
+#[tokio::test]
+async fn test_commit_100kb_state_from_commit_buffer_in_chunks() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+    let new_state: Vec<u8> = (0..STATE_SIZE).map(|i| (i % 251) as u8).collect();
+
+    // Init the commit buffer
+    let ix = dlp::instruction_builder::init_commit_buffer(validator.pubkey(), DELEGATED_PDA_ID, 0);
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Write the new state in chunks, refreshing the blockhash so it does not expire meanwhile
+    let mut blockhash = blockhash;
+    for (index, chunk) in new_state.chunks(CHUNK_SIZE).enumerate() {
+        blockhash = banks.get_latest_blockhash().await.unwrap();
+        let ix = dlp::instruction_builder::write_commit_buffer(
+            validator.pubkey(),
+            DELEGATED_PDA_ID,
+            (index * CHUNK_SIZE) as u32,
+            chunk.to_vec(),
+        );
+        let res = process_instruction(&banks, &validator, ix, blockhash).await;
+        assert!(res.is_ok());
+    }
+
+    // Assert the commit buffer holds the new state
+    let commit_buffer_pda = commit_buffer_pda_from_validator_and_delegated_account(
+        &validator.pubkey(),
+        &DELEGATED_PDA_ID,
+    );
+    let commit_buffer_account = banks.get_account(commit_buffer_pda).await.unwrap().unwrap();
+    assert_eq!(commit_buffer_account.data, new_state);
+    assert!(Rent::default().is_exempt(commit_buffer_account.lamports, STATE_SIZE));
+
+    // Commit the hash of the new state, too large to be copied in a commit state account
+    let ix = dlp::instruction_builder::commit_state_hash(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateHashArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data_hash: hash(&new_state).to_bytes(),
+        },
+    );
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Finalize with the new state supplied from the commit buffer
+    let ix = dlp::instruction_builder::finalize_from_buffer(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_buffer_pda,
+    );
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the new state was committed to the delegated account
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, new_state);
+
+    // Close the commit buffer
+    let ix = dlp::instruction_builder::close_commit_buffer(validator.pubkey(), DELEGATED_PDA_ID);
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert!(res.is_ok());
+    assert!(banks
+        .get_account(commit_buffer_pda)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_write_commit_buffer_of_another_validator_fails() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+    let ix = dlp::instruction_builder::init_commit_buffer(validator.pubkey(), DELEGATED_PDA_ID, 8);
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Write in the buffer of the validator, signed by another account
+    let other = Keypair::new();
+    let commit_buffer_pda = commit_buffer_pda_from_validator_and_delegated_account(
+        &validator.pubkey(),
+        &DELEGATED_PDA_ID,
+    );
+    let mut ix = dlp::instruction_builder::write_commit_buffer(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        0,
+        vec![1; 8],
+    );
+    ix.accounts[0].pubkey = other.pubkey();
+    ix.accounts[2].pubkey = commit_buffer_pda;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator, &other],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_err());
+}
+
+async fn process_instruction(
+    banks: &BanksClient,
+    validator: &Keypair,
+    ix: Instruction,
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[validator],
+        blockhash,
+    );
+    banks.process_transaction(tx).await
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA, already sized for the new state
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![0; STATE_SIZE],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator_keypair, blockhash)
+}
@@ -0,0 +1,572 @@
+use dlp::args::CommitStateArgs;
+use dlp::consts::COMMIT_BUNDLE_FINALIZE_PERIOD_SLOTS;
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_bundle_record_pda_from_delegated_account, commit_record_pda_from_delegated_account,
+    commit_state_pda_from_delegated_account, delegation_metadata_pda_from_delegated_account,
+    delegation_record_pda_from_delegated_account, validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{CommitBundleRecord, CommitRecord};
+use solana_program::instruction::InstructionError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    create_delegation_record_data, get_delegation_metadata_data, DELEGATED_PDA_OWNER_ID,
+    TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+#[tokio::test]
+async fn test_commit_and_finalize_bundle() {
+    // Setup
+    let (banks, validator, delegated_accounts, blockhash) = setup_program_test_env().await;
+    let new_states = [vec![1, 2, 3], vec![4, 5, 6, 7]];
+
+    // Submit the commit bundle tx
+    let ix = dlp::instruction_builder::commit_bundle(
+        validator.pubkey(),
+        delegated_accounts
+            .iter()
+            .zip(new_states.iter())
+            .map(|(delegated_account, new_state)| {
+                (
+                    *delegated_account,
+                    DELEGATED_PDA_OWNER_ID,
+                    0,
+                    CommitStateArgs {
+                        slot: 100,
+                        lamports: LAMPORTS_PER_SOL,
+                        allow_undelegation: false,
+                        data: new_state.clone(),
+                        actions: vec![],
+                    },
+                )
+            })
+            .collect(),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the bundle record was created and each commit record points to it
+    let commit_bundle_record_pda =
+        commit_bundle_record_pda_from_delegated_account(&delegated_accounts[0], 0);
+    let commit_bundle_record_account = banks
+        .get_account(commit_bundle_record_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let commit_bundle_record =
+        CommitBundleRecord::try_from_bytes_with_discriminator(&commit_bundle_record_account.data)
+            .unwrap();
+    assert_eq!(commit_bundle_record.identity, validator.pubkey());
+    assert_eq!(commit_bundle_record.account, delegated_accounts[0]);
+    assert_eq!(commit_bundle_record.nonce, 0);
+    assert_eq!(commit_bundle_record.commits_count, 2);
+    assert!(commit_bundle_record.expiry_slot >= COMMIT_BUNDLE_FINALIZE_PERIOD_SLOTS);
+    for delegated_account in delegated_accounts.iter() {
+        let commit_record_account = banks
+            .get_account(commit_record_pda_from_delegated_account(
+                delegated_account,
+                0,
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        let commit_record =
+            CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
+        assert_eq!(commit_record.bundle, commit_bundle_record_pda);
+    }
+
+    // Submit the finalize bundle tx
+    let ix = dlp::instruction_builder::finalize_bundle(
+        validator.pubkey(),
+        &delegated_accounts
+            .iter()
+            .map(|delegated_account| (*delegated_account, DELEGATED_PDA_OWNER_ID, 0))
+            .collect::<Vec<_>>(),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the bundle record was closed
+    assert!(banks
+        .get_account(commit_bundle_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Assert every account was finalized
+    for (delegated_account, new_state) in delegated_accounts.iter().zip(new_states.iter()) {
+        let commit_state_pda = commit_state_pda_from_delegated_account(delegated_account, 0);
+        assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+        let commit_record_pda = commit_record_pda_from_delegated_account(delegated_account, 0);
+        assert!(banks
+            .get_account(commit_record_pda)
+            .await
+            .unwrap()
+            .is_none());
+        let pda_account = banks
+            .get_account(*delegated_account)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&pda_account.data, new_state);
+    }
+}
+
+#[tokio::test]
+async fn test_finalize_bundled_commit_alone_fails() {
+    // Setup
+    let (banks, validator, delegated_accounts, blockhash) = setup_program_test_env().await;
+    commit_bundle(&banks, &validator, &delegated_accounts, blockhash).await;
+
+    // Finalizing a single commit of the bundle with finalize fails
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        delegated_accounts[1],
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidCommitBundle as u32)
+        )
+    );
+
+    // Finalizing part of the bundle with finalize bundle fails
+    let ix = dlp::instruction_builder::finalize_bundle(
+        validator.pubkey(),
+        &[(delegated_accounts[0], DELEGATED_PDA_OWNER_ID, 0)],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+
+    // Assert no account was finalized
+    for delegated_account in delegated_accounts.iter() {
+        let commit_record_pda = commit_record_pda_from_delegated_account(delegated_account, 0);
+        assert!(banks
+            .get_account(commit_record_pda)
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_commit_bundle_behind_newer_pending_commit_fails() {
+    // Setup
+    let (banks, validator, delegated_accounts, blockhash) = setup_program_test_env().await;
+
+    // Queue a commit of the first account at a newer slot than the bundle
+    let ix = dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        delegated_accounts[0],
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateArgs {
+            slot: 200,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data: vec![1, 2, 3],
+            actions: vec![],
+        },
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // The bundle cannot be queued behind it, since its commit of the first account is outdated
+    let ix = dlp::instruction_builder::commit_bundle(
+        validator.pubkey(),
+        delegated_accounts
+            .iter()
+            .enumerate()
+            .map(|(index, delegated_account)| {
+                (
+                    *delegated_account,
+                    DELEGATED_PDA_OWNER_ID,
+                    if index == 0 { 1 } else { 0 },
+                    CommitStateArgs {
+                        slot: 100,
+                        lamports: LAMPORTS_PER_SOL,
+                        allow_undelegation: false,
+                        data: vec![4, 5, 6],
+                        actions: vec![],
+                    },
+                )
+            })
+            .collect(),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::OutdatedSlot as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_commit_bundle_with_ephemeral_token_balance_fails() {
+    // Setup, with an ephemeral token balance, owned by the delegation program
+    let (program_test, validator, delegated_accounts) =
+        setup_program_test(&[DELEGATED_PDA_OWNER_ID, dlp::id()]);
+    let (banks, _, blockhash) = program_test.start().await;
+
+    // Its tokens could not be settled when finalizing the bundle
+    let ix = dlp::instruction_builder::commit_bundle(
+        validator.pubkey(),
+        delegated_accounts
+            .iter()
+            .zip([DELEGATED_PDA_OWNER_ID, dlp::id()])
+            .map(|(delegated_account, owner)| (*delegated_account, owner, 0, commit_args()))
+            .collect(),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidCommitBundle as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_abandon_commit_bundle() {
+    // Setup
+    let (banks, validator, delegated_accounts, blockhash) = setup_program_test_env().await;
+    commit_bundle(&banks, &validator, &delegated_accounts, blockhash).await;
+
+    // The bundle cannot be abandoned by anyone else before it expires
+    let signer = Keypair::new();
+    let ix = dlp::instruction_builder::abandon_commit_bundle(
+        signer.pubkey(),
+        validator.pubkey(),
+        delegated_accounts[0],
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator, &signer],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::CommitBundleNotExpired as u32)
+        )
+    );
+
+    // The validator can abandon its bundle
+    let ix = dlp::instruction_builder::abandon_commit_bundle(
+        validator.pubkey(),
+        validator.pubkey(),
+        delegated_accounts[0],
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+    assert!(banks
+        .get_account(commit_bundle_record_pda_from_delegated_account(
+            &delegated_accounts[0],
+            0
+        ))
+        .await
+        .unwrap()
+        .is_none());
+
+    // The commits of the abandoned bundle are finalized alone
+    let mut ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        delegated_accounts[1],
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    ix.accounts.extend(
+        dlp::instruction_builder::finalize_abandoned_commit_bundle_accounts(
+            delegated_accounts[0],
+            0,
+        ),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+    let pda_account = banks
+        .get_account(delegated_accounts[1])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(pda_account.data, vec![1, 2, 3]);
+    assert!(banks
+        .get_account(commit_record_pda_from_delegated_account(
+            &delegated_accounts[0],
+            0
+        ))
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+async fn test_abandon_expired_commit_bundle() {
+    // Setup
+    let (program_test, validator, delegated_accounts) =
+        setup_program_test(&[DELEGATED_PDA_OWNER_ID, DELEGATED_PDA_OWNER_ID]);
+    let mut context = program_test.start_with_context().await;
+    commit_bundle(
+        &context.banks_client,
+        &validator,
+        &delegated_accounts,
+        context.last_blockhash,
+    )
+    .await;
+
+    // Anyone can abandon the bundle once it expired, refunding the validator
+    let commit_bundle_record_pda =
+        commit_bundle_record_pda_from_delegated_account(&delegated_accounts[0], 0);
+    let commit_bundle_record_account = context
+        .banks_client
+        .get_account(commit_bundle_record_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let expiry_slot =
+        CommitBundleRecord::try_from_bytes_with_discriminator(&commit_bundle_record_account.data)
+            .unwrap()
+            .expiry_slot;
+    context.warp_to_slot(expiry_slot).unwrap();
+    let validator_lamports = context
+        .banks_client
+        .get_balance(validator.pubkey())
+        .await
+        .unwrap();
+
+    let ix = dlp::instruction_builder::abandon_commit_bundle(
+        context.payer.pubkey(),
+        validator.pubkey(),
+        delegated_accounts[0],
+        0,
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(validator.pubkey())
+            .await
+            .unwrap(),
+        validator_lamports + commit_bundle_record_account.lamports
+    );
+}
+
+async fn commit_bundle(
+    banks: &BanksClient,
+    validator: &Keypair,
+    delegated_accounts: &[Pubkey],
+    blockhash: Hash,
+) {
+    let ix = dlp::instruction_builder::commit_bundle(
+        validator.pubkey(),
+        delegated_accounts
+            .iter()
+            .map(|delegated_account| {
+                (
+                    *delegated_account,
+                    DELEGATED_PDA_OWNER_ID,
+                    0,
+                    CommitStateArgs {
+                        slot: 100,
+                        lamports: LAMPORTS_PER_SOL,
+                        allow_undelegation: false,
+                        data: vec![1, 2, 3],
+                        actions: vec![],
+                    },
+                )
+            })
+            .collect(),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+}
+
+fn commit_args() -> CommitStateArgs {
+    CommitStateArgs {
+        slot: 100,
+        lamports: LAMPORTS_PER_SOL,
+        allow_undelegation: false,
+        data: vec![1, 2, 3],
+        actions: vec![],
+    }
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Vec<Pubkey>, Hash) {
+    let (program_test, validator, delegated_accounts) =
+        setup_program_test(&[DELEGATED_PDA_OWNER_ID, DELEGATED_PDA_OWNER_ID]);
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator, delegated_accounts, blockhash)
+}
+
+/// Setup a delegated account for each of the given owners
+fn setup_program_test(owners: &[Pubkey]) -> (ProgramTest, Keypair, Vec<Pubkey>) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let delegated_accounts: Vec<Pubkey> = owners.iter().map(|_| Pubkey::new_unique()).collect();
+
+    program_test.add_account(
+        validator.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    for (delegated_account, owner) in delegated_accounts.iter().zip(owners) {
+        // Setup a delegated PDA
+        program_test.add_account(
+            *delegated_account,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: vec![],
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        // Setup the delegated account metadata PDA
+        let delegation_metadata_data = get_delegation_metadata_data(validator.pubkey(), None);
+        program_test.add_account(
+            delegation_metadata_pda_from_delegated_account(delegated_account),
+            Account {
+                lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+                data: delegation_metadata_data,
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        // Setup the delegated record PDA
+        let delegation_record_data =
+            create_delegation_record_data(validator.pubkey(), *owner, None);
+        program_test.add_account(
+            delegation_record_pda_from_delegated_account(delegated_account),
+            Account {
+                lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+                data: delegation_record_data,
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    (program_test, validator, delegated_accounts)
+}
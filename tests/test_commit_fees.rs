@@ -0,0 +1,393 @@
+use crate::fixtures::{
+    create_delegation_metadata_data, create_delegation_record_data,
+    create_program_config_data_with_commit_fee, create_validator_fees_vault_data,
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+use dlp::args::{CommitStateArgs, CommitStateHashArgs};
+use dlp::error::DlpError;
+use dlp::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    ephemeral_balance_pda_from_payer, program_config_from_program_id,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{DelegationRecord, ValidatorFeesVault};
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+mod fixtures;
+
+const COMMIT_FEE: u64 = 5_000;
+
+#[tokio::test]
+async fn test_commit_fee_charged_to_ephemeral_balance() {
+    // Setup
+    let (banks, payer, _, validator, blockhash) = setup_program_test_env().await;
+    let ephemeral_balance_pda = ephemeral_balance_pda_from_payer(&payer.pubkey(), 0);
+    let ephemeral_balance_init_lamports = get_lamports(&banks, ephemeral_balance_pda).await;
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator.pubkey());
+    let validator_fees_vault_init_lamports = get_lamports(&banks, validator_fees_vault_pda).await;
+
+    // Commit the state, charging the commit fee to the payer ephemeral balance
+    let ix = commit_state_with_fee_ix(&validator, payer.pubkey(), 0);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // Assert the commit fee was moved from the ephemeral balance to the validator fees vault
+    assert_eq!(
+        get_lamports(&banks, ephemeral_balance_pda).await,
+        ephemeral_balance_init_lamports - COMMIT_FEE
+    );
+    assert_eq!(
+        get_lamports(&banks, validator_fees_vault_pda).await,
+        validator_fees_vault_init_lamports + COMMIT_FEE
+    );
+
+    // Assert the delegation record of the ephemeral balance follows the debited lamports
+    let delegation_record_account = banks
+        .get_account(delegation_record_pda_from_delegated_account(
+            &ephemeral_balance_pda,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_account.data)
+            .unwrap();
+    assert_eq!(
+        delegation_record.lamports,
+        ephemeral_balance_init_lamports - COMMIT_FEE
+    );
+
+    // Assert the commit fee is recorded in the fees ledger
+    let validator_fees_vault_account = banks
+        .get_account(validator_fees_vault_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let validator_fees_vault =
+        ValidatorFeesVault::try_from_bytes_with_discriminator(&validator_fees_vault_account.data)
+            .unwrap();
+    assert_eq!(validator_fees_vault.ledger.commit_fees, COMMIT_FEE);
+}
+
+#[tokio::test]
+async fn test_commit_fee_charged_on_every_commit_path() {
+    let commit_ixs: Vec<fn(&Keypair, Pubkey) -> Instruction> = vec![
+        |validator, fee_payer| {
+            dlp::instruction_builder::commit_state_hash_with_fee(
+                validator.pubkey(),
+                DELEGATED_PDA_ID,
+                DELEGATED_PDA_OWNER_ID,
+                0,
+                CommitStateHashArgs {
+                    data_hash: [7; 32],
+                    slot: 100,
+                    allow_undelegation: false,
+                    lamports: LAMPORTS_PER_SOL,
+                },
+                fee_payer,
+                0,
+            )
+        },
+        |validator, fee_payer| {
+            dlp::instruction_builder::commit_bundle_with_fees(
+                validator.pubkey(),
+                vec![(DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID, 0, commit_args())],
+                vec![(fee_payer, 0)],
+            )
+        },
+        |validator, fee_payer| {
+            dlp::instruction_builder::commit_and_finalize_with_fee(
+                validator.pubkey(),
+                DELEGATED_PDA_ID,
+                DELEGATED_PDA_OWNER_ID,
+                commit_args(),
+                fee_payer,
+                0,
+            )
+        },
+    ];
+
+    for commit_ix in commit_ixs {
+        // Setup
+        let (banks, payer, _, validator, blockhash) = setup_program_test_env().await;
+        let ephemeral_balance_pda = ephemeral_balance_pda_from_payer(&payer.pubkey(), 0);
+        let ephemeral_balance_init_lamports = get_lamports(&banks, ephemeral_balance_pda).await;
+
+        // Commit the state, charging the commit fee to the payer ephemeral balance
+        let ix = commit_ix(&validator, payer.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&validator.pubkey()),
+            &[&validator],
+            blockhash,
+        );
+        let res = banks.process_transaction(tx).await;
+        assert!(res.is_ok());
+
+        // Assert the commit fee was charged and recorded in the fees ledger
+        assert_eq!(
+            get_lamports(&banks, ephemeral_balance_pda).await,
+            ephemeral_balance_init_lamports - COMMIT_FEE
+        );
+        let validator_fees_vault_account = banks
+            .get_account(validator_fees_vault_pda_from_validator(&validator.pubkey()))
+            .await
+            .unwrap()
+            .unwrap();
+        let validator_fees_vault = ValidatorFeesVault::try_from_bytes_with_discriminator(
+            &validator_fees_vault_account.data,
+        )
+        .unwrap();
+        assert_eq!(validator_fees_vault.ledger.commit_fees, COMMIT_FEE);
+    }
+}
+
+#[tokio::test]
+async fn test_commit_fee_rejects_ephemeral_balance_of_another_payer() {
+    // Setup
+    let (banks, _, other_payer, validator, blockhash) = setup_program_test_env().await;
+
+    // The ephemeral balance must belong to the payer of the delegation
+    let ix = commit_state_with_fee_ix(&validator, other_payer.pubkey(), 0);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidCommitFeePayer as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_commit_fee_rejects_insufficient_ephemeral_balance() {
+    // Setup
+    let (banks, payer, _, validator, blockhash) = setup_program_test_env().await;
+
+    // The ephemeral balance must remain rent exempt after paying the commit fee
+    let ix = commit_state_with_fee_ix(&validator, payer.pubkey(), 1);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InsufficientFunds)
+    );
+}
+
+fn commit_state_with_fee_ix(
+    validator: &Keypair,
+    fee_payer: Pubkey,
+    fee_payer_index: u8,
+) -> Instruction {
+    dlp::instruction_builder::commit_state_with_fee(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_args(),
+        fee_payer,
+        fee_payer_index,
+    )
+}
+
+fn commit_args() -> CommitStateArgs {
+    CommitStateArgs {
+        data: vec![0, 1, 2, 9, 9, 9, 6, 7, 8, 9],
+        slot: 100,
+        allow_undelegation: false,
+        lamports: LAMPORTS_PER_SOL,
+        actions: vec![],
+    }
+}
+
+async fn get_lamports(banks: &BanksClient, pubkey: Pubkey) -> u64 {
+    banks.get_account(pubkey).await.unwrap().unwrap().lamports
+}
+
+fn add_ephemeral_balance(
+    program_test: &mut ProgramTest,
+    payer: Pubkey,
+    index: u8,
+    validator: Pubkey,
+    lamports: u64,
+) {
+    let ephemeral_balance_pda = ephemeral_balance_pda_from_payer(&payer, index);
+    program_test.add_account(
+        ephemeral_balance_pda,
+        Account {
+            lamports,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let delegation_record_data =
+        create_delegation_record_data(validator, system_program::id(), Some(lamports));
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&ephemeral_balance_pda),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let delegation_metadata_data =
+        create_delegation_metadata_data(payer, &[b"balance", payer.as_ref(), &[index]], false);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&ephemeral_balance_pda),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let payer_keypair = Keypair::new();
+    let other_payer_keypair = Keypair::new();
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA, whose delegation was paid by the payer
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let delegation_metadata_data = get_delegation_metadata_data(payer_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let delegation_record_data =
+        get_delegation_record_data(validator_keypair.pubkey(), Some(LAMPORTS_PER_SOL));
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    let validator_fees_vault_data = create_validator_fees_vault_data(validator_keypair.pubkey());
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: validator_fees_vault_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the owner program config with a commit fee
+    let program_config_data =
+        create_program_config_data_with_commit_fee(validator_keypair.pubkey(), COMMIT_FEE);
+    program_test.add_account(
+        program_config_from_program_id(&DELEGATED_PDA_OWNER_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(program_config_data.len()),
+            data: program_config_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the ephemeral balances delegated to the validator, the second one of the payer
+    // cannot afford the commit fee while remaining rent exempt
+    add_ephemeral_balance(
+        &mut program_test,
+        payer_keypair.pubkey(),
+        0,
+        validator_keypair.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+    add_ephemeral_balance(
+        &mut program_test,
+        payer_keypair.pubkey(),
+        1,
+        validator_keypair.pubkey(),
+        Rent::default().minimum_balance(0) + COMMIT_FEE - 1,
+    );
+    add_ephemeral_balance(
+        &mut program_test,
+        other_payer_keypair.pubkey(),
+        0,
+        validator_keypair.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+
+    let (banks, _, blockhash) = program_test.start().await;
+    (
+        banks,
+        payer_keypair,
+        other_payer_keypair,
+        validator_keypair,
+        blockhash,
+    )
+}
@@ -32,6 +32,7 @@ async fn test_commit_on_curve() {
         slot: 100,
         allow_undelegation: true,
         lamports: new_account_balance,
+        actions: vec![],
     };
 
     // Commit the state for the delegated account
@@ -39,6 +40,7 @@ async fn test_commit_on_curve() {
         validator.pubkey(),
         payer_delegated.pubkey(),
         system_program::ID,
+        0,
         commit_args,
     );
     let tx = Transaction::new_signed_with_payer(
@@ -52,12 +54,12 @@ async fn test_commit_on_curve() {
     assert!(res.is_ok());
 
     // Assert the state commitment was created and contains the new state
-    let commit_state_pda = commit_state_pda_from_delegated_account(&payer_delegated.pubkey());
+    let commit_state_pda = commit_state_pda_from_delegated_account(&payer_delegated.pubkey(), 0);
     let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
     assert!(commit_state_account.data.is_empty());
 
     // Assert the record about the commitment exists
-    let commit_record_pda = commit_record_pda_from_delegated_account(&payer_delegated.pubkey());
+    let commit_record_pda = commit_record_pda_from_delegated_account(&payer_delegated.pubkey(), 0);
     let commit_record_account = banks.get_account(commit_record_pda).await.unwrap().unwrap();
     let commit_record =
         CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
@@ -0,0 +1,397 @@
+use dlp::args::CommitStateArgs;
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{CommitKind, CommitRecord, DelegationMetadata, DelegationRecord};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+use crate::fixtures::{
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+#[tokio::test]
+async fn test_pipelined_commits_are_finalized_in_order() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+    let new_states = [vec![1, 2, 3], vec![4, 5, 6, 7]];
+
+    // Commit two states without finalizing the first one
+    let commit_ixs = new_states
+        .iter()
+        .enumerate()
+        .map(|(nonce, new_state)| {
+            dlp::instruction_builder::commit_state(
+                validator.pubkey(),
+                DELEGATED_PDA_ID,
+                DELEGATED_PDA_OWNER_ID,
+                nonce as u64,
+                CommitStateArgs {
+                    slot: 100 + nonce as u64,
+                    lamports: LAMPORTS_PER_SOL,
+                    allow_undelegation: false,
+                    data: new_state.clone(),
+                    actions: vec![],
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    let tx = Transaction::new_signed_with_payer(
+        &commit_ixs,
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // Assert both commits are pending
+    let delegation_metadata = get_delegation_metadata(&banks).await;
+    assert_eq!(delegation_metadata.next_commit_nonce, 2);
+    assert_eq!(delegation_metadata.next_finalize_nonce, 0);
+
+    // Finalize the oldest commit
+    finalize(&banks, &validator, 0, blockhash).await;
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, new_states[0]);
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+
+    // Finalize the newest commit
+    finalize(&banks, &validator, 1, blockhash).await;
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, new_states[1]);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 1);
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+
+    let delegation_metadata = get_delegation_metadata(&banks).await;
+    assert_eq!(delegation_metadata.next_finalize_nonce, 2);
+    assert_eq!(delegation_metadata.last_update_external_slot, 101);
+}
+
+#[tokio::test]
+async fn test_outdated_pipelined_commit_is_skipped() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Commit a state, then a state from an older slot
+    let commit_ixs = [(100, vec![1, 2, 3]), (90, vec![4, 5, 6, 7])]
+        .into_iter()
+        .enumerate()
+        .map(|(nonce, (slot, new_state))| {
+            dlp::instruction_builder::commit_state(
+                validator.pubkey(),
+                DELEGATED_PDA_ID,
+                DELEGATED_PDA_OWNER_ID,
+                nonce as u64,
+                CommitStateArgs {
+                    slot,
+                    lamports: LAMPORTS_PER_SOL,
+                    allow_undelegation: false,
+                    data: new_state,
+                    actions: vec![],
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    let tx = Transaction::new_signed_with_payer(
+        &commit_ixs,
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // Assert the older state was skipped without being queued
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 1);
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    let delegation_metadata = get_delegation_metadata(&banks).await;
+    assert_eq!(delegation_metadata.next_commit_nonce, 1);
+    assert_eq!(delegation_metadata.last_commit_slot, 100);
+
+    // Finalize the queued commit
+    finalize(&banks, &validator, 0, blockhash).await;
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, vec![1, 2, 3]);
+
+    let delegation_metadata = get_delegation_metadata(&banks).await;
+    assert_eq!(delegation_metadata.next_finalize_nonce, 1);
+    assert_eq!(delegation_metadata.last_update_external_slot, 100);
+}
+
+#[tokio::test]
+async fn test_pipelined_commit_deposits_against_last_queued_commit() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+    let new_states = [vec![1, 2, 3], vec![4, 5, 6, 7]];
+
+    // Commit two states, each increasing the lamports by one SOL
+    let commit_ixs = new_states
+        .iter()
+        .enumerate()
+        .map(|(nonce, new_state)| {
+            dlp::instruction_builder::commit_state(
+                validator.pubkey(),
+                DELEGATED_PDA_ID,
+                DELEGATED_PDA_OWNER_ID,
+                nonce as u64,
+                CommitStateArgs {
+                    slot: 100 + nonce as u64,
+                    lamports: (2 + nonce as u64) * LAMPORTS_PER_SOL,
+                    allow_undelegation: false,
+                    data: new_state.clone(),
+                    actions: vec![],
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    let tx = Transaction::new_signed_with_payer(
+        &commit_ixs,
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // Assert the newest commit only deposited the lamports added since the oldest one, which
+    // also cover the rent of its commit state account
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 1);
+    let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
+    assert_eq!(commit_state_account.lamports, LAMPORTS_PER_SOL);
+    let delegation_metadata = get_delegation_metadata(&banks).await;
+    assert_eq!(
+        delegation_metadata.last_commit_lamports,
+        3 * LAMPORTS_PER_SOL
+    );
+
+    // Finalize both commits, the deposits cover the lamports settled by each of them
+    finalize(&banks, &validator, 0, blockhash).await;
+    finalize(&banks, &validator, 1, blockhash).await;
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, new_states[1]);
+    assert!(delegated_account.lamports >= 3 * LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn test_outdated_commit_followed_by_lower_lamports_commit() {
+    // Setup two pending commits, the oldest one being outdated since a more recent commit was
+    // finalized before commits were queued in slot order
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let mut delegation_metadata = DelegationMetadata::try_from_bytes_with_discriminator(
+        &get_delegation_metadata_data(validator.pubkey(), None),
+    )
+    .unwrap();
+    delegation_metadata.last_update_external_slot = 200;
+    delegation_metadata.next_commit_nonce = 2;
+    let mut delegation_metadata_data = vec![];
+    delegation_metadata
+        .to_bytes_with_discriminator(&mut delegation_metadata_data)
+        .unwrap();
+    let mut program_test = program_test_env(&validator, delegation_metadata_data);
+
+    // The outdated commit adds one SOL, the next commit removes half of it and only deposited
+    // against the outdated commit
+    let record_lamports = Rent::default().minimum_balance(500);
+    let commits = [
+        (
+            100,
+            record_lamports + LAMPORTS_PER_SOL,
+            vec![1, 2, 3],
+            LAMPORTS_PER_SOL,
+        ),
+        (
+            300,
+            record_lamports + LAMPORTS_PER_SOL / 2,
+            vec![4, 5, 6, 7],
+            0,
+        ),
+    ];
+    for (nonce, (slot, lamports, new_state, deposit)) in commits.iter().enumerate() {
+        program_test.add_account(
+            commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, nonce as u64),
+            Account {
+                lamports: Rent::default().minimum_balance(new_state.len()) + deposit,
+                data: new_state.clone(),
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        let commit_record = CommitRecord {
+            identity: validator.pubkey(),
+            account: DELEGATED_PDA_ID,
+            slot: *slot,
+            lamports: *lamports,
+            kind: CommitKind::Full.into(),
+            bundle: Pubkey::default(),
+            data_hash: [0; 32],
+            commit_slot: 0,
+            challenge_period: 0,
+            disputer: Pubkey::default(),
+            actions_len: 0,
+            payer: validator.pubkey(),
+            validate_commit: 0,
+        };
+        let mut commit_record_data = vec![0u8; CommitRecord::size_with_discriminator()];
+        commit_record
+            .to_bytes_with_discriminator(&mut commit_record_data)
+            .unwrap();
+        program_test.add_account(
+            commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, nonce as u64),
+            Account {
+                lamports: Rent::default().minimum_balance(commit_record_data.len()),
+                data: commit_record_data,
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+    let (banks, _, blockhash) = program_test.start().await;
+
+    // Discard the outdated commit, whose lamports are still settled
+    finalize(&banks, &validator, 0, blockhash).await;
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert!(delegated_account.data.is_empty());
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let delegation_record_account = banks
+        .get_account(delegation_record_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_account.data)
+            .unwrap();
+    assert_eq!(delegation_record.lamports, delegated_account.lamports);
+
+    // Finalize the next commit, settled against the lamports of the discarded one
+    finalize(&banks, &validator, 1, blockhash).await;
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, vec![4, 5, 6, 7]);
+    assert_eq!(delegated_account.lamports, commits[1].1);
+
+    let delegation_metadata = get_delegation_metadata(&banks).await;
+    assert_eq!(delegation_metadata.next_finalize_nonce, 2);
+    assert_eq!(delegation_metadata.last_update_external_slot, 300);
+}
+
+async fn finalize(banks: &BanksClient, validator: &Keypair, commit_nonce: u64, blockhash: Hash) {
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        commit_nonce,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+}
+
+async fn get_delegation_metadata(banks: &BanksClient) -> DelegationMetadata {
+    let delegation_metadata_pda = delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let delegation_metadata_account = banks
+        .get_account(delegation_metadata_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+        .unwrap()
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Hash) {
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let delegation_metadata_data = get_delegation_metadata_data(validator_keypair.pubkey(), None);
+    let program_test = program_test_env(&validator_keypair, delegation_metadata_data);
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator_keypair, blockhash)
+}
+
+fn program_test_env(validator_keypair: &Keypair, delegation_metadata_data: Vec<u8>) -> ProgramTest {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    program_test
+}
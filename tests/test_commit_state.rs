@@ -1,17 +1,20 @@
 use dlp::args::CommitStateArgs;
+use dlp::error::DlpError;
 use dlp::pda::{
     commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
-    validator_fees_vault_pda_from_validator,
+    validator_bond_pda_from_validator, validator_fees_vault_pda_from_validator,
 };
 use dlp::state::{CommitRecord, DelegationMetadata};
+use solana_program::instruction::InstructionError;
+use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
 use solana_program_test::{processor, BanksClient, ProgramTest};
 use solana_sdk::{
     account::Account,
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 
 use crate::fixtures::{
@@ -24,7 +27,7 @@ mod fixtures;
 #[tokio::test]
 async fn test_commit_new_state() {
     // Setup
-    let (banks, _, authority, blockhash) = setup_program_test_env().await;
+    let (banks, _, authority, _, blockhash) = setup_program_test_env(None).await;
     let new_state = vec![0, 1, 2, 9, 9, 9, 6, 7, 8, 9];
 
     let new_account_balance = 1_000_000;
@@ -33,6 +36,7 @@ async fn test_commit_new_state() {
         slot: 100,
         allow_undelegation: true,
         lamports: new_account_balance,
+        actions: vec![],
     };
 
     // Commit the state for the delegated account
@@ -40,6 +44,7 @@ async fn test_commit_new_state() {
         authority.pubkey(),
         DELEGATED_PDA_ID,
         DELEGATED_PDA_OWNER_ID,
+        0,
         commit_args,
     );
     let tx = Transaction::new_signed_with_payer(
@@ -53,7 +58,7 @@ async fn test_commit_new_state() {
     assert!(res.is_ok());
 
     // Assert the state commitment was created and contains the new state
-    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
     let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
     assert_eq!(commit_state_account.data, new_state.clone());
 
@@ -62,7 +67,7 @@ async fn test_commit_new_state() {
     assert!(new_account_balance < commit_state_account.lamports + delegated_account.lamports);
 
     // Assert the record about the commitment exists
-    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
     let commit_record_account = banks.get_account(commit_record_pda).await.unwrap().unwrap();
     let commit_record =
         CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
@@ -82,11 +87,147 @@ async fn test_commit_new_state() {
     assert!(delegation_metadata.is_undelegatable);
 }
 
-async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
+#[tokio::test]
+async fn test_commit_new_state_without_validator_bond() {
+    // Setup
+    let (banks, _, authority, _, blockhash) = setup_program_test_env(None).await;
+    let new_state = vec![0, 1, 2, 9, 9, 9, 6, 7, 8, 9];
+
+    let commit_args = CommitStateArgs {
+        data: new_state.clone(),
+        slot: 100,
+        allow_undelegation: true,
+        lamports: 1_000_000,
+        actions: vec![],
+    };
+
+    // Commit the state with the accounts of a commit that predates validator bonds
+    let mut ix = dlp::instruction_builder::commit_state(
+        authority.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_args,
+    );
+    let validator_bond = ix.accounts.pop().unwrap();
+    assert_eq!(
+        validator_bond.pubkey,
+        validator_bond_pda_from_validator(&authority.pubkey())
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[&authority],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the state commitment was created and contains the new state
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
+    assert_eq!(commit_state_account.data, new_state);
+}
+
+#[tokio::test]
+async fn test_commit_new_state_foreign_validator_fails() {
+    // Setup
+    let (banks, _, _, foreign_validator, blockhash) = setup_program_test_env(None).await;
+
+    let commit_args = CommitStateArgs {
+        data: vec![0, 1, 2, 9, 9, 9, 6, 7, 8, 9],
+        slot: 100,
+        allow_undelegation: true,
+        lamports: 1_000_000,
+        actions: vec![],
+    };
+
+    // Commit the state with a whitelisted validator that is not the delegation authority
+    let ix = dlp::instruction_builder::commit_state(
+        foreign_validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_args,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&foreign_validator.pubkey()),
+        &[&foreign_validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidDelegationAuthority as u32)
+        )
+    );
+
+    // Assert no commitment was created
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_commit_new_state_any_validator_with_default_authority() {
+    // Setup
+    let (banks, _, _, foreign_validator, blockhash) =
+        setup_program_test_env(Some(Pubkey::default())).await;
+
+    let new_state = vec![0, 1, 2, 9, 9, 9, 6, 7, 8, 9];
+    let commit_args = CommitStateArgs {
+        data: new_state.clone(),
+        slot: 100,
+        allow_undelegation: true,
+        lamports: 1_000_000,
+        actions: vec![],
+    };
+
+    // Any whitelisted validator can commit when the authority is the default pubkey
+    let ix = dlp::instruction_builder::commit_state(
+        foreign_validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_args,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&foreign_validator.pubkey()),
+        &[&foreign_validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // Assert the commitment was created by the foreign validator
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
+    assert_eq!(commit_state_account.data, new_state);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_account = banks.get_account(commit_record_pda).await.unwrap().unwrap();
+    let commit_record =
+        CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
+    assert_eq!(commit_record.identity, foreign_validator.pubkey());
+}
+
+async fn setup_program_test_env(
+    delegation_authority: Option<Pubkey>,
+) -> (BanksClient, Keypair, Keypair, Keypair, Hash) {
     let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
     program_test.prefer_bpf(true);
 
     let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let foreign_validator_keypair = Keypair::new();
 
     program_test.add_account(
         validator_keypair.pubkey(),
@@ -99,6 +240,28 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
         },
     );
 
+    // Setup a second whitelisted validator that is not the delegation authority
+    program_test.add_account(
+        foreign_validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&foreign_validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
     // Setup a delegated PDA
     program_test.add_account(
         DELEGATED_PDA_ID,
@@ -125,7 +288,10 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     );
 
     // Setup the delegated record PDA
-    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    let delegation_record_data = get_delegation_record_data(
+        delegation_authority.unwrap_or(validator_keypair.pubkey()),
+        None,
+    );
     program_test.add_account(
         delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
         Account {
@@ -150,5 +316,11 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     );
 
     let (banks, payer, blockhash) = program_test.start().await;
-    (banks, payer, validator_keypair, blockhash)
+    (
+        banks,
+        payer,
+        validator_keypair,
+        foreign_validator_keypair,
+        blockhash,
+    )
 }
@@ -0,0 +1,265 @@
+use dlp::args::{CommitStateArgs, CommitStateDiffArgs, StatePatch};
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{CommitKind, CommitRecord};
+use solana_program::instruction::InstructionError;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+#[tokio::test]
+async fn test_commit_state_diff_and_finalize() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+    let patches = vec![
+        StatePatch {
+            offset: 4,
+            data: vec![1, 2, 3],
+        },
+        StatePatch {
+            offset: 17,
+            data: vec![8, 9],
+        },
+    ];
+
+    // Submit the commit state diff tx
+    let ix = dlp::instruction_builder::commit_state_diff(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateDiffArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            patches,
+        },
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the commit record is a diff commit
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_account = banks.get_account(commit_record_pda).await.unwrap().unwrap();
+    let commit_record =
+        CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
+    assert_eq!(commit_record.kind, u64::from(CommitKind::Diff));
+
+    // Assert the delegated account is not modified before finalize
+    let pda_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(pda_account.data, DELEGATED_PDA.to_vec());
+
+    // Submit the finalize tx
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the commit PDAs were closed
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Assert the patches were applied on top of the delegated account data
+    let mut expected_data = DELEGATED_PDA.to_vec();
+    expected_data[4..7].copy_from_slice(&[1, 2, 3]);
+    expected_data[17..19].copy_from_slice(&[8, 9]);
+    let pda_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(pda_account.data, expected_data);
+}
+
+#[tokio::test]
+async fn test_commit_state_diff_out_of_range_fails() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Submit the commit state diff tx with a patch past the end of the account data
+    let ix = dlp::instruction_builder::commit_state_diff(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateDiffArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            patches: vec![StatePatch {
+                offset: DELEGATED_PDA.len() as u32 - 1,
+                data: vec![1, 2],
+            }],
+        },
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidStatePatch as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_commit_state_diff_behind_pending_commit_fails() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Commit a full state, which changes the data range, then patches on top of it
+    let ix_commit = dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data: vec![1, 2, 3],
+            actions: vec![],
+        },
+    );
+    let ix_commit_diff = dlp::instruction_builder::commit_state_diff(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        1,
+        CommitStateDiffArgs {
+            slot: 101,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            patches: vec![StatePatch {
+                offset: 4,
+                data: vec![1, 2],
+            }],
+        },
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix_commit, ix_commit_diff],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(DlpError::PendingCommits as u32)
+        )
+    );
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+
+    program_test.add_account(
+        validator.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA holding its last finalized state
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: DELEGATED_PDA.into(),
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data(validator.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator, blockhash)
+}
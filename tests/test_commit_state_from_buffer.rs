@@ -42,6 +42,7 @@ async fn test_commit_new_state_from_buffer() {
         DELEGATED_PDA_ID,
         DELEGATED_PDA_OWNER_ID,
         state_buffer_pda,
+        0,
         commit_args,
     );
     let tx = Transaction::new_signed_with_payer(
@@ -55,7 +56,7 @@ async fn test_commit_new_state_from_buffer() {
     assert!(res.is_ok());
 
     // Assert the state commitment was created and contains the new state
-    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
     let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
     assert_eq!(commit_state_account.data, NEW_STATE.to_vec());
 
@@ -64,7 +65,7 @@ async fn test_commit_new_state_from_buffer() {
     assert!(new_account_balance < commit_state_account.lamports + delegated_account.lamports);
 
     // Assert the record about the commitment exists
-    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
     let commit_record_account = banks.get_account(commit_record_pda).await.unwrap().unwrap();
     let commit_record =
         CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
@@ -0,0 +1,258 @@
+use dlp::args::{CommitStateHashArgs, FinalizeWithDataArgs};
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{CommitKind, CommitRecord};
+use solana_program::hash::hash;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+const NEW_STATE: [u8; 6] = [9, 8, 7, 6, 5, 4];
+const STATE_BUFFER_ID: Pubkey = pubkey!("4Vrn1pmuhC8s1mVTGNpbYBvhnUzLkQTtMTKSpBvQmkaz");
+
+#[tokio::test]
+async fn test_commit_state_hash_and_finalize_with_data() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+    commit_state_hash(&banks, &validator, blockhash).await;
+
+    // Assert only the hash of the new state is stored
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
+    assert!(commit_state_account.data.is_empty());
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_account = banks.get_account(commit_record_pda).await.unwrap().unwrap();
+    let commit_record =
+        CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
+    assert_eq!(commit_record.kind, u64::from(CommitKind::Hash));
+    assert_eq!(commit_record.data_hash, hash(&NEW_STATE).to_bytes());
+
+    // Finalize with the new state
+    let ix = dlp::instruction_builder::finalize_with_data(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        FinalizeWithDataArgs {
+            data: NEW_STATE.to_vec(),
+        },
+    );
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the new state was applied and the commit PDAs were closed
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, NEW_STATE.to_vec());
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_commit_state_hash_and_finalize_from_buffer() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+    commit_state_hash(&banks, &validator, blockhash).await;
+
+    // Finalize with the new state stored in a buffer
+    let ix = dlp::instruction_builder::finalize_from_buffer(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        STATE_BUFFER_ID,
+    );
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the new state was applied
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, NEW_STATE.to_vec());
+}
+
+#[tokio::test]
+async fn test_finalize_hash_commit_with_invalid_data_fails() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+    commit_state_hash(&banks, &validator, blockhash).await;
+
+    // Finalize with a state that does not match the hash
+    let ix = dlp::instruction_builder::finalize_with_data(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        FinalizeWithDataArgs {
+            data: vec![1, 2, 3],
+        },
+    );
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidCommitHash as u32)
+        )
+    );
+
+    // Finalize without the state
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let res = process_instruction(&banks, &validator, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidCommitHash as u32)
+        )
+    );
+
+    // Assert the commit is still pending
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+async fn commit_state_hash(banks: &BanksClient, validator: &Keypair, blockhash: Hash) {
+    let ix = dlp::instruction_builder::commit_state_hash(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateHashArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data_hash: hash(&NEW_STATE).to_bytes(),
+        },
+    );
+    let res = process_instruction(banks, validator, ix, blockhash).await;
+    assert!(res.is_ok());
+}
+
+async fn process_instruction(
+    banks: &BanksClient,
+    validator: &Keypair,
+    ix: Instruction,
+    blockhash: Hash,
+) -> Result<(), solana_program_test::BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[validator],
+        blockhash,
+    );
+    banks.process_transaction(tx).await
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a buffer holding the committed state
+    program_test.add_account(
+        STATE_BUFFER_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: NEW_STATE.to_vec(),
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator_keypair, blockhash)
+}
@@ -0,0 +1,353 @@
+use dlp::args::CommitStateArgs;
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::CommitRecord;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::rent::Rent;
+use solana_program::{ed25519_program, hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+#[tokio::test]
+async fn test_commit_state_relayed() {
+    // Setup
+    let (banks, validator, relayer, blockhash) = setup_program_test_env().await;
+    let validator_balance_before = banks.get_balance(validator.pubkey()).await.unwrap();
+
+    // Submit the commit signed by the validator through the relayer
+    let commit_args = commit_args();
+    let ed25519_ix = ed25519_instruction(
+        &validator,
+        &commit_args.relayed_commit_message(&DELEGATED_PDA_ID, 0),
+    );
+    let ix = dlp::instruction_builder::commit_state_relayed(
+        relayer.pubkey(),
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_args,
+    );
+    let res = process_instructions(&banks, &relayer, &[ed25519_ix, ix], blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the commit was recorded for the validator, without the validator paying for it
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
+    assert_eq!(commit_state_account.data, vec![1, 2, 3]);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_account = banks.get_account(commit_record_pda).await.unwrap().unwrap();
+    let commit_record =
+        CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
+    assert_eq!(commit_record.identity, validator.pubkey());
+    assert_eq!(
+        banks.get_balance(validator.pubkey()).await.unwrap(),
+        validator_balance_before
+    );
+
+    // The commit PDAs cannot be refunded to the validator on finalize
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let res = process_instructions(&banks, &validator, &[ix], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidReimbursementAccount as u32)
+        )
+    );
+
+    // Nor can they without the commit payer, which is the validator by default
+    let mut ix = dlp::instruction_builder::finalize_with_commit_payer(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        relayer.pubkey(),
+    );
+    ix.accounts.truncate(8);
+    let res = process_instructions(&banks, &validator, &[ix], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidReimbursementAccount as u32)
+        )
+    );
+
+    // Finalize, refunding the relayer the commit PDAs, whose deposit is entirely settled with
+    // the delegated account
+    let relayer_balance_before = banks.get_balance(relayer.pubkey()).await.unwrap();
+    let ix = dlp::instruction_builder::finalize_with_commit_payer(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        relayer.pubkey(),
+    );
+    let res = process_instructions(&banks, &validator, &[ix], blockhash).await;
+    assert!(res.is_ok());
+    assert_eq!(
+        banks.get_balance(relayer.pubkey()).await.unwrap(),
+        relayer_balance_before + commit_record_account.lamports
+    );
+}
+
+#[tokio::test]
+async fn test_commit_state_relayed_replay_fails() {
+    // Setup
+    let (banks, validator, relayer, blockhash) = setup_program_test_env().await;
+
+    // Submit the first commit signed by the validator
+    let ed25519_ix = ed25519_instruction(
+        &validator,
+        &commit_args().relayed_commit_message(&DELEGATED_PDA_ID, 0),
+    );
+    let ix = dlp::instruction_builder::commit_state_relayed(
+        relayer.pubkey(),
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_args(),
+    );
+    let res = process_instructions(&banks, &relayer, &[ed25519_ix.clone(), ix], blockhash).await;
+    assert!(res.is_ok());
+
+    // The relayer replays the signature for the next commit
+    let ix = dlp::instruction_builder::commit_state_relayed(
+        relayer.pubkey(),
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        1,
+        commit_args(),
+    );
+    let res = process_instructions(&banks, &relayer, &[ed25519_ix, ix], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(DlpError::InvalidEd25519Signature as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_commit_state_relayed_with_altered_args_fails() {
+    // Setup
+    let (banks, validator, relayer, blockhash) = setup_program_test_env().await;
+
+    // The relayer alters the committed lamports
+    let ed25519_ix = ed25519_instruction(
+        &validator,
+        &commit_args().relayed_commit_message(&DELEGATED_PDA_ID, 0),
+    );
+    let ix = dlp::instruction_builder::commit_state_relayed(
+        relayer.pubkey(),
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateArgs {
+            lamports: 2 * LAMPORTS_PER_SOL,
+            ..commit_args()
+        },
+    );
+    let res = process_instructions(&banks, &relayer, &[ed25519_ix, ix], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(DlpError::InvalidEd25519Signature as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_commit_state_relayed_without_validator_signature_fails() {
+    // Setup
+    let (banks, validator, relayer, blockhash) = setup_program_test_env().await;
+
+    // The commit is signed by the relayer instead of the validator
+    let ed25519_ix = ed25519_instruction(
+        &relayer,
+        &commit_args().relayed_commit_message(&DELEGATED_PDA_ID, 0),
+    );
+    let ix = dlp::instruction_builder::commit_state_relayed(
+        relayer.pubkey(),
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_args(),
+    );
+    let res = process_instructions(&banks, &relayer, &[ed25519_ix, ix.clone()], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(DlpError::InvalidEd25519Signature as u32)
+        )
+    );
+
+    // The commit is not signed at all
+    let res = process_instructions(&banks, &relayer, &[ix], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidEd25519Signature as u32)
+        )
+    );
+}
+
+fn commit_args() -> CommitStateArgs {
+    CommitStateArgs {
+        data: vec![1, 2, 3],
+        slot: 100,
+        allow_undelegation: false,
+        lamports: LAMPORTS_PER_SOL,
+        actions: vec![],
+    }
+}
+
+/// Builds an Ed25519 instruction verifying the signature of the message by the signer
+fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    let signature = signer.sign_message(message);
+    let public_key_offset: u16 = 16;
+    let signature_offset = public_key_offset + 32;
+    let message_data_offset = signature_offset + 64;
+    let mut data = vec![1, 0];
+    for value in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_data_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+async fn process_instructions(
+    banks: &BanksClient,
+    payer: &Keypair,
+    ixs: &[Instruction],
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(ixs, Some(&payer.pubkey()), &[payer], blockhash);
+    banks.process_transaction(tx).await
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let relayer_keypair = Keypair::new();
+
+    program_test.add_account(
+        relayer_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator_keypair, relayer_keypair, blockhash)
+}
@@ -5,7 +5,7 @@ use dlp::pda::{
     program_config_from_program_id, validator_fees_vault_pda_from_validator,
 };
 use dlp::state::{CommitRecord, DelegationMetadata};
-use fixtures::create_program_config_data;
+use fixtures::{create_legacy_program_config_data, create_program_config_data};
 use solana_program::rent::Rent;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
 use solana_program_test::{processor, BanksClient, ProgramTest};
@@ -24,17 +24,23 @@ mod fixtures;
 
 #[tokio::test]
 async fn test_commit_new_state_valid_config() {
-    test_commit_new_state(true).await
+    test_commit_new_state(true, false).await
 }
 
 #[tokio::test]
 async fn test_commit_new_state_invalid_config() {
-    test_commit_new_state(false).await
+    test_commit_new_state(false, false).await
 }
 
-async fn test_commit_new_state(valid_config: bool) {
+#[tokio::test]
+async fn test_commit_new_state_valid_legacy_config() {
+    test_commit_new_state(true, true).await
+}
+
+async fn test_commit_new_state(valid_config: bool, legacy_config: bool) {
     // Setup
-    let (banks, _, authority, blockhash) = setup_program_test_env(valid_config).await;
+    let (banks, _, authority, blockhash) =
+        setup_program_test_env(valid_config, legacy_config).await;
     let new_state = vec![0, 1, 2, 9, 9, 9, 6, 7, 8, 9];
 
     let new_account_balance = 1_000_000;
@@ -43,6 +49,7 @@ async fn test_commit_new_state(valid_config: bool) {
         slot: 100,
         allow_undelegation: true,
         lamports: new_account_balance,
+        actions: vec![],
     };
 
     // Commit the state for the delegated account
@@ -50,6 +57,7 @@ async fn test_commit_new_state(valid_config: bool) {
         authority.pubkey(),
         DELEGATED_PDA_ID,
         DELEGATED_PDA_OWNER_ID,
+        0,
         commit_args,
     );
     let tx = Transaction::new_signed_with_payer(
@@ -66,7 +74,7 @@ async fn test_commit_new_state(valid_config: bool) {
         assert!(res.is_ok());
 
         // Assert the state commitment was created and contains the new state
-        let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID);
+        let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
         let commit_state_account = banks.get_account(commit_state_pda).await.unwrap().unwrap();
         assert_eq!(commit_state_account.data, new_state.clone());
 
@@ -75,7 +83,7 @@ async fn test_commit_new_state(valid_config: bool) {
         assert!(new_account_balance < commit_state_account.lamports + delegated_account.lamports);
 
         // Assert the record about the commitment exists
-        let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
+        let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
         let commit_record_account = banks.get_account(commit_record_pda).await.unwrap().unwrap();
         let commit_record =
             CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap();
@@ -98,7 +106,10 @@ async fn test_commit_new_state(valid_config: bool) {
     }
 }
 
-async fn setup_program_test_env(valid_config: bool) -> (BanksClient, Keypair, Keypair, Hash) {
+async fn setup_program_test_env(
+    valid_config: bool,
+    legacy_config: bool,
+) -> (BanksClient, Keypair, Keypair, Hash) {
     let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
     program_test.prefer_bpf(true);
 
@@ -165,12 +176,17 @@ async fn setup_program_test_env(valid_config: bool) -> (BanksClient, Keypair, Ke
         },
     );
 
-    // Setup the program config
-    let program_config_data = create_program_config_data(if valid_config {
+    // Setup the program config, possibly in its legacy layout
+    let approved_validator = if valid_config {
         validator_keypair.pubkey()
     } else {
         Keypair::new().pubkey()
-    });
+    };
+    let program_config_data = if legacy_config {
+        create_legacy_program_config_data(approved_validator)
+    } else {
+        create_program_config_data(approved_validator)
+    };
     program_test.add_account(
         program_config_from_program_id(&DELEGATED_PDA_OWNER_ID),
         Account {
@@ -0,0 +1,338 @@
+use borsh::BorshDeserialize;
+use dlp::args::{
+    CommitStateArgs, CommitStateFromBufferArgs, ExternalCommittedState, ExternalValidateCommitArgs,
+};
+use dlp::consts::EXTERNAL_VALIDATE_COMMIT_DISCRIMINATOR;
+use dlp::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    program_config_from_program_id, validator_fees_vault_pda_from_validator,
+};
+use dlp::state::CommitKind;
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    create_program_config_data_with_commit_validation, get_delegation_metadata_data,
+    get_delegation_record_data, DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+/// Error returned by the owner program when rejecting a commit
+const INVALID_COMMIT_ERROR: u32 = 42;
+
+/// A committed state too large to be copied in the instruction data of the validation CPI
+const LARGE_STATE_LEN: usize = 9_000;
+
+/// Owner program validating that the committed balance, the first byte of the account data,
+/// never goes negative
+fn process_validate_commit(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (discriminator, data) = data.split_at(8);
+    assert_eq!(discriminator, EXTERNAL_VALIDATE_COMMIT_DISCRIMINATOR);
+    let args = ExternalValidateCommitArgs::try_from_slice(data)?;
+    assert_eq!(args.kind, u64::from(CommitKind::Full));
+    let delegated_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    assert_eq!(delegated_account.key, &DELEGATED_PDA_ID);
+    let old_data = delegated_account.try_borrow_data()?;
+    assert!(old_data.is_empty() || old_data[0] as i8 >= 0);
+    let new_data = match args.state {
+        ExternalCommittedState::Account { len } => {
+            let [_, state_account] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            assert_eq!(state_account.owner, &dlp::id());
+            state_account.try_borrow_data()?[..len as usize].to_vec()
+        }
+        ExternalCommittedState::Inline(new_data) => new_data,
+    };
+    if (new_data[0] as i8) < 0 {
+        return Err(ProgramError::Custom(INVALID_COMMIT_ERROR));
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_finalize_commit_validated_by_owner_program() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Commit and finalize a valid state
+    let res = commit_and_finalize_new_state(&banks, &validator, blockhash, 0, vec![10, 1]).await;
+    assert!(res.is_ok());
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, vec![10, 1]);
+
+    // Commit a state with a negative balance, rejected by the owner program on finalize
+    let res =
+        commit_and_finalize_new_state(&banks, &validator, blockhash, 1, vec![-5i8 as u8, 2]).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(1, InstructionError::Custom(INVALID_COMMIT_ERROR))
+    );
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, vec![10, 1]);
+}
+
+#[tokio::test]
+async fn test_finalize_commit_without_owner_program_accounts_fails() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Commit a state with a negative balance, then finalize it without the owner program accounts
+    let ix_commit = dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data: vec![-5i8 as u8, 2],
+            actions: vec![],
+        },
+    );
+    let mut ix_finalize = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    ix_finalize.accounts.truncate(8);
+    let res = process_instructions(&banks, &validator, &[ix_commit, ix_finalize], blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(1, InstructionError::NotEnoughAccountKeys)
+    );
+
+    // Assert the state was not applied
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_ne!(delegated_account.data, vec![-5i8 as u8, 2]);
+}
+
+#[tokio::test]
+async fn test_finalize_large_commit_validated_by_owner_program() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Commit a large state from a buffer, then finalize it
+    let ix_commit = dlp::instruction_builder::commit_state_from_buffer(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        state_buffer_pda(&validator.pubkey()),
+        0,
+        CommitStateFromBufferArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+        },
+    );
+    let res = process_instructions(&banks, &validator, &[ix_commit], blockhash).await;
+    assert!(res.is_ok());
+    let ix_finalize = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let res = process_instructions(&banks, &validator, &[ix_finalize], blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the large state was applied
+    let delegated_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(delegated_account.data, vec![1; LARGE_STATE_LEN]);
+}
+
+#[tokio::test]
+async fn test_commit_and_finalize_validated_by_owner_program() {
+    // Setup
+    let (banks, validator, blockhash) = setup_program_test_env().await;
+
+    // Commit and finalize a state with a negative balance at once
+    let ix = dlp::instruction_builder::commit_and_finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        CommitStateArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data: vec![-5i8 as u8, 2],
+            actions: vec![],
+        },
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(INVALID_COMMIT_ERROR))
+    );
+}
+
+async fn commit_and_finalize_new_state(
+    banks: &BanksClient,
+    validator: &Keypair,
+    blockhash: Hash,
+    commit_nonce: u64,
+    new_state: Vec<u8>,
+) -> Result<(), BanksClientError> {
+    let ix_commit = dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        commit_nonce,
+        CommitStateArgs {
+            slot: 100 + commit_nonce,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data: new_state,
+            actions: vec![],
+        },
+    );
+    let ix_finalize = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        commit_nonce,
+    );
+    process_instructions(banks, validator, &[ix_commit, ix_finalize], blockhash).await
+}
+
+fn state_buffer_pda(validator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"state_buffer"], validator).0
+}
+
+async fn process_instructions(
+    banks: &BanksClient,
+    validator: &Keypair,
+    ixs: &[Instruction],
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    let tx =
+        Transaction::new_signed_with_payer(ixs, Some(&validator.pubkey()), &[validator], blockhash);
+    banks.process_transaction(tx).await
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a buffer holding a large state to commit
+    program_test.add_account(
+        state_buffer_pda(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![1; LARGE_STATE_LEN],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the program config, requiring the owner program to validate the commits
+    let program_config_data =
+        create_program_config_data_with_commit_validation(validator_keypair.pubkey());
+    program_test.add_account(
+        program_config_from_program_id(&DELEGATED_PDA_OWNER_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(program_config_data.len()),
+            data: program_config_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the owner program, validating the commits
+    program_test.add_program(
+        "owner_program",
+        DELEGATED_PDA_OWNER_ID,
+        processor!(process_validate_commit),
+    );
+
+    let (banks, _, blockhash) = program_test.start().await;
+    (banks, validator_keypair, blockhash)
+}
@@ -0,0 +1,141 @@
+use dlp::args::DelegateArgs;
+use dlp::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+};
+use dlp::state::{DelegationMetadata, DelegationRecord};
+use solana_program::instruction::InstructionError;
+use solana_program::{native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+#[tokio::test]
+async fn test_delegate_many() {
+    // Setup
+    let (banks, payer, delegated_accounts, blockhash) = setup_program_test_env().await;
+
+    // Submit the delegate many tx, each account with its own validator
+    let validators: Vec<_> = delegated_accounts
+        .iter()
+        .map(|_| Keypair::new().pubkey())
+        .collect();
+    let ix = dlp::instruction_builder::delegate_many(
+        payer.pubkey(),
+        None,
+        delegated_accounts
+            .iter()
+            .zip(validators.iter())
+            .map(|(delegated_account, validator)| {
+                (
+                    delegated_account.pubkey(),
+                    DelegateArgs {
+                        commit_frequency_ms: u32::MAX,
+                        seeds: vec![],
+                        validator: Some(*validator),
+                        expiry: None,
+                    },
+                )
+            })
+            .collect(),
+    );
+    let mut signers = vec![&payer];
+    signers.extend(delegated_accounts.iter());
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &signers, blockhash);
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    for (delegated_account, validator) in delegated_accounts.iter().zip(validators.iter()) {
+        // Assert that the delegation record exists and can be parsed
+        let delegation_record_account = banks
+            .get_account(delegation_record_pda_from_delegated_account(
+                &delegated_account.pubkey(),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        let delegation_record =
+            DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_account.data)
+                .unwrap();
+        assert_eq!(delegation_record.owner, system_program::id());
+        assert_eq!(delegation_record.authority, *validator);
+
+        // Assert that the delegation metadata exists and can be parsed
+        let delegation_metadata_account = banks
+            .get_account(delegation_metadata_pda_from_delegated_account(
+                &delegated_account.pubkey(),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        let delegation_metadata = DelegationMetadata::try_from_bytes_with_discriminator(
+            &delegation_metadata_account.data,
+        )
+        .unwrap();
+        assert_eq!(delegation_metadata.rent_payer, payer.pubkey());
+        assert!(!delegation_metadata.is_undelegatable);
+    }
+}
+
+#[tokio::test]
+async fn test_delegate_many_with_missing_accounts_fails() {
+    // Setup
+    let (banks, payer, delegated_accounts, blockhash) = setup_program_test_env().await;
+
+    // Submit the delegate many tx, without the accounts of the last delegation
+    let mut ix = dlp::instruction_builder::delegate_many(
+        payer.pubkey(),
+        None,
+        delegated_accounts
+            .iter()
+            .map(|delegated_account| (delegated_account.pubkey(), DelegateArgs::default()))
+            .collect(),
+    );
+    ix.accounts.truncate(ix.accounts.len() - 4);
+    let mut signers = vec![&payer];
+    signers.extend(delegated_accounts.iter().take(delegated_accounts.len() - 1));
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &signers, blockhash);
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+
+    // Assert none of the accounts were delegated
+    for delegated_account in delegated_accounts.iter() {
+        assert!(banks
+            .get_account(delegation_record_pda_from_delegated_account(
+                &delegated_account.pubkey(),
+            ))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Vec<Keypair>, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    // Setup on curve accounts already assigned to the delegation program
+    let delegated_accounts: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for delegated_account in delegated_accounts.iter() {
+        program_test.add_account(
+            delegated_account.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: vec![],
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    let (banks, payer, blockhash) = program_test.start().await;
+    (banks, payer, delegated_accounts, blockhash)
+}
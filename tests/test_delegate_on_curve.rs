@@ -12,7 +12,7 @@ use dlp::pda::{
     delegate_buffer_pda_from_delegated_account_and_owner_program,
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
 };
-use dlp::state::{DelegationMetadata, DelegationRecord};
+use dlp::state::{DelegationExpiry, DelegationMetadata, DelegationRecord};
 
 mod fixtures;
 
@@ -56,6 +56,7 @@ async fn test_delegate_on_curve() {
             commit_frequency_ms: u32::MAX,
             seeds: vec![],
             validator: Some(alt_payer.pubkey()),
+            expiry: Some(DelegationExpiry::Slot(1_000)),
         },
     );
 
@@ -118,6 +119,10 @@ async fn test_delegate_on_curve() {
     let delegation_metadata =
         DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata.data).unwrap();
     assert!(!delegation_metadata.is_undelegatable);
+    assert_eq!(
+        delegation_metadata.expiry,
+        Some(DelegationExpiry::Slot(1_000))
+    );
 }
 
 async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
@@ -0,0 +1,718 @@
+use dlp::args::CommitStateArgs;
+use dlp::consts::{DISPUTE_BOND_LAMPORTS, DISPUTE_RESOLUTION_PERIOD_SLOTS};
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    fees_vault_pda, program_config_from_program_id, validator_bond_pda_from_validator,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{CommitRecord, DelegationMetadata, ProtocolFeesVault, ValidatorBond};
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{
+    processor, read_file, BanksClientError, ProgramTest, ProgramTestContext,
+};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    add_delegation_program_data, create_program_config_data_with_challenge_period,
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+const CHALLENGE_PERIOD: u64 = 10;
+const NEW_STATE: [u8; 4] = [1, 2, 3, 4];
+const VALIDATOR_BOND: u64 = LAMPORTS_PER_SOL;
+
+#[tokio::test]
+async fn test_finalize_after_challenge_period() {
+    // Setup
+    let (mut context, validator, _) = setup_program_test_env().await;
+    commit_state(&mut context, &validator).await;
+
+    // Finalize during the challenge period
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::ChallengePeriodNotElapsed as u32)
+        )
+    );
+
+    // Finalize once the challenge period has elapsed
+    let commit_record = get_commit_record(&mut context).await;
+    context
+        .warp_to_slot(commit_record.challenge_period_end())
+        .unwrap();
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+
+    // Assert the new state was applied
+    let delegated_account = context
+        .banks_client
+        .get_account(DELEGATED_PDA_ID)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(delegated_account.data, NEW_STATE.to_vec());
+}
+
+#[tokio::test]
+async fn test_disputed_commit_cannot_be_finalized() {
+    // Setup
+    let (mut context, validator, _) = setup_program_test_env().await;
+    commit_state(&mut context, &validator).await;
+
+    // Dispute the commit
+    let challenger = context.payer.insecure_clone();
+    let ix = dlp::instruction_builder::dispute_commit(challenger.pubkey(), DELEGATED_PDA_ID, 0);
+    let res = process_instruction(&mut context, &challenger, ix).await;
+    assert!(res.is_ok());
+
+    // Assert the commit is flagged and the challenger bonded
+    let commit_record = get_commit_record(&mut context).await;
+    assert!(commit_record.is_disputed());
+    assert_eq!(commit_record.disputer, challenger.pubkey());
+    let commit_record_account = get_account(
+        &mut context,
+        commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+    )
+    .await;
+    assert_eq!(
+        commit_record_account.lamports,
+        Rent::default().minimum_balance(commit_record_account.data.len()) + DISPUTE_BOND_LAMPORTS
+    );
+    assert!(get_delegation_metadata(&mut context).await.dispute_slot > 0);
+
+    // Finalize once the challenge period has elapsed
+    context
+        .warp_to_slot(commit_record.challenge_period_end())
+        .unwrap();
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::CommitDisputed as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_dispute_commit_after_challenge_period_fails() {
+    // Setup
+    let (mut context, validator, _) = setup_program_test_env().await;
+    commit_state(&mut context, &validator).await;
+
+    // Dispute the commit once the challenge period has elapsed
+    let commit_record = get_commit_record(&mut context).await;
+    context
+        .warp_to_slot(commit_record.challenge_period_end())
+        .unwrap();
+    let challenger = context.payer.insecure_clone();
+    let ix = dlp::instruction_builder::dispute_commit(challenger.pubkey(), DELEGATED_PDA_ID, 0);
+    let res = process_instruction(&mut context, &challenger, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::ChallengePeriodElapsed as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_rejected_dispute_pays_bond_to_validator() {
+    // Setup
+    let (mut context, validator, admin) = setup_program_test_env().await;
+    commit_state(&mut context, &validator).await;
+    let challenger = dispute_commit(&mut context).await;
+
+    // Anyone but the admin cannot resolve the dispute
+    let ix = dlp::instruction_builder::resolve_dispute(
+        challenger.pubkey(),
+        DELEGATED_PDA_ID,
+        challenger.pubkey(),
+        validator.pubkey(),
+        0,
+        false,
+    );
+    let res = process_instruction(&mut context, &challenger, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+
+    // Reject the dispute
+    let validator_lamports = get_account(&mut context, validator.pubkey()).await.lamports;
+    let ix = dlp::instruction_builder::resolve_dispute(
+        admin.pubkey(),
+        DELEGATED_PDA_ID,
+        challenger.pubkey(),
+        validator.pubkey(),
+        0,
+        false,
+    );
+    let res = process_instruction(&mut context, &admin, ix).await;
+    assert!(res.is_ok());
+
+    // Assert the validator received the bond and the dispute was cleared
+    let validator_account = get_account(&mut context, validator.pubkey()).await;
+    assert_eq!(
+        validator_account.lamports,
+        validator_lamports + DISPUTE_BOND_LAMPORTS
+    );
+    let commit_record = get_commit_record(&mut context).await;
+    assert!(!commit_record.is_disputed());
+    let delegation_metadata = get_delegation_metadata(&mut context).await;
+    assert_eq!(delegation_metadata.dispute_slot, 0);
+    assert!(!delegation_metadata.has_upheld_dispute);
+
+    // Finalize once the challenge period has elapsed
+    context
+        .warp_to_slot(commit_record.challenge_period_end())
+        .unwrap();
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+}
+
+#[tokio::test]
+async fn test_upheld_dispute_allows_force_undelegate() {
+    // Setup
+    let (mut context, validator, admin) = setup_program_test_env().await;
+    commit_state(&mut context, &validator).await;
+    let challenger = dispute_commit(&mut context).await;
+
+    // The pending commit cannot be discarded while the dispute is pending
+    let ix = dlp::instruction_builder::force_undelegate(
+        challenger.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        validator.pubkey(),
+        0,
+        &[validator.pubkey()],
+    );
+    let res = process_instruction(&mut context, &challenger, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::DelegationNotExpired as u32)
+        )
+    );
+
+    // Uphold the dispute
+    let challenger_lamports = get_account(&mut context, challenger.pubkey())
+        .await
+        .lamports;
+    let ix = dlp::instruction_builder::resolve_dispute(
+        admin.pubkey(),
+        DELEGATED_PDA_ID,
+        challenger.pubkey(),
+        validator.pubkey(),
+        0,
+        true,
+    );
+    let res = process_instruction(&mut context, &admin, ix).await;
+    assert!(res.is_ok());
+
+    // Assert the challenger was refunded and the commit stays disputed
+    let challenger_account = get_account(&mut context, challenger.pubkey()).await;
+    assert_eq!(
+        challenger_account.lamports,
+        challenger_lamports + DISPUTE_BOND_LAMPORTS
+    );
+    assert!(get_commit_record(&mut context).await.is_disputed());
+    let delegation_metadata = get_delegation_metadata(&mut context).await;
+    assert_eq!(delegation_metadata.dispute_slot, 0);
+    assert!(delegation_metadata.has_upheld_dispute);
+
+    // New commits are rejected
+    let ix = dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        1,
+        commit_args(),
+    );
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::CommitDisputed as u32)
+        )
+    );
+
+    // Anyone can force the undelegation, which discards the disputed commit
+    let ix = dlp::instruction_builder::force_undelegate(
+        challenger.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        validator.pubkey(),
+        0,
+        &[validator.pubkey()],
+    );
+    let res = process_instruction(&mut context, &challenger, ix).await;
+    assert!(res.is_ok());
+
+    // Assert the disputed commit was discarded and the account restored to its last state
+    let banks = &mut context.banks_client;
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    let delegated_account = get_account(&mut context, DELEGATED_PDA_ID).await;
+    assert_eq!(delegated_account.owner, DELEGATED_PDA_OWNER_ID);
+    assert!(delegated_account.data.is_empty());
+}
+
+#[tokio::test]
+async fn test_dispute_rejected_by_anyone_after_resolution_period() {
+    // Setup
+    let (mut context, validator, _) = setup_program_test_env().await;
+    commit_state(&mut context, &validator).await;
+    let challenger = dispute_commit(&mut context).await;
+
+    // Anyone can reject the dispute once the admin did not resolve it in time, but not uphold it
+    let dispute_slot = get_delegation_metadata(&mut context).await.dispute_slot;
+    context
+        .warp_to_slot(dispute_slot + DISPUTE_RESOLUTION_PERIOD_SLOTS)
+        .unwrap();
+    let ix = dlp::instruction_builder::resolve_dispute(
+        challenger.pubkey(),
+        DELEGATED_PDA_ID,
+        challenger.pubkey(),
+        validator.pubkey(),
+        0,
+        true,
+    );
+    let res = process_instruction(&mut context, &challenger, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+    let validator_lamports = get_account(&mut context, validator.pubkey()).await.lamports;
+    let ix = dlp::instruction_builder::resolve_dispute(
+        challenger.pubkey(),
+        DELEGATED_PDA_ID,
+        challenger.pubkey(),
+        validator.pubkey(),
+        0,
+        false,
+    );
+    let res = process_instruction(&mut context, &challenger, ix).await;
+    assert!(res.is_ok());
+
+    // Assert the validator received the bond and the dispute was rejected
+    let validator_account = get_account(&mut context, validator.pubkey()).await;
+    assert_eq!(
+        validator_account.lamports,
+        validator_lamports + DISPUTE_BOND_LAMPORTS
+    );
+    assert!(!get_commit_record(&mut context).await.is_disputed());
+    assert!(
+        !get_delegation_metadata(&mut context)
+            .await
+            .has_upheld_dispute
+    );
+
+    // The dispute cannot be resolved twice
+    let ix = dlp::instruction_builder::resolve_dispute(
+        challenger.pubkey(),
+        DELEGATED_PDA_ID,
+        challenger.pubkey(),
+        validator.pubkey(),
+        0,
+        false,
+    );
+    let res = process_instruction(&mut context, &challenger, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::CommitNotDisputed as u32)
+        )
+    );
+
+    // The commit can be finalized
+    let ix = dlp::instruction_builder::finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+}
+
+#[tokio::test]
+async fn test_slash_validator_bond_upholds_dispute() {
+    // Setup
+    let (mut context, validator, admin) = setup_program_test_env().await;
+    let ix = dlp::instruction_builder::deposit_validator_bond(validator.pubkey(), VALIDATOR_BOND);
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+    let ix =
+        dlp::instruction_builder::unbond_validator_bond(validator.pubkey(), VALIDATOR_BOND / 2);
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+    commit_state(&mut context, &validator).await;
+    let challenger = dispute_commit(&mut context).await;
+    let challenger_lamports = get_account(&mut context, challenger.pubkey())
+        .await
+        .lamports;
+
+    // Slash the bonded lamports, then the unbonding ones, to the protocol fees vault
+    let slashed = VALIDATOR_BOND * 3 / 4;
+    let ix = slash_validator_bond_ix(&admin, &validator, &challenger, slashed);
+    let res = process_instruction(&mut context, &admin, ix).await;
+    assert!(res.is_ok());
+
+    let validator_bond_account = get_account(
+        &mut context,
+        validator_bond_pda_from_validator(&validator.pubkey()),
+    )
+    .await;
+    let validator_bond =
+        ValidatorBond::try_from_bytes_with_discriminator(&validator_bond_account.data).unwrap();
+    assert_eq!(validator_bond.amount, 0);
+    assert_eq!(validator_bond.unbonding_amount, VALIDATOR_BOND / 4);
+
+    // Assert the dispute was upheld, refunding the challenger
+    let challenger_account = get_account(&mut context, challenger.pubkey()).await;
+    assert_eq!(
+        challenger_account.lamports,
+        challenger_lamports + DISPUTE_BOND_LAMPORTS
+    );
+    let delegation_metadata = get_delegation_metadata(&mut context).await;
+    assert_eq!(delegation_metadata.dispute_slot, 0);
+    assert!(delegation_metadata.has_upheld_dispute);
+
+    // Assert the slashed lamports are recorded in the protocol fees ledger
+    let fees_vault_account = get_account(&mut context, fees_vault_pda()).await;
+    let fees_ledger =
+        ProtocolFeesVault::try_from_bytes_with_discriminator(&fees_vault_account.data)
+            .unwrap()
+            .ledger;
+    assert_eq!(fees_ledger.slashed, slashed);
+
+    // The dispute slashes the validator once
+    let ix = slash_validator_bond_ix(&admin, &validator, &challenger, VALIDATOR_BOND / 4);
+    let res = process_instruction(&mut context, &admin, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::CommitNotDisputed as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_slash_validator_bond_requires_pending_dispute() {
+    // Setup
+    let (mut context, validator, admin) = setup_program_test_env().await;
+    let ix = dlp::instruction_builder::deposit_validator_bond(validator.pubkey(), VALIDATOR_BOND);
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+    commit_state(&mut context, &validator).await;
+    let challenger = context.payer.insecure_clone();
+
+    // The validator cannot be slashed for an undisputed commit
+    let ix = slash_validator_bond_ix(&admin, &validator, &challenger, VALIDATOR_BOND);
+    let res = process_instruction(&mut context, &admin, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::CommitNotDisputed as u32)
+        )
+    );
+
+    // Nor for more than its bond
+    dispute_commit(&mut context).await;
+    let ix = slash_validator_bond_ix(&admin, &validator, &challenger, 2 * VALIDATOR_BOND);
+    let res = process_instruction(&mut context, &admin, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InsufficientValidatorBond as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_commit_and_finalize_with_challenge_period_fails() {
+    // Setup
+    let (mut context, validator, _) = setup_program_test_env().await;
+
+    let ix = dlp::instruction_builder::commit_and_finalize(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        commit_args(),
+    );
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::ChallengePeriodNotElapsed as u32)
+        )
+    );
+}
+
+fn commit_args() -> CommitStateArgs {
+    CommitStateArgs {
+        data: NEW_STATE.to_vec(),
+        slot: 100,
+        allow_undelegation: false,
+        lamports: LAMPORTS_PER_SOL,
+        actions: vec![],
+    }
+}
+
+fn slash_validator_bond_ix(
+    admin: &Keypair,
+    validator: &Keypair,
+    challenger: &Keypair,
+    amount: u64,
+) -> Instruction {
+    dlp::instruction_builder::slash_validator_bond(
+        admin.pubkey(),
+        validator.pubkey(),
+        fees_vault_pda(),
+        DELEGATED_PDA_ID,
+        challenger.pubkey(),
+        0,
+        amount,
+    )
+}
+
+async fn commit_state(context: &mut ProgramTestContext, validator: &Keypair) {
+    let ix = dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        commit_args(),
+    );
+    let res = process_instruction(context, validator, ix).await;
+    assert!(res.is_ok());
+}
+
+async fn dispute_commit(context: &mut ProgramTestContext) -> Keypair {
+    let challenger = context.payer.insecure_clone();
+    let ix = dlp::instruction_builder::dispute_commit(challenger.pubkey(), DELEGATED_PDA_ID, 0);
+    let res = process_instruction(context, &challenger, ix).await;
+    assert!(res.is_ok());
+    challenger
+}
+
+async fn get_account(context: &mut ProgramTestContext, pubkey: Pubkey) -> Account {
+    context
+        .banks_client
+        .get_account(pubkey)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+async fn get_delegation_metadata(context: &mut ProgramTestContext) -> DelegationMetadata {
+    let delegation_metadata_account = get_account(
+        context,
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+    )
+    .await;
+    DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+        .unwrap()
+}
+
+async fn get_commit_record(context: &mut ProgramTestContext) -> CommitRecord {
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_account = context
+        .banks_client
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    *CommitRecord::try_from_bytes_with_discriminator(&commit_record_account.data).unwrap()
+}
+
+async fn process_instruction(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx =
+        Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], blockhash);
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn setup_program_test_env() -> (ProgramTestContext, Keypair, Keypair) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let admin_keypair = Keypair::new();
+
+    program_test.add_account(
+        admin_keypair.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // The admin resolves the disputes until the protocol config is initialized
+    add_delegation_program_data(&mut program_test, admin_keypair.pubkey());
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the owner program config with a challenge period
+    let program_config_data = create_program_config_data_with_challenge_period(
+        validator_keypair.pubkey(),
+        CHALLENGE_PERIOD,
+    );
+    program_test.add_account(
+        program_config_from_program_id(&DELEGATED_PDA_OWNER_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(program_config_data.len()),
+            data: program_config_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the owner program, to test the force undelegation
+    let data = read_file("tests/buffers/test_delegation.so");
+    program_test.add_account(
+        DELEGATED_PDA_OWNER_ID,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: solana_sdk::bpf_loader::id(),
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the protocol fees vault
+    program_test.add_account(
+        fees_vault_pda(),
+        Account {
+            lamports: Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator()),
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let context = program_test.start_with_context().await;
+    (context, validator_keypair, admin_keypair)
+}
@@ -0,0 +1,411 @@
+use crate::fixtures::{
+    create_mint_data, create_protocol_fees_vault_data, create_token_account_data,
+    create_validator_fees_vault_data, TEST_AUTHORITY,
+};
+use dlp::args::{CommitStateArgs, DelegateArgs, DelegateEphemeralBalanceArgs};
+use dlp::consts::TOKEN_PROGRAM_ID;
+use dlp::error::DlpError;
+use dlp::pda::{
+    delegation_record_pda_from_delegated_account, ephemeral_token_balance_escrow_pda_from_balance,
+    ephemeral_token_balance_pda_from_payer, fees_vault_pda,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{DelegationRecord, EphemeralTokenBalance};
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+mod fixtures;
+
+const TOP_UP_AMOUNT: u64 = 1_000;
+const PAYER_TOKENS: u64 = 10_000;
+
+#[tokio::test]
+async fn test_top_up_and_delegate_ephemeral_token_balance() {
+    // Setup
+    let env = setup_program_test_env().await;
+    let ephemeral_token_balance_pda =
+        ephemeral_token_balance_pda_from_payer(&env.payer.pubkey(), &env.mint, 0);
+    let escrow_pda = ephemeral_token_balance_escrow_pda_from_balance(&ephemeral_token_balance_pda);
+
+    // Top up the ephemeral token balance, creating it with its token escrow
+    let ix = dlp::instruction_builder::top_up_ephemeral_token_balance(
+        env.payer.pubkey(),
+        env.payer.pubkey(),
+        env.mint,
+        env.payer_token_account,
+        TOP_UP_AMOUNT,
+        0,
+    );
+    let res = process_instruction(&env.banks, &env.payer, ix, env.blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the tokens are held by the escrow, owned by the ephemeral token balance
+    assert_eq!(
+        get_token_amount(&env.banks, escrow_pda).await,
+        TOP_UP_AMOUNT
+    );
+    assert_eq!(
+        get_token_amount(&env.banks, env.payer_token_account).await,
+        PAYER_TOKENS - TOP_UP_AMOUNT
+    );
+    let escrow_account = env.banks.get_account(escrow_pda).await.unwrap().unwrap();
+    assert_eq!(escrow_account.owner, TOKEN_PROGRAM_ID);
+    assert_eq!(
+        escrow_account.data[32..64],
+        ephemeral_token_balance_pda.to_bytes()
+    );
+
+    // Delegate the ephemeral token balance
+    let ix = delegate_ix(&env);
+    let res = process_instruction(&env.banks, &env.payer, ix, env.blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the amount is synced with the escrow, and the delegation program stays the owner
+    let ephemeral_token_balance = get_ephemeral_token_balance(&env.banks, &env).await;
+    assert_eq!(ephemeral_token_balance.mint, env.mint);
+    assert_eq!(ephemeral_token_balance.amount, TOP_UP_AMOUNT);
+    let delegation_record_account = env
+        .banks
+        .get_account(delegation_record_pda_from_delegated_account(
+            &ephemeral_token_balance_pda,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_account.data)
+            .unwrap();
+    assert_eq!(delegation_record.owner, dlp::id());
+    assert_eq!(delegation_record.authority, env.validator.pubkey());
+}
+
+#[tokio::test]
+async fn test_finalize_undelegate_and_close_ephemeral_token_balance() {
+    // Setup
+    let env = setup_program_test_env().await;
+    top_up_and_delegate(&env).await;
+    let ephemeral_token_balance_pda =
+        ephemeral_token_balance_pda_from_payer(&env.payer.pubkey(), &env.mint, 0);
+    let escrow_pda = ephemeral_token_balance_escrow_pda_from_balance(&ephemeral_token_balance_pda);
+
+    // Commit and finalize the tokens spent in the ephemeral
+    let spent_amount = 400;
+    let ixs = commit_and_finalize_ixs(&env, TOP_UP_AMOUNT - spent_amount, true).await;
+    let res = process_instructions(&env.banks, &env.validator, &ixs, env.blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the spent tokens were settled to the validator payout destination
+    assert_eq!(
+        get_token_amount(&env.banks, env.validator_token_account).await,
+        spent_amount
+    );
+    assert_eq!(
+        get_token_amount(&env.banks, escrow_pda).await,
+        TOP_UP_AMOUNT - spent_amount
+    );
+    assert_eq!(
+        get_ephemeral_token_balance(&env.banks, &env).await.amount,
+        TOP_UP_AMOUNT - spent_amount
+    );
+
+    // Undelegate, the ephemeral token balance stays owned by the delegation program
+    let ix = dlp::instruction_builder::undelegate(
+        env.validator.pubkey(),
+        ephemeral_token_balance_pda,
+        dlp::id(),
+        env.payer.pubkey(),
+        1,
+    );
+    let res = process_instruction(&env.banks, &env.validator, ix, env.blockhash).await;
+    assert!(res.is_ok());
+    let ephemeral_token_balance_account = env
+        .banks
+        .get_account(ephemeral_token_balance_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ephemeral_token_balance_account.owner, dlp::id());
+    assert!(env
+        .banks
+        .get_account(delegation_record_pda_from_delegated_account(
+            &ephemeral_token_balance_pda,
+        ))
+        .await
+        .unwrap()
+        .is_none());
+
+    // Close the ephemeral token balance, refunding the remaining tokens
+    let ix = dlp::instruction_builder::close_ephemeral_token_balance(
+        env.payer.pubkey(),
+        env.mint,
+        env.payer_token_account,
+        0,
+    );
+    let res = process_instruction(&env.banks, &env.payer, ix, env.blockhash).await;
+    assert!(res.is_ok());
+    assert_eq!(
+        get_token_amount(&env.banks, env.payer_token_account).await,
+        PAYER_TOKENS - spent_amount
+    );
+    assert!(env
+        .banks
+        .get_account(ephemeral_token_balance_pda)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(env.banks.get_account(escrow_pda).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_delegated_ephemeral_token_balance_rejects_increase_and_close() {
+    // Setup
+    let env = setup_program_test_env().await;
+    top_up_and_delegate(&env).await;
+
+    // A delegated ephemeral token balance cannot be closed
+    let ix = dlp::instruction_builder::close_ephemeral_token_balance(
+        env.payer.pubkey(),
+        env.mint,
+        env.payer_token_account,
+        0,
+    );
+    let res = process_instruction(&env.banks, &env.payer, ix, env.blockhash).await;
+    assert!(res.is_err());
+
+    // The tokens of an ephemeral token balance can only be spent in the ephemeral
+    let ixs = commit_and_finalize_ixs(&env, TOP_UP_AMOUNT + 1, false).await;
+    let res = process_instructions(&env.banks, &env.validator, &ixs, env.blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(DlpError::InvalidEphemeralTokenBalance as u32)
+        )
+    );
+}
+
+struct TestEnv {
+    banks: BanksClient,
+    payer: Keypair,
+    validator: Keypair,
+    mint: Pubkey,
+    payer_token_account: Pubkey,
+    validator_token_account: Pubkey,
+    blockhash: Hash,
+}
+
+fn delegate_ix(env: &TestEnv) -> Instruction {
+    dlp::instruction_builder::delegate_ephemeral_token_balance(
+        env.payer.pubkey(),
+        env.payer.pubkey(),
+        env.mint,
+        DelegateEphemeralBalanceArgs {
+            delegate_args: DelegateArgs {
+                validator: Some(env.validator.pubkey()),
+                ..Default::default()
+            },
+            index: 0,
+        },
+    )
+}
+
+async fn top_up_and_delegate(env: &TestEnv) {
+    let top_up_ix = dlp::instruction_builder::top_up_ephemeral_token_balance(
+        env.payer.pubkey(),
+        env.payer.pubkey(),
+        env.mint,
+        env.payer_token_account,
+        TOP_UP_AMOUNT,
+        0,
+    );
+    let res = process_instructions(
+        &env.banks,
+        &env.payer,
+        &[top_up_ix, delegate_ix(env)],
+        env.blockhash,
+    )
+    .await;
+    assert!(res.is_ok());
+}
+
+async fn commit_and_finalize_ixs(
+    env: &TestEnv,
+    amount: u64,
+    allow_undelegation: bool,
+) -> Vec<Instruction> {
+    let ephemeral_token_balance_pda =
+        ephemeral_token_balance_pda_from_payer(&env.payer.pubkey(), &env.mint, 0);
+    let ephemeral_token_balance_account = env
+        .banks
+        .get_account(ephemeral_token_balance_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut data = vec![0u8; EphemeralTokenBalance::size_with_discriminator()];
+    EphemeralTokenBalance {
+        mint: env.mint,
+        amount,
+    }
+    .to_bytes_with_discriminator(&mut data)
+    .unwrap();
+    let commit_ix = dlp::instruction_builder::commit_state(
+        env.validator.pubkey(),
+        ephemeral_token_balance_pda,
+        dlp::id(),
+        0,
+        CommitStateArgs {
+            data,
+            slot: 100,
+            allow_undelegation,
+            lamports: ephemeral_token_balance_account.lamports,
+            actions: vec![],
+        },
+    );
+    let mut finalize_ix = dlp::instruction_builder::finalize(
+        env.validator.pubkey(),
+        ephemeral_token_balance_pda,
+        dlp::id(),
+        0,
+    );
+    finalize_ix.accounts.extend(
+        dlp::instruction_builder::finalize_ephemeral_token_balance_accounts(
+            ephemeral_token_balance_pda,
+            env.validator_token_account,
+        ),
+    );
+    vec![commit_ix, finalize_ix]
+}
+
+async fn process_instruction(
+    banks: &BanksClient,
+    signer: &Keypair,
+    ix: Instruction,
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    process_instructions(banks, signer, &[ix], blockhash).await
+}
+
+async fn process_instructions(
+    banks: &BanksClient,
+    signer: &Keypair,
+    ixs: &[Instruction],
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(ixs, Some(&signer.pubkey()), &[signer], blockhash);
+    banks.process_transaction(tx).await
+}
+
+async fn get_token_amount(banks: &BanksClient, token_account: Pubkey) -> u64 {
+    let account = banks.get_account(token_account).await.unwrap().unwrap();
+    u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+}
+
+async fn get_ephemeral_token_balance(banks: &BanksClient, env: &TestEnv) -> EphemeralTokenBalance {
+    let ephemeral_token_balance_pda =
+        ephemeral_token_balance_pda_from_payer(&env.payer.pubkey(), &env.mint, 0);
+    let account = banks
+        .get_account(ephemeral_token_balance_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    *EphemeralTokenBalance::try_from_bytes_with_discriminator(&account.data).unwrap()
+}
+
+fn add_token_account(program_test: &mut ProgramTest, pubkey: Pubkey, data: Vec<u8>) {
+    program_test.add_account(
+        pubkey,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: TOKEN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+async fn setup_program_test_env() -> TestEnv {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let payer = Keypair::new();
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let mint = Pubkey::new_unique();
+    let payer_token_account = Pubkey::new_unique();
+    let validator_token_account = Pubkey::new_unique();
+
+    for signer in [&payer, &validator] {
+        program_test.add_account(
+            signer.pubkey(),
+            Account {
+                lamports: 10 * LAMPORTS_PER_SOL,
+                data: vec![],
+                owner: system_program::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    // Setup the mint and the token accounts of the payer and of the validator payout destination
+    add_token_account(
+        &mut program_test,
+        mint,
+        create_mint_data(Pubkey::new_unique(), 2 * PAYER_TOKENS, 6),
+    );
+    add_token_account(
+        &mut program_test,
+        payer_token_account,
+        create_token_account_data(mint, payer.pubkey(), PAYER_TOKENS),
+    );
+    add_token_account(
+        &mut program_test,
+        validator_token_account,
+        create_token_account_data(mint, validator.pubkey(), 0),
+    );
+
+    // Setup the protocol fees vault
+    let protocol_fees_vault_data = create_protocol_fees_vault_data();
+    program_test.add_account(
+        fees_vault_pda(),
+        Account {
+            lamports: Rent::default().minimum_balance(protocol_fees_vault_data.len()),
+            data: protocol_fees_vault_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    let validator_fees_vault_data = create_validator_fees_vault_data(validator.pubkey());
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: validator_fees_vault_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let (banks, _, blockhash) = program_test.start().await;
+    TestEnv {
+        banks,
+        payer,
+        validator,
+        mint,
+        payer_token_account,
+        validator_token_account,
+        blockhash,
+    }
+}
@@ -0,0 +1,127 @@
+use dlp::event::{
+    CommitSkippedEvent, CommittedEvent, DelegatedEvent, DelegationFlaggedStaleEvent, DlpEvent,
+    EphemeralBalanceToppedUpEvent, EphemeralTokenBalanceToppedUpEvent, Event, EventDiscriminator,
+    FeesClaimedEvent, FinalizedEvent, UndelegatedEvent, ValidatorWhitelistedEvent,
+};
+use dlp::state::CommitKind;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn test_decode_events() {
+    let delegated_account = Pubkey::new_unique();
+    let validator = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+
+    let events = vec![
+        DlpEvent::Delegated(DelegatedEvent {
+            delegated_account,
+            owner,
+            authority: validator,
+            commit_frequency_ms: 30_000,
+            delegation_slot: 10,
+        }),
+        DlpEvent::Committed(CommittedEvent {
+            delegated_account,
+            validator,
+            commit_nonce: Some(1),
+            slot: 100,
+            lamports: 1_000,
+            allow_undelegation: true,
+            kind: CommitKind::Diff.into(),
+        }),
+        DlpEvent::CommitSkipped(CommitSkippedEvent {
+            delegated_account,
+            validator,
+            slot: 50,
+            last_update_external_slot: 100,
+        }),
+        DlpEvent::Finalized(FinalizedEvent {
+            delegated_account,
+            validator,
+            slot: 100,
+            lamports: 1_000,
+        }),
+        DlpEvent::Undelegated(UndelegatedEvent {
+            delegated_account,
+            owner,
+        }),
+        DlpEvent::FeesClaimed(FeesClaimedEvent {
+            fees_vault: Pubkey::new_unique(),
+            receiver: validator,
+            amount: 1_000,
+            protocol_fees: 100,
+        }),
+        DlpEvent::ValidatorWhitelisted(ValidatorWhitelistedEvent {
+            program: owner,
+            validator,
+            whitelisted: true,
+        }),
+        DlpEvent::EphemeralBalanceToppedUp(EphemeralBalanceToppedUpEvent {
+            payer: validator,
+            pubkey: delegated_account,
+            index: 2,
+            amount: 1_000,
+        }),
+        DlpEvent::DelegationFlaggedStale(DelegationFlaggedStaleEvent {
+            delegated_account,
+            authority: validator,
+            last_commit_timestamp: 1_700_000_000,
+        }),
+        DlpEvent::EphemeralTokenBalanceToppedUp(EphemeralTokenBalanceToppedUpEvent {
+            payer: validator,
+            pubkey: delegated_account,
+            mint: Pubkey::new_unique(),
+            index: 2,
+            amount: 1_000,
+        }),
+    ];
+
+    for (expected_discriminator, event) in events.into_iter().enumerate() {
+        let data = match &event {
+            DlpEvent::Delegated(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::Committed(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::CommitSkipped(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::Finalized(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::Undelegated(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::FeesClaimed(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::ValidatorWhitelisted(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::EphemeralBalanceToppedUp(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::DelegationFlaggedStale(event) => event.to_bytes_with_discriminator(),
+            DlpEvent::EphemeralTokenBalanceToppedUp(event) => event.to_bytes_with_discriminator(),
+        }
+        .unwrap();
+
+        // The discriminators are stable, in the order of the events
+        assert_eq!(data[..8], (expected_discriminator as u64).to_le_bytes());
+        assert_eq!(DlpEvent::try_from_bytes(&data).unwrap(), event);
+    }
+}
+
+#[test]
+fn test_decode_committed_event_without_nonce() {
+    // A state committed and finalized right away consumes no commit nonce
+    let event = CommittedEvent {
+        delegated_account: Pubkey::new_unique(),
+        validator: Pubkey::new_unique(),
+        commit_nonce: None,
+        slot: 100,
+        lamports: 1_000,
+        allow_undelegation: false,
+        kind: CommitKind::Full.into(),
+    };
+    let data = event.to_bytes_with_discriminator().unwrap();
+    assert_eq!(
+        DlpEvent::try_from_bytes(&data).unwrap(),
+        DlpEvent::Committed(event)
+    );
+}
+
+#[test]
+fn test_decode_invalid_events() {
+    assert!(DlpEvent::try_from_bytes(&[]).is_err());
+    assert!(DlpEvent::try_from_bytes(&[255, 0, 0, 0, 0, 0, 0, 0]).is_err());
+
+    // Truncated event
+    let data = EventDiscriminator::Committed.to_bytes();
+    assert!(DlpEvent::try_from_bytes(&[&data[..], &[1, 2, 3]].concat()).is_err());
+}
@@ -1,6 +1,7 @@
 use crate::fixtures::{
     get_commit_record_account_data, get_delegation_metadata_data, get_delegation_record_data,
-    COMMIT_NEW_STATE_ACCOUNT_DATA, DELEGATED_PDA_ID, TEST_AUTHORITY,
+    with_pending_commits, COMMIT_NEW_STATE_ACCOUNT_DATA, DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID,
+    TEST_AUTHORITY,
 };
 use dlp::pda::{
     commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
@@ -26,8 +27,8 @@ async fn test_finalize() {
 
     // Retrieve the accounts
     let delegation_record_pda = delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
-    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID);
-    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
 
     // Commit state record data
     let commit_record = banks.get_account(commit_record_pda).await.unwrap().unwrap();
@@ -39,7 +40,12 @@ async fn test_finalize() {
     let new_state_data_before_finalize = new_state_before_finalize.data.clone();
 
     // Submit the finalize tx
-    let ix = dlp::instruction_builder::finalize(authority.pubkey(), DELEGATED_PDA_ID);
+    let ix = dlp::instruction_builder::finalize(
+        authority.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
     let tx = Transaction::new_signed_with_payer(
         &[ix],
         Some(&authority.pubkey()),
@@ -85,6 +91,49 @@ async fn test_finalize() {
     );
 }
 
+#[tokio::test]
+async fn test_finalize_with_baseline_accounts() {
+    // Setup
+    let (banks, _, authority, blockhash) = setup_program_test_env().await;
+
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let new_state = banks.get_account(commit_state_pda).await.unwrap().unwrap();
+    let validator_balance_before = banks.get_balance(authority.pubkey()).await.unwrap();
+
+    // Submit the finalize tx without the owner program accounts and the commit payer, as
+    // built before commits could be validated or relayed
+    let mut ix = dlp::instruction_builder::finalize(
+        authority.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    ix.accounts.truncate(8);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[&authority],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the commit PDAs were closed, refunding the validator
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(banks.get_balance(authority.pubkey()).await.unwrap() > validator_balance_before);
+
+    // Assert the delegated account contains the data from the new state
+    let pda_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(new_state.data, pda_account.data);
+}
+
 async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
     program_test.prefer_bpf(true);
@@ -128,7 +177,8 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     );
 
     // Setup the delegated account metadata PDA
-    let delegation_metadata_data = get_delegation_metadata_data(authority.pubkey(), None);
+    let delegation_metadata_data =
+        with_pending_commits(&get_delegation_metadata_data(authority.pubkey(), None), 1);
     program_test.add_account(
         delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
         Account {
@@ -142,7 +192,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
 
     // Setup the commit state PDA
     program_test.add_account(
-        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
         Account {
             lamports: LAMPORTS_PER_SOL,
             data: COMMIT_NEW_STATE_ACCOUNT_DATA.into(),
@@ -154,7 +204,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
 
     let commit_record_data = get_commit_record_account_data(authority.pubkey());
     program_test.add_account(
-        commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
         Account {
             lamports: Rent::default().minimum_balance(commit_record_data.len()),
             data: commit_record_data,
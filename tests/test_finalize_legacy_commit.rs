@@ -0,0 +1,269 @@
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    fees_vault_pda, validator_fees_vault_pda_from_validator,
+};
+use dlp::state::DelegationMetadata;
+use solana_program::instruction::InstructionError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, read_file, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    create_protocol_fees_vault_data, create_validator_fees_vault_data, get_delegation_record_data,
+    get_legacy_commit_record_account_data, get_legacy_delegation_metadata_data,
+    COMMIT_NEW_STATE_ACCOUNT_DATA, DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+#[test]
+fn test_legacy_commit_pdas_are_the_first_commit_pdas() {
+    assert_eq!(
+        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+        Pubkey::find_program_address(&[b"state-diff", DELEGATED_PDA_ID.as_ref()], &dlp::id()).0
+    );
+    assert_eq!(
+        commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+        Pubkey::find_program_address(
+            &[b"commit-state-record", DELEGATED_PDA_ID.as_ref()],
+            &dlp::id()
+        )
+        .0
+    );
+    assert_ne!(
+        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 1),
+        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0)
+    );
+}
+
+#[tokio::test]
+async fn test_undelegate_with_legacy_commit_fails() {
+    // Setup
+    let (banks, _, authority, blockhash) = setup_program_test_env().await;
+
+    // The commit pending since before commits were queued is not finalized
+    let ix = dlp::instruction_builder::undelegate(
+        authority.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        authority.pubkey(),
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[&authority],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountOwner)
+    );
+}
+
+#[tokio::test]
+async fn test_finalize_legacy_commit_and_undelegate() {
+    // Setup
+    let (banks, _, authority, blockhash) = setup_program_test_env().await;
+
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let delegation_metadata_pda = delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID);
+
+    // The legacy commit is finalized as the first commit of the delegation
+    let ix_finalize = dlp::instruction_builder::finalize(
+        authority.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix_finalize],
+        Some(&authority.pubkey()),
+        &[&authority],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the legacy commit PDAs were closed
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Assert the delegated account contains the data from the new state
+    let pda_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert_eq!(pda_account.data, COMMIT_NEW_STATE_ACCOUNT_DATA);
+
+    // Assert the delegation metadata was migrated and the commit dequeued
+    let delegation_metadata_account = banks
+        .get_account(delegation_metadata_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+            .unwrap();
+    assert_eq!(
+        delegation_metadata_account.data.len(),
+        delegation_metadata.size_with_discriminator().unwrap()
+    );
+    assert_eq!(delegation_metadata.next_commit_nonce, 1);
+    assert_eq!(delegation_metadata.next_finalize_nonce, 1);
+    assert_eq!(delegation_metadata.last_update_external_slot, 100);
+
+    // The account can now be undelegated
+    let ix_undelegate = dlp::instruction_builder::undelegate(
+        authority.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        authority.pubkey(),
+        1,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix_undelegate],
+        Some(&authority.pubkey()),
+        &[&authority],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    let pda_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert!(pda_account.owner.eq(&DELEGATED_PDA_OWNER_ID));
+    assert_eq!(pda_account.data, COMMIT_NEW_STATE_ACCOUNT_DATA);
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+    let authority = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+
+    program_test.add_account(
+        authority.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(authority.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated metadata PDA, in the layout it had before commits were queued
+    let delegation_metadata_data = get_legacy_delegation_metadata_data(authority.pubkey(), true);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the committed state PDA, pending since before commits were queued
+    program_test.add_account(
+        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: COMMIT_NEW_STATE_ACCOUNT_DATA.into(),
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the commit state record PDA, in its legacy layout
+    let commit_record_data = get_legacy_commit_record_account_data(authority.pubkey());
+    program_test.add_account(
+        commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+        Account {
+            lamports: Rent::default().minimum_balance(commit_record_data.len()),
+            data: commit_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup program to test undelegation
+    let data = read_file("tests/buffers/test_delegation.so");
+    program_test.add_account(
+        DELEGATED_PDA_OWNER_ID,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: solana_sdk::bpf_loader::id(),
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the protocol fees vault, with its fees ledger
+    let fees_vault_data = create_protocol_fees_vault_data();
+    program_test.add_account(
+        fees_vault_pda(),
+        Account {
+            lamports: Rent::default().minimum_balance(fees_vault_data.len()),
+            data: fees_vault_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault, with its fees ledger
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&authority.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: create_validator_fees_vault_data(authority.pubkey()),
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let (banks, payer, blockhash) = program_test.start().await;
+    (banks, payer, authority, blockhash)
+}
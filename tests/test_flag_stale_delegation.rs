@@ -0,0 +1,252 @@
+use dlp::args::CommitStateArgs;
+use dlp::error::DlpError;
+use dlp::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    validator_bond_pda_from_validator, validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{DelegationMetadata, DelegationRecord, ValidatorBond};
+use solana_program::clock::Clock;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::rent::Rent;
+use solana_program::{native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_delegation_metadata_data, get_delegation_record_data, DELEGATED_PDA_ID,
+    DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+const COMMIT_FREQUENCY_MS: u64 = 1_000;
+
+#[tokio::test]
+async fn test_flag_stale_delegation() {
+    // Setup, the last commit is older than the commit frequency times the staleness multiplier
+    let (mut context, validator) = setup_program_test_env(COMMIT_FREQUENCY_MS, 1).await;
+    let flagger = Keypair::new();
+    let ix = dlp::instruction_builder::deposit_validator_bond(validator.pubkey(), LAMPORTS_PER_SOL);
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+
+    // Anyone can flag the delegation
+    let res =
+        process_instruction(&mut context, &flagger, flag_stale_ix(&flagger, &validator)).await;
+    assert!(res.is_ok());
+
+    // Assert the delegation is stale and can be force undelegated
+    let delegation_metadata = get_delegation_metadata(&mut context).await;
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    assert!(delegation_metadata.is_stale);
+    assert!(delegation_metadata.is_force_undelegatable(&clock));
+
+    // Assert the stale delegation counts against the validator
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator.pubkey());
+    let validator_bond_account = context
+        .banks_client
+        .get_account(validator_bond_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let validator_bond =
+        ValidatorBond::try_from_bytes_with_discriminator(&validator_bond_account.data).unwrap();
+    assert_eq!(validator_bond.stale_delegations, 1);
+
+    // A stale delegation cannot be committed to, nor flagged again
+    let res = process_instruction(&mut context, &validator, commit_state_ix(&validator)).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::DelegationStale as u32)
+        )
+    );
+    let res =
+        process_instruction(&mut context, &flagger, flag_stale_ix(&flagger, &validator)).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::DelegationStale as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_flag_recently_committed_delegation_fails() {
+    // Setup, the delegation was committed in the future of the test clock
+    let (mut context, validator) = setup_program_test_env(COMMIT_FREQUENCY_MS, i64::MAX / 2).await;
+    let flagger = Keypair::new();
+
+    let res =
+        process_instruction(&mut context, &flagger, flag_stale_ix(&flagger, &validator)).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::DelegationNotStale as u32)
+        )
+    );
+    let delegation_metadata = get_delegation_metadata(&mut context).await;
+    assert!(!delegation_metadata.is_stale);
+}
+
+#[tokio::test]
+async fn test_flag_delegation_without_commit_frequency_fails() {
+    // Setup, the delegation does not commit at a fixed frequency
+    let (mut context, validator) = setup_program_test_env(0, 0).await;
+    let flagger = Keypair::new();
+
+    let res =
+        process_instruction(&mut context, &flagger, flag_stale_ix(&flagger, &validator)).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::DelegationNotStale as u32)
+        )
+    );
+}
+
+fn flag_stale_ix(flagger: &Keypair, validator: &Keypair) -> Instruction {
+    dlp::instruction_builder::flag_stale_delegation(
+        flagger.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        validator.pubkey(),
+    )
+}
+
+fn commit_state_ix(validator: &Keypair) -> Instruction {
+    dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateArgs {
+            slot: 100,
+            lamports: LAMPORTS_PER_SOL,
+            allow_undelegation: false,
+            data: vec![1, 2, 3],
+            actions: vec![],
+        },
+    )
+}
+
+async fn process_instruction(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, signer],
+        blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn get_delegation_metadata(context: &mut ProgramTestContext) -> DelegationMetadata {
+    let delegation_metadata_pda = delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let delegation_metadata_account = context
+        .banks_client
+        .get_account(delegation_metadata_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+        .unwrap()
+}
+
+async fn setup_program_test_env(
+    commit_frequency_ms: u64,
+    last_commit_timestamp: i64,
+) -> (ProgramTestContext, Keypair) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+
+    program_test.add_account(
+        validator.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegation record PDA, with the commit frequency
+    let mut delegation_record_data =
+        get_delegation_record_data(validator.pubkey(), Some(LAMPORTS_PER_SOL));
+    DelegationRecord::try_from_bytes_with_discriminator_mut(&mut delegation_record_data)
+        .unwrap()
+        .commit_frequency_ms = commit_frequency_ms;
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegation metadata PDA, with the last commit timestamp
+    let mut delegation_metadata = DelegationMetadata::try_from_bytes_with_discriminator(
+        &get_delegation_metadata_data(validator.pubkey(), None),
+    )
+    .unwrap();
+    delegation_metadata.last_commit_timestamp = last_commit_timestamp;
+    let mut delegation_metadata_data = vec![];
+    delegation_metadata
+        .to_bytes_with_discriminator(&mut delegation_metadata_data)
+        .unwrap();
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let context = program_test.start_with_context().await;
+    (context, validator)
+}
@@ -0,0 +1,254 @@
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    fees_vault_pda,
+};
+use dlp::state::{DelegationExpiry, ProtocolFeesVault};
+use solana_program::instruction::InstructionError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, read_file, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_commit_record_account_data, get_delegation_metadata_data_with_expiry,
+    get_delegation_record_data, with_pending_commits, COMMIT_NEW_STATE_ACCOUNT_DATA, DELEGATED_PDA,
+    DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+const EXPIRY_SLOT: u64 = 100;
+
+#[tokio::test]
+async fn test_force_undelegate_before_expiry_fails() {
+    // Setup
+    let (context, validator, rent_payer) = setup_program_test_env().await;
+
+    // Anyone can submit the force undelegate tx
+    let ix = dlp::instruction_builder::force_undelegate(
+        context.payer.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        rent_payer,
+        0,
+        &[validator.pubkey()],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::DelegationNotExpired as u32)
+        )
+    );
+
+    // Assert the account is still delegated
+    let pda_account = context
+        .banks_client
+        .get_account(DELEGATED_PDA_ID)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(pda_account.owner.eq(&dlp::id()));
+}
+
+#[tokio::test]
+async fn test_force_undelegate_after_expiry() {
+    // Setup
+    let (mut context, validator, rent_payer) = setup_program_test_env().await;
+    context.warp_to_slot(EXPIRY_SLOT + 1).unwrap();
+
+    let validator_balance_before = context
+        .banks_client
+        .get_balance(validator.pubkey())
+        .await
+        .unwrap();
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
+    let pending_commit_lamports = context
+        .banks_client
+        .get_balance(commit_state_pda)
+        .await
+        .unwrap()
+        + context
+            .banks_client
+            .get_balance(commit_record_pda)
+            .await
+            .unwrap();
+
+    // Submit the force undelegate tx, signed by someone other than the validator
+    let ix = dlp::instruction_builder::force_undelegate(
+        context.payer.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        rent_payer,
+        0,
+        &[validator.pubkey()],
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the pending commit was discarded and refunded to the validator
+    let banks = &mut context.banks_client;
+    assert!(banks.get_account(commit_state_pda).await.unwrap().is_none());
+    assert!(banks
+        .get_account(commit_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+    let validator_balance_after = banks.get_balance(validator.pubkey()).await.unwrap();
+    assert_eq!(
+        validator_balance_after,
+        validator_balance_before + pending_commit_lamports
+    );
+
+    // Assert the delegation PDAs were closed
+    let delegation_record_pda = delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    assert!(banks
+        .get_account(delegation_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+    let delegation_metadata_pda = delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    assert!(banks
+        .get_account(delegation_metadata_pda)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Assert the account is back to the owner with the last finalized state
+    let pda_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert!(pda_account.owner.eq(&DELEGATED_PDA_OWNER_ID));
+    assert_eq!(pda_account.data, DELEGATED_PDA.to_vec());
+}
+
+async fn setup_program_test_env() -> (ProgramTestContext, Keypair, Pubkey) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let rent_payer = Keypair::new().pubkey();
+
+    program_test.add_account(
+        validator.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA holding its last finalized state
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: DELEGATED_PDA.into(),
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated metadata PDA with an expiry
+    let delegation_metadata_data = with_pending_commits(
+        &get_delegation_metadata_data_with_expiry(rent_payer, DelegationExpiry::Slot(EXPIRY_SLOT)),
+        1,
+    );
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a pending commit which was never finalized
+    program_test.add_account(
+        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: COMMIT_NEW_STATE_ACCOUNT_DATA.into(),
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let commit_record_data = get_commit_record_account_data(validator.pubkey());
+    program_test.add_account(
+        commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+        Account {
+            lamports: Rent::default().minimum_balance(commit_record_data.len()),
+            data: commit_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup program to test undelegation
+    let data = read_file("tests/buffers/test_delegation.so");
+    program_test.add_account(
+        DELEGATED_PDA_OWNER_ID,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: solana_sdk::bpf_loader::id(),
+            executable: true,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the protocol fees vault
+    program_test.add_account(
+        fees_vault_pda(),
+        Account {
+            lamports: Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator()),
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let context = program_test.start_with_context().await;
+    (context, validator, rent_payer)
+}
@@ -1,4 +1,4 @@
-use crate::fixtures::TEST_AUTHORITY;
+use crate::fixtures::{add_delegation_program_data, TEST_AUTHORITY};
 use dlp::pda::validator_fees_vault_pda_from_validator;
 use solana_program::pubkey::Pubkey;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
@@ -53,6 +53,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     program_test.prefer_bpf(true);
 
     let admin_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    add_delegation_program_data(&mut program_test, admin_keypair.pubkey());
 
     program_test.add_account(
         admin_keypair.pubkey(),
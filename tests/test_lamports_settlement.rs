@@ -9,7 +9,7 @@ use dlp::pda::{
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
     fees_vault_pda, validator_fees_vault_pda_from_validator,
 };
-use dlp::state::{CommitRecord, DelegationMetadata};
+use dlp::state::{CommitRecord, DelegationMetadata, ProtocolFeesVault, ValidatorFeesVault};
 use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
@@ -112,7 +112,8 @@ pub async fn test_commit_system_account_after_balance_decrease(
         setup_program_for_commit_test_env(SetupProgramCommitTestEnvArgs {
             delegated_account_init_lamports: LAMPORTS_PER_SOL,
             delegated_account_current_lamports: LAMPORTS_PER_SOL,
-            validator_vault_init_lamports: Rent::default().minimum_balance(0),
+            validator_vault_init_lamports: Rent::default()
+                .minimum_balance(ValidatorFeesVault::size_with_discriminator()),
             delegated_account,
             owner_program,
         })
@@ -150,7 +151,10 @@ pub async fn test_commit_system_account_after_balance_decrease(
         .await
         .unwrap()
         .unwrap();
-    assert!(validator_vault.lamports >= Rent::default().minimum_balance(0) + 100);
+    assert!(
+        validator_vault.lamports
+            >= Rent::default().minimum_balance(ValidatorFeesVault::size_with_discriminator()) + 100
+    );
 }
 
 async fn test_commit_system_account_after_balance_increase(also_undelegate: bool, is_pda: bool) {
@@ -160,7 +164,8 @@ async fn test_commit_system_account_after_balance_increase(also_undelegate: bool
         setup_program_for_commit_test_env(SetupProgramCommitTestEnvArgs {
             delegated_account_init_lamports: LAMPORTS_PER_SOL,
             delegated_account_current_lamports: LAMPORTS_PER_SOL,
-            validator_vault_init_lamports: Rent::default().minimum_balance(0),
+            validator_vault_init_lamports: Rent::default()
+                .minimum_balance(ValidatorFeesVault::size_with_discriminator()),
             delegated_account,
             owner_program,
         })
@@ -198,7 +203,10 @@ async fn test_commit_system_account_after_balance_increase(also_undelegate: bool
         .await
         .unwrap()
         .unwrap();
-    assert!(validator_vault.lamports >= Rent::default().minimum_balance(0));
+    assert!(
+        validator_vault.lamports
+            >= Rent::default().minimum_balance(ValidatorFeesVault::size_with_discriminator())
+    );
 }
 
 async fn test_commit_system_account_after_balance_decrease_and_increase_mainchain(
@@ -211,7 +219,8 @@ async fn test_commit_system_account_after_balance_decrease_and_increase_mainchai
         setup_program_for_commit_test_env(SetupProgramCommitTestEnvArgs {
             delegated_account_init_lamports: LAMPORTS_PER_SOL,
             delegated_account_current_lamports: LAMPORTS_PER_SOL + 9000, // Simulate someone transferring lamports to the delegated account
-            validator_vault_init_lamports: Rent::default().minimum_balance(0),
+            validator_vault_init_lamports: Rent::default()
+                .minimum_balance(ValidatorFeesVault::size_with_discriminator()),
             delegated_account,
             owner_program,
         })
@@ -252,7 +261,10 @@ async fn test_commit_system_account_after_balance_decrease_and_increase_mainchai
         .await
         .unwrap()
         .unwrap();
-    assert!(validator_vault.lamports >= Rent::default().minimum_balance(0));
+    assert!(
+        validator_vault.lamports
+            >= Rent::default().minimum_balance(ValidatorFeesVault::size_with_discriminator())
+    );
 }
 
 async fn test_commit_system_account_after_balance_increase_and_increase_mainchain(
@@ -265,7 +277,8 @@ async fn test_commit_system_account_after_balance_increase_and_increase_mainchai
         setup_program_for_commit_test_env(SetupProgramCommitTestEnvArgs {
             delegated_account_init_lamports: LAMPORTS_PER_SOL,
             delegated_account_current_lamports: LAMPORTS_PER_SOL + 8200, // Simulate someone transferring lamports to the delegated account
-            validator_vault_init_lamports: Rent::default().minimum_balance(0),
+            validator_vault_init_lamports: Rent::default()
+                .minimum_balance(ValidatorFeesVault::size_with_discriminator()),
             delegated_account,
             owner_program,
         })
@@ -306,7 +319,10 @@ async fn test_commit_system_account_after_balance_increase_and_increase_mainchai
         .await
         .unwrap()
         .unwrap();
-    assert!(validator_vault.lamports >= Rent::default().minimum_balance(0));
+    assert!(
+        validator_vault.lamports
+            >= Rent::default().minimum_balance(ValidatorFeesVault::size_with_discriminator())
+    );
 }
 
 fn get_delegated_account_and_owner(is_pda: bool) -> (Pubkey, Pubkey) {
@@ -334,6 +350,7 @@ async fn finalize_and_maybe_undelegate(
         authority,
         blockhash,
         delegated_account,
+        owner_program,
     })
     .await;
     if also_undelegate {
@@ -361,12 +378,13 @@ async fn undelegate(args: UndelegateArgs<'_>) {
     let delegation_record_pda =
         delegation_record_pda_from_delegated_account(&args.delegated_account);
 
-    // Submit the undelegate tx
+    // Submit the undelegate tx, the single commit was finalized
     let ix = dlp::instruction_builder::undelegate(
         args.authority.pubkey(),
         args.delegated_account,
         args.owner_program,
         args.authority.pubkey(),
+        1,
     );
     let tx = Transaction::new_signed_with_payer(
         &[ix],
@@ -407,10 +425,16 @@ struct FinalizeNewStateArgs<'a> {
     authority: &'a Keypair,
     blockhash: Hash,
     delegated_account: Pubkey,
+    owner_program: Pubkey,
 }
 
 async fn finalize_new_state(args: FinalizeNewStateArgs<'_>) {
-    let ix = dlp::instruction_builder::finalize(args.authority.pubkey(), args.delegated_account);
+    let ix = dlp::instruction_builder::finalize(
+        args.authority.pubkey(),
+        args.delegated_account,
+        args.owner_program,
+        0,
+    );
     let tx = Transaction::new_signed_with_payer(
         &[ix],
         Some(&args.authority.pubkey()),
@@ -450,6 +474,7 @@ async fn commit_new_state(args: CommitNewStateArgs<'_>) {
         slot: 100,
         allow_undelegation: true,
         lamports: args.new_delegated_account_lamports,
+        actions: vec![],
     };
 
     // Commit the state for the delegated account
@@ -457,6 +482,7 @@ async fn commit_new_state(args: CommitNewStateArgs<'_>) {
         args.authority.pubkey(),
         args.delegated_account,
         args.delegated_account_owner,
+        0,
         commit_args,
     );
     let tx = Transaction::new_signed_with_payer(
@@ -470,7 +496,7 @@ async fn commit_new_state(args: CommitNewStateArgs<'_>) {
     assert!(res.is_ok());
 
     // Assert the state commitment was created and contains the new state
-    let commit_state_pda = commit_state_pda_from_delegated_account(&args.delegated_account);
+    let commit_state_pda = commit_state_pda_from_delegated_account(&args.delegated_account, 0);
     let commit_state_account = args
         .banks
         .get_account(commit_state_pda)
@@ -492,7 +518,7 @@ async fn commit_new_state(args: CommitNewStateArgs<'_>) {
     );
 
     // Assert the record about the commitment exists
-    let commit_record_pda = commit_record_pda_from_delegated_account(&args.delegated_account);
+    let commit_record_pda = commit_record_pda_from_delegated_account(&args.delegated_account, 0);
     let commit_record_account = args
         .banks
         .get_account(commit_record_pda)
@@ -597,7 +623,7 @@ async fn setup_program_for_commit_test_env(
     program_test.add_account(
         fees_vault_pda(),
         Account {
-            lamports: Rent::default().minimum_balance(0),
+            lamports: Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator()),
             data: vec![],
             owner: dlp::id(),
             executable: false,
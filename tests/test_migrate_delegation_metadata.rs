@@ -0,0 +1,163 @@
+use dlp::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+};
+use dlp::state::DelegationMetadata;
+use solana_program::instruction::InstructionError;
+use solana_program::rent::Rent;
+use solana_program::{native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_delegation_record_on_curve_data, get_legacy_delegation_metadata_data_on_curve,
+    ON_CURVE_KEYPAIR, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+#[tokio::test]
+async fn test_read_legacy_delegation_metadata() {
+    // Setup
+    let (mut context, validator, delegated_on_curve) = setup_program_test_env().await;
+
+    // Assert the missing fields of the legacy layout are read as their defaults
+    let delegation_metadata = get_delegation_metadata(&mut context, &delegated_on_curve).await;
+    assert_eq!(delegation_metadata.rent_payer, validator.pubkey());
+    assert_eq!(delegation_metadata.expiry, None);
+    assert_eq!(delegation_metadata.undelegation_request_slot, 0);
+    assert_eq!(delegation_metadata.next_commit_nonce, 0);
+    assert_eq!(delegation_metadata.next_finalize_nonce, 0);
+    assert_eq!(delegation_metadata.last_commit_timestamp, 0);
+    assert!(!delegation_metadata.is_stale);
+}
+
+#[tokio::test]
+async fn test_request_undelegation_of_legacy_delegation_metadata() {
+    // Setup
+    let (mut context, _, delegated_on_curve) = setup_program_test_env().await;
+    context.warp_to_slot(10).unwrap();
+
+    // The legacy delegation metadata cannot be written back without being migrated
+    let request_ix = dlp::instruction_builder::request_undelegation(
+        delegated_on_curve.pubkey(),
+        system_program::id(),
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[request_ix.clone()],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &delegated_on_curve],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::AccountDataTooSmall)
+    );
+
+    // Migrate the delegation metadata, then request the undelegation
+    let migrate_ix = dlp::instruction_builder::migrate_delegation_metadata(
+        context.payer.pubkey(),
+        delegated_on_curve.pubkey(),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[migrate_ix, request_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &delegated_on_curve],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the delegation metadata was resized and the request slot recorded
+    let delegation_metadata_account = context
+        .banks_client
+        .get_account(delegation_metadata_pda_from_delegated_account(
+            &delegated_on_curve.pubkey(),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+            .unwrap();
+    assert_eq!(
+        delegation_metadata_account.data.len(),
+        delegation_metadata.size_with_discriminator().unwrap()
+    );
+    assert!(
+        delegation_metadata_account.lamports
+            >= Rent::default().minimum_balance(delegation_metadata_account.data.len())
+    );
+    assert!(delegation_metadata.undelegation_request_slot >= 10);
+}
+
+async fn get_delegation_metadata(
+    context: &mut ProgramTestContext,
+    delegated_on_curve: &Keypair,
+) -> DelegationMetadata {
+    let delegation_metadata_account = context
+        .banks_client
+        .get_account(delegation_metadata_pda_from_delegated_account(
+            &delegated_on_curve.pubkey(),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+        .unwrap()
+}
+
+async fn setup_program_test_env() -> (ProgramTestContext, Keypair, Keypair) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let delegated_on_curve = Keypair::from_bytes(&ON_CURVE_KEYPAIR).unwrap();
+
+    // Setup a delegated on curve account
+    program_test.add_account(
+        delegated_on_curve.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data =
+        get_delegation_record_on_curve_data(validator.pubkey(), Some(LAMPORTS_PER_SOL));
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&delegated_on_curve.pubkey()),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA, in its legacy layout
+    let delegation_metadata_data = get_legacy_delegation_metadata_data_on_curve(validator.pubkey());
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&delegated_on_curve.pubkey()),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let context = program_test.start_with_context().await;
+    (context, validator, delegated_on_curve)
+}
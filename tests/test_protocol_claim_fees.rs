@@ -1,12 +1,15 @@
-use crate::fixtures::TEST_AUTHORITY;
+use crate::fixtures::{add_delegation_program_data, TEST_AUTHORITY};
 use dlp::pda::fees_vault_pda;
+use dlp::state::ProtocolFeesVault;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
-use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
 use solana_sdk::{
     account::Account,
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 
 mod fixtures;
@@ -19,37 +22,153 @@ async fn test_protocol_claim_fees() {
     let fees_vault_pda = fees_vault_pda();
 
     // Submit the claim fees tx
-    let ix = dlp::instruction_builder::protocol_claim_fees(admin.pubkey());
-    let tx = Transaction::new_signed_with_payer(
-        &[ix],
-        Some(&payer.pubkey()),
-        &[&payer, &admin],
-        blockhash,
-    );
-    let res = banks.process_transaction(tx).await;
+    let ix = dlp::instruction_builder::protocol_claim_fees(admin.pubkey(), admin.pubkey(), None);
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
     assert!(res.is_ok());
 
-    // Assert that fees vault now only have the rent exemption amount
-    let fees_vault_account = banks.get_account(fees_vault_pda).await.unwrap();
-    assert!(fees_vault_account.is_some());
-    assert_eq!(
-        fees_vault_account.unwrap().lamports,
-        Rent::default().minimum_balance(8)
-    );
+    // Assert that fees vault was resized to hold the fees ledger, and now only have the rent
+    // exemption amount
+    let min_rent = Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator());
+    let fees_vault_account = banks.get_account(fees_vault_pda).await.unwrap().unwrap();
+    assert_eq!(fees_vault_account.lamports, min_rent);
+
+    // Assert that the claim is recorded in the fees ledger
+    let fees_ledger =
+        ProtocolFeesVault::try_from_bytes_with_discriminator(&fees_vault_account.data)
+            .unwrap()
+            .ledger;
+    assert_eq!(fees_ledger.claimed, LAMPORTS_PER_SOL - min_rent);
 
     // Assert that the admin account now has the fees
     let admin_account = banks.get_account(admin.pubkey()).await.unwrap();
     assert_eq!(
         admin_account.unwrap().lamports,
-        LAMPORTS_PER_SOL * 2 - Rent::default().minimum_balance(8)
+        LAMPORTS_PER_SOL * 2 - min_rent
+    );
+}
+
+#[tokio::test]
+async fn test_protocol_claim_fees_partial_to_destination() {
+    // Setup
+    let (banks, payer, admin, blockhash) = setup_program_test_env().await;
+    let treasury = Pubkey::new_unique();
+    let claim_amount = LAMPORTS_PER_SOL / 4;
+
+    // Claim part of the fees to a treasury which is not the admin
+    let ix =
+        dlp::instruction_builder::protocol_claim_fees(admin.pubkey(), treasury, Some(claim_amount));
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the treasury received the claimed amount, and the vault kept the rest
+    let treasury_account = banks.get_account(treasury).await.unwrap().unwrap();
+    assert_eq!(treasury_account.lamports, claim_amount);
+    let fees_vault_account = banks.get_account(fees_vault_pda()).await.unwrap().unwrap();
+    assert_eq!(fees_vault_account.lamports, LAMPORTS_PER_SOL - claim_amount);
+    let fees_ledger =
+        ProtocolFeesVault::try_from_bytes_with_discriminator(&fees_vault_account.data)
+            .unwrap()
+            .ledger;
+    assert_eq!(fees_ledger.claimed, claim_amount);
+
+    // Assert the admin was not paid
+    let admin_account = banks.get_account(admin.pubkey()).await.unwrap().unwrap();
+    assert_eq!(admin_account.lamports, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn test_protocol_claim_fees_insufficient_funds() {
+    // Setup
+    let (banks, payer, admin, blockhash) = setup_program_test_env().await;
+    let treasury = Keypair::new();
+    let min_rent = Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator());
+    let claimable_amount = LAMPORTS_PER_SOL - min_rent;
+
+    // Claiming more than the vault holds fails
+    let ix = dlp::instruction_builder::protocol_claim_fees(
+        admin.pubkey(),
+        treasury.pubkey(),
+        Some(2 * LAMPORTS_PER_SOL),
+    );
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InsufficientFunds)
+    );
+
+    // Claiming a single lamport of the rent exemption fails
+    let ix = dlp::instruction_builder::protocol_claim_fees(
+        admin.pubkey(),
+        treasury.pubkey(),
+        Some(claimable_amount + 1),
+    );
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InsufficientFunds)
+    );
+
+    // Claiming up to the rent exemption succeeds
+    let ix = dlp::instruction_builder::protocol_claim_fees(
+        admin.pubkey(),
+        treasury.pubkey(),
+        Some(claimable_amount),
     );
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert!(res.is_ok());
+    let fees_vault_account = banks.get_account(fees_vault_pda()).await.unwrap().unwrap();
+    assert_eq!(fees_vault_account.lamports, min_rent);
+    let treasury_account = banks.get_account(treasury.pubkey()).await.unwrap().unwrap();
+    assert_eq!(treasury_account.lamports, claimable_amount);
+}
+
+#[tokio::test]
+async fn test_protocol_claim_fees_from_unfunded_legacy_vault_fails() {
+    // Setup a fees vault created before the fees ledger, holding only its own rent exemption
+    let (banks, payer, admin, blockhash) =
+        setup_program_test_env_with_fees_vault_lamports(Rent::default().minimum_balance(0)).await;
+
+    // Claiming fails, since the vault cannot afford the rent of its fees ledger
+    let ix = dlp::instruction_builder::protocol_claim_fees(admin.pubkey(), admin.pubkey(), Some(0));
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::AccountNotRentExempt)
+    );
+
+    // Assert the fees vault was not resized
+    let fees_vault_account = banks.get_account(fees_vault_pda()).await.unwrap().unwrap();
+    assert!(fees_vault_account.data.is_empty());
+}
+
+async fn process_instruction(
+    banks: &BanksClient,
+    payer: &Keypair,
+    signer: &Keypair,
+    ix: Instruction,
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, signer],
+        blockhash,
+    );
+    banks.process_transaction(tx).await
 }
 
 async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
+    setup_program_test_env_with_fees_vault_lamports(LAMPORTS_PER_SOL).await
+}
+
+async fn setup_program_test_env_with_fees_vault_lamports(
+    fees_vault_lamports: u64,
+) -> (BanksClient, Keypair, Keypair, Hash) {
     let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
     program_test.prefer_bpf(true);
 
     let admin_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    add_delegation_program_data(&mut program_test, admin_keypair.pubkey());
 
     program_test.add_account(
         admin_keypair.pubkey(),
@@ -66,7 +185,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     program_test.add_account(
         fees_vault_pda(),
         Account {
-            lamports: LAMPORTS_PER_SOL,
+            lamports: fees_vault_lamports,
             data: vec![],
             owner: dlp::id(),
             executable: false,
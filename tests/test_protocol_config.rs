@@ -0,0 +1,292 @@
+use crate::fixtures::{add_delegation_program_data, TEST_AUTHORITY};
+use dlp::args::{InitProtocolConfigArgs, UpdateProtocolConfigArgs};
+use dlp::error::DlpError;
+use dlp::pda::{fees_vault_pda, protocol_config_pda, validator_fees_vault_pda_from_validator};
+use dlp::state::ProtocolConfig;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+mod fixtures;
+
+#[tokio::test]
+async fn test_init_and_update_protocol_config() {
+    // Setup
+    let (banks, payer, upgrade_authority, blockhash) = setup_program_test_env().await;
+    let admin = Keypair::new();
+
+    // Only the upgrade authority can initialize the protocol config
+    let ix = init_protocol_config_ix(&payer, &admin);
+    let res = process_instruction(&banks, &payer, &payer, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+    let ix = init_protocol_config_ix(&upgrade_authority, &admin);
+    let res = process_instruction(&banks, &payer, &upgrade_authority, ix, blockhash).await;
+    assert!(res.is_ok());
+    assert_eq!(
+        get_protocol_config(&banks).await,
+        ProtocolConfig {
+            admin: admin.pubkey(),
+            pending_admin: Pubkey::default(),
+            rent_fees_bps: 500,
+            protocol_fees_bps: 2_500,
+            min_commit_frequency_ms: 1_000,
+            commit_fee: 0,
+        }
+    );
+
+    // Only the admin can update the protocol config
+    let args = UpdateProtocolConfigArgs {
+        protocol_fees_bps: Some(3_000),
+        ..Default::default()
+    };
+    let ix = dlp::instruction_builder::update_protocol_config(upgrade_authority.pubkey(), args);
+    let res = process_instruction(&banks, &payer, &upgrade_authority, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+
+    // Fee rates cannot exceed 100%
+    let args = UpdateProtocolConfigArgs {
+        rent_fees_bps: Some(10_001),
+        ..Default::default()
+    };
+    let ix = dlp::instruction_builder::update_protocol_config(admin.pubkey(), args);
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidProtocolConfig as u32)
+        )
+    );
+
+    // Update the protocol fees and the commit fee, the other parameters are unchanged
+    let args = UpdateProtocolConfigArgs {
+        protocol_fees_bps: Some(3_000),
+        commit_fee: Some(5_000),
+        ..Default::default()
+    };
+    let ix = dlp::instruction_builder::update_protocol_config(admin.pubkey(), args);
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert!(res.is_ok());
+    assert_eq!(
+        get_protocol_config(&banks).await,
+        ProtocolConfig {
+            admin: admin.pubkey(),
+            pending_admin: Pubkey::default(),
+            rent_fees_bps: 500,
+            protocol_fees_bps: 3_000,
+            min_commit_frequency_ms: 1_000,
+            commit_fee: 5_000,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_rotate_protocol_admin() {
+    // Setup
+    let (banks, payer, upgrade_authority, blockhash) = setup_program_test_env().await;
+    let admin = Keypair::new();
+    let new_admin = Keypair::new();
+
+    // Initialize the protocol config, the upgrade authority hands over to the admin
+    let ix = init_protocol_config_ix(&upgrade_authority, &admin);
+    let res = process_instruction(&banks, &payer, &upgrade_authority, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // The admin proposes a new admin, which has to accept before the rotation takes effect
+    let ix = dlp::instruction_builder::propose_protocol_admin(admin.pubkey(), new_admin.pubkey());
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert!(res.is_ok());
+    assert_eq!(get_protocol_config(&banks).await.admin, admin.pubkey());
+    assert_eq!(
+        get_protocol_config(&banks).await.pending_admin,
+        new_admin.pubkey()
+    );
+
+    // Only the pending admin can accept the rotation
+    let ix = dlp::instruction_builder::accept_protocol_admin(admin.pubkey());
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+    let ix = dlp::instruction_builder::accept_protocol_admin(new_admin.pubkey());
+    let res = process_instruction(&banks, &payer, &new_admin, ix, blockhash).await;
+    assert!(res.is_ok());
+    let protocol_config = get_protocol_config(&banks).await;
+    assert_eq!(protocol_config.admin, new_admin.pubkey());
+    assert_eq!(protocol_config.pending_admin, Pubkey::default());
+
+    // Only the new admin operates the protocol, the upgrade authority no longer does
+    let ix = dlp::instruction_builder::protocol_claim_fees(
+        upgrade_authority.pubkey(),
+        upgrade_authority.pubkey(),
+        None,
+    );
+    let res = process_instruction(&banks, &payer, &upgrade_authority, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+    let ix = dlp::instruction_builder::propose_protocol_admin(admin.pubkey(), admin.pubkey());
+    let res = process_instruction(&banks, &payer, &admin, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+    let ix =
+        dlp::instruction_builder::protocol_claim_fees(new_admin.pubkey(), new_admin.pubkey(), None);
+    let res = process_instruction(&banks, &payer, &new_admin, ix, blockhash).await;
+    assert!(res.is_ok());
+}
+
+#[tokio::test]
+async fn test_validator_claim_fees_with_protocol_config() {
+    // Setup
+    let (banks, payer, upgrade_authority, blockhash) = setup_program_test_env().await;
+    let ix = init_protocol_config_ix(&upgrade_authority, &upgrade_authority);
+    let res = process_instruction(&banks, &payer, &upgrade_authority, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // The validator claims its fees
+    let validator = &upgrade_authority;
+    let fees_vault_init_lamports = get_lamports(&banks, fees_vault_pda()).await;
+    let validator_init_lamports = get_lamports(&banks, validator.pubkey()).await;
+    let ix = dlp::instruction_builder::validator_claim_fees(validator.pubkey(), Some(100_000));
+    let res = process_instruction(&banks, &payer, validator, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the protocol fees follow the protocol config
+    assert_eq!(
+        get_lamports(&banks, fees_vault_pda()).await,
+        fees_vault_init_lamports + 25_000
+    );
+    assert_eq!(
+        get_lamports(&banks, validator.pubkey()).await,
+        validator_init_lamports + 75_000
+    );
+}
+
+fn init_protocol_config_ix(authority: &Keypair, admin: &Keypair) -> Instruction {
+    dlp::instruction_builder::init_protocol_config(
+        authority.pubkey(),
+        InitProtocolConfigArgs {
+            admin: admin.pubkey(),
+            rent_fees_bps: 500,
+            protocol_fees_bps: 2_500,
+            min_commit_frequency_ms: 1_000,
+            commit_fee: 0,
+        },
+    )
+}
+
+async fn process_instruction(
+    banks: &BanksClient,
+    payer: &Keypair,
+    signer: &Keypair,
+    ix: Instruction,
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, signer],
+        blockhash,
+    );
+    banks.process_transaction(tx).await
+}
+
+async fn get_protocol_config(banks: &BanksClient) -> ProtocolConfig {
+    let protocol_config_account = banks
+        .get_account(protocol_config_pda())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        protocol_config_account.data.len(),
+        ProtocolConfig::size_with_discriminator().unwrap()
+    );
+    assert_eq!(
+        protocol_config_account.lamports,
+        Rent::default().minimum_balance(protocol_config_account.data.len())
+    );
+    ProtocolConfig::try_from_bytes_with_discriminator(&protocol_config_account.data).unwrap()
+}
+
+async fn get_lamports(banks: &BanksClient, pubkey: Pubkey) -> u64 {
+    banks.get_account(pubkey).await.unwrap().unwrap().lamports
+}
+
+async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    // The upgrade authority is also a validator
+    let upgrade_authority = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    add_delegation_program_data(&mut program_test, upgrade_authority.pubkey());
+
+    program_test.add_account(
+        upgrade_authority.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the fees vault account
+    program_test.add_account(
+        fees_vault_pda(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&upgrade_authority.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let (banks, payer, blockhash) = program_test.start().await;
+    (banks, payer, upgrade_authority, blockhash)
+}
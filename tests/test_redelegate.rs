@@ -0,0 +1,575 @@
+use dlp::error::DlpError;
+use dlp::pda::{
+    commit_record_pda_from_delegated_account, commit_state_pda_from_delegated_account,
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    program_config_from_program_id, validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{DelegationMetadata, DelegationRecord, ProgramConfig};
+use solana_program::instruction::InstructionError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::{native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    create_program_config_data, get_commit_record_account_data, get_delegation_metadata_data,
+    get_delegation_record_data, get_legacy_delegation_metadata_data_on_curve,
+    COMMIT_NEW_STATE_ACCOUNT_DATA, DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+const LAST_UPDATE_EXTERNAL_SLOT: u64 = 42;
+const STALE_LAST_COMMIT_TIMESTAMP: i64 = 1;
+
+#[tokio::test]
+async fn test_redelegate() {
+    // Setup
+    let (mut context, validator, new_validator) = setup_program_test_env(false, |_| {}, None).await;
+    context.warp_to_slot(10).unwrap();
+
+    // Submit the redelegate tx
+    let ix = dlp::instruction_builder::redelegate(
+        validator.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the delegation record now points to the new validator
+    let delegation_record_account = context
+        .banks_client
+        .get_account(delegation_record_pda_from_delegated_account(
+            &DELEGATED_PDA_ID,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_account.data)
+            .unwrap();
+    assert_eq!(delegation_record.authority, new_validator);
+    assert!(delegation_record.delegation_slot >= 10);
+
+    // Assert the commit bookkeeping was reset
+    let delegation_metadata_account = context
+        .banks_client
+        .get_account(delegation_metadata_pda_from_delegated_account(
+            &DELEGATED_PDA_ID,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+            .unwrap();
+    assert_eq!(delegation_metadata.last_update_external_slot, 0);
+    assert!(delegation_metadata.last_commit_timestamp > STALE_LAST_COMMIT_TIMESTAMP);
+    assert!(!delegation_metadata.is_undelegatable);
+
+    // Assert the delegated account is still delegated
+    let pda_account = context
+        .banks_client
+        .get_account(DELEGATED_PDA_ID)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(pda_account.owner.eq(&dlp::id()));
+}
+
+#[tokio::test]
+async fn test_redelegate_with_pending_commit_fails() {
+    // Setup
+    let (context, validator, new_validator) = setup_program_test_env(true, |_| {}, None).await;
+
+    // Submit the redelegate tx
+    let ix = dlp::instruction_builder::redelegate(
+        validator.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountOwner)
+    );
+}
+
+#[tokio::test]
+async fn test_redelegate_undelegatable_account_fails() {
+    // Setup, with an account marked as undelegatable by the validator
+    assert_redelegate_fails(
+        |delegation_metadata| delegation_metadata.is_undelegatable = true,
+        DlpError::DelegationUndelegatable,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_redelegate_after_undelegation_request_fails() {
+    // Setup, with an undelegation requested by the owner program
+    assert_redelegate_fails(
+        |delegation_metadata| delegation_metadata.undelegation_request_slot = 5,
+        DlpError::UndelegationRequested,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_redelegate_stale_delegation_fails() {
+    // Setup, with a delegation flagged as stale
+    assert_redelegate_fails(
+        |delegation_metadata| delegation_metadata.is_stale = true,
+        DlpError::DelegationStale,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_redelegate_after_upheld_dispute_fails() {
+    // Setup, with a delegation whose last dispute was upheld
+    assert_redelegate_fails(
+        |delegation_metadata| delegation_metadata.has_upheld_dispute = true,
+        DlpError::CommitDisputed,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_redelegate_with_legacy_delegation_metadata() {
+    // Setup, with a delegation metadata in its legacy layout
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let (context, validator, new_validator) = setup_program_test_env_with_metadata_data(
+        false,
+        get_legacy_delegation_metadata_data_on_curve(validator.pubkey()),
+        validator.pubkey(),
+        None,
+    )
+    .await;
+
+    // Submit the redelegate tx
+    let ix = dlp::instruction_builder::redelegate(
+        validator.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the delegation metadata was resized to its current layout
+    let delegation_metadata_account = context
+        .banks_client
+        .get_account(delegation_metadata_pda_from_delegated_account(
+            &DELEGATED_PDA_ID,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+            .unwrap();
+    assert_eq!(
+        delegation_metadata_account.data.len(),
+        delegation_metadata.size_with_discriminator().unwrap()
+    );
+    assert!(delegation_metadata.last_commit_timestamp > 0);
+}
+
+#[tokio::test]
+async fn test_redelegate_from_foreign_validator_fails() {
+    // Setup, with a delegation whose authority is another validator
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let (context, validator, new_validator) = setup_program_test_env_with_metadata_data(
+        false,
+        get_delegation_metadata_data(validator.pubkey(), None),
+        Keypair::new().pubkey(),
+        None,
+    )
+    .await;
+
+    // Submit the redelegate tx, signed by a whitelisted validator which is not the authority
+    let ix = dlp::instruction_builder::redelegate(
+        validator.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidDelegationAuthority as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_redelegate_default_authority_from_arbitrary_signer_fails() {
+    // Setup, with a delegation any validator can commit to
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let (context, _, new_validator) = setup_program_test_env_with_metadata_data(
+        false,
+        get_delegation_metadata_data(validator.pubkey(), None),
+        Pubkey::default(),
+        None,
+    )
+    .await;
+
+    // Submit the redelegate tx, signed by a keypair which is not a whitelisted validator
+    let arbitrary_signer = Keypair::new();
+    let ix = dlp::instruction_builder::redelegate(
+        arbitrary_signer.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &arbitrary_signer],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountOwner)
+    );
+
+    // Assert the delegation record still has no authority
+    let delegation_record_account = context
+        .banks_client
+        .get_account(delegation_record_pda_from_delegated_account(
+            &DELEGATED_PDA_ID,
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_record =
+        DelegationRecord::try_from_bytes_with_discriminator(&delegation_record_account.data)
+            .unwrap();
+    assert_eq!(delegation_record.authority, Pubkey::default());
+}
+
+#[tokio::test]
+async fn test_redelegate_to_unapproved_validator_fails() {
+    // Setup, with a program config approving the validator but not the new validator
+    let (context, validator, new_validator) =
+        setup_program_test_env(false, |_| {}, Some((true, false))).await;
+
+    // Submit the redelegate tx
+    let ix = dlp::instruction_builder::redelegate(
+        validator.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidWhitelistProgramConfig as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_redelegate_from_unapproved_validator_fails() {
+    // Setup, with a program config approving the new validator but not the validator
+    let (context, validator, new_validator) =
+        setup_program_test_env(false, |_| {}, Some((false, true))).await;
+
+    // Submit the redelegate tx
+    let ix = dlp::instruction_builder::redelegate(
+        validator.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidWhitelistProgramConfig as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_redelegate_to_approved_validator() {
+    // Setup, with a program config approving both validators
+    let (context, validator, new_validator) =
+        setup_program_test_env(false, |_| {}, Some((true, true))).await;
+
+    // Submit the redelegate tx
+    let ix = dlp::instruction_builder::redelegate(
+        validator.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+}
+
+async fn assert_redelegate_fails(update_metadata: fn(&mut DelegationMetadata), error: DlpError) {
+    let (context, validator, new_validator) =
+        setup_program_test_env(false, update_metadata, None).await;
+
+    // Submit the redelegate tx
+    let ix = dlp::instruction_builder::redelegate(
+        validator.pubkey(),
+        new_validator,
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+    );
+}
+
+async fn setup_program_test_env(
+    with_pending_commit: bool,
+    update_metadata: fn(&mut DelegationMetadata),
+    approved_validators: Option<(bool, bool)>,
+) -> (ProgramTestContext, Keypair, Pubkey) {
+    // Setup the delegated account metadata, with a state committed by the current validator
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let mut delegation_metadata = DelegationMetadata::try_from_bytes_with_discriminator(
+        &get_delegation_metadata_data(validator.pubkey(), None),
+    )
+    .unwrap();
+    delegation_metadata.last_update_external_slot = LAST_UPDATE_EXTERNAL_SLOT;
+    delegation_metadata.last_commit_timestamp = STALE_LAST_COMMIT_TIMESTAMP;
+    if with_pending_commit {
+        delegation_metadata.next_commit_nonce = 1;
+    }
+    update_metadata(&mut delegation_metadata);
+    let mut delegation_metadata_data = vec![];
+    delegation_metadata
+        .to_bytes_with_discriminator(&mut delegation_metadata_data)
+        .unwrap();
+    setup_program_test_env_with_metadata_data(
+        with_pending_commit,
+        delegation_metadata_data,
+        validator.pubkey(),
+        approved_validators,
+    )
+    .await
+}
+
+async fn setup_program_test_env_with_metadata_data(
+    with_pending_commit: bool,
+    delegation_metadata_data: Vec<u8>,
+    delegation_authority: Pubkey,
+    approved_validators: Option<(bool, bool)>,
+) -> (ProgramTestContext, Keypair, Pubkey) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let new_validator = Keypair::new().pubkey();
+
+    program_test.add_account(
+        validator.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(delegation_authority, None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the new validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&new_validator),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a pending commit which was never finalized
+    if with_pending_commit {
+        program_test.add_account(
+            commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: COMMIT_NEW_STATE_ACCOUNT_DATA.into(),
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        let commit_record_data = get_commit_record_account_data(validator.pubkey());
+        program_test.add_account(
+            commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
+            Account {
+                lamports: Rent::default().minimum_balance(commit_record_data.len()),
+                data: commit_record_data,
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    // Setup the program config
+    if let Some((approve_validator, approve_new_validator)) = approved_validators {
+        let mut program_config = ProgramConfig::try_from_bytes_with_discriminator(
+            &create_program_config_data(Keypair::new().pubkey()),
+        )
+        .unwrap();
+        if approve_validator {
+            program_config
+                .approved_validators
+                .insert(validator.pubkey());
+        }
+        if approve_new_validator {
+            program_config.approved_validators.insert(new_validator);
+        }
+        let mut program_config_data = vec![];
+        program_config
+            .to_bytes_with_discriminator(&mut program_config_data)
+            .unwrap();
+        program_test.add_account(
+            program_config_from_program_id(&DELEGATED_PDA_OWNER_ID),
+            Account {
+                lamports: Rent::default().minimum_balance(program_config_data.len()),
+                data: program_config_data,
+                owner: dlp::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    let context = program_test.start_with_context().await;
+    (context, validator, new_validator)
+}
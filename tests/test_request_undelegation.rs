@@ -0,0 +1,231 @@
+use dlp::consts::UNDELEGATION_REQUEST_GRACE_PERIOD_SLOTS;
+use dlp::error::DlpError;
+use dlp::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    fees_vault_pda,
+};
+use dlp::state::{DelegationMetadata, ProtocolFeesVault};
+use solana_program::instruction::InstructionError;
+use solana_program::rent::Rent;
+use solana_program::{native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    get_delegation_metadata_data_on_curve, get_delegation_record_on_curve_data, ON_CURVE_KEYPAIR,
+    TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+#[tokio::test]
+async fn test_request_undelegation() {
+    // Setup
+    let (mut context, _, delegated_on_curve) = setup_program_test_env().await;
+    context.warp_to_slot(10).unwrap();
+
+    // Submit the request undelegation tx, signed by the delegated account
+    let ix = dlp::instruction_builder::request_undelegation(
+        delegated_on_curve.pubkey(),
+        system_program::id(),
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &delegated_on_curve],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the request slot was recorded in the delegation metadata
+    let delegation_metadata_account = context
+        .banks_client
+        .get_account(delegation_metadata_pda_from_delegated_account(
+            &delegated_on_curve.pubkey(),
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let delegation_metadata =
+        DelegationMetadata::try_from_bytes_with_discriminator(&delegation_metadata_account.data)
+            .unwrap();
+    assert!(delegation_metadata.undelegation_request_slot >= 10);
+}
+
+#[tokio::test]
+async fn test_request_undelegation_without_signature_fails() {
+    // Setup
+    let (context, _, delegated_on_curve) = setup_program_test_env().await;
+
+    // Submit the request undelegation tx without the delegated account signature
+    let mut ix = dlp::instruction_builder::request_undelegation(
+        delegated_on_curve.pubkey(),
+        system_program::id(),
+    );
+    ix.accounts[0].is_signer = false;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature)
+    );
+}
+
+#[tokio::test]
+async fn test_force_undelegate_after_request_grace_period() {
+    // Setup
+    let (mut context, validator, delegated_on_curve) = setup_program_test_env().await;
+    context.warp_to_slot(10).unwrap();
+
+    // Request the undelegation and try to force it right away
+    let request_ix = dlp::instruction_builder::request_undelegation(
+        delegated_on_curve.pubkey(),
+        system_program::id(),
+    );
+    let force_ix = dlp::instruction_builder::force_undelegate(
+        context.payer.pubkey(),
+        delegated_on_curve.pubkey(),
+        system_program::id(),
+        validator.pubkey(),
+        0,
+        &[],
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[request_ix.clone(), force_ix.clone()],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &delegated_on_curve],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(DlpError::DelegationNotExpired as u32)
+        )
+    );
+
+    // Request the undelegation alone
+    let tx = Transaction::new_signed_with_payer(
+        &[request_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &delegated_on_curve],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    assert!(res.is_ok());
+
+    // Force the undelegation once the grace period elapsed
+    context
+        .warp_to_slot(10 + UNDELEGATION_REQUEST_GRACE_PERIOD_SLOTS + 1)
+        .unwrap();
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[force_ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        blockhash,
+    );
+    let res = context.banks_client.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Assert the delegation PDAs were closed
+    let banks = &mut context.banks_client;
+    let delegation_record_pda =
+        delegation_record_pda_from_delegated_account(&delegated_on_curve.pubkey());
+    assert!(banks
+        .get_account(delegation_record_pda)
+        .await
+        .unwrap()
+        .is_none());
+    let delegation_metadata_pda =
+        delegation_metadata_pda_from_delegated_account(&delegated_on_curve.pubkey());
+    assert!(banks
+        .get_account(delegation_metadata_pda)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Assert that the account owner is now set to the system program
+    let account = banks
+        .get_account(delegated_on_curve.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(account.owner.eq(&system_program::id()));
+}
+
+async fn setup_program_test_env() -> (ProgramTestContext, Keypair, Keypair) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+    let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    let delegated_on_curve = Keypair::from_bytes(&ON_CURVE_KEYPAIR).unwrap();
+
+    // Setup a delegated on curve account
+    program_test.add_account(
+        delegated_on_curve.pubkey(),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data =
+        get_delegation_record_on_curve_data(validator.pubkey(), Some(LAMPORTS_PER_SOL));
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&delegated_on_curve.pubkey()),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data_on_curve(validator.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&delegated_on_curve.pubkey()),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the protocol fees vault
+    program_test.add_account(
+        fees_vault_pda(),
+        Account {
+            lamports: Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator()),
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let context = program_test.start_with_context().await;
+    (context, validator, delegated_on_curve)
+}
@@ -7,7 +7,7 @@ use dlp::pda::{
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
     ephemeral_balance_pda_from_payer, fees_vault_pda, validator_fees_vault_pda_from_validator,
 };
-use dlp::state::DelegationRecord;
+use dlp::state::{DelegationRecord, ProtocolFeesVault};
 use solana_program::rent::Rent;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
 use solana_program_test::{processor, BanksClient, ProgramTest};
@@ -183,6 +183,7 @@ async fn test_undelegate() {
         ephemeral_balance_pda,
         system_program::id(),
         validator.pubkey(),
+        0,
     );
 
     let tx = Transaction::new_signed_with_payer(
@@ -231,6 +232,7 @@ async fn test_undelegate_and_close() {
         ephemeral_balance_pda,
         system_program::id(),
         validator.pubkey(),
+        0,
     );
 
     let ix_close = dlp::instruction_builder::close_ephemeral_balance(payer_alt.pubkey(), 0);
@@ -296,7 +298,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     program_test.add_account(
         fees_vault_pda(),
         Account {
-            lamports: Rent::default().minimum_balance(0),
+            lamports: Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator()),
             data: vec![],
             owner: dlp::id(),
             executable: false,
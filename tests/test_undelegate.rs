@@ -3,6 +3,8 @@ use dlp::pda::{
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
     fees_vault_pda, validator_fees_vault_pda_from_validator,
 };
+use dlp::state::{ProtocolFeesVault, ValidatorFeesVault};
+use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
 use solana_program_test::{processor, read_file, BanksClient, ProgramTest};
@@ -13,8 +15,10 @@ use solana_sdk::{
 };
 
 use crate::fixtures::{
+    create_protocol_fees_vault_data, create_validator_fees_vault_data,
     get_commit_record_account_data, get_delegation_metadata_data, get_delegation_record_data,
-    COMMIT_NEW_STATE_ACCOUNT_DATA, DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+    with_pending_commits, COMMIT_NEW_STATE_ACCOUNT_DATA, DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID,
+    TEST_AUTHORITY,
 };
 
 mod fixtures;
@@ -26,21 +30,31 @@ async fn test_finalize_and_undelegate() {
 
     // Retrieve the accounts
     let delegation_record_pda = delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
-    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
 
     // Save the new state data before undelegating
     let new_state_before_finalize = banks.get_account(commit_state_pda).await.unwrap().unwrap();
     let new_state_data_before_finalize = new_state_before_finalize.data.clone();
+    let fees_vault_pda = fees_vault_pda();
+    let fees_vault_lamports = get_lamports(&banks, fees_vault_pda).await;
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&authority.pubkey());
+    let validator_fees_vault_lamports = get_lamports(&banks, validator_fees_vault_pda).await;
 
     // Create the finalize tx
-    let ix_finalize = dlp::instruction_builder::finalize(authority.pubkey(), DELEGATED_PDA_ID);
+    let ix_finalize = dlp::instruction_builder::finalize(
+        authority.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+    );
 
-    // Create the undelegate tx
+    // Create the undelegate tx, once the commit is finalized
     let ix_undelegate = dlp::instruction_builder::undelegate(
         authority.pubkey(),
         DELEGATED_PDA_ID,
         DELEGATED_PDA_OWNER_ID,
         authority.pubkey(),
+        1,
     );
 
     // Submit the transaction
@@ -73,6 +87,36 @@ async fn test_finalize_and_undelegate() {
 
     // Assert the delegated account contains the data from the new state
     assert_eq!(new_state_data_before_finalize, pda_account.data);
+
+    // Assert the fees ledgers account for the lamports earned by the vaults
+    let fees_vault_account = banks.get_account(fees_vault_pda).await.unwrap().unwrap();
+    let fees_ledger =
+        ProtocolFeesVault::try_from_bytes_with_discriminator(&fees_vault_account.data)
+            .unwrap()
+            .ledger;
+    assert!(fees_ledger.rent_fees > 0);
+    assert_eq!(
+        fees_ledger.earned(),
+        Some(fees_vault_account.lamports - fees_vault_lamports)
+    );
+    let validator_fees_vault_account = banks
+        .get_account(validator_fees_vault_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let validator_fees_ledger =
+        ValidatorFeesVault::try_from_bytes_with_discriminator(&validator_fees_vault_account.data)
+            .unwrap()
+            .ledger;
+    assert!(validator_fees_ledger.rent_fees > 0);
+    assert_eq!(
+        validator_fees_ledger.earned(),
+        Some(validator_fees_vault_account.lamports - validator_fees_vault_lamports)
+    );
+}
+
+async fn get_lamports(banks: &BanksClient, pubkey: Pubkey) -> u64 {
+    banks.get_account(pubkey).await.unwrap().unwrap().lamports
 }
 
 async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
@@ -117,7 +161,10 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     );
 
     // Setup the delegated metadata PDA
-    let delegation_metadata_data = get_delegation_metadata_data(authority.pubkey(), Some(true));
+    let delegation_metadata_data = with_pending_commits(
+        &get_delegation_metadata_data(authority.pubkey(), Some(true)),
+        1,
+    );
     program_test.add_account(
         delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
         Account {
@@ -131,7 +178,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
 
     // Setup the committed state PDA
     program_test.add_account(
-        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
         Account {
             lamports: LAMPORTS_PER_SOL,
             data: COMMIT_NEW_STATE_ACCOUNT_DATA.into(),
@@ -144,7 +191,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     // Setup the commit state record PDA
     let commit_record_data = get_commit_record_account_data(authority.pubkey());
     program_test.add_account(
-        commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        commit_record_pda_from_delegated_account(&DELEGATED_PDA_ID, 0),
         Account {
             lamports: Rent::default().minimum_balance(commit_record_data.len()),
             data: commit_record_data,
@@ -167,24 +214,25 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
         },
     );
 
-    // Setup the protocol fees vault
+    // Setup the protocol fees vault, with its fees ledger
+    let fees_vault_data = create_protocol_fees_vault_data();
     program_test.add_account(
         fees_vault_pda(),
         Account {
-            lamports: Rent::default().minimum_balance(0),
-            data: vec![],
+            lamports: Rent::default().minimum_balance(fees_vault_data.len()),
+            data: fees_vault_data,
             owner: dlp::id(),
             executable: false,
             rent_epoch: 0,
         },
     );
 
-    // Setup the validator fees vault
+    // Setup the validator fees vault, with its fees ledger
     program_test.add_account(
         validator_fees_vault_pda_from_validator(&authority.pubkey()),
         Account {
             lamports: LAMPORTS_PER_SOL,
-            data: vec![],
+            data: create_validator_fees_vault_data(authority.pubkey()),
             owner: dlp::id(),
             executable: false,
             rent_epoch: 0,
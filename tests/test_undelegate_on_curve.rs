@@ -2,6 +2,7 @@ use dlp::pda::{
     delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
     fees_vault_pda, validator_fees_vault_pda_from_validator,
 };
+use dlp::state::ProtocolFeesVault;
 use solana_program::rent::Rent;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
 use solana_program_test::{processor, BanksClient, ProgramTest};
@@ -33,6 +34,7 @@ async fn test_undelegate_on_curve() {
         delegated_on_curve.pubkey(),
         system_program::id(),
         validator.pubkey(),
+        0,
     );
     let tx = Transaction::new_signed_with_payer(
         &[ix],
@@ -83,7 +85,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
 
     // Setup the delegated record PDA
     let delegation_record_data =
-        get_delegation_record_on_curve_data(payer_alt.pubkey(), Some(LAMPORTS_PER_SOL));
+        get_delegation_record_on_curve_data(validator.pubkey(), Some(LAMPORTS_PER_SOL));
     program_test.add_account(
         delegation_record_pda_from_delegated_account(&payer_alt.pubkey()),
         Account {
@@ -125,7 +127,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     program_test.add_account(
         fees_vault_pda(),
         Account {
-            lamports: Rent::default().minimum_balance(0),
+            lamports: Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator()),
             data: vec![],
             owner: dlp::id(),
             executable: false,
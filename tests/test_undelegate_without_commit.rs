@@ -3,13 +3,15 @@ use dlp::pda::{
     delegation_record_pda_from_delegated_account, fees_vault_pda,
     validator_fees_vault_pda_from_validator,
 };
+use dlp::state::ProtocolFeesVault;
 use solana_program::rent::Rent;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
 use solana_program_test::{processor, read_file, BanksClient, ProgramTest};
 use solana_sdk::{
     account::Account,
+    instruction::InstructionError,
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 
 use crate::fixtures::{
@@ -26,7 +28,7 @@ async fn test_undelegate_without_commit() {
 
     // Retrieve the accounts
     let delegation_record_pda = delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID);
-    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID);
+    let commit_state_pda = commit_state_pda_from_delegated_account(&DELEGATED_PDA_ID, 0);
 
     // Save the new state data before undelegating
     let delegated_pda_state_before_undelegation =
@@ -39,6 +41,7 @@ async fn test_undelegate_without_commit() {
         DELEGATED_PDA_ID,
         DELEGATED_PDA_OWNER_ID,
         validator.pubkey(),
+        0,
     );
     let tx = Transaction::new_signed_with_payer(
         &[ix],
@@ -75,6 +78,37 @@ async fn test_undelegate_without_commit() {
     assert_eq!(new_state_data_before_finalize, pda_account.data);
 }
 
+#[tokio::test]
+async fn test_undelegate_without_protocol_config_fails() {
+    // Setup
+    let (banks, _, validator, blockhash) = setup_program_test_env().await;
+
+    // Submit the undelegate tx without the protocol config
+    let mut ix = dlp::instruction_builder::undelegate(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        validator.pubkey(),
+        0,
+    );
+    ix.accounts.truncate(12);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+
+    // Assert that the account is still delegated
+    let pda_account = banks.get_account(DELEGATED_PDA_ID).await.unwrap().unwrap();
+    assert!(pda_account.owner.eq(&dlp::id()));
+}
+
 async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
     program_test.prefer_bpf(true);
@@ -146,7 +180,7 @@ async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     program_test.add_account(
         fees_vault_pda(),
         Account {
-            lamports: Rent::default().minimum_balance(0),
+            lamports: Rent::default().minimum_balance(ProtocolFeesVault::size_with_discriminator()),
             data: vec![],
             owner: dlp::id(),
             executable: false,
@@ -0,0 +1,263 @@
+use dlp::args::CommitStateArgs;
+use dlp::error::DlpError;
+use dlp::pda::{
+    delegation_metadata_pda_from_delegated_account, delegation_record_pda_from_delegated_account,
+    program_config_from_program_id, validator_bond_pda_from_validator,
+    validator_fees_vault_pda_from_validator,
+};
+use dlp::state::{ProgramConfig, ValidatorBond};
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::rent::Rent;
+use solana_program::{native_token::LAMPORTS_PER_SOL, system_program};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::fixtures::{
+    add_delegation_program_data, get_delegation_metadata_data, get_delegation_record_data,
+    DELEGATED_PDA_ID, DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY,
+};
+
+mod fixtures;
+
+const MIN_VALIDATOR_BOND: u64 = LAMPORTS_PER_SOL;
+
+#[tokio::test]
+async fn test_commit_requires_min_validator_bond() {
+    // Setup
+    let (mut context, validator) = setup_program_test_env().await;
+
+    // Commit without a bond
+    let res = process_instruction(&mut context, &validator, commit_state_ix(&validator)).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InsufficientValidatorBond as u32)
+        )
+    );
+
+    // Deposit the minimum bond
+    let ix =
+        dlp::instruction_builder::deposit_validator_bond(validator.pubkey(), MIN_VALIDATOR_BOND);
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+    let validator_bond = get_validator_bond(&mut context, &validator.pubkey()).await;
+    assert_eq!(validator_bond.validator, validator.pubkey());
+    assert_eq!(validator_bond.amount, MIN_VALIDATOR_BOND);
+
+    // Commit without the validator bond account, which then holds no bond
+    let mut ix = commit_state_ix(&validator);
+    ix.accounts.pop();
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InsufficientValidatorBond as u32)
+        )
+    );
+
+    // Commit with the minimum bond
+    let res = process_instruction(&mut context, &validator, commit_state_ix(&validator)).await;
+    assert!(res.is_ok());
+}
+
+#[tokio::test]
+async fn test_unbond_and_withdraw_validator_bond() {
+    // Setup
+    let (mut context, validator) = setup_program_test_env().await;
+    let ix =
+        dlp::instruction_builder::deposit_validator_bond(validator.pubkey(), MIN_VALIDATOR_BOND);
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+
+    // Unbond part of the bond
+    let ix =
+        dlp::instruction_builder::unbond_validator_bond(validator.pubkey(), MIN_VALIDATOR_BOND / 4);
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+    let validator_bond = get_validator_bond(&mut context, &validator.pubkey()).await;
+    assert_eq!(validator_bond.amount, MIN_VALIDATOR_BOND * 3 / 4);
+    assert_eq!(validator_bond.unbonding_amount, MIN_VALIDATOR_BOND / 4);
+
+    // The unbonded lamports no longer back the commits
+    let res = process_instruction(&mut context, &validator, commit_state_ix(&validator)).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InsufficientValidatorBond as u32)
+        )
+    );
+
+    // Withdraw during the unbonding period
+    let ix = dlp::instruction_builder::withdraw_validator_bond(validator.pubkey());
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::UnbondingNotElapsed as u32)
+        )
+    );
+
+    // Withdraw once the unbonding period has elapsed
+    context
+        .warp_to_slot(validator_bond.unbonding_end_slot)
+        .unwrap();
+    let validator_bond_pda = validator_bond_pda_from_validator(&validator.pubkey());
+    let bond_balance_before = get_balance(&mut context, &validator_bond_pda).await;
+    let ix = dlp::instruction_builder::withdraw_validator_bond(validator.pubkey());
+    let res = process_instruction(&mut context, &validator, ix).await;
+    assert!(res.is_ok());
+
+    let validator_bond = get_validator_bond(&mut context, &validator.pubkey()).await;
+    assert_eq!(validator_bond.amount, MIN_VALIDATOR_BOND * 3 / 4);
+    assert_eq!(validator_bond.unbonding_amount, 0);
+    let bond_balance_after = get_balance(&mut context, &validator_bond_pda).await;
+    assert_eq!(
+        bond_balance_before - bond_balance_after,
+        MIN_VALIDATOR_BOND / 4
+    );
+}
+
+fn commit_state_ix(validator: &Keypair) -> Instruction {
+    dlp::instruction_builder::commit_state(
+        validator.pubkey(),
+        DELEGATED_PDA_ID,
+        DELEGATED_PDA_OWNER_ID,
+        0,
+        CommitStateArgs {
+            data: vec![1, 2, 3],
+            slot: 100,
+            allow_undelegation: false,
+            lamports: LAMPORTS_PER_SOL,
+            actions: vec![],
+        },
+    )
+}
+
+async fn get_validator_bond(context: &mut ProgramTestContext, validator: &Pubkey) -> ValidatorBond {
+    let validator_bond_account = context
+        .banks_client
+        .get_account(validator_bond_pda_from_validator(validator))
+        .await
+        .unwrap()
+        .unwrap();
+    *ValidatorBond::try_from_bytes_with_discriminator(&validator_bond_account.data).unwrap()
+}
+
+async fn get_balance(context: &mut ProgramTestContext, account: &Pubkey) -> u64 {
+    context.banks_client.get_balance(*account).await.unwrap()
+}
+
+async fn process_instruction(
+    context: &mut ProgramTestContext,
+    signer: &Keypair,
+    ix: Instruction,
+) -> Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx =
+        Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], blockhash);
+    context.banks_client.process_transaction(tx).await
+}
+
+async fn setup_program_test_env() -> (ProgramTestContext, Keypair) {
+    let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
+    program_test.prefer_bpf(true);
+
+    let validator_keypair = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    add_delegation_program_data(&mut program_test, validator_keypair.pubkey());
+
+    program_test.add_account(
+        validator_keypair.pubkey(),
+        Account {
+            lamports: 10 * LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup a delegated PDA
+    program_test.add_account(
+        DELEGATED_PDA_ID,
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated account metadata PDA
+    let delegation_metadata_data = get_delegation_metadata_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_metadata_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_metadata_data.len()),
+            data: delegation_metadata_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the delegated record PDA
+    let delegation_record_data = get_delegation_record_data(validator_keypair.pubkey(), None);
+    program_test.add_account(
+        delegation_record_pda_from_delegated_account(&DELEGATED_PDA_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(delegation_record_data.len()),
+            data: delegation_record_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the validator fees vault
+    program_test.add_account(
+        validator_fees_vault_pda_from_validator(&validator_keypair.pubkey()),
+        Account {
+            lamports: LAMPORTS_PER_SOL,
+            data: vec![],
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    // Setup the owner program config with a minimum validator bond
+    let mut program_config = ProgramConfig {
+        min_validator_bond: MIN_VALIDATOR_BOND,
+        ..Default::default()
+    };
+    program_config
+        .approved_validators
+        .insert(validator_keypair.pubkey());
+    let mut program_config_data = vec![];
+    program_config
+        .to_bytes_with_discriminator(&mut program_config_data)
+        .unwrap();
+    program_test.add_account(
+        program_config_from_program_id(&DELEGATED_PDA_OWNER_ID),
+        Account {
+            lamports: Rent::default().minimum_balance(program_config_data.len()),
+            data: program_config_data,
+            owner: dlp::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    let context = program_test.start_with_context().await;
+    (context, validator_keypair)
+}
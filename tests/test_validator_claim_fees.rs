@@ -1,12 +1,16 @@
 use crate::fixtures::TEST_AUTHORITY;
-use dlp::consts::PROTOCOL_FEES_PERCENTAGE;
+use dlp::consts::{DEFAULT_PROTOCOL_FEES_BPS, MAX_FEES_BPS};
+use dlp::error::DlpError;
 use dlp::pda::{fees_vault_pda, validator_fees_vault_pda_from_validator};
+use dlp::state::{FeesLedger, ValidatorFeesVault};
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_program::pubkey::Pubkey;
 use solana_program::{hash::Hash, native_token::LAMPORTS_PER_SOL, system_program};
-use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
 use solana_sdk::{
     account::Account,
     signature::{Keypair, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
 
 mod fixtures;
@@ -61,7 +65,8 @@ async fn test_validator_claim_fees() {
     );
 
     // Assert the fees vault now has prev lamports + fees
-    let protocol_fees = (withdrawal_amount * u64::from(PROTOCOL_FEES_PERCENTAGE)) / 100;
+    let protocol_fees =
+        (withdrawal_amount * u64::from(DEFAULT_PROTOCOL_FEES_BPS)) / u64::from(MAX_FEES_BPS);
     let fees_vault_account = banks.get_account(fees_vault_pda).await.unwrap();
     assert!(fees_vault_account.is_some());
     assert_eq!(
@@ -77,6 +82,132 @@ async fn test_validator_claim_fees() {
     );
 }
 
+#[tokio::test]
+async fn test_validator_claim_fees_with_withdrawal_authority() {
+    // Setup
+    let (banks, payer, validator, blockhash) = setup_program_test_env().await;
+    let withdrawal_authority = Keypair::new();
+    let payout_destination = Pubkey::new_unique();
+    let withdrawal_amount = LAMPORTS_PER_SOL / 2;
+
+    // The validator identity hands the vault over to a withdrawal authority
+    let ix = dlp::instruction_builder::set_validator_fees_vault_authority(
+        validator.pubkey(),
+        validator.pubkey(),
+        withdrawal_authority.pubkey(),
+        payout_destination,
+    );
+    let res = process_instruction(&banks, &payer, &validator, ix, blockhash).await;
+    assert!(res.is_ok());
+    let validator_fees_vault_pda = validator_fees_vault_pda_from_validator(&validator.pubkey());
+    let validator_fees_vault_account = banks
+        .get_account(validator_fees_vault_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let validator_fees_vault =
+        ValidatorFeesVault::try_from_bytes_with_discriminator(&validator_fees_vault_account.data)
+            .unwrap();
+    assert_eq!(
+        validator_fees_vault.withdrawal_authority,
+        withdrawal_authority.pubkey()
+    );
+    assert_eq!(validator_fees_vault.payout_destination, payout_destination);
+    assert_eq!(validator_fees_vault.ledger, FeesLedger::default());
+
+    // The validator identity can no longer claim the fees nor update the withdrawal settings
+    let ix =
+        dlp::instruction_builder::validator_claim_fees(validator.pubkey(), Some(withdrawal_amount));
+    let res = process_instruction(&banks, &payer, &validator, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+    let ix = dlp::instruction_builder::set_validator_fees_vault_authority(
+        validator.pubkey(),
+        validator.pubkey(),
+        validator.pubkey(),
+        validator.pubkey(),
+    );
+    let res = process_instruction(&banks, &payer, &validator, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::Unauthorized as u32)
+        )
+    );
+
+    // The fees can only be paid out to the payout destination
+    let ix = dlp::instruction_builder::validator_claim_fees_with_authority(
+        validator.pubkey(),
+        withdrawal_authority.pubkey(),
+        withdrawal_authority.pubkey(),
+        Some(withdrawal_amount),
+    );
+    let res = process_instruction(&banks, &payer, &withdrawal_authority, ix, blockhash).await;
+    assert_eq!(
+        res.unwrap_err().unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(DlpError::InvalidPayoutDestination as u32)
+        )
+    );
+    let ix = dlp::instruction_builder::validator_claim_fees_with_authority(
+        validator.pubkey(),
+        withdrawal_authority.pubkey(),
+        payout_destination,
+        Some(withdrawal_amount),
+    );
+    let res = process_instruction(&banks, &payer, &withdrawal_authority, ix, blockhash).await;
+    assert!(res.is_ok());
+
+    // Assert the payout destination received the fees, minus the protocol fees
+    let protocol_fees =
+        (withdrawal_amount * u64::from(DEFAULT_PROTOCOL_FEES_BPS)) / u64::from(MAX_FEES_BPS);
+    let payout_destination_account = banks
+        .get_account(payout_destination)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        payout_destination_account.lamports,
+        withdrawal_amount - protocol_fees
+    );
+
+    // Assert the claim is recorded in the fees ledger
+    let validator_fees_vault_account = banks
+        .get_account(validator_fees_vault_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    let validator_fees_ledger =
+        ValidatorFeesVault::try_from_bytes_with_discriminator(&validator_fees_vault_account.data)
+            .unwrap()
+            .ledger;
+    assert_eq!(validator_fees_ledger.claimed, withdrawal_amount);
+    assert_eq!(validator_fees_ledger.protocol_share, protocol_fees);
+}
+
+async fn process_instruction(
+    banks: &BanksClient,
+    payer: &Keypair,
+    signer: &Keypair,
+    ix: Instruction,
+    blockhash: Hash,
+) -> Result<(), BanksClientError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, signer],
+        blockhash,
+    );
+    banks.process_transaction(tx).await
+}
+
 async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
     program_test.prefer_bpf(true);
@@ -1,4 +1,4 @@
-use crate::fixtures::{DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY};
+use crate::fixtures::{add_delegation_program_data, DELEGATED_PDA_OWNER_ID, TEST_AUTHORITY};
 use dlp::pda::program_config_from_program_id;
 use dlp::state::ProgramConfig;
 use solana_program::rent::Rent;
@@ -97,10 +97,167 @@ async fn test_remove_validator_for_program() {
         .contains(&validator.pubkey()));
 }
 
+#[tokio::test]
+async fn test_set_challenge_period_for_program() {
+    // Setup
+    let (banks, _, validator, blockhash) = setup_program_test_env().await;
+
+    let ix = dlp::instruction_builder::set_challenge_period_for_program(
+        validator.pubkey(),
+        DELEGATED_PDA_OWNER_ID,
+        100,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Check that the challenge period is set
+    let program_config_account = banks
+        .get_account(program_config_from_program_id(&DELEGATED_PDA_OWNER_ID))
+        .await;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(
+        &program_config_account.unwrap().unwrap().data,
+    )
+    .unwrap();
+    assert_eq!(program_config.challenge_period, 100);
+    assert!(program_config.approved_validators.is_empty());
+}
+
+#[tokio::test]
+async fn test_set_min_validator_bond_for_program() {
+    // Setup
+    let (banks, _, validator, blockhash) = setup_program_test_env().await;
+
+    let ix = dlp::instruction_builder::set_min_validator_bond_for_program(
+        validator.pubkey(),
+        DELEGATED_PDA_OWNER_ID,
+        LAMPORTS_PER_SOL,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Check that the minimum validator bond is set
+    let program_config_account = banks
+        .get_account(program_config_from_program_id(&DELEGATED_PDA_OWNER_ID))
+        .await;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(
+        &program_config_account.unwrap().unwrap().data,
+    )
+    .unwrap();
+    assert_eq!(program_config.min_validator_bond, LAMPORTS_PER_SOL);
+}
+
+#[tokio::test]
+async fn test_set_commit_validation_for_program() {
+    // Setup
+    let (banks, _, validator, blockhash) = setup_program_test_env().await;
+
+    let ix = dlp::instruction_builder::set_commit_validation_for_program(
+        validator.pubkey(),
+        DELEGATED_PDA_OWNER_ID,
+        true,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Check that the commit validation is required
+    let program_config_account = banks
+        .get_account(program_config_from_program_id(&DELEGATED_PDA_OWNER_ID))
+        .await;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(
+        &program_config_account.unwrap().unwrap().data,
+    )
+    .unwrap();
+    assert!(program_config.validate_commits);
+}
+
+#[tokio::test]
+async fn test_set_commit_staleness_for_program() {
+    // Setup
+    let (banks, _, validator, blockhash) = setup_program_test_env().await;
+
+    let ix = dlp::instruction_builder::set_commit_staleness_for_program(
+        validator.pubkey(),
+        DELEGATED_PDA_OWNER_ID,
+        5,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Check that the commit staleness multiplier is set
+    let program_config_account = banks
+        .get_account(program_config_from_program_id(&DELEGATED_PDA_OWNER_ID))
+        .await;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(
+        &program_config_account.unwrap().unwrap().data,
+    )
+    .unwrap();
+    assert_eq!(program_config.commit_staleness_multiplier, 5);
+}
+
+#[tokio::test]
+async fn test_set_commit_fee_for_program() {
+    // Setup
+    let (banks, _, validator, blockhash) = setup_program_test_env().await;
+
+    let ix = dlp::instruction_builder::set_commit_fee_for_program(
+        validator.pubkey(),
+        DELEGATED_PDA_OWNER_ID,
+        5_000,
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&validator.pubkey()),
+        &[&validator],
+        blockhash,
+    );
+    let res = banks.process_transaction(tx).await;
+    println!("{:?}", res);
+    assert!(res.is_ok());
+
+    // Check that the commit fee is set
+    let program_config_account = banks
+        .get_account(program_config_from_program_id(&DELEGATED_PDA_OWNER_ID))
+        .await;
+    let program_config = ProgramConfig::try_from_bytes_with_discriminator(
+        &program_config_account.unwrap().unwrap().data,
+    )
+    .unwrap();
+    assert_eq!(program_config.commit_fee, 5_000);
+}
+
 async fn setup_program_test_env() -> (BanksClient, Keypair, Keypair, Hash) {
     let mut program_test = ProgramTest::new("dlp", dlp::ID, processor!(dlp::process_instruction));
     program_test.prefer_bpf(true);
     let validator = Keypair::from_bytes(&TEST_AUTHORITY).unwrap();
+    add_delegation_program_data(&mut program_test, validator.pubkey());
 
     program_test.add_account(
         validator.pubkey(),